@@ -0,0 +1,207 @@
+//
+//                           c r s a l l o c . r s
+//
+// Summary:
+//    An allocation counting global allocator for the Rust 2D array access
+//    benchmark harness.
+//
+// Introduction:
+//    One of the differences between the ways of holding a 2D array is how
+//    much allocation they involve. A flat array is a single allocation,
+//    where a vector of vectors is one for each row plus one for the vector
+//    of rows, and how many bytes each has in use at once depends on how it's
+//    built. Rather than work that out, this module counts it. It provides a
+//    global allocator that passes every request on to the system allocator,
+//    counting as it goes the allocations, deallocations and reallocations,
+//    the bytes allocated, and the most bytes in use at any one time.
+//
+//    It's only built with the trackalloc feature, which makes it the
+//    program's allocator. A normal build uses the system allocator
+//    directly, with no counting at all.
+//
+//    The counting is a handful of atomic additions per allocation, so it
+//    only costs anything where allocations are made. None of the kernels
+//    allocates anything while it's being timed, except with a fresh output
+//    array for each call, and the harness checks that, and reports any
+//    allocations made while a kernel was being run.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+//  The counts. ALLOCATIONS includes zeroed allocations, and BYTES_ALLOCATED
+//  is the total asked for, including any growth in a reallocation.
+//  IN_USE is the bytes currently allocated, and PEAK the most there have
+//  been since the start, or since the last reset_peak().
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static DEALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static REALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static BYTES_ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static IN_USE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+//  The allocations made while a kernel was being run - see kernel_started()
+//  and kernel_finished().
+
+static KERNEL_ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+//  The allocator itself, which counts and then hands on to the system.
+
+pub struct TrackingAllocator;
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+fn grown (bytes: usize) {
+   BYTES_ALLOCATED.fetch_add(bytes,Ordering::Relaxed);
+   let in_use = IN_USE.fetch_add(bytes,Ordering::Relaxed) + bytes;
+   PEAK.fetch_max(in_use,Ordering::Relaxed);
+}
+
+fn shrunk (bytes: usize) {
+   IN_USE.fetch_sub(bytes,Ordering::Relaxed);
+}
+
+//  Safety: each call is passed straight on to the system allocator, which
+//  meets all the requirements of GlobalAlloc, and the counting neither
+//  allocates nor panics.
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+   unsafe fn alloc (&self,layout: Layout) -> *mut u8 {
+      let pointer = System.alloc(layout);
+      if !pointer.is_null() {
+         ALLOCATIONS.fetch_add(1,Ordering::Relaxed);
+         grown(layout.size());
+      }
+      pointer
+   }
+
+   unsafe fn alloc_zeroed (&self,layout: Layout) -> *mut u8 {
+      let pointer = System.alloc_zeroed(layout);
+      if !pointer.is_null() {
+         ALLOCATIONS.fetch_add(1,Ordering::Relaxed);
+         grown(layout.size());
+      }
+      pointer
+   }
+
+   unsafe fn dealloc (&self,pointer: *mut u8,layout: Layout) {
+      System.dealloc(pointer,layout);
+      DEALLOCATIONS.fetch_add(1,Ordering::Relaxed);
+      shrunk(layout.size());
+   }
+
+   unsafe fn realloc (&self,pointer: *mut u8,layout: Layout,new_size: usize)
+                                                                   -> *mut u8 {
+      let new_pointer = System.realloc(pointer,layout,new_size);
+      if !new_pointer.is_null() {
+         REALLOCATIONS.fetch_add(1,Ordering::Relaxed);
+         if new_size > layout.size() {
+            grown(new_size - layout.size());
+         } else {
+            shrunk(layout.size() - new_size);
+         }
+      }
+      new_pointer
+   }
+}
+
+//  A snapshot of the counts.
+
+#[derive(Clone, Copy, Debug)]
+pub struct AllocStats {
+   pub allocations: usize,
+   pub deallocations: usize,
+   pub reallocations: usize,
+   pub bytes_allocated: usize,
+   pub in_use: usize,
+   pub peak: usize,
+}
+
+//  Returns the counts as they are now.
+
+pub fn stats () -> AllocStats {
+   AllocStats {
+      allocations: ALLOCATIONS.load(Ordering::Relaxed),
+      deallocations: DEALLOCATIONS.load(Ordering::Relaxed),
+      reallocations: REALLOCATIONS.load(Ordering::Relaxed),
+      bytes_allocated: BYTES_ALLOCATED.load(Ordering::Relaxed),
+      in_use: IN_USE.load(Ordering::Relaxed),
+      peak: PEAK.load(Ordering::Relaxed),
+   }
+}
+
+//  Starts the peak again from the bytes in use now, so that the peak for
+//  some part of the run can be found.
+
+pub fn reset_peak () {
+   PEAK.store(IN_USE.load(Ordering::Relaxed),Ordering::Relaxed);
+}
+
+//  Called just before and just after a kernel is run - warmup and timed
+//  trials together - so that any allocations it makes can be reported.
+//  kernel_started() returns the count of allocations so far, to be passed
+//  to kernel_finished().
+
+pub fn kernel_started () -> usize {
+   ALLOCATIONS.load(Ordering::Relaxed)
+}
+
+pub fn kernel_finished (started: usize) {
+   let made = ALLOCATIONS.load(Ordering::Relaxed).wrapping_sub(started);
+   KERNEL_ALLOCATIONS.fetch_add(made,Ordering::Relaxed);
+}
+
+//  Returns the allocations made while kernels were being run since the
+//  last call, and starts the count again.
+
+pub fn take_kernel_allocations () -> usize {
+   KERNEL_ALLOCATIONS.swap(0,Ordering::Relaxed)
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The allocator can't allocate, since it would be called recursively,
+     which is why the counts are plain atomics rather than anything more
+     elaborate, and why there's no reporting here - that's left to the
+     caller, using stats().
+
+   o Relaxed ordering is enough. The counts are only read once the work
+     being counted has finished, and any threads doing it have been joined,
+     which orders everything that matters.
+
+   o The peak is kept with fetch_max() on the new total, so two threads
+     allocating at once can't lose a peak.
+
+   o A reallocation that can't be done in place is, as far as the system is
+     concerned, an allocation, a copy and a deallocation. It's counted as
+     one reallocation regardless, since that's what the program asked for.
+
+*/
@@ -0,0 +1,594 @@
+//
+//                           c r s a r r a y . r s
+//
+// Summary:
+//    A simple 2D array type for the Rust versions of the 2D array access test.
+//
+// Introduction:
+//    The original Rust versions of the 2D array test use vectors of vectors to
+//    represent a 2D array, which is the closest Rust gets to a built-in 2D
+//    rectangular array. The obvious alternative is a single flat 1D vector,
+//    with the code working out the index of each element from its row and
+//    column numbers. That is generally faster, but means that every routine
+//    that accesses the array has to get the index calculation right, and it
+//    is remarkably easy to get it wrong - using nx where ny was meant, for
+//    example, gives code that runs perfectly well for square arrays.
+//
+//    Array2D wraps such a flat vector, together with its dimensions, so the
+//    index calculation is done in just one place. Elements can be accessed
+//    using array[[ix,iy]], which is bounds checked on each of the two indices
+//    separately (so an out of range ix is an error, rather than quietly picking
+//    up an element from the next row), or using the unsafe get_unchecked() and
+//    get_unchecked_mut() methods, which do no checking at all.
+//
+// Layout:
+//    Array2D uses row-major order, just like the vector of vectors versions.
+//    Element (ix,iy) - column ix, row iy - is held at offset iy * nx + ix in
+//    the underlying vector, so the elements of each row are contiguous in
+//    memory and it is the X-value, the column number, that varies fastest.
+//    Code that wants to work along a row as efficiently as possible can get
+//    the row as a slice using row() or row_mut().
+//
+// Storage:
+//    The elements are held in a single allocation that is normally just
+//    what a Vec<T> would use, but which try_new_aligned() can align to a
+//    larger boundary and pad to a multiple of it - a page, say, so that
+//    the whole of an array can be made read-only (see crsprotect.rs).
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//    14th Oct 2026. Added the Element trait. agent.
+//    14th Oct 2026. Added try_new(). agent.
+//    14th Oct 2026. Added try_new_prepared(). agent.
+//    14th Oct 2026. Element types can be multiplied. agent.
+//    14th Oct 2026. Element types can be subtracted and divided. agent.
+//    14th Oct 2026. Element types are 'static. agent.
+//    14th Oct 2026. Added try_new_aligned() and storage_bytes(), and the
+//                   Storage type they need. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::alloc::{self, Layout};
+use std::fmt::{self, Debug};
+use std::mem;
+use std::ops::{Add, Deref, DerefMut, Div, Index, IndexMut, Mul, Sub};
+use std::ptr::{self, NonNull};
+use std::slice;
+
+use crate::crserror::BenchError;
+
+//  ----------------------------------------------------------------------------
+//
+//                               A r r a y 2 D
+
+//  A 2D array of nx columns and ny rows, held in row-major order in a single
+//  flat vector of nx * ny elements.
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Array2D<T> {
+   nx: usize,
+   ny: usize,
+   data: Storage<T>,
+}
+
+impl<T: Clone> Array2D<T> {
+
+   //  Creates an array of nx columns and ny rows, with every element set to
+   //  the same value. Panics if nx * ny overflows or the memory can't be
+   //  allocated.
+
+   pub fn new (nx: usize,ny: usize,value: T) -> Array2D<T> {
+      match Array2D::try_new(nx,ny,value) {
+         Ok(array) => array,
+         Err(error) => panic!("{}",error),
+      }
+   }
+
+   //  As new(), but returns an error rather than panicking if nx * ny
+   //  overflows or the memory can't be allocated.
+
+   pub fn try_new (nx: usize,ny: usize,value: T)
+                                          -> Result<Array2D<T>,BenchError> {
+      Array2D::try_new_prepared(nx,ny,value,|_,_| {})
+   }
+
+   //  As try_new(), but calls prepare() with the address and the length in
+   //  bytes of the array's storage once it's been allocated, before any of
+   //  it is written. That's the time to tell the system how the memory is
+   //  to be used - that it should be in huge pages, say (see crshuge.rs).
+
+   pub fn try_new_prepared<F> (nx: usize,ny: usize,value: T,prepare: F)
+            -> Result<Array2D<T>,BenchError> where F: FnOnce(*const u8,usize) {
+      Array2D::try_new_aligned(nx,ny,value,mem::align_of::<T>(),prepare)
+   }
+
+   //  As try_new_prepared(), but with the storage aligned to align bytes -
+   //  a power of two - and padded to a whole number of them, so that it
+   //  starts and ends on an align boundary however small the array is.
+
+   pub fn try_new_aligned<F> (nx: usize,ny: usize,value: T,align: usize,
+                              prepare: F) -> Result<Array2D<T>,BenchError>
+                                             where F: FnOnce(*const u8,usize) {
+      let mut data = match nx.checked_mul(ny)
+                  .and_then(|len| Storage::try_with_capacity(len,align)) {
+         Some(data) => data,
+         None => return Err(BenchError::Allocation { nx, ny }),
+      };
+      prepare(data.as_ptr() as *const u8,data.bytes());
+      data.fill(value);
+      Ok(Array2D { nx, ny, data })
+   }
+}
+
+impl<T> Array2D<T> {
+
+   //  Creates an array of nx columns and ny rows, setting each element to the
+   //  value returned by calling value_fn(ix,iy) for that element. The elements
+   //  are set in memory order, row by row.
+
+   pub fn from_fn<F> (nx: usize,ny: usize,mut value_fn: F) -> Array2D<T>
+                                          where F: FnMut(usize,usize) -> T {
+      let len = nx.checked_mul(ny).expect("Array size overflows");
+      let mut data = Storage::with_capacity(len,mem::align_of::<T>());
+      for iy in 0..ny {
+         for ix in 0..nx {
+            data.push(value_fn(ix,iy));
+         }
+      }
+      Array2D { nx, ny, data }
+   }
+
+   //  The number of columns in the array - the range of the X index.
+
+   pub fn nx (&self) -> usize {
+      self.nx
+   }
+
+   //  The number of rows in the array - the range of the Y index.
+
+   pub fn ny (&self) -> usize {
+      self.ny
+   }
+
+   //  The total number of elements in the array, nx * ny.
+
+   pub fn len (&self) -> usize {
+      self.data.len()
+   }
+
+   //  True if the array has no elements, ie if either dimension is zero.
+
+   pub fn is_empty (&self) -> bool {
+      self.data.is_empty()
+   }
+
+   //  The size in bytes of the memory holding the elements, including any
+   //  padding try_new_aligned() added at the end.
+
+   pub fn storage_bytes (&self) -> usize {
+      self.data.bytes()
+   }
+
+   //  The offset of element (ix,iy) in the underlying flat vector. This is
+   //  the one place where the row-major index calculation is coded. It does
+   //  no checking of the two indices.
+
+   #[inline(always)]
+   pub fn offset (&self,ix: usize,iy: usize) -> usize {
+      iy * self.nx + ix
+   }
+
+   //  The whole array as a flat slice, in memory (row-major) order.
+
+   pub fn as_slice (&self) -> &[T] {
+      &self.data
+   }
+
+   pub fn as_mut_slice (&mut self) -> &mut [T] {
+      &mut self.data
+   }
+
+   //  Row iy of the array as a slice of nx elements. Panics if iy is out of
+   //  range.
+
+   #[inline(always)]
+   pub fn row (&self,iy: usize) -> &[T] {
+      assert!(iy < self.ny,"Row index {} out of range, ny = {}",iy,self.ny);
+      let start = self.offset(0,iy);
+      &self.data[start..start + self.nx]
+   }
+
+   #[inline(always)]
+   pub fn row_mut (&mut self,iy: usize) -> &mut [T] {
+      assert!(iy < self.ny,"Row index {} out of range, ny = {}",iy,self.ny);
+      let start = self.offset(0,iy);
+      &mut self.data[start..start + self.nx]
+   }
+
+   //  Returns element (ix,iy) with no bounds checking at all. The caller must
+   //  ensure that ix < nx and iy < ny. That's the whole of the safety
+   //  contract, and it's given here rather than in a doc comment, hence the
+   //  allows.
+
+   #[allow(clippy::missing_safety_doc)]
+   #[inline(always)]
+   pub unsafe fn get_unchecked (&self,ix: usize,iy: usize) -> &T {
+      self.data.get_unchecked(self.offset(ix,iy))
+   }
+
+   #[allow(clippy::missing_safety_doc)]
+   #[inline(always)]
+   pub unsafe fn get_unchecked_mut (&mut self,ix: usize,iy: usize) -> &mut T {
+      let offset = self.offset(ix,iy);
+      self.data.get_unchecked_mut(offset)
+   }
+}
+
+//  Indexing uses array[[ix,iy]] - column first, then row - and checks each
+//  index against its own dimension. Checking only the combined offset against
+//  the vector length would let an out of range ix wrap into the next row.
+
+impl<T> Index<[usize; 2]> for Array2D<T> {
+   type Output = T;
+
+   #[inline(always)]
+   fn index (&self,index: [usize; 2]) -> &T {
+      let [ix,iy] = index;
+      assert!(ix < self.nx && iy < self.ny,
+         "Index [{},{}] out of range for {} x {} array",ix,iy,self.nx,self.ny);
+      &self.data[self.offset(ix,iy)]
+   }
+}
+
+impl<T> IndexMut<[usize; 2]> for Array2D<T> {
+
+   #[inline(always)]
+   fn index_mut (&mut self,index: [usize; 2]) -> &mut T {
+      let [ix,iy] = index;
+      assert!(ix < self.nx && iy < self.ny,
+         "Index [{},{}] out of range for {} x {} array",ix,iy,self.nx,self.ny);
+      let offset = self.offset(ix,iy);
+      &mut self.data[offset]
+   }
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                               S t o r a g e
+
+//  The memory holding an array's elements: one allocation, with room for
+//  capacity elements, of which the first len have been set. It's what a
+//  Vec<T> would be, except that the alignment can be larger than T needs,
+//  with the allocation padded to a multiple of it. Elements are only ever
+//  added at the end, and all of them are dropped with the storage.
+
+struct Storage<T> {
+   ptr: NonNull<T>,
+   len: usize,
+   capacity: usize,
+   layout: Layout,
+}
+
+//  Safety: Storage owns its elements, just as a Vec does, so it can go to
+//  or be shared with another thread whenever they can.
+
+unsafe impl<T: Send> Send for Storage<T> {}
+unsafe impl<T: Sync> Sync for Storage<T> {}
+
+impl<T> Storage<T> {
+
+   //  Allocates room for capacity elements, aligned to align bytes, or to
+   //  what T needs if that's more. Returns None if align isn't a power of
+   //  two, the size overflows, or the memory can't be allocated.
+
+   fn try_with_capacity (capacity: usize,align: usize) -> Option<Storage<T>> {
+      let align = align.max(mem::align_of::<T>());
+      if !align.is_power_of_two() { return None; }
+      let bytes = capacity.checked_mul(mem::size_of::<T>())?
+                                       .checked_add(align - 1)? & !(align - 1);
+      let layout = Layout::from_size_align(bytes,align).ok()?;
+
+      //  An empty allocation is never made, and its pointer just needs to
+      //  be aligned and not null. Safety: otherwise, the layout has a size.
+
+      let ptr = if bytes == 0 { align as *mut u8 }
+                else { unsafe { alloc::alloc(layout) } };
+      let ptr = NonNull::new(ptr as *mut T)?;
+      Some(Storage { ptr, len: 0, capacity, layout })
+   }
+
+   //  As try_with_capacity(), but treating a failure as a Vec would - a
+   //  panic if the size overflows, and aborting if there's no memory.
+
+   fn with_capacity (capacity: usize,align: usize) -> Storage<T> {
+      match Storage::try_with_capacity(capacity,align) {
+         Some(storage) => storage,
+         None => match capacity.checked_mul(mem::size_of::<T>())
+                           .and_then(|bytes| Layout::from_size_align(bytes,
+                                                 mem::align_of::<T>()).ok()) {
+            Some(layout) => alloc::handle_alloc_error(layout),
+            None => panic!("Array of {} elements is too large",capacity),
+         },
+      }
+   }
+
+   //  Adds an element at the end. Panics if the storage is full.
+
+   fn push (&mut self,value: T) {
+      assert!(self.len < self.capacity,"Array storage is full");
+
+      //  Safety: the element is within the allocation, and hasn't been set.
+
+      unsafe { self.ptr.as_ptr().add(self.len).write(value); }
+      self.len += 1;
+   }
+
+   //  The size of the allocation in bytes.
+
+   fn bytes (&self) -> usize {
+      self.layout.size()
+   }
+}
+
+impl<T: Clone> Storage<T> {
+
+   //  Sets all the elements that haven't been set to copies of value. The
+   //  length is only updated at the end, so a clone() that panics leaks
+   //  the elements set so far, but never drops one that wasn't.
+
+   fn fill (&mut self,value: T) {
+      for index in self.len..self.capacity {
+
+         //  Safety: as for push().
+
+         unsafe { self.ptr.as_ptr().add(index).write(value.clone()); }
+      }
+      self.len = self.capacity;
+   }
+}
+
+impl<T> Deref for Storage<T> {
+   type Target = [T];
+
+   #[inline(always)]
+   fn deref (&self) -> &[T] {
+
+      //  Safety: the first len elements have been set, and the pointer is
+      //  aligned and not null even if there are none.
+
+      unsafe { slice::from_raw_parts(self.ptr.as_ptr(),self.len) }
+   }
+}
+
+impl<T> DerefMut for Storage<T> {
+
+   #[inline(always)]
+   fn deref_mut (&mut self) -> &mut [T] {
+
+      //  Safety: as for deref(), and the storage is borrowed mutably.
+
+      unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(),self.len) }
+   }
+}
+
+impl<T> Drop for Storage<T> {
+   fn drop (&mut self) {
+
+      //  Safety: the elements were set, and are dropped just the once, and
+      //  the memory is given back with the layout it was allocated with.
+
+      unsafe {
+         ptr::drop_in_place(&mut **self as *mut [T]);
+         if self.layout.size() > 0 {
+            alloc::dealloc(self.ptr.as_ptr() as *mut u8,self.layout);
+         }
+      }
+   }
+}
+
+//  A copy has the same alignment and padding as the original.
+
+impl<T: Clone> Clone for Storage<T> {
+   fn clone (&self) -> Storage<T> {
+      let mut copy = Storage::with_capacity(self.capacity,self.layout.align());
+      for value in self.iter() {
+         copy.push(value.clone());
+      }
+      copy
+   }
+}
+
+impl<T: Debug> Debug for Storage<T> {
+   fn fmt (&self,formatter: &mut fmt::Formatter) -> fmt::Result {
+      (**self).fmt(formatter)
+   }
+}
+
+impl<T: PartialEq> PartialEq for Storage<T> {
+   fn eq (&self,other: &Storage<T>) -> bool {
+      **self == **other
+   }
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                               E l e m e n t
+
+//  The operations the test needs from an array element type: adding two
+//  elements, and converting an index sum to an element - and the other
+//  three arithmetic operations, which only the fma4 kernel (see
+//  crssub_lanes.rs) and the expression interpreter (see crsexpr.rs) use.
+//  Kernels written in terms of Element, rather than f32, can be used with
+//  any of the types that implement it. For f32, from_index(ix + iy) is
+//  exactly (ix + iy) as f32, so a generic kernel does just what the
+//  f32-only versions do. Element types are 'static, so a kernel can use
+//  Any to pick out one element type for special handling, as the
+//  regtranspose kernel (see crssub_regtranspose.rs) does for f32.
+
+pub trait Element: 'static + Copy + Debug + PartialEq + Send + Sync
+                       + Add<Output = Self> + Sub<Output = Self>
+                                   + Mul<Output = Self> + Div<Output = Self> {
+   fn from_index (index: usize) -> Self;
+}
+
+impl Element for f32 {
+   #[inline(always)]
+   fn from_index (index: usize) -> f32 { index as f32 }
+}
+
+impl Element for f64 {
+   #[inline(always)]
+   fn from_index (index: usize) -> f64 { index as f64 }
+}
+
+impl Element for i32 {
+   #[inline(always)]
+   fn from_index (index: usize) -> i32 { index as i32 }
+}
+
+impl Element for i64 {
+   #[inline(always)]
+   fn from_index (index: usize) -> i64 { index as i64 }
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                                 T e s t s
+
+#[cfg(test)]
+mod tests {
+   use super::Array2D;
+
+   //  Element (ix,iy) is at offset iy * nx + ix, so X varies fastest, and a
+   //  non-square array shows up an nx/ny mix-up.
+
+   #[test]
+   fn offset_is_row_major () {
+      let array = Array2D::from_fn(3,2,|ix,iy| ix * 10 + iy);
+      assert_eq!(array.offset(0,0),0);
+      assert_eq!(array.offset(1,0),1);
+      assert_eq!(array.offset(0,1),3);
+      assert_eq!(array.offset(2,1),5);
+      assert_eq!(array.as_slice(),&[0,10,20,1,11,21]);
+      assert_eq!(array[[2,0]],20);
+      assert_eq!(array[[0,1]],1);
+      assert_eq!(array.row(1),&[1,11,21]);
+   }
+
+   //  An out of range ix panics, even though its offset is still within
+   //  the data, rather than picking up an element of the next row.
+
+   #[test]
+   #[should_panic(expected = "Index [3,0] out of range for 3 x 2 array")]
+   fn index_checks_ix () {
+      let array = Array2D::new(3,2,0.0f32);
+      let _ = array[[3,0]];
+   }
+
+   #[test]
+   #[should_panic(expected = "Index [0,2] out of range for 3 x 2 array")]
+   fn index_checks_iy () {
+      let mut array = Array2D::new(3,2,0.0f32);
+      array[[0,2]] = 1.0;
+   }
+
+   #[test]
+   #[should_panic(expected = "Row index 2 out of range, ny = 2")]
+   fn row_checks_iy () {
+      let array = Array2D::new(3,2,0.0f32);
+      let _ = array.row(2);
+   }
+
+   //  get_unchecked() and get_unchecked_mut() reach the same elements, in
+   //  the same (ix,iy) order, as indexing does.
+
+   #[test]
+   fn unchecked_matches_index () {
+      let mut array = Array2D::from_fn(4,3,|ix,iy| (ix * 10 + iy) as f32);
+      for iy in 0..3 {
+         for ix in 0..4 {
+            assert_eq!(unsafe { *array.get_unchecked(ix,iy) },array[[ix,iy]]);
+            unsafe { *array.get_unchecked_mut(ix,iy) += 100.0; }
+            assert_eq!(array[[ix,iy]],(ix * 10 + iy) as f32 + 100.0);
+         }
+      }
+      assert_eq!(array.len(),12);
+      assert!(!array.is_empty());
+      assert!(Array2D::new(0,3,0.0f32).is_empty());
+   }
+
+   //  Aligned storage starts on the boundary and is padded to a multiple
+   //  of it, even for an array far smaller than that, and a copy keeps the
+   //  alignment. An alignment that isn't a power of two is an error.
+
+   #[test]
+   fn aligned_storage () {
+      let array = Array2D::try_new_aligned(3,2,1.5f32,4096,|address,bytes| {
+         assert_eq!(address as usize % 4096,0);
+         assert_eq!(bytes,4096);
+      }).unwrap();
+      assert_eq!(array.storage_bytes(),4096);
+      assert_eq!(array.as_slice(),&[1.5; 6]);
+      let copy = array.clone();
+      assert_eq!(copy.as_slice().as_ptr() as usize % 4096,0);
+      assert_eq!(copy,array);
+      let large = Array2D::try_new_aligned(1025,1,0i32,4096,|_,_| {}).unwrap();
+      assert_eq!(large.storage_bytes(),8192);
+      assert_eq!(Array2D::try_new(5,3,0.0f64).unwrap().storage_bytes(),120);
+      assert_eq!(Array2D::new(0,3,0.0f32).storage_bytes(),0);
+      assert!(Array2D::try_new_aligned(3,2,0.0f32,24,|_,_| {}).is_err());
+      assert!(Array2D::try_new(usize::MAX,2,0.0f32).is_err());
+   }
+
+   //  Every element is dropped with the array, and only once.
+
+   #[test]
+   fn elements_dropped () {
+      use std::rc::Rc;
+      let counted = Rc::new(());
+      let array = Array2D::new(4,3,Rc::clone(&counted));
+      let copy = array.clone();
+      assert_eq!(Rc::strong_count(&counted),25);
+      drop(array);
+      drop(copy);
+      assert_eq!(Rc::strong_count(&counted),1);
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The dimensions are deliberately private, with nx() and ny() accessors,
+     so nothing can change them without also changing the size of the data
+     vector. Most of the point of the type is that nx * ny always matches
+     the length of the data.
+
+   o The index order [ix,iy] is the opposite of the [iy][ix] used with the
+     vector of vectors versions. It matches the (ix,iy) order used by
+     offset() and get_unchecked(), and by most of the comments in the
+     study, where X is the column number and comes first. It does mean
+     that code converted from array[iy][ix] needs the indices swapping
+     round.
+
+*/
@@ -0,0 +1,190 @@
+//
+//                         c r s a r r a y 3 d . r s
+//
+// Summary:
+//    A simple 3D array type for the Rust array access tests.
+//
+// Introduction:
+//    Astronomical data often comes as cubes rather than images - two spatial
+//    axes and a spectral one, say - so the obvious extension to the 2D array
+//    study is to see how the same operation fares on a 3D array. Array3D is
+//    the 3D equivalent of Array2D (see crsarray.rs): a single flat vector
+//    together with its three dimensions, so the index calculation is done in
+//    just one place. A vector of vectors of vectors would add a second level
+//    of indirection to every access, and this study already has plenty of
+//    data on what one level costs.
+//
+//    Elements are accessed using array[[ix,iy,iz]], which checks each index
+//    against its own dimension, just as Array2D does.
+//
+// Layout:
+//    Array3D is an array of nz planes, each of ny rows of nx columns, held in
+//    row-major order. Element (ix,iy,iz) is at offset iz * ny * nx + iy * nx
+//    + ix in the underlying vector, so each row is contiguous, each plane is
+//    a contiguous block of rows, and it is the X-value, the column number,
+//    that varies fastest. A row can be had as a slice using row_mut().
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::ops::{Index, IndexMut};
+
+use crate::crserror::BenchError;
+
+//  An array of nz planes, each of ny rows of nx columns, held in a single
+//  flat vector of nx * ny * nz elements.
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Array3D<T> {
+   nx: usize,
+   ny: usize,
+   nz: usize,
+   data: Vec<T>,
+}
+
+impl<T: Clone> Array3D<T> {
+
+   //  Creates an array with every element set to the same value, returning
+   //  an error if nx * ny * nz overflows or the memory can't be allocated.
+
+   pub fn try_new (nx: usize,ny: usize,nz: usize,value: T)
+                                          -> Result<Array3D<T>,BenchError> {
+      let error = BenchError::Allocation3D { nx, ny, nz };
+      let len = match nx.checked_mul(ny).and_then(|len| len.checked_mul(nz)) {
+         Some(len) => len,
+         None => return Err(error),
+      };
+      let mut data = Vec::new();
+      if data.try_reserve_exact(len).is_err() { return Err(error); }
+      data.resize(len,value);
+      Ok(Array3D { nx, ny, nz, data })
+   }
+}
+
+impl<T> Array3D<T> {
+
+   //  Creates an array, setting each element to the value returned by
+   //  calling value_fn(ix,iy,iz) for that element. The elements are set in
+   //  memory order.
+
+   pub fn from_fn<F> (nx: usize,ny: usize,nz: usize,mut value_fn: F)
+                          -> Array3D<T> where F: FnMut(usize,usize,usize) -> T {
+      let mut data = Vec::with_capacity(nx * ny * nz);
+      for iz in 0..nz {
+         for iy in 0..ny {
+            for ix in 0..nx {
+               data.push(value_fn(ix,iy,iz));
+            }
+         }
+      }
+      Array3D { nx, ny, nz, data }
+   }
+
+   //  The number of columns, rows and planes - the ranges of the X, Y and
+   //  Z indices.
+
+   pub fn nx (&self) -> usize {
+      self.nx
+   }
+
+   pub fn ny (&self) -> usize {
+      self.ny
+   }
+
+   pub fn nz (&self) -> usize {
+      self.nz
+   }
+
+   //  The offset of element (ix,iy,iz) in the underlying flat vector - the
+   //  one place the index calculation is coded. It does no checking.
+
+   #[inline(always)]
+   pub fn offset (&self,ix: usize,iy: usize,iz: usize) -> usize {
+      (iz * self.ny + iy) * self.nx + ix
+   }
+
+   //  The whole array as a flat slice, in memory order.
+
+   pub fn as_slice (&self) -> &[T] {
+      &self.data
+   }
+
+   pub fn as_mut_slice (&mut self) -> &mut [T] {
+      &mut self.data
+   }
+
+   //  Row iy of plane iz as a mutable slice of nx elements. Panics if either
+   //  index is out of range.
+
+   #[inline(always)]
+   pub fn row_mut (&mut self,iy: usize,iz: usize) -> &mut [T] {
+      assert!(iy < self.ny && iz < self.nz,"Row {} of plane {} out of range \
+                       for {} x {} planes",iy,iz,self.ny,self.nz);
+      let start = self.offset(0,iy,iz);
+      &mut self.data[start..start + self.nx]
+   }
+}
+
+//  Indexing uses array[[ix,iy,iz]], and checks each index against its own
+//  dimension.
+
+impl<T> Index<[usize; 3]> for Array3D<T> {
+   type Output = T;
+
+   #[inline(always)]
+   fn index (&self,index: [usize; 3]) -> &T {
+      let [ix,iy,iz] = index;
+      assert!(ix < self.nx && iy < self.ny && iz < self.nz,
+         "Index [{},{},{}] out of range for {} x {} x {} array",
+                                       ix,iy,iz,self.nx,self.ny,self.nz);
+      &self.data[self.offset(ix,iy,iz)]
+   }
+}
+
+impl<T> IndexMut<[usize; 3]> for Array3D<T> {
+
+   #[inline(always)]
+   fn index_mut (&mut self,index: [usize; 3]) -> &mut T {
+      let [ix,iy,iz] = index;
+      assert!(ix < self.nx && iy < self.ny && iz < self.nz,
+         "Index [{},{},{}] out of range for {} x {} x {} array",
+                                       ix,iy,iz,self.nx,self.ny,self.nz);
+      let offset = self.offset(ix,iy,iz);
+      &mut self.data[offset]
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o This is deliberately a separate type, rather than a generalisation of
+     Array2D over the number of dimensions. The 2D code is what the study
+     is about, and it's better left exactly as it was measured.
+
+   o The offset is worked out as (iz * ny + iy) * nx + ix, which is the
+     same as iz * ny * nx + iy * nx + ix but saves a multiplication.
+
+*/
@@ -0,0 +1,316 @@
+//
+//                             c r s a s m . r s
+//
+// Summary:
+//    Reads the assembly listing of the Rust 2D array access benchmark
+//    harness, to see what the compiler made of the kernels.
+//
+// Introduction:
+//    The notes on the original Rust test programs say that, as far as can
+//    be seen, rustc inlines csub() anyway, so there's no call overhead in
+//    the timings. That's easy to say and rather harder to check, and what
+//    the compiler does can change from one release to the next. This
+//    module reads the assembly listing rustc writes for the harness, and
+//    finds, for each function in it, how many instructions it has, the
+//    loops it contains, and the functions it calls. The harness uses that
+//    (see --asm-report in crsbench.rs) to list the kernels that still exist
+//    as functions in their own right, how tight their loops are, and any
+//    call made to one - which is a call that wasn't inlined.
+//
+//    To get a listing, compile the harness exactly as it's normally built,
+//    with the same options, but add '--emit asm'. For example:
+//
+//       rustc -O --emit asm -o crsbench.s crsbench.rs
+//
+//    The listing has to be of the very build being studied - the options,
+//    the target CPU and the compiler version all change the code - and it
+//    has to be from the same source, since nothing here can tell if it
+//    isn't.
+//
+// Listings:
+//    Both the x86-64 and the AArch64 listings rustc writes can be read, for
+//    ELF and Mach-O targets. A line at the start of a line that ends in a
+//    colon is a label, and a label that doesn't start with '.' or 'L' is
+//    the start of a function (or of some data, which has no instructions
+//    and is ignored). An indented line that isn't a directive, a comment or
+//    blank is an instruction. A loop is a jump back to a label earlier in
+//    the same function, and its size is the number of instructions from
+//    the label to the jump, inclusive. That's a loop in the compiler's
+//    layout rather than in the source, and the smallest of them is usually
+//    - but not always - the innermost loop of the source.
+//
+//    Rust symbol names are demangled, using the legacy scheme rustc uses
+//    by default, with the hash at the end dropped, so the kernel in
+//    crssub.rs is crsbench::crssub::csub. Any other name is left as it is.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::collections::HashMap;
+use std::fs;
+
+use crate::crserror::BenchError;
+
+//  What the listing says about one function.
+
+pub struct AsmFunction {
+
+   //  The demangled name.
+
+   pub name: String,
+
+   //  The number of instructions.
+
+   pub instructions: usize,
+
+   //  The size of each loop, in instructions, smallest first.
+
+   pub loops: Vec<usize>,
+
+   //  The demangled name of each function called, in the order of the
+   //  calls, or '(indirect)' for a call through a register or memory.
+
+   pub calls: Vec<String>,
+}
+
+impl AsmFunction {
+
+   //  Returns true if this is one of the kernels - a function whose name,
+   //  or the name of a function it's part of, such as a closure, starts
+   //  with 'csub'.
+
+   pub fn is_kernel (&self) -> bool {
+      is_kernel_name(&self.name)
+   }
+}
+
+//  Returns true if a demangled name is that of a kernel, or part of one.
+//  Anything in angle brackets is ignored, so a library function used with
+//  a kernel's closure as a type parameter isn't taken for the kernel.
+
+pub fn is_kernel_name (name: &str) -> bool {
+   let mut path = String::new();
+   let mut depth = 0;
+   for c in name.chars() {
+      match c {
+         '<' => depth += 1,
+         '>' => depth -= 1,
+         c if depth == 0 => path.push(c),
+         _ => {}
+      }
+   }
+   path.split("::").any(|part| part.starts_with("csub"))
+}
+
+//  Reads an assembly listing, and returns each function in it that has any
+//  instructions, in the order they appear.
+
+pub fn read_listing (path: &str) -> Result<Vec<AsmFunction>,BenchError> {
+   let text = match fs::read_to_string(path) {
+      Ok(text) => text,
+      Err(error) => return Err(BenchError::Io { path: path.to_string(),
+                                 action: "read", error: error.to_string() }),
+   };
+   let mut functions = Vec::new();
+   let mut current: Option<AsmFunction> = None;
+   let mut labels: HashMap<String,usize> = HashMap::new();
+   for line in text.lines() {
+      let line = without_comment(line);
+      if !line.starts_with(|c: char| c.is_whitespace()) {
+         let label = match line.trim_end().strip_suffix(':') {
+            Some(label) => label,
+            None => continue,
+         };
+         if label.starts_with('.') || label.starts_with('L') {
+            if let Some(ref function) = current {
+               labels.insert(label.to_string(),function.instructions);
+            }
+         } else {
+            functions.extend(current.take());
+            labels.clear();
+            current = Some(AsmFunction { name: demangle(label),
+                           instructions: 0, loops: Vec::new(),
+                                                      calls: Vec::new() });
+         }
+         continue;
+      }
+      let instruction = line.trim();
+      if instruction.is_empty() || instruction.starts_with('.')
+                                   || instruction.starts_with('#') {
+         continue;
+      }
+      let function = match current {
+         Some(ref mut function) => function,
+         None => continue,
+      };
+      let mut words = instruction.splitn(2,char::is_whitespace);
+      let mnemonic = words.next().unwrap_or("");
+      let operands = words.next().unwrap_or("").trim();
+      let target = operands.rsplit([',', ' ']).next()
+                                                          .unwrap_or("");
+      if is_call(mnemonic) {
+         function.calls.push(call_target(mnemonic,operands));
+      } else if is_jump(mnemonic) {
+         if let Some(&start) = labels.get(target) {
+            function.loops.push(function.instructions - start + 1);
+         }
+      }
+      function.instructions += 1;
+   }
+   functions.extend(current.take());
+   functions.retain(|function| function.instructions > 0);
+   if functions.is_empty() {
+      return Err(BenchError::Format { path: path.to_string(),
+         message: "has no functions in it, so isn't an assembly listing"
+                                                          .to_string() });
+   }
+   for function in functions.iter_mut() {
+      function.loops.sort_unstable();
+   }
+   Ok(functions)
+}
+
+//  Returns a line with any comment removed. A comment starts with '# ' on
+//  x86-64, and with '//' or ';' on AArch64 - where a '#' with no space
+//  after it starts an immediate value.
+
+fn without_comment (line: &str) -> &str {
+   let start = ["# ","//",";"].iter().filter_map(|marker| line.find(marker))
+                                                                     .min();
+   match start {
+      Some(index) => &line[..index],
+      None => line,
+   }
+}
+
+//  The x86-64 and AArch64 instructions that call a function.
+
+fn is_call (mnemonic: &str) -> bool {
+   mnemonic == "call" || mnemonic == "callq" || mnemonic == "bl"
+                                                         || mnemonic == "blr"
+}
+
+//  The x86-64 and AArch64 instructions that jump, conditionally or not, to
+//  a label. An x86-64 jump is any 'j' instruction, and AArch64 has b,
+//  b.cond, and the compare or test and branch instructions.
+
+fn is_jump (mnemonic: &str) -> bool {
+   mnemonic.starts_with('j') || mnemonic == "b" || mnemonic.starts_with("b.")
+        || mnemonic == "cbz" || mnemonic == "cbnz" || mnemonic == "tbz"
+        || mnemonic == "tbnz"
+}
+
+//  Returns the demangled name of the function a call goes to, given the
+//  call instruction and its operands, or '(indirect)' if it doesn't name
+//  one. An x86-64 call through memory can still name the function, as in
+//  'call *name@GOTPCREL(%rip)', but AArch64 blr always uses a register.
+
+fn call_target (mnemonic: &str,operands: &str) -> String {
+   let symbol = operands.trim_start_matches('*');
+   let symbol = symbol.split(['@', '(']).next().unwrap_or("");
+   if mnemonic == "blr" || symbol.is_empty() || symbol.starts_with('%')
+                       || symbol.starts_with(|c: char| c.is_ascii_digit()) {
+      "(indirect)".to_string()
+   } else {
+      demangle(symbol)
+   }
+}
+
+//  The escapes the legacy mangling scheme uses for characters that can't
+//  appear in a symbol.
+
+const ESCAPES: [(&str,&str); 11] = [("$LT$","<"),("$GT$",">"),("$u20$"," "),
+   ("$C$",","),("$RF$","&"),("$BP$","*"),("$u7b$","{"),("$u7d$","}"),
+   ("$u5b$","["),("$u5d$","]"),("$u27$","'")];
+
+//  Demangles a Rust symbol in the legacy scheme - _ZN, then each part of
+//  the path as a length and the text, then E - leaving out the hash that
+//  ends it. Anything else, or anything that doesn't parse, is returned as
+//  it was.
+
+pub fn demangle (symbol: &str) -> String {
+   let mangled = match symbol.trim_start_matches('_').strip_prefix("ZN") {
+      Some(mangled) => mangled,
+      None => return symbol.to_string(),
+   };
+   let mut parts = Vec::new();
+   let mut rest = mangled;
+   while !rest.starts_with('E') {
+      let digits = rest.len() - rest.trim_start_matches(|c: char|
+                                                    c.is_ascii_digit()).len();
+      let length: usize = match rest[..digits].parse() {
+         Ok(length) => length,
+         Err(_) => return symbol.to_string(),
+      };
+      if digits + length > rest.len() { return symbol.to_string(); }
+      parts.push(&rest[digits..digits + length]);
+      rest = &rest[digits + length..];
+   }
+   if let Some(last) = parts.last() {
+      if last.len() == 17 && last.starts_with('h')
+                        && last[1..].chars().all(|c| c.is_ascii_hexdigit()) {
+         parts.pop();
+      }
+   }
+   let mut name = parts.join("::").replace("..","::");
+   for &(escape,text) in &ESCAPES {
+      name = name.replace(escape,text);
+   }
+   name.replace("_$","$")
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o This isn't a disassembler, and doesn't try to be. It only needs to
+     tell instructions from everything else, and to recognise calls and
+     jumps, which it does from the mnemonic alone. Anything it doesn't
+     recognise is counted as an instruction and otherwise ignored.
+
+   o Code that rustc or LLVM moves out of a function - a cold path split
+     off into a function of its own, say - is counted as a separate
+     function, and a call to it is a real call, even if it's never made.
+     The panic paths for the bounds checks are the usual case, and make
+     calls from kernels that don't affect the timing at all.
+
+   o With the compiler in use when this was written, the claim holds for
+     the original test programs - csub() is inlined into main() - but not
+     for the harness, where each kernel is called from many places, and
+     most of them are left as functions and called. That doesn't make the
+     harness timings wrong, since a call per pass over the array costs
+     next to nothing, but it does mean the loops timed are those of the
+     kernel's own function.
+
+   o Only direct calls can be seen. A kernel called through a function
+     pointer or a trait object - as the Kernel trait calls them - makes an
+     indirect call, and those can't be tied to the function they reach.
+
+   o The newer v0 mangling scheme (symbols starting _R) isn't demangled.
+     rustc only uses it when asked to, and the names are still readable
+     enough to pick out the kernels by eye, though is_kernel() won't
+     recognise them.
+
+*/
@@ -0,0 +1,6209 @@
+//
+//                           c r s b e n c h . r s
+//
+// Summary:
+//    Benchmark harness for the Rust versions of the 2D array access test.
+//
+// Introduction:
+//    This is a test program written as part of a study into how well different
+//    languages handle accessing elements of 2D rectangular arrays - the sort of
+//    thing that are common in astronomy and similar scientific disciplines.
+//    The original Rust test programs, crsmain.rs and its variants, each test
+//    one way of coding the basic array manipulation and leave the timing to be
+//    done externally, usually by Run.py. That's the right way to compare
+//    different languages, but when comparing different ways of coding the
+//    same thing in Rust it gets tedious, and a lot of the time measured is
+//    process startup and checking.
+//
+//    This program brings the various Rust versions of csub() together in one
+//    place. It does the timing itself, repeating the timed run a number of
+//    times to get some idea of how consistent the timings are, and can write
+//    the results as CSV so they can be collected and compared.
+//
+// Structure:
+//    As with the other test programs, the input array is set up (by default)
+//    with the sum of the row and column indices in descending order, the
+//    selected version of csub() is called a large number of times, and the
+//    result is checked against the expected values. The input array is held
+//    as an Array2D (see crsarray.rs); modes that use vectors of vectors get a
+//    copy of the data in that form, made before any timing starts.
+//
+// Building:
+//    It is enough to pass this one source file to the Rust compiler. It will
+//    pick up the various other modules from their separate source files, eg:
+//
+//    rustc -O -C target-cpu=native -C opt-level=3 crsbench.rs
+//
+//    The compiler doesn't tell the program what options it was given, so
+//    if the results are to say how it was built - for --compare-build -
+//    the optimisation level and the target CPU have to be given again, as
+//    environment variables, eg:
+//
+//    CRS_OPT_LEVEL=3 CRS_TARGET_CPU=native \
+//             rustc -C opt-level=3 -C target-cpu=native crsbench.rs
+//
+//    Otherwise they are recorded as 'unknown' (see crsbuild.rs).
+//
+//    Some optional parts of the program are only built if the corresponding
+//    feature is enabled, using --cfg, eg:
+//
+//    rustc -O --cfg 'feature="extprec"' crsbench.rs
+//
+//    The features are:
+//
+//    extprec        reports the errors in the results compared with reference
+//                   values calculated in extended (double double) precision.
+//                   See crsprec.rs.
+//    sqlite         allows the results to be written to an SQLite database,
+//                   using --sqlite. The program is then linked with the
+//                   SQLite library, which must be installed. See crsdb.rs.
+//    danger_alias   allows --danger-alias, which deliberately gives one of
+//                   the unsafe kernels an output overlapping its input, to
+//                   show what goes wrong. It's a teaching aid, and nothing
+//                   else. See crssub_alias.rs.
+//    trackalloc     replaces the system allocator with one that counts
+//                   every allocation, deallocation and reallocation, and the
+//                   bytes in use (see crsalloc.rs). At the end of the run
+//                   the totals, and the most bytes in use at once, are
+//                   reported, any allocations made while a kernel was being
+//                   run are reported after its timing, and
+//                   --bench-construction reports the allocations each way
+//                   of building an array makes. Nothing is counted in a
+//                   build without it.
+//    simd           on x86_64, has the regtranspose mode handle f32 arrays
+//                   with SSE intrinsics, rather than in plain Rust. See
+//                   crssub_regtranspose.rs.
+//    fits           allows --fits, which takes the array dimensions from a
+//                   FITS image, and --fits-data, which uses its pixel
+//                   values as the input. See crsfits.rs.
+//
+// Invocation:
+//    ./crsbench [irpt [ny [nx]]] [options]
+//
+//    where irpt, ny and nx are the repeat count (default 100000), number of
+//    rows (default 10) and number of columns (default 2000), in the same
+//    order as they are taken by crsmain. These, and the whole numbers given
+//    to the options, can have a suffix: k (1000), M (1000000), G (10^9), or
+//    Ki (1024), Mi (1024^2), Gi (1024^3), so --repeats 1M --cols 2k is the
+//    same as --repeats 1000000 --cols 2000. Options are:
+//
+//    --repeats N    the number of times csub() is called per trial.
+//    --rows N       the number of rows in the array (ny).
+//    --cols N       the number of columns in the array (nx).
+//    --dims D       the number of dimensions, 2 (the default) or 3. A 3D
+//                   array has nz planes, each of ny rows of nx columns, and
+//                   uses the 3D version of csub() (crssub3d.rs), which adds
+//                   the sum of all three indices. This needs the flat mode,
+//                   and the diagnostics that only make sense in 2D, such as
+//                   --sweep-2d, --equiv and --count-ops, aren't available.
+//    --nz N         the number of planes in a 3D array (default 10).
+//    --mode M       which version of csub() to test:
+//                     index  - vectors of vectors, array[iy][ix] (crssub.rs)
+//                     unsafe - vectors of vectors, unchecked (crssub_unsafe.rs)
+//                     checked - vectors of vectors, exactly as for unsafe
+//                              but with the bounds checks left in, so the
+//                              two differ only in the checks
+//                              (crssub_checked.rs)
+//                     iter   - vectors of vectors, iterators (crssub_iter.rs)
+//                     flat   - a flat Array2D (crssub1d.rs)
+//                     threads - a flat Array2D, split into bands of rows
+//                              processed in parallel (crssub_threads.rs)
+//                     split  - as threads, but the rows are split in half,
+//                              and in half again, with split_at_mut(),
+//                              giving each half to a thread of its own
+//                              (crssub_split.rs)
+//                     const  - a flat Array2D, with the number of columns
+//                              fixed at compile time for nx = 256, 512,
+//                              1024 or 2048 (crssub_const.rs). The results
+//                              of a specialised kernel are also checked
+//                              against those of the flat mode, so eg
+//                              --sweep-2d 256,512,1024,2048x10 checks them
+//                              all.
+//                     chunks - a flat Array2D, with each row handled in
+//                              chunks of 8 (crssub_chunks.rs)
+//                     rawptr - a flat Array2D, walked using raw pointers
+//                              (crssub_rawptr.rs)
+//                     morton - a flat Array2D, visited in Morton (Z) order
+//                              rather than row by row (crssub_morton.rs)
+//                     gather - a flat Array2D, with the output written in
+//                              order but the input read through a
+//                              permutation index (crssub_gather.rs). See
+//                              --perm-seed.
+//                     window - a flat Array2D, processed a sliding window
+//                              of rows at a time (crssub_window.rs). See
+//                              --window and --stride.
+//                   unrolled - a flat Array2D, with the inner loop unrolled
+//                              by hand (crssub_unrolled.rs). See --unroll.
+//                      simd4 - a flat Array2D, each row handled in groups of
+//                              four lanes (crssub_lanes.rs).
+//                       fma4 - as simd4, but each element as in * 1 + addend,
+//                              the shape of a fused multiply-add, to see
+//                              whether the compiler contracts it into FMA
+//                              instructions (crssub_lanes.rs). Whether the
+//                              results are bit for bit those of the strict
+//                              scalar reference is reported.
+//                       expr - a flat Array2D, each element set to the
+//                              value of an expression, interpreted
+//                              (crsexpr.rs). See --expr. This is for
+//                              trying out new kernels, not for timing.
+//                     binary - a flat Array2D, each element set to the
+//                              sum of the input element and the matching
+//                              element of a second input array, plus the
+//                              index sum (crssub_binary.rs). This reads
+//                              two arrays for each one it writes, so the
+//                              bandwidth figures count 12 bytes an element.
+//               regtranspose - a flat Array2D, with the results written
+//                              to the output transposed, treating it as nx
+//                              rows of ny columns, a 4 x 4 block at a time,
+//                              each block transposed in registers
+//                              (crssub_regtranspose.rs). With the simd
+//                              feature, f32 blocks are handled with SSE
+//                              shuffles on x86_64.
+//                     affine - a flat Array2D, each element of a row set to
+//                              the input element times a gain plus an
+//                              offset, both the same along the row and
+//                              different for each row, as calibration does
+//                              (crssub_affine.rs). That's a multiply and an
+//                              add, so the flop figures count two for each
+//                              element, and there's no index sum.
+//                       copy - a flat Array2D, each element set to the
+//                              input element and nothing more, with no
+//                              arithmetic (crssub_copy.rs). This is the
+//                              floor the other modes build on - the time
+//                              to move the data - so the difference between
+//                              its time and the flat mode's is the cost
+//                              of the arithmetic. The output is passed
+//                              through black_box() after every call, so the
+//                              compiler can't drop any of the copies.
+//                     prefix - a flat Array2D, each element set to the sum
+//                              of the input elements along its row up to
+//                              and including its own (crssub_prefix.rs).
+//                              Each sum depends on the one before, so the
+//                              additions along a row can't be done side by
+//                              side, and the time is set by the latency of
+//                              an addition - in contrast to every other
+//                              mode, whose elements are independent. One
+//                              addition an element, and no index sum.
+//                   The default is index. --list-modes lists the modes,
+//                   with their properties, and shows which are available.
+//    --trials N     the number of timed trials - default 5. With more than
+//                   one, the mean time per call is reported with its
+//                   standard error and a 95% confidence interval, taking
+//                   each trial's time per call as one measurement and using
+//                   Student's t with N - 1 degrees of freedom. That assumes
+//                   the trials are independent and their times normally
+//                   distributed, neither of which is quite true (see the
+//                   notes in crsstats.rs), so treat it as a guide. The CSV
+//                   fields are sem_ns_per_call and ci95_ns_per_call, the
+//                   half width of the interval.
+//    --warmup N     the number of untimed calls made first - default 0.
+//    --warmup-seconds T
+//                   instead of a fixed number of warmup calls, makes as many
+//                   as fit in T seconds, and reports how many that was. This
+//                   gives a slow machine the same chance as a fast one to
+//                   reach its full clock speed. It can't be combined with
+//                   --warmup.
+//    --warmup-to-freq
+//                   instead of a fixed number or time, makes warmup calls in
+//                   short bursts, reading the CPU frequency from the Linux
+//                   cpufreq files in sysfs after each, until the frequency
+//                   levels off, and reports the frequency reached. If it
+//                   hasn't levelled off after 10 seconds, a warning says so.
+//                   Where there are no frequency readings, as in most
+//                   virtual machines, it warns and warms up for 1 second
+//                   instead. It can't be combined with --warmup or
+//                   --warmup-seconds.
+//    --format F     'prose' (the default), 'csv', or 'jsonl' for one JSON
+//                   object per result on each line, flushed as soon as
+//                   it's written so a sweep can be followed as it runs, or
+//                   'prometheus' for the Prometheus text exposition format,
+//                   written once everything has been run, which can be
+//                   redirected to a file for a node_exporter textfile
+//                   collector to pick up (see crsreport.rs).
+//    --precision N  prints every floating point result - times, rates,
+//                   ratios, percentages and the sum checksum - with N
+//                   decimal places, in every format, and in the files and
+//                   streams written with --append and --report-tcp. By
+//                   default times and rates have 3 places, ratios such as
+//                   the arithmetic intensity 4, except in CSV, where all
+//                   but the times have 4, and the sum checksum has as many
+//                   as it needs to be exact.
+//    --si-times     shows each time in the prose output - the results, the
+//                   tables and the diagnostics - scaled to whichever of ns,
+//                   µs, ms or s suits it, with the unit, as in '3.21 µs',
+//                   rather than as so many ns, which makes a sweep over a
+//                   wide range of sizes easier to read. The places are as
+//                   for --precision, whatever the unit. CSV, JSON lines and
+//                   Prometheus output, and the files and streams written
+//                   with --append and --report-tcp, always stay in ns (see
+//                   crsunits.rs).
+//    --baseline FILE
+//                   a CSV file written by an earlier run. Each result is
+//                   compared against the baseline result with the same
+//                   configuration and the program fails if it is slower by
+//                   more than the regression threshold, or if it has no
+//                   baseline result to compare with. A baseline line that
+//                   can't be read is an error, not skipped.
+//    --regress-threshold PCT
+//                   the regression threshold, in percent - default 5.
+//    --compare-build FILE,FILE...
+//                   instead of running anything, reads two or more CSV files
+//                   written by builds of the program with different options
+//                   - each recording its optimisation level and target CPU,
+//                   as described under Building, above - and prints a table
+//                   of the minimum time per call for each configuration that
+//                   is in every file, and the speedup of each build over the
+//                   first, with the geometric mean of the speedups. Results
+//                   are matched on all their configuration fields, and each
+//                   file must come from just one build. With --format csv,
+//                   the table is written as CSV.
+//    --verify-both  as well as the standard check, compare the results with
+//                   the true sums, both exactly and to within a tolerance,
+//                   and report both verdicts. Only the standard check
+//                   decides whether the test passed.
+//    --verify-shuffle
+//                   repeat the standard check, visiting the elements in a
+//                   random order shuffled using the --seed value, and
+//                   report how many are wrong, where they are, and a
+//                   random sample of them, rather than just the first in
+//                   memory order.
+//    --inner-repeat K
+//                   makes each timed call a call to a routine that is never
+//                   inlined, and which makes K passes over the array. The
+//                   times reported are per pass, so comparing K = 1 with a
+//                   large K shows the cost of the call itself.
+//    --threads N    the number of threads used by the parallel modes - the
+//                   default is the number of CPUs available.
+//    --check-coverage
+//                   for the parallel modes, after the timing, runs an
+//                   instrumented version of the kernel that counts the writes
+//                   to each element, and fails unless every element was
+//                   written exactly once. This is slow.
+//    --check-conflict
+//                   works out, from the addresses of the input and output
+//                   arrays and the length of a row, whether they are likely
+//                   to conflict in a set associative cache - whether the
+//                   two arrays map onto the same cache sets element for
+//                   element, or the rows of a column crowd into fewer sets
+//                   than there are ways to hold them - and prints a warning
+//                   if so (see crsconflict.rs). Nothing is measured, so this
+//                   is a hint as to why some sizes run slowly. This needs a
+//                   mode that uses a flat Array2D.
+//    --cache-line N, --cache-assoc N, --cache-size N
+//                   the geometry of the cache for --check-conflict - the
+//                   bytes in a line, the ways in each set, and the bytes in
+//                   the whole cache. The defaults, 64, 8 and 32768, are
+//                   those of a typical L1 data cache.
+//    --sweep-2d NXLIST'x'NYLIST
+//                   runs every combination of the given column and row
+//                   counts, eg --sweep-2d 500,1000,2000x10,20,40 runs nine
+//                   configurations. A sweep is limited to 1000 runs. If a
+//                   configuration can't be run - if its arrays are too big
+//                   to allocate, for example - the sweep reports it and
+//                   carries on, with a line whose status is 'failed' in
+//                   any CSV output, and finishes with a count of the runs
+//                   that succeeded and failed. The exit status is then 4.
+//                   Failed runs aren't written to an SQLite database.
+//    --sweep-shard K/N
+//                   runs just one shard of a sweep, so that a big sweep can
+//                   be split between N machines. The configurations of a
+//                   sweep are numbered from 0, in the order they would be
+//                   run - in order of increasing column count, and for each
+//                   column count in order of increasing row count, with any
+//                   configurations beyond the limit of 1000 dropped first -
+//                   and shard K runs those whose number leaves a remainder
+//                   of K when divided by N. K runs from 0 to N - 1, so every
+//                   configuration is in exactly one of the N shards, and
+//                   running shards 0/N to (N-1)/N with the same sweep, eg
+//                   with --append, covers the whole sweep with no overlaps.
+//                   A shard can be empty, if N is more than the number of
+//                   configurations.
+//    --drift-check N
+//                   repeats the whole run - every configuration - N times,
+//                   pausing between repetitions, then fits a straight line
+//                   to the minimum per-call times of each configuration and
+//                   reports its slope. If the times rise by more than the
+//                   regression threshold over the N repetitions, there's a
+//                   warning that the machine may be throttling. All the
+//                   results are reported, so with CSV each configuration
+//                   has N lines.
+//    --drift-pause-ms P
+//                   the pause between drift check repetitions, in
+//                   milliseconds - default 1000.
+//    --tolerance REL
+//                   the relative tolerance used by --verify-both and
+//                   --equiv - default two f32 epsilons, about 2.4e-7.
+//    --input I      how the input array is filled - 'standard' (the default)
+//                   uses the usual descending index sums, 'random' uses
+//                   pseudo random values over the same range, and
+//                   'gaussian' uses normally distributed values. The option
+//                   can also be given as --input-dist.
+//    --mean M       the mean for the gaussian input - default 0.
+//    --stddev S     the standard deviation for the gaussian input - default 1.
+//    --seed N       the seed for the random and gaussian inputs - default 1.
+//    --seed-per-trial
+//                   with the random or gaussian input, refills the input
+//                   array before each trial, outside the timed part, using
+//                   seeds N, N+1, N+2 and so on, so no trial finds the
+//                   previous trial's data still in the cache. The seeds
+//                   used are reported.
+//    --fits FILE    sets the number of columns and rows to the NAXIS1 and
+//                   NAXIS2 of the 2D image in the FITS file FILE, replacing
+//                   any dimensions given otherwise, so a kernel can be timed
+//                   on the shape of real data. Only the header is read.
+//                   Only available if the program is built with the fits
+//                   feature.
+//    --fits-data    with --fits, uses the image's pixel values as the
+//                   input, instead of one of the inputs --input provides.
+//                   The image has to be of 32 bit floating point values
+//                   (BITPIX = -32), the element type of the arrays, and
+//                   unscaled. The input is reported as 'fits'.
+//    --trial-fresh-thread
+//                   runs each trial in a thread of its own, spawned for it
+//                   and joined before the next trial starts, so nothing a
+//                   thread keeps to itself - an allocator's thread cache,
+//                   say - carries over from one trial to the next. Any
+//                   warmup calls are made in the first trial's thread.
+//                   The trials are then run again, as usual, all on the one
+//                   thread, and the spread of the times per call with each
+//                   is reported, with the ratio of their variances, to
+//                   show whether it made a difference. The results
+//                   reported are those with a fresh thread for each trial.
+//    --loop-order O 'ij' (the default) nests the loops with iy outside and ix
+//                   inside, which goes through the row-major arrays in
+//                   memory order. 'ji' swaps them, keeping the storage the
+//                   same, to show the cost of the wrong loop order on its
+//                   own. Only the flat mode has a ji version, and its
+//                   results are also checked against those of the ij one.
+//    --out-stride S for the flat mode, uses a version of the kernel that
+//                   writes successive results S elements apart in the output
+//                   array, wrapping round at the end (crssub_strided.rs). S
+//                   must be less than nx * ny, with no common factor, so each
+//                   element is written once. The results are put back in
+//                   order before they are checked.
+//    --perm-seed N  for the gather mode, shuffles the permutation index
+//                   using the seed N, so the input is read in a random
+//                   order. Without this, the index is the identity, the
+//                   input is read in memory order, and the output is
+//                   checked element by element as usual. With it, each
+//                   output element comes from a different place, and the
+//                   output is checked by sorting it and comparing it with
+//                   the sorted expected results. The permutation is made
+//                   before the timing starts.
+//    --window W     for the window mode, the height of the window, in rows
+//                   (default 4, or ny if that's less). Each call works
+//                   through the array processing every row of the window,
+//                   then moving it on, so only W rows are in use at a time.
+//    --stride S     for the window mode, the number of rows the window moves
+//                   on by each time (default 1). S can't be more than W, or
+//                   rows would be missed. With S less than W every row is
+//                   processed about W / S times before the window passes
+//                   it, and the times and throughputs in the results are
+//                   still per pass over the array, so the throughput for the
+//                   elements actually processed, and the working set - the
+//                   bytes in the W rows of the input and output - are
+//                   reported as well. The output is checked as usual, since
+//                   every row has been processed by the end of a call.
+//    --unroll N     for the unrolled mode, the number of columns each pass of
+//                   the inner loop handles, as N statements written out by
+//                   hand, with a plain loop for any columns left over
+//                   (default 4). N can be 1, 2, 4 or 8.
+//    --expr EXPR    for the expr mode, the expression each output element
+//                   is set to, in terms of in (the input element), ix and
+//                   iy, eg 'in * 2 + ix - iy / 3' - see crsexpr.rs for the
+//                   details. The default is 'in + ix + iy'. The expression
+//                   is interpreted for every element, so the times are far
+//                   longer than those of a compiled kernel, and are only a
+//                   rough guide to the cost of one expression against
+//                   another. The results are checked against a separate
+//                   evaluation of the expression, rather than as usual,
+//                   and the operation count, the flops and the roofline
+//                   all use the operations in the expression.
+//    --peak-bandwidth GBS
+//                   the peak memory bandwidth of the machine, in Gbyte/s
+//                   (10^9 bytes per second). The achieved bandwidth - the
+//                   bytes each pass moves, as declared for the mode in the
+//                   MODES table, divided by the minimum time per pass - is
+//                   always reported, and with this it's also given as a
+//                   fraction of the peak. The declared bytes don't include
+//                   the extra read that a write-allocate cache makes for
+//                   each output line, so the real traffic can be higher.
+//    --roofline     with --peak-bandwidth, reports the shortest time a pass
+//                   could possibly take if it were limited only by memory
+//                   bandwidth - the declared bytes it moves divided by the
+//                   peak bandwidth - next to the minimum time actually
+//                   achieved, and the efficiency, the first as a percentage
+//                   of the second. That's a limit set by the hardware, not
+//                   a comparison with another language, but it is only a
+//                   limit for a memory-bound kernel: it says nothing about
+//                   one held back by its arithmetic, and an array small
+//                   enough to stay in cache isn't limited by the memory
+//                   bandwidth at all, so can do better than 100%.
+//    --vs-memcpy    once the trials are done, times copy_from_slice() - a
+//                   memcpy() - of the input array into a scratch array of
+//                   the same size, under the same conditions, and reports
+//                   the kernel's minimum time as a multiple of the copy's,
+//                   scaled to the bytes the mode declares it moves. Close
+//                   to 1 means the kernel is memory bound; much more means
+//                   it's doing more than moving the data. The copy uses a
+//                   single thread, so for a parallel mode this compares it
+//                   with one thread's worth of copying.
+//    --checksum-algo A
+//                   reports a checksum of the output array, worked out in one
+//                   of three ways (see crschecksum.rs). 'sum' is the sum of
+//                   the elements, in double precision - a quick sanity
+//                   check, but one that depends on the order of the
+//                   additions and can't show an exact match. 'fnv' (a 64-bit
+//                   FNV-1a hash) and 'crc32' (the zlib CRC-32) are worked out
+//                   from the bytes of the elements, as little endian IEEE
+//                   754 floats in memory order, and so only match if the
+//                   output is bit for bit the same. Use one of those to
+//                   compare the output with that of the programs in other
+//                   languages - crc32 is the easiest to reproduce elsewhere.
+//                   In CSV output, the checksum_algo and checksum fields are
+//                   'none' unless this is given.
+//    --strict-fp    also checks the output bit for bit against the result
+//                   of strict IEEE 754 single precision arithmetic - the
+//                   index sum rounded to f32, then added to the input value
+//                   and rounded again - worked out independently of the
+//                   compiler's f32 code (see strict_reference() in
+//                   crsverify.rs). A mismatch anywhere counts as a failure.
+//                   This is the check that the output is exactly the same
+//                   as any other language's, whatever the compiler and its
+//                   flags. There's nothing in the Rust kernels that needs
+//                   turning off to get there: rustc never contracts a
+//                   multiply and an add into an FMA, or reassociates
+//                   floating point arithmetic, unless the code explicitly
+//                   asks for it (with mul_add() or the unstable fast-math
+//                   intrinsics), and none of the kernels do, whatever
+//                   target features are enabled. Rust has no way to ask for
+//                   strictness in code that isn't strict already, so this
+//                   can only check. It isn't available on 32-bit x86 without
+//                   SSE2, where the x87 unit's extended precision can leak
+//                   into results. Nor can the program control the
+//                   flush-to-zero setting, which other code in the process
+//                   could change - but then the check would show it, as
+//                   long as the input has denormals. The self test makes
+//                   this check for every mode.
+//    --equiv A,B    instead of timing anything, runs modes A and B once each
+//                   on the same input and reports whether their outputs are
+//                   bitwise identical and, if not, the first element where
+//                   they differ and how many differ by more than the
+//                   tolerance. The exit status is 1 if any do. This is the
+//                   way to check a new kernel against a trusted one before
+//                   believing its timings. Options that select what is timed,
+//                   like --sweep-2d and --drift-check, are ignored.
+//    --compare-types
+//                   instead of the usual run, times the selected mode with
+//                   each of f32, f64, i32 and i64 elements in turn, under
+//                   exactly the same conditions, checks each result, and
+//                   prints a table of the per-call times and their ratio to
+//                   the f32 time, and then the geometric mean of the ratios
+//                   for the other three types - the way to summarise ratios
+//                   (see crsstats.rs). With CSV output, that goes to
+//                   standard error. This needs a mode whose kernel is
+//                   generic over the element type - one that uses a flat
+//                   Array2D - and the standard input, whose values are exact
+//                   in all four types. The exit status is 1 if any result is
+//                   wrong.
+//    --compare-unroll
+//                   instead of the usual run, times the flat mode and then
+//                   the unrolled mode with each of its unroll factors in
+//                   turn, under exactly the same conditions, checks each
+//                   result, and prints a table of the per-call times and
+//                   their ratio to the flat time, and the geometric mean of
+//                   the ratios for the unroll factors - which shows whether
+//                   unrolling by hand helps an optimiser that would unroll
+//                   the plain loop anyway. It needs --mode unrolled, and no
+//                   --unroll. As for --compare-types, the standard input is
+//                   used, and the exit status is 1 if any result is wrong.
+//    --false-share-study
+//                   instead of the usual run, times a kernel that deals the
+//                   elements out to the threads a cache line's worth at a
+//                   time (see crssub_interleaved.rs), first with the chunks
+//                   aligned to the cache lines of the output, so no two
+//                   threads write the same line, then with them shifted
+//                   half a line along, so every line is written by two -
+//                   false sharing. It prints a table of the per-call times,
+//                   giving where the chunk boundaries fall in a line in
+//                   each case, and the ratio of the two, checks both
+//                   results, and exits with status 1 if either is wrong.
+//                   It needs --mode threads and at least two threads, and
+//                   the line size is that given by --cache-line.
+//    --order-study  instead of the usual run, times the flat mode with both
+//                   loop orders - along the rows, as the data is stored,
+//                   and down the columns - on square arrays from 16 to 4096
+//                   on a side, and prints a table of the time per element
+//                   for each, and their ratio, giving the bytes between
+//                   successive elements of a column and the working set.
+//                   It then reports the crossover, the first size at which
+//                   going down the columns takes at least twice as long.
+//                   With --format csv the table is CSV, one line per size,
+//                   ready for plotting with gnuplot or anything else, and
+//                   the crossover goes to standard error. It needs --mode
+//                   flat, and no --loop-order or --out-stride; --nx and
+//                   --ny are ignored, and the exit status is 1 if any
+//                   result is wrong.
+//    --bench-construction
+//                   instead of the usual run, times the building of an
+//                   array of the given size - as a single vector, and as a
+//                   vector of vectors, each in a few of the ways a program
+//                   might well do it (see crsconstruct.rs) - and prints a
+//                   table of the per-call times, each including dropping the
+//                   array again, and their ratio to the time for the plain
+//                   vec![0.0; nx * ny]. Each way is first checked to build
+//                   the array it should, every element zero, and the exit
+//                   status is 1 if any doesn't. The mode and the input
+//                   aren't used, but everything about the timing - trials,
+//                   repeats, budget and warmup - is as usual, so for a big
+//                   array use --budget-ms or a small --repeats.
+//    --find-cliff   instead of the usual run, looks for the size at which
+//                   the working set stops fitting in the last level cache.
+//                   The selected mode is timed with the row length given by
+//                   --cols and a geometric series of row counts, four to
+//                   each doubling, so that the bytes the kernel touches go
+//                   from 16 KiB (or one row, if that's more) to 512 MiB.
+//                   Unless there's a --budget-ms, each trial makes enough
+//                   calls to handle about 10^8 elements, whatever the size.
+//                   The minimum time per element is reported for each size,
+//                   and a transition is a run of sizes over which that time
+//                   climbs steeply, by at least 25% in all, and stays up for
+//                   the next doubling - so a single slow size doesn't count,
+//                   and nothing is found in the top doubling. Each one found is
+//                   listed, and the last - the one at the largest working
+//                   set - is taken to be the cliff. The cache size it
+//                   implies is the geometric mean of the sizes either side
+//                   of its steepest step. The earlier transitions are
+//                   usually the smaller caches. The same restrictions apply
+//                   as for --compare-types, and the exit status is 1 if any
+//                   result is wrong.
+//    --torture      instead of the usual run, runs the selected mode once
+//                   for each combination of some awkward sizes as the
+//                   number of columns and of rows - 1, 3, 13, 17, 127, 257,
+//                   1021, 4095 and 4097, leaving out those with more than
+//                   2^22 elements - and checks each result. These are the
+//                   sizes that exercise the code that handles what's left
+//                   over at the end of a row, or of an array, by a kernel
+//                   that works in groups, chunks, tiles or threads, which
+//                   is where a newly optimised kernel is most likely to be
+//                   wrong. Any shape that gets the wrong results, or that
+//                   the mode can't be used for, is listed, with a summary
+//                   at the end, and --format csv gives a line for every
+//                   shape. The given array size isn't used, the input is as
+//                   usual, and the exit status is 1 if any result is wrong.
+//    --concurrent-arrays N
+//                   instead of the usual run, starts N threads, each with
+//                   its own pair of arrays - allocated, and so placed in
+//                   memory, by the thread itself - and has them all time
+//                   the selected mode at the same time, starting each trial
+//                   together. This is different from the threads mode, which
+//                   shares one pair of arrays between its threads: here each
+//                   thread is an independent copy of the usual run, and the
+//                   point is to see how much memory bandwidth the system can
+//                   deliver in total, which one thread usually can't use.
+//                   The time per call and bandwidth of each thread are
+//                   reported, along with the aggregate bandwidth - the bytes
+//                   moved by all the threads in a trial, divided by the time
+//                   the slowest of them took - and its fraction of any
+//                   --peak-bandwidth. Use --budget-ms, so the threads all
+//                   stop together, for the cleanest aggregate figure. Each
+//                   thread's output is checked separately, and the exit
+//                   status is 1 if any is wrong. This needs a mode other
+//                   than the threads mode, and can't be used with
+//                   --seed-per-trial, or with the other options that replace
+//                   the usual run.
+//    --danger-alias OFFSET
+//                   only in a program built with the danger_alias feature,
+//                   and only from the command line: instead of the usual
+//                   run, deliberately breaks the rule that the unsafe
+//                   kernels rely on, that their input and output don't
+//                   overlap. The input and output are put in one buffer,
+//                   with the output OFFSET elements after the input (or
+//                   before it, if OFFSET is negative), and the raw pointer
+//                   kernel is run over them once (see crssub_alias.rs). The
+//                   number of output elements that are wrong is reported,
+//                   along with the first few, and then the same kernel is
+//                   run with separate arrays for comparison - the only way
+//                   the borrow checker lets the real kernels be called. The
+//                   output is meant to be wrong, nothing is timed, and
+//                   nothing is written to any result file, database or
+//                   collector. The exit status is 1 if the output is wrong.
+//                   This can't be combined with the other options that
+//                   replace the usual run, or with a sweep.
+//    --fresh-alloc  allocates a new output array before every call of the
+//                   kernel, freeing the one from the call before, so the
+//                   times include the allocation - as they would for code
+//                   that allocates a fresh buffer for each operation. These
+//                   times are not the time taken by the kernel, and are
+//                   always reported as including the allocation, and in CSV
+//                   output the fresh_alloc field is true. The output of the
+//                   last call is the one checked. This needs a mode that
+//                   uses a flat Array2D.
+//    --hugepages    on Linux, asks for the input and output arrays to be
+//                   backed by transparent huge pages, using madvise(), and
+//                   reports how much of them really was, and the size of
+//                   the pages used (see crshuge.rs). Comparing the times
+//                   with and without shows what the TLB misses cost for a
+//                   large array. Where huge pages can't be had - on another
+//                   system, or with them turned off - a warning says so and
+//                   the usual pages are used. This needs a mode that uses a
+//                   flat Array2D, and in CSV output the hugepages field is
+//                   true.
+//    --mlock        locks the input and output arrays into memory with
+//                   mlock(), once they have been filled, so that none of
+//                   their pages can be swapped out, or taken away and
+//                   faulted back in, during the timing - and unlocks them
+//                   at the end of the run. The amount locked is reported.
+//                   An unprivileged process can only lock as much memory as
+//                   its limit allows (see 'ulimit -l'), and where the arrays
+//                   can't be locked a warning says why, and they are used
+//                   unlocked (see crslock.rs). This needs a mode that uses
+//                   a flat Array2D, and in CSV output the mlock field is
+//                   true.
+//    --protect-input
+//                   on Linux, makes the pages of the input array read-only
+//                   with mprotect() from before the warmup to the end of
+//                   the timed trials, so that a kernel that writes to its
+//                   input - a bug only the unsafe modes could have - stops
+//                   with a segmentation fault at the write. How much of the
+//                   input was protected is reported. The input is allocated
+//                   page aligned, and padded to whole pages, so that all of
+//                   it can be, however small it is (see crsprotect.rs).
+//                   This needs a mode that uses a flat Array2D, and doesn't
+//                   change the times.
+//    --diff-map PATH
+//                   if the results don't match the expected values, writes
+//                   the absolute difference between each output element and
+//                   its expected value to PATH, as raw f32 values in the
+//                   byte order given by --endian, row by row, so the pattern
+//                   of the errors can be seen. Nothing is written if the
+//                   results are correct. With a sweep, the same file is used
+//                   for every failing configuration, so ends up with the
+//                   last one.
+//    --svg-histogram PATH
+//                   once the trials are done, writes a histogram of their
+//                   times per call to PATH, as a standalone SVG image (see
+//                   crssvg.rs), with the time per call along the bottom and
+//                   the number of trials up the side. It's drawn from the
+//                   times the run reports anyway, so it doesn't change
+//                   them, but with the default five trials there isn't much
+//                   to see - it's meant for use with --trials set high.
+//    --compare-with PATH
+//                   after the timing, reads the output of another
+//                   implementation - the C version of the test, say - from
+//                   PATH, as raw f32 values in the byte order given by
+//                   --endian, row by row, and checks that this run's output
+//                   matches it bit for bit. The result is reported as PASS
+//                   or FAIL, with the number of elements that differ and
+//                   the first of them, and a FAIL counts as a failure to
+//                   verify the results, so the exit status is 1. A file
+//                   that can't be read, or doesn't hold exactly ny rows of
+//                   nx values, is an error. Both implementations need to
+//                   have had the same input, so this is usually used with
+//                   the standard input. It needs a mode whose results
+//                   correspond element by element with the input, and
+//                   can't be used with a sweep, or with any of the options
+//                   that replace the usual run.
+//    --emit-canonical PATH
+//                   after the timing, writes this run's output to PATH, as
+//                   raw f32 values in the byte order given by --endian, row
+//                   by row - the form --compare-with reads - so that the
+//                   output of one build can be kept and compared with that
+//                   of another (see Checking builds against each other,
+//                   above). The file is written whether or not the results
+//                   are correct. It has the same restrictions as
+//                   --compare-with, and can be combined with it.
+//    --endian E     the byte order of the values in any raw f32 file the
+//                   program reads or writes: 'little' (the default), 'big'
+//                   or 'native', which is whatever this machine uses. A
+//                   file written little or big endian reads back the same
+//                   on any machine, so the default only needs changing to
+//                   match a program that insists on big endian. Any byte
+//                   swapping is done as the file is written or read, never
+//                   while timing.
+//    --sysinfo      once everything has been run, reports the CPU model, the
+//                   numbers of logical and physical cores, and the operating
+//                   system (see crssysinfo.rs). This is never part of the
+//                   CSV results, and goes to standard error with CSV output.
+//    --freq-check   once everything has been run, looks at the Linux cpufreq
+//                   settings and warns if any CPU seems to be running above
+//                   its base frequency, if turbo boost is enabled, or if any
+//                   CPU isn't using the 'performance' governor - all of
+//                   which make timings hard to reproduce. It's advisory only,
+//                   and says so if there's no frequency information to look
+//                   at, as is usual in a virtual machine.
+//    --report-startup
+//                   once everything has been run, reports the time from
+//                   entering main() to the start of the first timed trial -
+//                   parsing the arguments, allocating and filling the
+//                   arrays, and the warmup - and what fraction that was of
+//                   the time the whole run took. For a tiny array that can
+//                   be most of it, which is worth knowing before timing the
+//                   whole program from outside, with time(1) or similar. The
+//                   time the system takes to load the program, before
+//                   main() is entered, isn't included.
+//    --budget-ms T  instead of making a fixed number of calls, each trial
+//                   calls the kernel for as long as it takes to use up T
+//                   milliseconds, then reports how many calls fitted in, and
+//                   the throughput. The repeat count is ignored. The clock
+//                   is read between batches of calls, not after each one.
+//    --auto-repeats sets the repeat count from the size of the array, so
+//                   that each trial makes about the same number of element
+//                   operations - calls times elements - whatever the size:
+//                   2G of them by default, which gives the default repeat
+//                   count for the default array. Any repeat count given is
+//                   ignored. In a sweep each configuration gets the count
+//                   for its own size, so a tiny array is called often
+//                   enough to time well, and a huge one doesn't take hours.
+//                   The count chosen is reported in the prose banner for
+//                   each configuration, and as the repeats of each result.
+//    --repeat-ops N the element operations a trial makes with
+//                   --auto-repeats, eg 10G - default 2G.
+//    --time-source S
+//                   the clock the trials are timed with: 'monotonic', the
+//                   default, the wall clock as it always has been, or
+//                   'cputime', the CPU time the process has used, read
+//                   with clock_gettime(CLOCK_PROCESS_CPUTIME_ID). CPU time
+//                   leaves out any time the process was descheduled while
+//                   something else ran, so comparing the two shows how much
+//                   the scheduler is getting in the way. It's only
+//                   available on Linux - anywhere else there's a warning,
+//                   and the monotonic clock is used. Any time budget is
+//                   measured on the same clock. The clock used is reported
+//                   in the banner and in the time_source field of the
+//                   results, and (see crsclock.rs) for a parallel mode CPU
+//                   time is that of all the threads together.
+//    --config PATH  reads a configuration from PATH, either written by
+//                   --export-config or by hand, as lines of 'name = value'
+//                   using the names listed in crsconfig.rs (which describes
+//                   the format), eg 'nx = 4000' or 'mode = "unsafe"'. A
+//                   name that isn't known gets a warning, and is ignored.
+//                   The file is read before anything else on the command
+//                   line, wherever it comes, so that any other options given
+//                   override what's in the file. An option that is just a
+//                   flag can only turn something on, not off. Any warmup
+//                   option replaces the file's warmup as a whole - eg
+//                   --warmup-seconds replaces a warmup count. The result is
+//                   checked just as if everything had been given on the
+//                   command line.
+//    --strict-args  makes any argument that can't be read an error, so the
+//                   program exits with status 2 (see Exit status) without
+//                   running anything. Without it, as in crsmain.rs, a value
+//                   that can't be read - eg '--repeats ten' - gets a warning
+//                   and the default is used instead, and so do the warnings
+//                   about a --config file. Other mistakes, such as an
+//                   unknown option, are always errors. Use this for scripted
+//                   runs, where a typo that quietly runs the default is worse
+//                   than no run at all.
+//    --interactive  instead of running once, starts a session that reads
+//                   commands from standard input - 'set cols 4000', 'mode
+//                   flat', 'run', 'compare unsafe index', and so on - so a
+//                   series of runs can be made without starting the program
+//                   again each time. The rest of the command line gives the
+//                   configuration the session starts with. 'set' takes any
+//                   option, as on the command line but without the '--',
+//                   and each run is exactly what the program would do with
+//                   the options set so far. In a session, arguments are
+//                   always parsed as --strict-args parses them, and a
+//                   mistake is reported and ignored. 'compare A B' checks
+//                   that the two modes give the same output, then times
+//                   both and shows the ratio of their best times. 'help'
+//                   lists the commands. The exit status is that of the last
+//                   run or comparison.
+//    --export-config PATH
+//                   writes the configuration, with everything resolved - the
+//                   defaults, the number of threads, the values read from any
+//                   --config file and the options given - to PATH, in the
+//                   form --config reads, and then carries on with the run.
+//                   Reading the file back with --config repeats the run
+//                   exactly, and it's the thing to pass on to anyone else
+//                   who wants to. It leaves out anything not set, and the
+//                   options that say where results go - --append, --sqlite,
+//                   --baseline and the like - so the repeat doesn't add to
+//                   or write over the results of the original run.
+//    --meta-file PATH
+//                   writes everything that isn't a result - the
+//                   configuration, the banner for each run, the informational
+//                   messages and the system information - to PATH as text,
+//                   so standard output only has the results. Warnings still
+//                   go to standard error. If PATH can't be written, that is
+//                   reported and the run carries on as if it wasn't given.
+//    --label TEXT   tags every result with TEXT - a machine name, a
+//                   compiler version, notes, anything but control
+//                   characters - so that results collated from many runs
+//                   say where they came from. The label is the first field
+//                   of the CSV, JSON and database output, quoted in the CSV
+//                   if it needs to be, and the prose banner starts with
+//                   it. It isn't part of the configuration, so a baseline
+//                   with a different label still matches.
+//    --append FILE  appends each result to FILE as a line of CSV, whatever
+//                   the output format, writing the CSV header first if the
+//                   file doesn't exist or is empty. A file that already has
+//                   a different header is left alone. If FILE can't be used
+//                   that is reported and the run carries on without it.
+//    --sqlite DB    writes each result as a row in the SQLite database DB,
+//                   along with the system details and the time of the run,
+//                   creating the database and its table if need be (see
+//                   crsdb.rs for the schema). Only available if the program
+//                   is built with the sqlite feature. As for --append, if DB
+//                   can't be used that is reported and the run carries on.
+//    --report-tcp HOST:PORT
+//                   connects to a collector listening on the given TCP port
+//                   and sends it each result as it's produced, as a line of
+//                   CSV, after a header line, whatever the output format -
+//                   so the results of a long sweep, or of runs on many
+//                   machines, can be watched as they arrive (see
+//                   crsreport.rs). If the connection can't be made, or is
+//                   lost, that is reported, and the run carries on with its
+//                   usual output.
+//    --list-modes   lists the modes, with their properties, and exits.
+//    --selftest     runs every available mode, with each element type its
+//                   kernel supports and the main variations on the flat
+//                   mode, once each on a tiny array, verifies each, and
+//                   checks that raw files round trip in each byte order
+//                   (using the temporary directory). The flat array modes
+//                   are also run through the Kernel trait, along with the
+//                   example kernel from crskernel.rs. Each mode is also
+//                   run on 20000 elements arranged as 100x200, 200x100,
+//                   1x20000 and 20000x1, to catch a kernel that mixes up
+//                   the dimensions, and on 25 random shapes of up to 64x64,
+//                   each with its own random input, the same for every
+//                   mode and every run. A random shape that fails is
+//                   listed with the command that runs it again. The
+//                   argument parser is also given 500 random command lines
+//                   - see --fuzz-args. It reports PASS or FAIL for each and
+//                   overall, and exits with status 1 if any of them fail.
+//                   It takes a few tens of ms, and is meant as a quick
+//                   check that a build is sound.
+//    --fuzz-args N  runs the argument parser on N random command lines made
+//                   from the options in the usage summary, values the
+//                   parser ought to find awkward - empty, negative, too big
+//                   with a suffix, not numbers at all, expressions nested
+//                   tens of thousands deep - and random strings. Each is
+//                   parsed both strictly and in the default way, which
+//                   replaces an invalid number with the default. It checks
+//                   that the parser never panics, that any configuration
+//                   it gives is one that builds again, and that a command
+//                   line the strict parsing accepts gives the same
+//                   configuration both ways. Case n is always the same
+//                   command line, and the self test and the unit tests
+//                   (rustc --test) run the first 500 of them, so this is
+//                   the way to fuzz for longer - 'crsbench --fuzz-args
+//                   100000' takes a few seconds. A case that
+//                   fails is listed with its number and its arguments, and
+//                   the exit status is 1 if any do. The options that exit,
+//                   or that read or write a file, are left out.
+//    --asm-report FILE
+//                   reads FILE as an assembly listing, as written by
+//                   'rustc -O --emit asm' for this program or for one of
+//                   the original test programs, lists each kernel that is
+//                   still a function of its own, with its size and the
+//                   sizes of its loops, along with any main program, and
+//                   then every call to a kernel - a call that wasn't
+//                   inlined. It exits with status 1 if there are any such
+//                   calls. See crsasm.rs.
+//    --help         prints a summary of the options, and exits.
+//    --count-ops    after the timing, runs the kernel once more using an
+//                   element type that counts every addition made with it
+//                   (see crsopcount.rs), and fails unless the count is
+//                   exactly nx * ny. This works where hardware counters
+//                   aren't available, but is slow, so is best used with a
+//                   small array. Only the modes that use a flat Array2D,
+//                   whose kernels are generic, can be counted.
+//    --verify-every K
+//                   after the timing, runs the kernel again, untimed, nrpt
+//                   times over on the same input and output arrays, checking
+//                   the output - and that the input hasn't changed - after
+//                   every K calls and after the last. It reports the first
+//                   call after which anything was wrong, and the result
+//                   then counts as incorrect. The usual check only sees the
+//                   output of the last call, and this is for tracking down
+//                   a kernel that only goes wrong after it has been called
+//                   a number of times, eg by leaving something behind in
+//                   the arrays that the next call trips over. None of it is
+//                   part of the timing, but it can take a while with a
+//                   small K and a large array.
+//    --row-access   after the timing, times nothing but getting at the first
+//                   element of each row, in_array[iy][0], for every row of a
+//                   vector of vectors, and reports the time per row. That is
+//                   pure row pointer chasing, with next to no arithmetic, so
+//                   it isolates the cost of the extra indirection through
+//                   the outer Vec. The same is timed for the flat Array2D,
+//                   where the row is found by calculation, for comparison.
+//                   This uses the same repeat and trial counts as the main
+//                   timing.
+//    --per-row-timing
+//                   after the timing, makes passes over a flat Array2D,
+//                   whatever the mode, timing each row separately, and
+//                   reports how the time per row is spread: the mean, the
+//                   standard deviation and the slowest rows. Rows that take
+//                   much longer than the rest can point to page or cache set
+//                   effects at particular offsets. Reading the clock for
+//                   every row adds its own overhead, so these times are
+//                   only for comparing rows with each other, never with the
+//                   main timing. It uses the same repeat and trial counts.
+//
+// Exit status:
+//    0  all went well.
+//    1  the results did not match the expected values.
+//    2  the command line could not be understood.
+//    3  a result was slower than its baseline by more than the threshold, or
+//       had no baseline result, or the baseline file could not be used.
+//    4  the run could not continue, eg because an array could not be
+//       allocated, or a configuration of a sweep could not be run.
+//
+// Checking builds against each other:
+//    Several of the kernels use unsafe code, and if any of it has undefined
+//    behaviour the optimiser is entitled to make the program do anything at
+//    all - which usually shows up as an optimised build giving different
+//    results from an unoptimised one. The timing can't tell, and the usual
+//    check only says whether the results are right for this build. To check
+//    that a release build gives exactly what a debug build does, build both,
+//    write the output of the debug build with --emit-canonical, then run
+//    the release build with the same options and --compare-with, eg:
+//
+//    rustc crsbench.rs -o crsbench_debug
+//    rustc -O -C target-cpu=native crsbench.rs -o crsbench
+//    ./crsbench_debug --mode unsafe --input random --repeats 1 --trials 1 \
+//                                          --emit-canonical unsafe_debug.f32
+//    ./crsbench --mode unsafe --input random --compare-with unsafe_debug.f32
+//
+//    and the same for the rawptr mode, the other one that isn't safe, and
+//    any others --list-modes shows. The comparison is bit for bit, and a
+//    FAIL means the two builds disagree, which for these kernels - which do
+//    nothing the optimiser is allowed to reorder - shouldn't happen. The
+//    random input is deterministic, given the seed, and exercises more
+//    values than the standard one. The debug build is slow, which is why
+//    it makes just the one call. The same array size has to be used for
+//    both, and is worth varying - odd sizes, and sizes that aren't a
+//    multiple of any vector width, are where the edge handling lives.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version, with CSV output and a baseline
+//                   regression check. agent.
+//    14th Oct 2026. Added the extprec feature, reporting errors against an
+//                   extended precision reference. agent.
+//    14th Oct 2026. Moved checking to crsverify.rs. Added --verify-both. agent.
+//    14th Oct 2026. Added --inner-repeat. Timing is now done by one generic
+//                   routine, time_kernel(), for all modes. agent.
+//    14th Oct 2026. Added the threads mode and --check-coverage. agent.
+//    14th Oct 2026. Added --sweep-2d. The work for each configuration is now
+//                   done by run_config(). agent.
+//    14th Oct 2026. The configuration is now a BenchConfig, set up using the
+//                   builder in crsconfig.rs. agent.
+//    14th Oct 2026. Added --count-ops. agent.
+//    14th Oct 2026. Added --input, --seed and --seed-per-trial. Input
+//                   arrays are now set up by crsinput.rs. agent.
+//    14th Oct 2026. Added the const mode. agent.
+//    14th Oct 2026. Added --diff-map. agent.
+//    14th Oct 2026. Added --loop-order. agent.
+//    14th Oct 2026. Added --sysinfo. agent.
+//    14th Oct 2026. Added the chunks mode. agent.
+//    14th Oct 2026. Added --budget-ms. Trials now record the number of
+//                   calls made as well as the time taken. agent.
+//    14th Oct 2026. Added --meta-file. agent.
+//    14th Oct 2026. Added the rawptr mode. agent.
+//    14th Oct 2026. Added --list-modes and --help. agent.
+//    14th Oct 2026. Added the gaussian input, with --mean and --stddev. agent.
+//    14th Oct 2026. Added --drift-check and --drift-pause-ms. agent.
+//    14th Oct 2026. Added --out-stride. agent.
+//    14th Oct 2026. Reports the arithmetic intensity of each kernel, and the
+//                   achieved throughputs. agent.
+//    14th Oct 2026. Added --equiv. agent.
+//    14th Oct 2026. Each mode now says how its results are checked. agent.
+//    14th Oct 2026. Added --warmup-seconds. agent.
+//    14th Oct 2026. Errors from the modules are now BenchErrors, and an
+//                   array that can't be allocated is reported rather than
+//                   aborting the program. agent.
+//    14th Oct 2026. Added --append. agent.
+//    14th Oct 2026. Added --freq-check. agent.
+//    14th Oct 2026. Added the morton mode. agent.
+//    14th Oct 2026. Added --peak-bandwidth. agent.
+//    14th Oct 2026. Added --selftest. agent.
+//    14th Oct 2026. Added the sqlite feature, and --sqlite. agent.
+//    14th Oct 2026. Added --dims and --nz, for 3D arrays. agent.
+//    14th Oct 2026. Added --row-access. agent.
+//    14th Oct 2026. Added --compare-types. agent.
+//    14th Oct 2026. Whole numbers can now have suffixes, eg 2k or 1M. agent.
+//    14th Oct 2026. Added the checked mode. agent.
+//    14th Oct 2026. Added --warmup-to-freq. agent.
+//    14th Oct 2026. Added --checksum-algo. agent.
+//    14th Oct 2026. Added --sweep-shard. agent.
+//    14th Oct 2026. Added --endian. Raw files are now little endian by
+//                   default, rather than in the native byte order. agent.
+//    14th Oct 2026. Added --fresh-alloc. agent.
+//    14th Oct 2026. Added --verify-every. agent.
+//    14th Oct 2026. Added run_benchmark(), which times anything that
+//                   implements the Kernel trait (see crskernel.rs). agent.
+//    14th Oct 2026. Added --find-cliff. agent.
+//    14th Oct 2026. Added --concurrent-arrays. agent.
+//    14th Oct 2026. Added --strict-fp. agent.
+//    14th Oct 2026. Added --config and --export-config. agent.
+//    14th Oct 2026. --config files now use 'name = value', and names that
+//                   aren't known only get a warning. agent.
+//    14th Oct 2026. Added the gather mode, and --perm-seed. agent.
+//    14th Oct 2026. Added --report-tcp. agent.
+//    14th Oct 2026. Added --hugepages. agent.
+//    14th Oct 2026. Added --precision. agent.
+//    14th Oct 2026. Added --per-row-timing. agent.
+//    14th Oct 2026. A sweep carries on past a configuration that fails. agent.
+//    14th Oct 2026. Added the danger_alias feature, and --danger-alias. agent.
+//    14th Oct 2026. Added the window mode, --window and --stride. agent.
+//    14th Oct 2026. The time per element is reported. agent.
+//    14th Oct 2026. Added --roofline. agent.
+//    14th Oct 2026. Added --verify-shuffle. agent.
+//    14th Oct 2026. The self test runs each mode on arrays of several
+//                   shapes. agent.
+//    14th Oct 2026. Added the jsonl format. agent.
+//    14th Oct 2026. Added --asm-report. agent.
+//    14th Oct 2026. Added the unrolled mode, --unroll and --compare-unroll.
+//                   agent.
+//    14th Oct 2026. Argument parsing returns a ConfigError, and added
+//                   --strict-args. agent.
+//    14th Oct 2026. Added --bench-construction. agent.
+//    14th Oct 2026. Added --interactive, and moved the running of a
+//                   configuration out of main() into run(). agent.
+//    14th Oct 2026. The mean time per call has a standard error and a 95%
+//                   confidence interval. agent.
+//    14th Oct 2026. Added --mlock. agent.
+//    14th Oct 2026. Added the simd4 and fma4 modes. agent.
+//    14th Oct 2026. Added the expr mode, and --expr. agent.
+//    14th Oct 2026. Added --compare-with. agent.
+//    14th Oct 2026. Added the trackalloc feature. agent.
+//    14th Oct 2026. Added --torture. agent.
+//    14th Oct 2026. Added --label. agent.
+//    14th Oct 2026. Added the binary mode. agent.
+//    14th Oct 2026. Added --time-source. agent.
+//    14th Oct 2026. Added the split mode. agent.
+//    14th Oct 2026. Added --report-startup. agent.
+//    14th Oct 2026. Added --check-conflict, --cache-line, --cache-assoc and
+//                   --cache-size. agent.
+//    14th Oct 2026. Added --protect-input. agent.
+//    14th Oct 2026. Added --compare-build, and the build details recorded
+//                   with the results. agent.
+//    14th Oct 2026. The self test also runs each mode on random shapes. agent.
+//    14th Oct 2026. Added --false-share-study. agent.
+//    14th Oct 2026. Added --svg-histogram. agent.
+//    14th Oct 2026. Added the affine mode. agent.
+//    14th Oct 2026. Added the copy mode. agent.
+//    14th Oct 2026. Added --trial-fresh-thread. agent.
+//    14th Oct 2026. Added --vs-memcpy. agent.
+//    14th Oct 2026. Added --fuzz-args, and a fuzz test of the argument
+//                   parser to --selftest. agent.
+//    14th Oct 2026. Added --order-study. agent.
+//    14th Oct 2026. Added --si-times, and a check of the scaling to
+//                   --selftest. agent.
+//    14th Oct 2026. Added the prefix mode. agent.
+//    14th Oct 2026. Added the fits feature, --fits and --fits-data, and a
+//                   FITS round trip to --selftest. agent.
+//    14th Oct 2026. Added --auto-repeats and --repeat-ops. agent.
+//    14th Oct 2026. Added --emit-canonical, and the procedure for checking
+//                   debug and release builds against each other. agent.
+//    14th Oct 2026. Added the regtranspose mode and the simd feature. agent.
+//    14th Oct 2026. --compare-types and --compare-unroll report the
+//                   geometric mean of the ratios. agent.
+//    14th Oct 2026. Added the prometheus format. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::hint::black_box;
+use std::io::{BufRead, Write};
+use std::process;
+use std::thread;
+use std::sync::Barrier;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "trackalloc")]
+mod crsalloc;
+pub mod crsarray;
+mod crsarray3d;
+mod crsasm;
+mod crsbuild;
+mod crschecksum;
+mod crsclock;
+pub mod crsconfig;
+mod crsconflict;
+mod crsconstruct;
+#[cfg(feature = "sqlite")]
+mod crsdb;
+pub mod crserror;
+mod crsexpr;
+#[cfg(feature = "fits")]
+mod crsfits;
+pub mod crskernel;
+mod crshuge;
+mod crsinput;
+mod crslock;
+mod crsopcount;
+#[cfg(feature = "extprec")]
+mod crsprec;
+mod crsprotect;
+pub mod crsreport;
+mod crssub;
+mod crssub1d;
+mod crssub_affine;
+#[cfg(feature = "danger_alias")]
+mod crssub_alias;
+mod crssub_binary;
+mod crssub3d;
+mod crssub_checked;
+mod crssub_chunks;
+mod crssub_const;
+mod crssub_copy;
+mod crssub_gather;
+mod crssub_interleaved;
+mod crssub_iter;
+mod crssub_lanes;
+mod crssub_morton;
+mod crssub_prefix;
+mod crssub_rawptr;
+mod crssub_regtranspose;
+mod crssub_split;
+mod crssub_strided;
+mod crssub_unrolled;
+mod crssub_window;
+mod crssub_threads;
+mod crsstats;
+mod crssub_unsafe;
+mod crssvg;
+mod crssysinfo;
+mod crsunits;
+mod crsverify;
+
+use crsarray::{Array2D, Element};
+use crsarray3d::Array3D;
+use crsopcount::CountedF32;
+use crsconfig::{BenchConfig, BenchConfigBuilder, Format, LoopOrder, Mode,
+                default_threads};
+use crshuge::HugePages;
+use crslock::MemoryLock;
+use crsprotect::ReadOnly;
+use crsclock::{Stopwatch, TimeSource};
+use crsinput::Input;
+use crsconfig::{DEFAULT_DIMS, DEFAULT_NRPT, DEFAULT_NX, DEFAULT_NY,
+                DEFAULT_NZ, DEFAULT_THRESHOLD, DEFAULT_TRIALS, DEFAULT_WARMUP};
+use crserror::{BenchError, ConfigError};
+use crsexpr::Expr;
+use crskernel::Kernel;
+use crsverify::Endian;
+use crsreport::{BenchResult, ResultStream};
+
+//  Exit status values.
+
+const EXIT_OK: i32 = 0;
+const EXIT_VERIFY_FAILED: i32 = 1;
+const EXIT_USAGE: i32 = 2;
+const EXIT_REGRESSION: i32 = 3;
+const EXIT_ERROR: i32 = 4;
+
+//  Reports an error that means the run can't continue, and exits.
+
+fn fail (error: &BenchError) -> ! {
+   eprintln!("{}",error);
+   process::exit(EXIT_ERROR);
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                           C o n f i g u r a t i o n
+
+//  The suffixes a whole number can have, and their multipliers. The SI
+//  prefixes are case sensitive, as they are in SI - 'm' would be milli.
+
+const SUFFIXES: [(&str,usize); 6] = [("Ki",1 << 10),("Mi",1 << 20),
+   ("Gi",1 << 30),("k",1000),("M",1000000),("G",1000000000)];
+
+//  Parses a whole number, which can have one of the SUFFIXES, returning
+//  None if it isn't valid - including if the multiplied value doesn't fit
+//  in a usize. The digits are checked first, because usize's from_str()
+//  would also accept a leading '+'.
+
+fn parse_count (value: &str) -> Option<usize> {
+   let (digits,multiplier) = match SUFFIXES.iter()
+                              .find(|&&(suffix,_)| value.ends_with(suffix)) {
+      Some(&(suffix,multiplier)) =>
+                              (&value[..value.len() - suffix.len()],multiplier),
+      None => (value,1),
+   };
+   if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+      return None;
+   }
+   digits.parse::<usize>().ok()?.checked_mul(multiplier)
+}
+
+//  Deals with a value for 'what' that can't be read. If the parsing is
+//  strict, that's an error. Otherwise, as in crsmain.rs, it produces a
+//  warning and the default value is used instead. The warnings can be
+//  turned off, using QUIET_WARNINGS, which the fuzz test does so as not to
+//  bury its report in them.
+
+static QUIET_WARNINGS: AtomicBool = AtomicBool::new(false);
+
+fn invalid<T: std::fmt::Display> (value: &str,what: &str,default: T,
+                                       strict: bool) -> Result<T,ConfigError> {
+   let error = ConfigError::Value { what: what.to_string(),
+                                                 value: value.to_string() };
+   if strict { return Err(error); }
+   if !QUIET_WARNINGS.load(Ordering::Relaxed) {
+      eprintln!("{}, using {}",error,default);
+   }
+   Ok(default)
+}
+
+//  Parses a number from the command line, dealing with an invalid one as
+//  invalid() does.
+
+fn parse_number (value: &str,what: &str,default: usize,strict: bool)
+                                               -> Result<usize,ConfigError> {
+   match parse_count(value) {
+      Some(number) => Ok(number),
+      None => invalid(value,what,default,strict),
+   }
+}
+
+//  Returns the value that follows the option at args[*iarg], stepping past
+//  it. An option that comes last is reported as unknown if it is, since
+//  that's the real problem, and otherwise as needing a value.
+
+fn option_value<'a> (args: &'a [String],iarg: &mut usize)
+                                               -> Result<&'a str,ConfigError> {
+   if *iarg + 1 >= args.len() {
+      let option = args[*iarg].as_str();
+      return Err(ConfigError::Usage(if known_option(option) {
+         format!("{} needs a value",option)
+      } else {
+         format!("Unknown option: {}",option)
+      }));
+   }
+   *iarg += 1;
+   Ok(args[*iarg].as_str())
+}
+
+//  The options that are only alternative names for others, and so aren't
+//  in the usage summary.
+
+const OPTION_ALIASES: [&str; 1] = ["--input-dist"];
+
+//  True if option is one of those in the usage summary, or an alias.
+
+fn known_option (option: &str) -> bool {
+   OPTION_ALIASES.contains(&option) || USAGE.lines()
+                .any(|line| line.split_whitespace().next() == Some(option))
+}
+
+//  Parses a floating point value from the command line, dealing with an
+//  invalid one in the same way as parse_number().
+
+fn parse_real (value: &str,what: &str,default: f64,strict: bool)
+                                                 -> Result<f64,ConfigError> {
+   match value.parse::<f64>() {
+      Ok(number) if number >= 0.0 => Ok(number),
+      _ => invalid(value,what,default,strict),
+   }
+}
+
+//  Parses a floating point value that can be negative, otherwise as for
+//  parse_real().
+
+fn parse_float (value: &str,what: &str,default: f64,strict: bool)
+                                                 -> Result<f64,ConfigError> {
+   match value.parse::<f64>() {
+      Ok(number) if number.is_finite() => Ok(number),
+      _ => invalid(value,what,default,strict),
+   }
+}
+
+//  Parses the offset for --danger-alias, a whole number that can be
+//  negative. Unlike the other numbers, one that can't be read is always an
+//  error, not a warning - this is not an option to guess a value for.
+
+fn parse_offset (value: &str) -> Result<isize,ConfigError> {
+   let (negative,digits) = match value.strip_prefix('-') {
+      Some(digits) => (true,digits),
+      None => (false,value),
+   };
+   match parse_count(digits) {
+      Some(number) if number <= isize::MAX as usize => {
+         Ok(if negative { -(number as isize) } else { number as isize })
+      }
+      _ => Err(ConfigError::Value { what: "--danger-alias offset".to_string(),
+                                                  value: value.to_string() }),
+   }
+}
+
+//  Reports a command line problem and exits.
+
+fn usage_error (message: &str) -> ! {
+   eprintln!("{}",message);
+   process::exit(EXIT_USAGE);
+}
+
+//  Works through the command line arguments, setting values in a builder
+//  for the configuration. Anything that isn't an option is taken to be one
+//  of the positional arguments irpt, ny and nx, in that order. If the
+//  configuration is to be exported, that's done once it has been built.
+//  With strict set, a value that can't be read, or a warning about the
+//  configuration file, is an error - otherwise it's a warning, and the
+//  default is used.
+
+fn parse_args (args: &[String],strict: bool)
+                                           -> Result<BenchConfig,ConfigError> {
+   let number = |value: &str,what: &str,default: usize|
+                                      parse_number(value,what,default,strict);
+   let real = |value: &str,what: &str,default: f64|
+                                        parse_real(value,what,default,strict);
+   let float = |value: &str,what: &str,default: f64|
+                                       parse_float(value,what,default,strict);
+
+   //  A configuration file is read first, wherever it comes on the command
+   //  line, so that everything else overrides it. The first warmup option
+   //  given replaces whatever warmup the file had.
+
+   let config_file = args.iter().position(|arg| arg == "--config")
+                                       .and_then(|iarg| args.get(iarg + 1));
+   let mut builder = match config_file {
+      Some(path) => match BenchConfigBuilder::from_file(path) {
+         Ok((builder,warnings)) => {
+            if strict && !warnings.is_empty() {
+               return Err(ConfigError::Usage(format!("{}: {}",path,
+                                                       warnings.join("; "))));
+            }
+            for warning in warnings {
+               eprintln!("Warning: {}",warning);
+            }
+            builder
+         }
+         Err(error) => fail(&error),
+      },
+      None => BenchConfig::builder(),
+   };
+   let mut warmup_replaced = config_file.is_none();
+   let mut export_path = None;
+   let mut positional = 0;
+   let mut iarg = 1;
+   while iarg < args.len() {
+      let arg = args[iarg].as_str();
+      let warmup_option = arg == "--warmup" || arg == "--warmup-seconds"
+                                                || arg == "--warmup-to-freq";
+      if warmup_option && !warmup_replaced {
+         builder = builder.reset_warmup();
+         warmup_replaced = true;
+      }
+      if arg == "--help" {
+         print_usage();
+         process::exit(EXIT_OK);
+      } else if arg == "--list-modes" {
+         list_modes();
+         process::exit(EXIT_OK);
+      } else if arg == "--selftest" {
+         process::exit(self_test());
+      } else if arg == "--fuzz-args" {
+         let value = option_value(args,&mut iarg)?;
+         process::exit(fuzz_args(number(value,"Fuzz cases",
+                                                   SELF_TEST_FUZZ_CASES)?));
+      } else if arg == "--asm-report" {
+         process::exit(asm_report(option_value(args,&mut iarg)?));
+      } else if arg == "--verify-both" {
+         builder = builder.verify_both(true);
+      } else if arg == "--verify-shuffle" {
+         builder = builder.verify_shuffle(true);
+      } else if arg == "--strict-args" || arg == "--interactive" {
+         //  Already seen by main(), before the parsing started.
+      } else if arg == "--strict-fp" {
+         builder = builder.strict_fp(true);
+      } else if arg == "--check-coverage" {
+         builder = builder.check_coverage(true);
+      } else if arg == "--check-conflict" {
+         builder = builder.check_conflict(true);
+      } else if arg == "--count-ops" {
+         builder = builder.count_ops(true);
+      } else if arg == "--row-access" {
+         builder = builder.row_access(true);
+      } else if arg == "--per-row-timing" {
+         builder = builder.per_row_timing(true);
+      } else if arg == "--compare-types" {
+         builder = builder.compare_types(true);
+      } else if arg == "--compare-unroll" {
+         builder = builder.compare_unroll(true);
+      } else if arg == "--false-share-study" {
+         builder = builder.false_share_study(true);
+      } else if arg == "--order-study" {
+         builder = builder.order_study(true);
+      } else if arg == "--bench-construction" {
+         builder = builder.bench_construction(true);
+      } else if arg == "--find-cliff" {
+         builder = builder.find_cliff(true);
+      } else if arg == "--torture" {
+         builder = builder.torture(true);
+      } else if arg == "--fresh-alloc" {
+         builder = builder.fresh_alloc(true);
+      } else if arg == "--roofline" {
+         builder = builder.roofline(true);
+      } else if arg == "--auto-repeats" {
+         builder = builder.auto_repeats(true);
+      } else if arg == "--vs-memcpy" {
+         builder = builder.vs_memcpy(true);
+      } else if arg == "--si-times" {
+         builder = builder.si_times(true);
+      } else if arg == "--hugepages" {
+         builder = builder.hugepages(true);
+      } else if arg == "--mlock" {
+         builder = builder.mlock(true);
+      } else if arg == "--protect-input" {
+         builder = builder.protect_input(true);
+      } else if arg == "--seed-per-trial" {
+         builder = builder.seed_per_trial(true);
+      } else if arg == "--fits-data" {
+         builder = builder.fits_data(true);
+      } else if arg == "--trial-fresh-thread" {
+         builder = builder.trial_fresh_thread(true);
+      } else if arg == "--sysinfo" {
+         builder = builder.sysinfo(true);
+      } else if arg == "--freq-check" {
+         builder = builder.freq_check(true);
+      } else if arg == "--report-startup" {
+         builder = builder.report_startup(true);
+      } else if arg == "--warmup-to-freq" {
+         builder = builder.warmup_to_freq(true);
+      } else if arg.starts_with("--") {
+         let value = option_value(args,&mut iarg)?;
+         builder = match arg {
+            "--repeats" => builder.nrpt(
+                                 number(value,"Repeats",DEFAULT_NRPT)?),
+            "--rows" => builder.ny(number(value,"Rows",DEFAULT_NY)?),
+            "--cols" => builder.nx(number(value,"Columns",DEFAULT_NX)?),
+            "--dims" => builder.dims(
+                                 number(value,"Dimensions",DEFAULT_DIMS)?),
+            "--nz" => builder.nz(number(value,"Planes",DEFAULT_NZ)?),
+            "--trials" => builder.trials(
+                                 number(value,"Trials",DEFAULT_TRIALS)?),
+            "--warmup" => builder.warmup(
+                                 number(value,"Warmup",DEFAULT_WARMUP)?),
+            "--warmup-seconds" => builder.warmup_seconds(
+                                 Some(real(value,"Warmup time",1.0)?)),
+            "--mode" => builder.mode(value),
+            "--format" => builder.format(value),
+            "--precision" => builder.precision(
+                                 Some(number(value,"Precision",3)?)),
+            "--input" | "--input-dist" => builder.input(value),
+            "--mean" => builder.mean(float(value,"Mean",
+                                                     crsinput::DEFAULT_MEAN)?),
+            "--stddev" => builder.stddev(float(value,"Standard deviation",
+                                                  crsinput::DEFAULT_STDDEV)?),
+            "--seed" => builder.seed(number(value,"Seed",
+                                    crsinput::DEFAULT_SEED as usize)? as u64),
+            "--fits" => builder.fits(value),
+            "--baseline" => builder.baseline(value),
+            "--compare-build" => builder.compare_build(value),
+            "--meta-file" => builder.meta_file(value),
+            "--append" => builder.append(value),
+            "--sqlite" => builder.sqlite(value),
+            "--report-tcp" => builder.report_tcp(value),
+            "--budget-ms" => builder.budget_ms(
+                  Some(number(value,"Budget",1000)? as u64)),
+            "--repeat-ops" => builder.repeat_ops(number(value,
+                  "Element operations",crsconfig::DEFAULT_REPEAT_OPS)?),
+            "--diff-map" => builder.diff_map(value),
+            "--svg-histogram" => builder.svg_histogram(value),
+            "--compare-with" => builder.compare_with(value),
+            "--emit-canonical" => builder.emit_canonical(value),
+            "--label" => builder.label(value),
+            "--endian" => builder.endian(value),
+            "--time-source" => builder.time_source(value),
+            "--cache-line" => builder.cache_line(number(value,"Cache line",
+                                             crsconfig::DEFAULT_CACHE_LINE)?),
+            "--cache-assoc" => builder.cache_assoc(number(value,"Cache ways",
+                                            crsconfig::DEFAULT_CACHE_ASSOC)?),
+            "--cache-size" => builder.cache_size(number(value,"Cache size",
+                                             crsconfig::DEFAULT_CACHE_SIZE)?),
+            "--loop-order" => builder.loop_order(value),
+            "--equiv" => match value.split_once(',') {
+               Some((name_a,name_b)) => builder.equiv(name_a,name_b),
+               None => return Err(ConfigError::Usage(
+                         "--equiv needs two modes, eg flat,index".to_string())),
+            },
+            "--out-stride" => builder.out_stride(
+                                 Some(number(value,"Output stride",1)?)),
+            "--window" => builder.window(
+                                     Some(number(value,"Window",1)?)),
+            "--stride" => builder.window_stride(
+                              Some(number(value,"Window stride",1)?)),
+            "--unroll" => builder.unroll(Some(number(value,
+                            "Unroll factor",crsconfig::DEFAULT_UNROLL)?)),
+            "--expr" => builder.expr(Some(value.to_string())),
+            "--perm-seed" => builder.perm_seed(
+                     Some(number(value,"Permutation seed",1)? as u64)),
+            "--regress-threshold" => builder.threshold(
+                   real(value,"Regression threshold",DEFAULT_THRESHOLD)?),
+            "--sweep-2d" => builder.sweep_2d(Some(parse_sweep_2d(value)?)),
+            "--sweep-shard" => builder.sweep_shard(
+                                           Some(parse_sweep_shard(value)?)),
+            "--threads" => builder.threads(
+                           number(value,"Threads",default_threads())?),
+            "--verify-every" => builder.verify_every(
+                                 Some(number(value,"Verify every",1)?)),
+            "--danger-alias" => builder.danger_alias(
+                                                Some(parse_offset(value)?)),
+            "--concurrent-arrays" => builder.concurrent_arrays(
+                            Some(number(value,"Concurrent arrays",1)?)),
+            "--drift-check" => builder.drift_check(
+                                 Some(number(value,"Drift check",2)?)),
+            "--drift-pause-ms" => builder.drift_pause_ms(
+                  number(value,"Drift pause",
+                         crsconfig::DEFAULT_DRIFT_PAUSE_MS as usize)? as u64),
+            "--inner-repeat" => builder.inner_repeat(
+                                 Some(number(value,"Inner repeat",1)?)),
+            "--peak-bandwidth" => builder.peak_bandwidth(
+                          Some(real(value,"Peak bandwidth",0.0)?)),
+            "--checksum-algo" => builder.checksum(value),
+            "--tolerance" => builder.tolerance(
+                   real(value,"Tolerance",crsverify::DEFAULT_TOLERANCE)?),
+            "--config" => builder,
+            "--export-config" => { export_path = Some(value); builder }
+            _ => return Err(ConfigError::Usage(
+                                       format!("Unknown option: {}",arg))),
+         };
+      } else {
+         builder = match positional {
+            0 => builder.nrpt(number(arg,"Repeats",DEFAULT_NRPT)?),
+            1 => builder.ny(number(arg,"Rows",DEFAULT_NY)?),
+            2 => builder.nx(number(arg,"Columns",DEFAULT_NX)?),
+            _ => return Err(ConfigError::Usage(
+                                    format!("Unexpected argument {}",arg))),
+         };
+         positional += 1;
+      }
+      iarg += 1;
+   }
+   let config = builder.build()?;
+   if let Some(path) = export_path {
+      if let Err(error) = config.write_file(path) { fail(&error); }
+   }
+   Ok(config)
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                                 H e l p
+
+//  A brief summary of the options. The full descriptions are in the comments
+//  at the top of this file.
+
+const USAGE: &str = "\
+Usage: crsbench [irpt [ny [nx]]] [options]
+
+   --repeats N         calls per trial (default 100000)
+   --rows N            rows in the array, ny (default 10)
+   --cols N            columns in the array, nx (default 2000)
+   --dims D            2 for a 2D array (the default), 3 for a 3D array
+   --nz N              planes in a 3D array, nz (default 10)
+   --mode M            the version of csub() to test (default index)
+   --trials N          timed trials (default 5)
+   --warmup N          untimed calls before the trials (default 0)
+   --warmup-seconds T  untimed calls for T seconds, instead of --warmup
+   --warmup-to-freq    untimed calls until the CPU frequency levels off
+   --format F          prose, csv, jsonl or prometheus (default prose)
+   --precision N       print every result with N decimal places
+   --si-times          show times scaled to ns, µs, ms or s, with the unit
+   --input I           standard, random or gaussian (default standard)
+   --mean M            mean for the gaussian input (default 0)
+   --stddev S          standard deviation for the gaussian input (default 1)
+   --seed N            seed for the random inputs (default 1)
+   --seed-per-trial    fresh random input for each trial
+   --fits FILE         take the dimensions from a FITS image (feature)
+   --fits-data         with --fits, use the image as the input
+   --trial-fresh-thread
+                       run each trial in a newly spawned thread
+   --baseline FILE     compare with the results in an earlier CSV file
+   --regress-threshold PCT
+                       slowdown that counts as a regression (default 5)
+   --compare-build FILE,FILE...
+                       compare the results of builds with different options
+   --verify-both       also check the results against the true sums
+   --verify-shuffle    recheck in a random order, reporting every mismatch
+   --tolerance REL     relative tolerance for --verify-both and --equiv
+   --strict-fp         check the output bit for bit against strict IEEE f32
+   --inner-repeat K    K passes per (never inlined) call
+   --budget-ms T       run each trial for T ms rather than a fixed count
+   --auto-repeats      set the repeats from the array size, for comparable
+                       trials across a sweep
+   --repeat-ops N      element operations a trial for --auto-repeats (2G)
+   --time-source S     time with the monotonic clock or cputime (Linux)
+   --threads N         threads for the parallel modes
+   --check-coverage    check each element is written once (parallel modes)
+   --check-conflict    warn if the arrays are likely to conflict in the cache
+   --cache-line N      bytes in a cache line, for the check (default 64)
+   --cache-assoc N     ways in each cache set, for the check (default 8)
+   --cache-size N      bytes in the cache, for the check (default 32768)
+   --count-ops         count the element additions (flat array modes)
+   --verify-every K    rerun untimed, checking the output every K calls
+   --row-access        time the row indirection on its own, nested and flat
+   --per-row-timing    time each row of a flat pass, to see the spread
+   --loop-order O      ij or ji (ji only for the flat mode)
+   --out-stride S      scatter the output with stride S (flat mode only)
+   --perm-seed N       shuffle the gather mode's permutation with seed N
+   --window W          rows in the window mode's window (default 4)
+   --stride S          rows the window mode's window moves on by (default 1)
+   --unroll N          the unrolled mode's unroll factor, 1, 2, 4 or 8 (4)
+   --expr EXPR         the expr mode's expression (default 'in + ix + iy')
+   --equiv A,B         check modes A and B give the same output, no timing
+   --compare-types     time f32, f64, i32 and i64 elements (flat array modes)
+   --compare-unroll    time flat and each unroll factor (--mode unrolled)
+   --false-share-study time aligned and misaligned chunks (--mode threads)
+   --order-study       time both loop orders over square sizes (--mode flat)
+   --bench-construction
+                       time the ways of building a flat or nested array
+   --find-cliff        find the working set where the last level cache runs out
+   --torture           check the mode at awkward sizes, primes and near 2^n
+   --concurrent-arrays N
+                       time N threads at once, each with its own arrays
+   --danger-alias OFFSET
+                       overlap the output with the input, to show the result
+                       is wrong (danger_alias feature, demonstration only)
+   --fresh-alloc       allocate a new output array for every call, and time it
+   --hugepages         ask for the arrays to be in huge pages (Linux)
+   --mlock             lock the arrays into memory once they are filled
+   --protect-input     make the input read-only while the kernel runs (Linux)
+   --peak-bandwidth GBS
+                       report bandwidth as a fraction of this peak (Gbyte/s)
+   --roofline          with --peak-bandwidth, report the bandwidth-bound time
+   --vs-memcpy         report the time as a multiple of a memcpy's
+   --checksum-algo A   report a sum, fnv or crc32 checksum of the output
+   --drift-check N     repeat everything N times, looking for a trend
+   --drift-pause-ms P  pause between drift check repetitions (default 1000)
+   --sweep-2d NXLISTxNYLIST
+                       run every combination of the listed dimensions
+   --sweep-shard K/N   run only shard K (0 to N-1) of N shards of the sweep
+   --diff-map PATH     write the error map here if the results are wrong
+   --svg-histogram PATH
+                       write a histogram of the trial times here, as SVG
+   --compare-with PATH check the output is bit for bit that in a raw f32 file
+   --emit-canonical PATH
+                       write the output as a raw f32 file, for --compare-with
+   --endian E          little, big or native, for raw files (default little)
+   --config PATH       start from the configuration in this file
+   --strict-args       make any argument that can't be read an error
+   --interactive       read commands - set, run, compare, help - from stdin
+   --export-config PATH
+                       write the configuration to this file, for --config
+   --meta-file PATH    write everything but the results here
+   --label TEXT        tag every result with this label, eg the machine
+   --append FILE       append the results to a CSV file, header if new
+   --sqlite DB         write the results to an SQLite database (feature)
+   --report-tcp HOST:PORT
+                       send each result as CSV to a collector over TCP
+   --sysinfo           report the CPU and OS at the end
+   --freq-check        warn about CPU boost and frequency governors
+   --report-startup    report the time taken before the first timed trial
+   --list-modes        list the modes, with their properties
+   --selftest          run and verify every variant on a tiny array
+   --fuzz-args N       check the argument parser on N random command lines
+   --asm-report FILE   report on the kernels in an assembly listing
+   --help              print this summary
+";
+
+//  Prints the usage summary, followed by the names of the modes.
+
+fn print_usage () {
+   print!("{}",USAGE);
+   let names: Vec<&str> = crsconfig::MODES.iter()
+            .filter(|info| info.available).map(|info| info.name).collect();
+   println!("\nModes: {}",names.join(", "));
+}
+
+//  Lists all the modes, with their properties, including any that aren't
+//  available because this build doesn't have a feature they need.
+
+fn list_modes () {
+   println!("{:<13}{:<8}{:<8}{:<10}{:<10}{:<11}{:<11}{:<13}description",
+           "mode","layout","safe","parallel","feature","available",
+           "intensity","check");
+   for info in crsconfig::MODES {
+      println!("{:<13}{:<8}{:<8}{:<10}{:<10}{:<11}{:<11.4}{:<13}{} ({})",
+               info.name,
+               if info.flat { "flat" } else { "nested" },
+               if info.safe { "yes" } else { "no" },
+               if info.parallel { "yes" } else { "no" },
+               info.feature.unwrap_or("-"),
+               if info.available { "yes" } else { "no" },
+               info.intensity(),info.check.name(),info.description,info.source);
+   }
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                               S w e e p s
+
+//  The largest number of configurations a sweep will run.
+
+const MAX_SWEEP_RUNS: usize = 1000;
+
+//  Parses a comma-separated list of dimensions.
+
+fn parse_list (value: &str,what: &str) -> Result<Vec<usize>,ConfigError> {
+   let mut list = Vec::new();
+   for item in value.split(',') {
+      match parse_count(item.trim()) {
+         Some(number) if number > 0 => list.push(number),
+         _ => return Err(ConfigError::Usage(format!(
+                                "Invalid {} '{}' in sweep",what,item))),
+      }
+   }
+   Ok(list)
+}
+
+//  Parses the value of --sweep-2d, which is a list of column counts and a
+//  list of row counts separated by an 'x', eg 500,1000,2000x10,20,40.
+
+fn parse_sweep_2d (value: &str)
+                              -> Result<(Vec<usize>,Vec<usize>),ConfigError> {
+   match value.find('x') {
+      Some(split) => Ok((parse_list(&value[..split],"column count")?,
+                         parse_list(&value[split + 1..],"row count")?)),
+      None => Err(ConfigError::Usage(format!(
+              "--sweep-2d needs column and row lists separated by 'x', not {}",
+                                                                    value))),
+   }
+}
+
+//  Parses the value of --sweep-shard, which is a shard number and a number
+//  of shards, separated by a '/', eg 2/4. Whether the shard number is in
+//  range is left to the configuration check.
+
+fn parse_sweep_shard (value: &str) -> Result<(usize,usize),ConfigError> {
+   let mut parts = value.splitn(2,'/');
+   let shard = parts.next().and_then(|part| part.trim().parse::<usize>().ok());
+   let shards = parts.next().and_then(|part| part.trim().parse::<usize>().ok());
+   match (shard,shards) {
+      (Some(shard),Some(shards)) if shards > 0 => Ok((shard,shards)),
+      _ => Err(ConfigError::Usage(format!(
+          "--sweep-shard needs a shard number and a (nonzero) number of \
+                                       shards, eg 0/4, not {}",value))),
+   }
+}
+
+//  Returns the configurations for a 2D sweep - every combination of the
+//  column and row counts, in order of increasing nx then increasing ny, with
+//  everything else as in the base configuration. If there are more than
+//  MAX_SWEEP_RUNS of them, only the first MAX_SWEEP_RUNS are used. With a
+//  shard K of N, only every Nth of those, starting with number K, is
+//  returned.
+
+fn sweep_configs (base: &BenchConfig,sweep: &(Vec<usize>,Vec<usize>))
+                                                        -> Vec<BenchConfig> {
+   let (ref nx_list,ref ny_list) = *sweep;
+   let total = nx_list.len() * ny_list.len();
+   if total > MAX_SWEEP_RUNS {
+      eprintln!("Warning: the sweep has {} configurations, only the first {} \
+                                     will be run",total,MAX_SWEEP_RUNS);
+   }
+   let mut configs = Vec::new();
+   for &nx in nx_list {
+      for &ny in ny_list {
+         match base.to_builder().nx(nx).ny(ny).sweep_2d(None)
+                                                 .sweep_shard(None).build() {
+            Ok(config) => configs.push(config),
+            Err(message) => usage_error(&message.to_string()),
+         }
+      }
+   }
+   configs.truncate(MAX_SWEEP_RUNS);
+   if let Some((shard,shards)) = base.sweep_shard {
+      let count = configs.len();
+      configs = configs.into_iter().skip(shard).step_by(shards).collect();
+      info(base,&format!("Sweep shard {}/{}: {} of the {} configurations",
+                                         shard,shards,configs.len(),count));
+      if configs.is_empty() {
+         eprintln!("Warning: sweep shard {}/{} has no configurations",
+                                                               shard,shards);
+      }
+   }
+   configs
+}
+
+//  The result recorded for a configuration of a sweep that couldn't be run,
+//  so that it still gets its line in the output. Only the configuration
+//  fields mean anything - a sweep is always of 2D arrays.
+
+fn failed_result (config: &BenchConfig) -> BenchResult {
+   BenchResult {
+      label: config.label.clone(),
+      mode: config.mode.name().to_string(),
+      ny: config.ny,
+      nx: config.nx,
+      nz: None,
+      repeats: config.nrpt,
+      trials: config.trials,
+      inner_repeat: config.inner_repeat,
+      threads: if config.mode.is_parallel() { config.threads } else { 1 },
+      input: config.input_label(),
+      seed: config.seed,
+      loop_order: config.loop_order.name().to_string(),
+      budget_ms: config.budget_ms,
+      out_stride: config.out_stride,
+      perm_seed: config.perm_seed,
+      window: config.window_shape().map(|shape| shape.0),
+      window_stride: config.window_shape().map(|shape| shape.1),
+      unroll: config.unroll_factor(),
+      expr: config.expression().map(|expr| expr.to_string()),
+      fresh_alloc: config.fresh_alloc,
+      hugepages: config.hugepages,
+      mlock: config.mlock,
+      time_source: config.time_source.effective().name().to_string(),
+      flops: config.flops(),
+      bytes: config.mode.info().bytes,
+      peak_bandwidth: config.peak_bandwidth,
+      calls: 0,
+      min_ns_per_call: f64::NAN,
+      mean_ns_per_call: f64::NAN,
+      sem_ns_per_call: None,
+      ci95_ns_per_call: None,
+      verified: false,
+      checksum_algo: None,
+      checksum: None,
+      failed: true,
+      precision: config.precision,
+   }
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                               T i m i n g
+
+//  Makes one timed 'call' of the kernel, which is inner_repeat passes over
+//  the array. This is deliberately never inlined, so there is a real call
+//  boundary and the call overhead can be measured, but the kernel itself
+//  can still be inlined into the loop here - unless it's a Kernel timed by
+//  run_benchmark(), which is a virtual call.
+
+#[inline(never)]
+fn invoke<F: FnMut()> (kernel: &mut F,inner_repeat: usize) {
+   for _irpt in 0..inner_repeat {
+      kernel();
+   }
+}
+
+//  The time taken by one trial, and the number of calls it made.
+
+#[derive(Clone, Copy, Debug)]
+struct Trial {
+   time: Duration,
+   calls: usize,
+}
+
+//  Calls the kernel repeatedly until the budget has been used up, returning
+//  the time actually taken and the number of calls made. Reading the clock
+//  after every call would swamp a fast kernel, so calls are made in batches
+//  and the clock is only read between batches. The batch size starts at one
+//  and doubles until a batch takes at least a thousandth of the budget, so
+//  the overrun at the end is never more than a small fraction of it.
+
+fn run_for_budget<F: FnMut()> (call: &mut F,budget: Duration,
+                                          source: TimeSource) -> Trial {
+   let start = Stopwatch::start(source);
+   let mut calls = 0;
+   let mut batch = 1;
+   loop {
+      let batch_start = Stopwatch::start(source);
+      for _irpt in 0..batch {
+         call();
+      }
+      calls += batch;
+      let elapsed = start.elapsed();
+      if elapsed >= budget {
+         return Trial { time: elapsed, calls };
+      }
+      if batch_start.elapsed() * 1000 < budget { batch *= 2; }
+   }
+}
+
+//  With --warmup-to-freq, the warmup calls are made in bursts of
+//  FREQ_BURST_MS milliseconds, with the CPU frequency read after each, until
+//  the last FREQ_PLATEAU readings agree to within FREQ_TOLERANCE, or for at
+//  most FREQ_TIMEOUT_SECS seconds. If there are no frequency readings, the
+//  warmup runs for FREQ_FALLBACK_SECS seconds instead, and a warning (just
+//  the one, however many configurations are run) says so.
+
+const FREQ_BURST_MS: u64 = 50;
+const FREQ_PLATEAU: usize = 4;
+const FREQ_TOLERANCE: f64 = 0.01;
+const FREQ_TIMEOUT_SECS: u64 = 10;
+const FREQ_FALLBACK_SECS: f64 = 1.0;
+
+static FREQ_WARNED: AtomicBool = AtomicBool::new(false);
+
+//  Makes the warmup calls to the kernel - either config.warmup of them, or
+//  as many as fit in config.warmup_seconds, in which case the number made
+//  is reported, or enough to bring the CPU up to a steady frequency. The
+//  clock is read after every call, but none of this is timed.
+
+fn warm_up<F: FnMut()> (config: &BenchConfig,call: &mut F) {
+   if config.warmup_to_freq {
+      warm_up_to_frequency(config,call);
+      return;
+   }
+   match config.warmup_seconds {
+      None => {
+         for _iwarm in 0..config.warmup {
+            call();
+         }
+      }
+      Some(seconds) => {
+         let (calls,elapsed) = call_for(call,Duration::from_secs_f64(seconds));
+         info(config,&format!("Warmup: {} calls in {:.3} seconds",calls,
+                                                      elapsed.as_secs_f64()));
+      }
+   }
+}
+
+//  Calls the kernel repeatedly for the given time, returning the number of
+//  calls made and the time they actually took.
+
+fn call_for<F: FnMut()> (call: &mut F,limit: Duration) -> (usize,Duration) {
+   let start = Instant::now();
+   let mut calls: usize = 0;
+   while start.elapsed() < limit {
+      call();
+      calls += 1;
+   }
+   (calls,start.elapsed())
+}
+
+//  Makes warmup calls until the CPU frequency levels off, and reports the
+//  frequency reached - or, if it never levels off, warns that it didn't.
+//  Falls back on a fixed warmup time if the frequency can't be read.
+
+fn warm_up_to_frequency<F: FnMut()> (config: &BenchConfig,call: &mut F) {
+   if crssysinfo::frequency_reading().is_none() {
+      if !FREQ_WARNED.swap(true,Ordering::Relaxed) {
+         eprintln!("Warning: No CPU frequency readings are available, so \
+                 the warmup runs for {:.1} seconds instead",FREQ_FALLBACK_SECS);
+      }
+      let (calls,elapsed) =
+                    call_for(call,Duration::from_secs_f64(FREQ_FALLBACK_SECS));
+      info(config,&format!("Warmup: {} calls in {:.3} seconds",calls,
+                                                      elapsed.as_secs_f64()));
+      return;
+   }
+   let timeout = Duration::from_secs(FREQ_TIMEOUT_SECS);
+   let start = Instant::now();
+   let mut calls: usize = 0;
+   let mut readings: Vec<u64> = Vec::new();
+   let mut last = None;
+   let mut steady = false;
+   while !steady && start.elapsed() < timeout {
+      calls += call_for(call,Duration::from_millis(FREQ_BURST_MS)).0;
+      let reading = match crssysinfo::frequency_reading() {
+         Some(reading) => reading,
+         None => break,
+      };
+      readings.push(reading.current_khz);
+      last = Some(reading);
+      if readings.len() >= FREQ_PLATEAU {
+         let recent = &readings[readings.len() - FREQ_PLATEAU..];
+         let lowest = *recent.iter().min().unwrap_or(&0) as f64;
+         let highest = *recent.iter().max().unwrap_or(&0) as f64;
+         steady = highest - lowest <= highest * FREQ_TOLERANCE;
+      }
+   }
+   let reached = match last {
+      Some(reading) => match reading.max_khz {
+         Some(max_khz) => format!("{} MHz, of a maximum {} MHz",
+                                    reading.current_khz / 1000,max_khz / 1000),
+         None => format!("{} MHz",reading.current_khz / 1000),
+      },
+      None => "an unknown frequency".to_string(),
+   };
+   let elapsed = start.elapsed().as_secs_f64();
+   if steady {
+      info(config,&format!("Warmup: {} calls in {:.3} seconds, levelling off \
+                                                 at {}",calls,elapsed,reached));
+   } else {
+      eprintln!("Warning: The CPU frequency hadn't levelled off after {} \
+                calls in {:.3} seconds - it was last {}",calls,elapsed,reached);
+   }
+}
+
+//  Makes the warmup calls to the kernel, then times config.trials sets of
+//  config.nrpt calls, returning the time taken by each set. Unless
+//  --inner-repeat was specified, the kernel is called directly, as in the
+//  other test programs, and will generally be inlined - though not a
+//  Kernel timed by run_benchmark(), which is a virtual call (see
+//  crskernel.rs). With --budget-ms, each trial makes as many calls as fit
+//  in the budget instead.
+
+//  When main() was entered, and when the first timed trial of the run
+//  started, for --report-startup.
+
+static MAIN_ENTERED: OnceLock<Instant> = OnceLock::new();
+static TIMING_STARTED: OnceLock<Instant> = OnceLock::new();
+
+fn time_kernel<F: FnMut()> (config: &BenchConfig,mut kernel: F) -> Vec<Trial> {
+   let mut trials = Vec::with_capacity(config.trials);
+   #[cfg(feature = "trackalloc")]
+   let started = crsalloc::kernel_started();
+   match config.inner_repeat {
+      None => {
+         warm_up(config,&mut kernel);
+         TIMING_STARTED.get_or_init(Instant::now);
+         for _itrial in 0..config.trials {
+            if let Some(budget) = config.budget_ms {
+               trials.push(run_for_budget(&mut kernel,
+                           Duration::from_millis(budget),config.time_source));
+            } else {
+               let start = Stopwatch::start(config.time_source);
+               for _irpt in 0..config.nrpt {
+                  kernel();
+               }
+               trials.push(Trial { time: start.elapsed(), calls: config.nrpt });
+            }
+         }
+      }
+      Some(inner_repeat) => {
+         warm_up(config,&mut || invoke(&mut kernel,inner_repeat));
+         TIMING_STARTED.get_or_init(Instant::now);
+         for _itrial in 0..config.trials {
+            if let Some(budget) = config.budget_ms {
+               trials.push(run_for_budget(
+                           &mut || invoke(&mut kernel,inner_repeat),
+                           Duration::from_millis(budget),config.time_source));
+            } else {
+               let start = Stopwatch::start(config.time_source);
+               for _irpt in 0..config.nrpt {
+                  invoke(&mut kernel,inner_repeat);
+               }
+               trials.push(Trial { time: start.elapsed(), calls: config.nrpt });
+            }
+         }
+      }
+   }
+   #[cfg(feature = "trackalloc")]
+   crsalloc::kernel_finished(started);
+   trials
+}
+
+//  Runs and times the selected version of csub(), returning the timing of
+//  each trial. The result of the last call is left in out_array. Modes
+//  that use vectors of vectors work on copies of the arrays in that form,
+//  made before the timing starts and copied back afterwards.
+
+fn run_trials (config: &BenchConfig,in_array: &Array2D<f32>,
+                                 out_array: &mut Array2D<f32>) -> Vec<Trial> {
+   let nx = config.nx;
+   let ny = config.ny;
+   if config.fresh_alloc {
+      return run_fresh_alloc_trials(config,in_array,out_array);
+   }
+   if let Some(trials) = run_generic_trials(config,in_array,out_array) {
+      return trials;
+   }
+
+   //  What's left are the modes that use vectors of vectors.
+
+   let in_nested: Vec<Vec<f32>> =
+                        (0..ny).map(|iy| in_array.row(iy).to_vec()).collect();
+   let mut out_nested = vec![vec![0.0f32; nx]; ny];
+   let times = match config.mode {
+      Mode::Unsafe => time_kernel(config,|| crssub_unsafe::csub(
+                                 black_box(&in_nested),nx,ny,&mut out_nested)),
+      Mode::Iter => time_kernel(config,|| crssub_iter::csub(
+                                 black_box(&in_nested),nx,ny,&mut out_nested)),
+      Mode::Checked => time_kernel(config,|| crssub_checked::csub_checked(
+                                 black_box(&in_nested),nx,ny,&mut out_nested)),
+      _ => time_kernel(config,|| crssub::csub(
+                                 black_box(&in_nested),nx,ny,&mut out_nested)),
+   };
+   for (iy,row) in out_nested.iter().enumerate() {
+      out_array.row_mut(iy).copy_from_slice(row);
+   }
+   times
+}
+
+//  Runs and times the selected version of csub() for any element type, as
+//  run_trials() does. Returns None, having done nothing, for the modes that
+//  use vectors of vectors, whose versions of csub() only work with f32.
+
+fn run_generic_trials<T: Element> (config: &BenchConfig,in_array: &Array2D<T>,
+                             out_array: &mut Array2D<T>) -> Option<Vec<Trial>> {
+   if !config.mode.info().flat { return None; }
+   let prepared = prepare(config);
+   with_kernel(config,&prepared,TimeKernel { config,in_array,out_array })
+}
+
+//  Runs the trials as run_trials() does, but allocates a new output array
+//  for each call, replacing - and so freeing - the one before, so the time
+//  includes the allocation. The allocation is zero filled, just as every
+//  other output array is. What's left in out_array at the end is the
+//  output of the last call.
+
+fn run_fresh_alloc_trials (config: &BenchConfig,in_array: &Array2D<f32>,
+                                 out_array: &mut Array2D<f32>) -> Vec<Trial> {
+   let (nx,ny) = (config.nx,config.ny);
+   let prepared = prepare(config);
+   time_kernel(config,|| {
+      let mut fresh = match Array2D::try_new(nx,ny,0.0f32) {
+         Ok(fresh) => fresh,
+         Err(error) => fail(&error),
+      };
+      call_prepared(config,black_box(in_array),&mut fresh,&prepared);
+      *out_array = fresh;
+   })
+}
+
+//  Runs the trials as run_trials() does, but refills the input array with
+//  fresh random values before each trial, outside the timing, using the
+//  seeds that follow on from config.seed. Any warmup calls are made before
+//  the first trial only. Returns the trial timings and the seeds used.
+
+fn run_seeded_trials (config: &BenchConfig,in_array: &mut Array2D<f32>,
+                        out_array: &mut Array2D<f32>) -> (Vec<Trial>,Vec<u64>) {
+   let mut trials = Vec::with_capacity(config.trials);
+   let mut seeds = Vec::with_capacity(config.trials);
+   for itrial in 0..config.trials {
+      let seed = crsinput::trial_seed(config.seed,itrial);
+      crsinput::fill(in_array,config.input,seed);
+      let trial_config = single_trial_config(config,itrial);
+      trials.extend(run_trials(&trial_config,in_array,out_array));
+      seeds.push(seed);
+   }
+   (trials,seeds)
+}
+
+//  Runs the trials as run_trials() does, but each in a thread of its own,
+//  spawned for the trial and joined before the next one is spawned. The
+//  threads are scoped, so they can borrow the arrays, and the output array
+//  is lent to one of them at a time. Any warmup calls are made before the
+//  first trial only.
+
+fn run_fresh_thread_trials (config: &BenchConfig,in_array: &Array2D<f32>,
+                                 out_array: &mut Array2D<f32>) -> Vec<Trial> {
+   let mut trials = Vec::with_capacity(config.trials);
+   for itrial in 0..config.trials {
+      let trial_config = single_trial_config(config,itrial);
+      let out = &mut *out_array;
+      trials.extend(thread::scope(|scope| {
+         scope.spawn(|| run_trials(&trial_config,in_array,out)).join()
+                   .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+      }));
+   }
+   trials
+}
+
+//  The configuration for trial itrial when the trials are run one at a
+//  time: a single trial, with no warmup after the first.
+
+fn single_trial_config (config: &BenchConfig,itrial: usize) -> BenchConfig {
+   let mut trial_builder = config.to_builder().trials(1);
+   if itrial > 0 {
+      trial_builder = trial_builder.warmup(0).warmup_seconds(None)
+                                                       .warmup_to_freq(false);
+   }
+   match trial_builder.build() {
+      Ok(trial_config) => trial_config,
+      Err(message) => usage_error(&message.to_string()),
+   }
+}
+
+//  Reports, for --trial-fresh-thread, the spread of the times per call of
+//  the trials run in fresh threads and of those run on the one thread: the
+//  standard deviation, that as a percentage of the mean, and the ratio of
+//  the variances. There's no spread to compare with fewer than two trials.
+
+fn report_fresh_threads (config: &BenchConfig,fresh: &[Trial],
+                                                 same_thread: &[Trial]) {
+   if fresh.len() < 2 || same_thread.len() < 2 { return; }
+   let spread = |trials: &[Trial]| {
+      let values = per_call_values(config,trials);
+      let std_dev = crsstats::std_dev(&values);
+      (std_dev,100.0 * std_dev / crsstats::mean(&values))
+   };
+   let (fresh_dev,fresh_percent) = spread(fresh);
+   let (same_dev,same_percent) = spread(same_thread);
+   let (time,percent) = (config.places(3),config.places(1));
+   let ratio = if same_dev > 0.0 {
+      format!("{:.*}",config.places(3),(fresh_dev / same_dev).powi(2))
+   } else {
+      "undefined".to_string()
+   };
+   info(config,&format!("Trial spread: standard deviation {} per call \
+      ({:.*}% of the mean) with a fresh thread for each trial, {} ({:.*}%) \
+      with every trial on the same thread - variance ratio {}",
+      config.time_text(fresh_dev,time),percent,fresh_percent,
+      config.time_text(same_dev,time),percent,same_percent,ratio));
+}
+
+//  Makes one untimed call of the selected version of csub() for any element
+//  type, leaving any strided output scattered. Returns false, having done
+//  nothing, for the modes that use vectors of vectors, since those versions
+//  of csub() only work with f32.
+
+fn call_generic<T: Element> (config: &BenchConfig,in_array: &Array2D<T>,
+                                          out_array: &mut Array2D<T>) -> bool {
+   call_prepared(config,in_array,out_array,&prepare(config))
+}
+
+//  Whatever a mode needs made before it can be called: the gather mode's
+//  permutation index, the binary mode's second input array, and the affine
+//  mode's gains and offsets. Every other mode has nothing here.
+
+struct Prepared<T: Element> {
+   perm: Vec<(u32,u32)>,
+   second: Array2D<T>,
+   calibration: crssub_affine::Calibration<T>,
+}
+
+//  Makes what the selected mode needs, for call_prepared().
+
+fn prepare<T: Element> (config: &BenchConfig) -> Prepared<T> {
+   let second = if config.mode == Mode::Binary {
+      crssub_binary::second_input(config.nx,config.ny)
+   } else {
+      Array2D::try_new(0,0,T::from_index(0))
+   };
+   let rows = if config.mode == Mode::Affine { config.ny } else { 0 };
+   Prepared { perm: gather_permutation(config),
+      second: match second {
+         Ok(second) => second,
+         Err(error) => fail(&error),
+      },
+      calibration: crssub_affine::calibration(rows) }
+}
+
+//  Makes a call as call_generic() does, but with what the mode needs
+//  already made, by prepare(), so that making it isn't repeated for every
+//  call.
+
+fn call_prepared<T: Element> (config: &BenchConfig,in_array: &Array2D<T>,
+               out_array: &mut Array2D<T>,prepared: &Prepared<T>) -> bool {
+   with_kernel(config,prepared,CallOnce { in_array,out_array }).is_some()
+}
+
+//  Something to be done with the kernel for the selected mode, passed to
+//  with_kernel(). The kernel arrives as a closure of its own type, so
+//  whatever calls it - the timing loop, in particular - is compiled for
+//  that one kernel, and can have the kernel inlined into it, just as if the
+//  kernel had been called directly.
+
+trait KernelUser<T: Element> {
+   type Output;
+   fn use_kernel<F: FnMut(&Array2D<T>,&mut Array2D<T>)> (self,kernel: F)
+                                                              -> Self::Output;
+}
+
+//  Calls the kernel once.
+
+struct CallOnce<'a,T: Element> {
+   in_array: &'a Array2D<T>,
+   out_array: &'a mut Array2D<T>,
+}
+
+impl<'a,T: Element> KernelUser<T> for CallOnce<'a,T> {
+   type Output = ();
+   fn use_kernel<F: FnMut(&Array2D<T>,&mut Array2D<T>)> (self,mut kernel: F) {
+      kernel(self.in_array,self.out_array);
+   }
+}
+
+//  Times the kernel, as time_kernel() does.
+
+struct TimeKernel<'a,T: Element> {
+   config: &'a BenchConfig,
+   in_array: &'a Array2D<T>,
+   out_array: &'a mut Array2D<T>,
+}
+
+impl<'a,T: Element> KernelUser<T> for TimeKernel<'a,T> {
+   type Output = Vec<Trial>;
+   fn use_kernel<F: FnMut(&Array2D<T>,&mut Array2D<T>)> (self,mut kernel: F)
+                                                                -> Vec<Trial> {
+      let TimeKernel { config,in_array,out_array } = self;
+      time_kernel(config,|| kernel(black_box(in_array),out_array))
+   }
+}
+
+//  Passes the kernel for the selected mode, as a closure, to the given
+//  KernelUser, and returns what that returns. This is the one match on the
+//  modes that use a flat Array2D. Returns None, having done nothing, for
+//  the modes that use vectors of vectors.
+
+fn with_kernel<T: Element,U: KernelUser<T>> (config: &BenchConfig,
+                   prepared: &Prepared<T>,user: U) -> Option<U::Output> {
+   Some(match config.mode {
+      Mode::Flat => match config.loop_order {
+         LoopOrder::Ij if config.out_stride.is_some() => {
+            let stride = config.out_stride.unwrap_or(1);
+            user.use_kernel(|input,output|
+                          crssub_strided::csub_strided(input,output,stride))
+         }
+         LoopOrder::Ij => user.use_kernel(|input,output|
+                                            crssub1d::csub1d(input,output)),
+         LoopOrder::Ji => user.use_kernel(|input,output|
+                                         crssub1d::csub1d_ji(input,output)),
+      },
+      Mode::Threads => user.use_kernel(|input,output|
+                  crssub_threads::csub_threads(input,output,config.threads)),
+      Mode::Split => user.use_kernel(|input,output|
+                      crssub_split::csub_split(input,output,config.threads)),
+      Mode::Const => user.use_kernel(|input,output|
+                                  crssub_const::csub_dispatch(input,output)),
+      Mode::Chunks => user.use_kernel(|input,output|
+                                  crssub_chunks::csub_chunks(input,output)),
+      Mode::RawPtr => user.use_kernel(|input,output|
+                                  crssub_rawptr::csub_rawptr(input,output)),
+      Mode::Morton => user.use_kernel(|input,output|
+                                  crssub_morton::csub_morton(input,output)),
+      Mode::Gather => user.use_kernel(|input,output|
+                     crssub_gather::csub_gather(input,output,&prepared.perm)),
+      Mode::Window => {
+         let (window,stride) = config.window_shape().unwrap_or((1,1));
+         user.use_kernel(|input,output|
+                      crssub_window::csub_window(input,output,window,stride))
+      }
+      Mode::Unrolled => {
+         let factor = config.unroll_factor().unwrap_or(1);
+         user.use_kernel(|input,output|
+                        crssub_unrolled::csub_unrolled(input,output,factor))
+      }
+      Mode::Simd4 => user.use_kernel(|input,output|
+                                    crssub_lanes::csub_simd4(input,output)),
+      Mode::Fma4 => user.use_kernel(|input,output|
+                                     crssub_lanes::csub_fma4(input,output)),
+      Mode::Expr => {
+         let expr = config.expression().unwrap_or(Expr::STANDARD);
+         user.use_kernel(|input,output|
+                                   crsexpr::csub_expr(input,output,&expr))
+      }
+      Mode::Binary => user.use_kernel(|input,output|
+                 crssub_binary::csub_binary(input,&prepared.second,output)),
+      Mode::RegTranspose => user.use_kernel(|input,output|
+                     crssub_regtranspose::csub_regtranspose(input,output)),
+      Mode::Affine => user.use_kernel(|input,output|
+             crssub_affine::csub_affine(input,&prepared.calibration,output)),
+      Mode::Copy => user.use_kernel(|input,output| {
+         crssub_copy::csub_copy(input,output);
+         black_box(&*output);
+      }),
+      Mode::Prefix => user.use_kernel(|input,output|
+                                  crssub_prefix::csub_prefix(input,output)),
+      Mode::Index | Mode::Unsafe | Mode::Checked | Mode::Iter => return None,
+   })
+}
+
+//  Returns the permutation index the gather mode reads its input through -
+//  shuffled, if a seed was given, or else the identity. Other modes don't
+//  use one, and get an empty index.
+
+fn gather_permutation (config: &BenchConfig) -> Vec<(u32,u32)> {
+   if config.mode != Mode::Gather { return Vec::new(); }
+   match config.perm_seed {
+      Some(seed) => crssub_gather::shuffled(config.nx,config.ny,seed),
+      None => crssub_gather::identity(config.nx,config.ny),
+   }
+}
+
+//  Makes one call of the selected version of csub(), using CountedF32
+//  elements, and returns the number of element additions it made. Returns
+//  None for the modes that use vectors of vectors.
+
+fn count_ops (config: &BenchConfig,in_array: &Array2D<f32>) -> Option<usize> {
+   let nx = config.nx;
+   let ny = config.ny;
+   let counted_in =
+               Array2D::from_fn(nx,ny,|ix,iy| CountedF32(in_array[[ix,iy]]));
+   let mut counted_out = Array2D::new(nx,ny,CountedF32(0.0));
+   crsopcount::reset();
+   if !call_generic(config,&counted_in,&mut counted_out) { return None; }
+   Some(crsopcount::additions())
+}
+
+//  Makes one untimed call of the selected version of csub() for the modes
+//  that use vectors of vectors.
+
+fn call_nested (mode: Mode,in_nested: &Vec<Vec<f32>>,nx: usize,ny: usize,
+                                              out_nested: &mut Vec<Vec<f32>>) {
+   match mode {
+      Mode::Unsafe => crssub_unsafe::csub(in_nested,nx,ny,out_nested),
+      Mode::Iter => crssub_iter::csub(in_nested,nx,ny,out_nested),
+      Mode::Checked => crssub_checked::csub_checked(in_nested,nx,ny,out_nested),
+      _ => crssub::csub(in_nested,nx,ny,out_nested),
+   }
+}
+
+//  Calls the selected version of csub() config.nrpt times, untimed, on the
+//  same input and output arrays, checking the output after every nth call
+//  and after the last, and checking that the input is still as it was. The
+//  output array starts out zeroed, as for the timed runs. Returns None if
+//  every check passed, or the number of the first call - counting from 1
+//  - after which something was wrong, and what it was.
+
+fn verify_every (config: &BenchConfig,in_array: &Array2D<f32>,every: usize)
+                     -> Result<Option<(usize,&'static str)>,BenchError> {
+   let (nx,ny) = (config.nx,config.ny);
+   let check = config.check();
+   let nested = !config.mode.info().flat;
+   let prepared = prepare(config);
+   let mut input = in_array.clone();
+   let mut out_array = Array2D::try_new(nx,ny,0.0f32)?;
+   let mut in_nested: Vec<Vec<f32>> = Vec::new();
+   let mut out_nested: Vec<Vec<f32>> = Vec::new();
+   if nested {
+      in_nested = (0..ny).map(|iy| in_array.row(iy).to_vec()).collect();
+      out_nested = vec![vec![0.0f32; nx]; ny];
+   }
+   for icall in 1..=config.nrpt {
+      if nested {
+         call_nested(config.mode,&in_nested,nx,ny,&mut out_nested);
+      } else {
+         call_prepared(config,&input,&mut out_array,&prepared);
+      }
+      if icall % every != 0 && icall != config.nrpt { continue; }
+      if nested {
+         for iy in 0..ny {
+            input.row_mut(iy).copy_from_slice(&in_nested[iy]);
+            out_array.row_mut(iy).copy_from_slice(&out_nested[iy]);
+         }
+      }
+      if input.as_slice() != in_array.as_slice() {
+         return Ok(Some((icall,"input")));
+      }
+      let verified = match config.out_stride {
+         Some(stride) => check.verify(in_array,
+                                 &crssub_strided::gather(&out_array,stride)),
+         None => check.verify(in_array,&out_array),
+      };
+      if !verified { return Ok(Some((icall,"output"))); }
+   }
+   Ok(None)
+}
+
+//  Set once writing to the meta file has failed, after which it isn't used.
+
+static META_FAILED: AtomicBool = AtomicBool::new(false);
+
+//  Appends a line to the meta file, if there is one and it's still usable,
+//  returning true if it was written. A failure is reported once, and the
+//  line then goes wherever it would have gone without a meta file.
+
+fn write_meta (config: &BenchConfig,text: &str) -> bool {
+   let path = match config.meta_file {
+      Some(ref path) if !META_FAILED.load(Ordering::Relaxed) => path,
+      _ => return false,
+   };
+   let written = OpenOptions::new().append(true).create(true).open(path)
+                                .and_then(|mut file| writeln!(file,"{}",text));
+   match written {
+      Ok(()) => true,
+      Err(error) => {
+         eprintln!("Warning: cannot write to meta file {}: {}",path,error);
+         META_FAILED.store(true,Ordering::Relaxed);
+         false
+      }
+   }
+}
+
+//  Prints a line of information for the user. This goes to the meta file,
+//  if there is one, and otherwise to standard output with the prose format,
+//  but to standard error with other formats so as not to get mixed up with
+//  the results.
+
+fn info (config: &BenchConfig,text: &str) {
+   if write_meta(config,text) { return; }
+   if config.format == Format::Prose {
+      println!("{}",text);
+   } else {
+      eprintln!("{}",text);
+   }
+}
+
+//  Prints a line of the banner that describes each configuration as it's
+//  run. Like info(), this goes to the meta file if there is one, but without
+//  a meta file it's only printed with the prose format.
+
+fn banner (config: &BenchConfig,text: &str) {
+   if write_meta(config,text) { return; }
+   if config.format == Format::Prose {
+      println!("{}",text);
+   }
+}
+
+//  Times getting at the first element of every row, config.nrpt times over,
+//  for a vector of vectors made from the input and for the input itself,
+//  and returns the minimum over the trials of the time per row access, in
+//  ns, for each. The values are combined using integer additions of their
+//  bits, which take a single cycle and don't depend on what the values
+//  are, so the time is almost all in finding the rows. black_box() stops
+//  the compiler from deciding that the rows can't have changed between
+//  passes, which would let it read them just once.
+
+fn time_row_access (config: &BenchConfig,in_array: &Array2D<f32>) -> (f64,f64) {
+   let ny = in_array.ny();
+   let in_nested: Vec<Vec<f32>> =
+                        (0..ny).map(|iy| in_array.row(iy).to_vec()).collect();
+   let accesses = (config.nrpt * ny).max(1) as f64;
+   let mut nested_ns = f64::INFINITY;
+   let mut flat_ns = f64::INFINITY;
+   for _itrial in 0..config.trials {
+      let mut bits = 0u32;
+      let start = Instant::now();
+      for _irpt in 0..config.nrpt {
+         let rows = black_box(&in_nested);
+         for row in rows.iter() {
+            bits = bits.wrapping_add(row[0].to_bits());
+         }
+      }
+      let elapsed = start.elapsed();
+      black_box(bits);
+      nested_ns = nested_ns.min(elapsed.as_secs_f64() * 1.0e9 / accesses);
+      let start = Instant::now();
+      for _irpt in 0..config.nrpt {
+         let array = black_box(in_array);
+         for iy in 0..ny {
+            bits = bits.wrapping_add(array[[0,iy]].to_bits());
+         }
+      }
+      let elapsed = start.elapsed();
+      black_box(bits);
+      flat_ns = flat_ns.min(elapsed.as_secs_f64() * 1.0e9 / accesses);
+   }
+   (nested_ns,flat_ns)
+}
+
+//  Used for --per-row-timing. Makes nrpt passes over a flat Array2D, doing
+//  the same as csub1d() but reading the clock either side of each row, and
+//  returns the minimum over the trials of the mean time for each row, in ns.
+//  The output goes into an array of its own, so the one checked for the
+//  main timing isn't touched.
+
+fn time_per_row (config: &BenchConfig,in_array: &Array2D<f32>)
+                                           -> Result<Vec<f64>,BenchError> {
+   let nx = in_array.nx();
+   let ny = in_array.ny();
+   let mut out_array = Array2D::try_new(nx,ny,0.0f32)?;
+   let mut row_ns = vec![f64::INFINITY; ny];
+   for _itrial in 0..config.trials {
+      let mut totals = vec![Duration::ZERO; ny];
+      for _irpt in 0..config.nrpt {
+         let input = black_box(in_array);
+         for iy in 0..ny {
+            let start = Instant::now();
+            for ix in 0..nx {
+               out_array[[ix,iy]] = input[[ix,iy]] + f32::from_index(ix + iy);
+            }
+            totals[iy] += start.elapsed();
+         }
+         black_box(&mut out_array);
+      }
+      let calls = config.nrpt.max(1) as f64;
+      for (ns,total) in row_ns.iter_mut().zip(&totals) {
+         *ns = ns.min(total.as_secs_f64() * 1.0e9 / calls);
+      }
+   }
+   Ok(row_ns)
+}
+
+//  Reports the spread of the per-row times from time_per_row(): the mean,
+//  the standard deviation across the rows, the fastest row, and the
+//  slowest few rows, each as a multiple of the mean.
+
+const SLOWEST_ROWS: usize = 5;
+
+fn report_per_row (config: &BenchConfig,row_ns: &[f64]) {
+   let rows = row_ns.len() as f64;
+   let mean = row_ns.iter().sum::<f64>() / rows;
+   let variance = row_ns.iter().map(|ns| (ns - mean) * (ns - mean))
+                                                        .sum::<f64>() / rows;
+   let stddev = variance.sqrt();
+   let mut order: Vec<usize> = (0..row_ns.len()).collect();
+   order.sort_by(|&a,&b| row_ns[b].total_cmp(&row_ns[a]));
+   let fastest = order[order.len() - 1];
+   let time = config.places(3);
+   let percent = config.places(1);
+   info(config,&format!("Per-row timing: mean {} per row over {} rows, \
+          std dev {} ({:.*}% of the mean), fastest row {} at {}",
+          config.time_text(mean,time),row_ns.len(),
+          config.time_text(stddev,time),percent,100.0 * stddev / mean,
+          fastest,config.time_text(row_ns[fastest],time)));
+   let slowest: Vec<String> = order.iter().take(SLOWEST_ROWS).map(|&iy| {
+      format!("{} at {} ({:.*} x mean)",iy,config.time_text(row_ns[iy],time),
+                                         config.places(2),row_ns[iy] / mean)
+   }).collect();
+   info(config,&format!("Per-row timing: slowest rows {}",
+                                                        slowest.join(", ")));
+   info(config,"Per-row timing: reading the clock for each row adds \
+                 overhead, so compare these rows with each other, not with \
+                 the main timing");
+}
+
+//  Reports what the window mode actually did in a call, for a minimum time
+//  per call of min_ns: how many windows it used and how many rows they
+//  processed, the working set - the rows of the input and output in the
+//  window - and the throughput for all the elements processed, which the
+//  usual per-pass figures understate when the windows overlap.
+
+fn report_window (config: &BenchConfig,window: usize,stride: usize,
+                                                                min_ns: f64) {
+   let (windows,rows) = crssub_window::window_counts(config.ny,window,stride);
+   let row_bytes = config.nx * config.mode.info().bytes;
+   info(config,&format!("Window: {} rows, moving on by {}, so each call uses \
+          {} windows and processes {} rows, {:.*} times the array",window,
+          stride,windows,rows,config.places(2),
+          rows as f64 / config.ny as f64));
+   info(config,&format!("Window: working set {} of the arrays' {}, {:.*} \
+          Gbyte/s for the elements actually processed",
+          bytes_text((window * row_bytes) as f64),
+          bytes_text((config.ny * row_bytes) as f64),config.places(3),
+          (rows * row_bytes) as f64 / min_ns));
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                             R u n   C o n f i g
+
+//  Allocates one of the arrays for run_config(), zero filled and with its
+//  storage aligned to align bytes, advising the system to put it in huge
+//  pages if they were asked for. They are given out as the array is
+//  filled, so that's when the advice has to be given.
+
+fn new_array (config: &BenchConfig,huge: &mut HugePages,align: usize)
+                                          -> Result<Array2D<f32>,BenchError> {
+   if config.hugepages {
+      Array2D::try_new_aligned(config.nx,config.ny,0.0f32,align,
+                                 |address,bytes| huge.advise(address,bytes))
+   } else {
+      Array2D::try_new_aligned(config.nx,config.ny,0.0f32,align,|_,_| {})
+   }
+}
+
+//  Copies the pixel data of the FITS image into the input array, for
+//  --fits-data. build() has already made the array the size of the image,
+//  but the file could have changed since.
+
+#[cfg(feature = "fits")]
+fn load_fits (config: &BenchConfig,in_array: &mut Array2D<f32>)
+                                                   -> Result<(),BenchError> {
+   let path = match config.fits {
+      Some(ref path) => path,
+      None => return Ok(()),
+   };
+   let (_,image) = crsfits::read_image(path)?;
+   if image.nx() != in_array.nx() || image.ny() != in_array.ny() {
+      return Err(BenchError::Format { path: path.clone(),
+         message: format!("now has {} rows of {} columns, not {} of {}",
+                     image.ny(),image.nx(),in_array.ny(),in_array.nx()) });
+   }
+   in_array.as_mut_slice().copy_from_slice(image.as_slice());
+   Ok(())
+}
+
+//  Without the fits feature, build() won't allow --fits-data.
+
+#[cfg(not(feature = "fits"))]
+fn load_fits (_config: &BenchConfig,_in_array: &mut Array2D<f32>)
+                                                   -> Result<(),BenchError> {
+   Ok(())
+}
+
+//  Works out whether the input and output arrays are likely to conflict in
+//  the cache, for --check-conflict, reporting what it finds and warning if
+//  they are.
+
+fn report_conflicts (config: &BenchConfig,in_array: &Array2D<f32>,
+                                                  out_array: &Array2D<f32>) {
+   let geometry = config.cache_geometry();
+   let in_base = in_array.as_slice().as_ptr() as usize;
+   let out_base = out_array.as_slice().as_ptr() as usize;
+   let row_bytes = in_array.nx() * std::mem::size_of::<f32>();
+   let conflicts = crsconflict::analyse(&geometry,in_base,out_base,
+                                                    row_bytes,in_array.ny());
+   info(config,&format!("Cache conflicts: {} byte lines, {} ways, {} sets, \
+      way span {} bytes; output starts {} bytes into a way span from the \
+      input; a column's {} lines fall in {} sets, at most {} in one",
+      geometry.line,geometry.assoc,geometry.sets(),geometry.way_span(),
+      conflicts.offset,conflicts.column_lines,conflicts.column_sets,
+                                                        conflicts.worst_set));
+   if conflicts.arrays_collide {
+      eprintln!("Warning: the input and output arrays start {} bytes apart, \
+         modulo the {} byte way span, so their corresponding elements map to \
+         the same cache sets",conflicts.offset,geometry.way_span());
+   }
+   if conflicts.columns_collide(&geometry) {
+      eprintln!("Warning: rows of {} bytes put the {} lines of a column in \
+         only {} of the {} cache sets, up to {} in one of {} ways, so going \
+         down a column will evict lines",row_bytes,conflicts.column_lines,
+         conflicts.column_sets,geometry.sets(),conflicts.worst_set,
+                                                             geometry.assoc);
+   }
+}
+
+//  Describes how much of the arrays ended up in huge pages, for --hugepages.
+
+fn huge_pages_text (huge: &HugePages) -> String {
+   if !huge.problems().is_empty() {
+      return "Huge pages: not used".to_string();
+   }
+   match huge.backing() {
+      None => "Huge pages: asked for, but /proc/self/smaps can't be read \
+                         to see whether any were obtained".to_string(),
+      Some(backing) => {
+         let mut text = format!("Huge pages: {} of the {} of the arrays is \
+              in {} pages",bytes_text(backing.huge_bytes as f64),
+              bytes_text(backing.total_bytes as f64),
+              bytes_text(backing.huge_page_size as f64));
+         if backing.huge_bytes < backing.total_bytes
+                                             && backing.base_page_size > 0 {
+            text.push_str(&format!(", the rest in {} pages",
+                                  bytes_text(backing.base_page_size as f64)));
+         }
+         text
+      }
+   }
+}
+
+//  Reports any allocations made while the kernel was being run, as counted
+//  by the tracking allocator. There should be none, except with a fresh
+//  output array for each call - or the threads that the parallel modes
+//  start - and where there are, the times include the cost of counting
+//  them.
+
+#[cfg(feature = "trackalloc")]
+fn report_kernel_allocations (config: &BenchConfig) {
+   match crsalloc::take_kernel_allocations() {
+      0 => info(config,"Allocation tracking: no allocations were made while \
+                                                 the kernel was being run"),
+      made => info(config,&format!("Allocation tracking: {} allocations \
+            were made while the kernel was being run, warmup included, and \
+            the times include counting them",made)),
+   }
+}
+
+//  Reports the allocation totals for the whole run, as counted by the
+//  tracking allocator.
+
+#[cfg(feature = "trackalloc")]
+fn report_allocations (config: &BenchConfig) {
+   let stats = crsalloc::stats();
+   info(config,&format!("Allocations: {} made, {} freed and {} reallocated, \
+         {} allocated in all and at most {} in use at once",stats.allocations,
+         stats.deallocations,stats.reallocations,
+         bytes_text(stats.bytes_allocated as f64),
+         bytes_text(stats.peak as f64)));
+}
+
+//  Reports the time from entering main() to the start of the first timed
+//  trial, and how much of the time the run has taken so far that was.
+
+fn report_startup (config: &BenchConfig) {
+   let entered = match MAIN_ENTERED.get() {
+      Some(&entered) => entered,
+      None => return,
+   };
+   let total_ms = entered.elapsed().as_secs_f64() * 1000.0;
+   match TIMING_STARTED.get() {
+      None => info(config,&format!("Startup: no trials were timed, in a \
+                                           run of {:.3} ms",total_ms)),
+      Some(&started) => {
+         let startup_ms =
+                  started.duration_since(entered).as_secs_f64() * 1000.0;
+         info(config,&format!("Startup: {:.3} ms from entering main() to \
+             the first timed trial, {:.1}% of the {:.3} ms run",startup_ms,
+             100.0 * startup_ms / total_ms,total_ms));
+      }
+   }
+}
+
+//  Set once the warning that process CPU time isn't available has been
+//  given, so it's only given once, however many configurations are run.
+
+static CPU_TIME_WARNED: AtomicBool = AtomicBool::new(false);
+
+//  Says which clock the trials are timed with, if it isn't the usual one,
+//  or warns if process CPU time was asked for and isn't available.
+
+fn report_time_source (config: &BenchConfig) {
+   let source = config.time_source.effective();
+   if source != config.time_source {
+      if !CPU_TIME_WARNED.swap(true,Ordering::Relaxed) {
+         eprintln!("Warning: Process CPU time can't be read on this system, \
+                              so the trials are timed with {} instead",
+                                                       source.description());
+      }
+   } else if source != TimeSource::Monotonic {
+      banner(config,&format!("Trials are timed with {}, which leaves out \
+                  any time the process wasn't running",source.description()));
+   }
+}
+
+//  Runs one configuration: sets up the arrays, runs and times the selected
+//  version of csub(), checks the results and, for the prose format, reports
+//  them. Returns the result, or an error if the arrays can't be allocated.
+
+fn run_config (config: &BenchConfig) -> Result<BenchResult,BenchError> {
+
+   if config.dims == 3 { return run_config_3d(config); }
+
+   let nx = config.nx;
+   let ny = config.ny;
+
+   let repeats = match config.budget_ms {
+      Some(budget) => format!("budget = {} ms",budget),
+      None if config.auto_repeats => format!("repeats = {} (automatic, for \
+         {} element operations a trial)",config.nrpt,config.repeat_ops),
+      None => format!("repeats = {}",config.nrpt),
+   };
+   if let Some(ref label) = config.label {
+      banner(config,&format!("Label: {}",label));
+   }
+   banner(config,&format!("Arrays have {} rows of {} columns, {}, mode = {}",
+                                          ny,nx,repeats,config.mode.name()));
+   if config.mode.is_parallel() {
+      banner(config,&format!("Using {} threads",config.threads));
+   }
+   if let Some(expr) = config.expression() {
+      banner(config,&format!("Each element is set to {}, interpreted - this \
+         is a flexibility mode, and the times aren't those of a compiled \
+                                                          kernel",expr));
+   }
+   if config.fresh_alloc {
+      banner(config,"Each call allocates a new output array, and the times \
+                                                  include the allocation");
+   }
+   if config.trial_fresh_thread {
+      banner(config,"Each trial runs in a newly spawned thread, and the \
+                     trials are run again on one thread for comparison");
+   }
+   if let Some(ref path) = config.fits {
+      let also = if config.fits_data { ", which is also the input" }
+                                                               else { "" };
+      banner(config,&format!("Dimensions are those of the FITS image in {}{}",
+                                                                path,also));
+   }
+   report_time_source(config);
+
+   //  Set up the arrays - by default with the same input values as the
+   //  other test programs - then run and time the selected version of
+   //  csub(). With a seed per trial the input is refilled for each trial,
+   //  and what's left in it at the end is the input for the last trial,
+   //  which is what the output needs to be checked against.
+
+   //  An input that's to be protected is page aligned and padded to whole
+   //  pages, so that all of it can be.
+
+   let mut huge = HugePages::new();
+   let align = std::mem::align_of::<f32>();
+   let in_align = if config.protect_input { crsprotect::page_size() }
+                  else { align };
+   let mut in_array = new_array(config,&mut huge,in_align)?;
+   crsinput::fill(&mut in_array,config.input,config.seed);
+   if config.fits_data {
+      load_fits(config,&mut in_array)?;
+   }
+   let mut out_array = new_array(config,&mut huge,align)?;
+   if config.hugepages {
+      for problem in huge.problems() {
+         eprintln!("Warning: {} - the usual pages will be used",problem);
+      }
+   }
+   if config.check_conflict {
+      report_conflicts(config,&in_array,&out_array);
+   }
+
+   //  Any lock is taken once the arrays have been filled, and is released
+   //  when it's dropped, which - since it's declared after them - happens
+   //  before the arrays themselves are dropped.
+
+   let mut lock = MemoryLock::new();
+   if config.mlock {
+      for array in &[&in_array,&out_array] {
+         let bytes = std::mem::size_of_val(array.as_slice());
+         lock.lock(array.as_slice().as_ptr() as *const u8,bytes);
+      }
+      for problem in lock.problems() {
+         eprintln!("Warning: {} - the arrays won't be locked",problem);
+      }
+      if lock.problems().is_empty() {
+         info(config,&format!("Memory lock: {} of arrays locked into memory",
+                                    bytes_text(lock.locked_bytes() as f64)));
+      }
+   }
+
+   //  Protecting the input works in the same way, and is released as soon
+   //  as the trials are over.
+
+   let mut read_only = ReadOnly::new();
+   if config.protect_input {
+      let bytes = std::mem::size_of_val(in_array.as_slice());
+      read_only.protect(in_array.as_slice().as_ptr() as *const u8,
+                                                   in_array.storage_bytes());
+      for problem in read_only.problems() {
+         eprintln!("Warning: {} - the input won't be protected",problem);
+      }
+      if read_only.problems().is_empty() {
+         info(config,&format!("Input protection: the {} input, in {} of \
+            whole pages, is read-only while the kernel runs",
+            bytes_text(bytes as f64),
+                             bytes_text(read_only.protected_bytes() as f64)));
+      }
+   }
+
+   let trials = if config.seed_per_trial {
+      let (trials,seeds) =
+                        run_seeded_trials(config,&mut in_array,&mut out_array);
+      let seed_list: Vec<String> = seeds.iter().map(|seed| seed.to_string())
+                                                                     .collect();
+      info(config,&format!("Input {}, trial seeds {}",config.input.describe(),
+                                                        seed_list.join(",")));
+      trials
+   } else {
+      if config.input.is_random() {
+         info(config,&format!("Input {}, seed {}",config.input.describe(),
+                                                                config.seed));
+      }
+      if config.trial_fresh_thread {
+         let trials = run_fresh_thread_trials(config,&in_array,&mut out_array);
+         let same_thread = run_trials(config,&in_array,&mut out_array);
+         report_fresh_threads(config,&trials,&same_thread);
+         trials
+      } else {
+         run_trials(config,&in_array,&mut out_array)
+      }
+   };
+   read_only.release();
+   if config.hugepages {
+      info(config,&huge_pages_text(&huge));
+   }
+   #[cfg(feature = "trackalloc")]
+   report_kernel_allocations(config);
+
+   //  With a strided output the results are scattered through the output
+   //  array, and need to be put back in their proper places before any of
+   //  the checks can be made.
+
+   if let Some(stride) = config.out_stride {
+      out_array = crssub_strided::gather(&out_array,stride);
+   }
+
+   //  Each mode says how its results are to be checked. The element-wise
+   //  diagnostics only make sense for kernels that are checked that way.
+
+   let check = config.check();
+   let elementwise = check.is_elementwise();
+   let mut verified = check.verify(&in_array,&out_array);
+
+   //  The strict check is bitwise, against the correctly rounded results,
+   //  so it also catches a wrong sign on a zero or a different NaN.
+
+   if config.strict_fp && !elementwise {
+      eprintln!("--strict-fp only applies to kernels checked element by \
+                                                                  element");
+   } else if config.strict_fp {
+      let reference = crsverify::strict_reference(&in_array)?;
+      let comparison = crsverify::compare_outputs(&out_array,&reference,0.0);
+      match comparison.first_difference {
+         None => info(config,"Strict floating point check: bit for bit the \
+                                         correctly rounded IEEE results"),
+         Some((ix,iy)) => {
+            eprintln!("Strict floating point check failed: {} elements \
+                differ from the correctly rounded IEEE results, first at \
+                ({},{}), {:e} rather than {:e}",comparison.differences,ix,iy,
+                out_array[[ix,iy]],reference[[ix,iy]]);
+            verified = false;
+         }
+      }
+   } else if config.mode == Mode::Fma4 {
+
+      //  The fma4 kernel is there to see what contraction into FMA does, so
+      //  it always reports how its results compare with the strict ones -
+      //  but for information, since a difference isn't an error unless
+      //  --strict-fp asked for none.
+
+      let reference = crsverify::strict_reference(&in_array)?;
+      let comparison = crsverify::compare_outputs(&out_array,&reference,0.0);
+      match comparison.first_difference {
+         None => info(config,"FMA contraction check: bit for bit the \
+                                   strict scalar reference results"),
+         Some((ix,iy)) => eprintln!("FMA contraction check: {} elements \
+                differ from the strict scalar reference results, first at \
+                ({},{}), {:e} rather than {:e}",comparison.differences,ix,iy,
+                out_array[[ix,iy]],reference[[ix,iy]]),
+      }
+   }
+
+   //  The output can be written out, for comparison with another build's
+   //  using --compare-with. Not being able to write it is an error, since
+   //  the comparison can't be made without it.
+
+   if let Some(ref path) = config.emit_canonical {
+      crsverify::write_raw(path,&out_array,config.endian)?;
+      info(config,&format!("Canonical output ({} rows of {} columns, {} \
+                   endian) written to {}",ny,nx,config.endian.name(),path));
+   }
+
+   //  The output can be compared with another implementation's. That
+   //  does count as part of the verification, since the point of it is to
+   //  back up a claim that the two give the same results.
+
+   if let Some(ref path) = config.compare_with {
+      let other = crsverify::read_raw(path,nx,ny,config.endian)?;
+      let comparison = crsverify::compare_outputs(&out_array,&other,0.0);
+      match comparison.first_difference {
+         None => info(config,&format!("Comparison with {}: PASS, bit for bit \
+                                                         identical",path)),
+         Some((ix,iy)) => {
+            eprintln!("Comparison with {}: FAIL, {} of {} elements differ, \
+                first at ({},{}), {:e} here rather than {:e}",path,
+                comparison.differences,nx * ny,ix,iy,out_array[[ix,iy]],
+                other[[ix,iy]]);
+            verified = false;
+         }
+      }
+   }
+
+   //  If the values are wrong, a map of the errors can be written out.
+
+   if !verified && elementwise {
+      if let Some(ref path) = config.diff_map {
+         let map = crsverify::diff_map(&in_array,&out_array);
+         match crsverify::write_raw(path,&map,config.endian) {
+            Ok(()) => eprintln!("Difference map ({} rows of {} columns) \
+                                                written to {}",ny,nx,path),
+            Err(message) => eprintln!("{}",message),
+         }
+      }
+   }
+
+   //  The coverage check is a separate, untimed, run of the instrumented
+   //  version of the parallel kernel. A failure counts as a failure to
+   //  verify the results.
+
+   if config.check_coverage {
+      if config.mode.is_parallel() {
+         let counters: Vec<AtomicU32> =
+                              (0..nx * ny).map(|_| AtomicU32::new(0)).collect();
+         let mut scratch = Array2D::try_new(nx,ny,0.0f32)?;
+         if config.mode == Mode::Split {
+            crssub_split::csub_split_counted(&in_array,&mut scratch,
+                                                     config.threads,&counters);
+         } else {
+            crssub_threads::csub_threads_counted(&in_array,&mut scratch,
+                                                     config.threads,&counters);
+         }
+         let check = crsverify::check_coverage(&counters,nx);
+         match check.first_bad {
+            None => info(config,&format!(
+                 "Coverage check: every element written exactly once by {} \
+                                                   threads",config.threads)),
+            Some((ix,iy,count)) => {
+               eprintln!("Coverage check failed: {} elements missed, {} \
+                   written more than once, first at ({},{}) written {} times",
+                   check.missed,check.repeated,ix,iy,count);
+            }
+         }
+         if !check.passed() { verified = false; }
+      } else {
+         eprintln!("--check-coverage only applies to the parallel modes");
+      }
+   }
+
+   //  The swapped loop order must give exactly the same results as the
+   //  usual order - all that should change is the time taken.
+
+   if config.loop_order == LoopOrder::Ji {
+      let mut ij_out = Array2D::try_new(nx,ny,0.0f32)?;
+      crssub1d::csub1d(&in_array,&mut ij_out);
+      if ij_out.as_slice() == out_array.as_slice() {
+         info(config,"Loop order ji matches loop order ij");
+      } else {
+         eprintln!("Loop order ji does not match loop order ij");
+         verified = false;
+      }
+   }
+
+   //  The const mode only uses a specialised kernel for some column counts.
+   //  When it does, its results must be identical to those of the dynamic
+   //  version, not just correct.
+
+   if config.mode == Mode::Const {
+      if crssub_const::is_specialised(nx) {
+         let mut dynamic_out = Array2D::try_new(nx,ny,0.0f32)?;
+         crssub1d::csub1d(&in_array,&mut dynamic_out);
+         if dynamic_out.as_slice() == out_array.as_slice() {
+            info(config,&format!("Specialised kernel for nx = {} matches \
+                                              the dynamic version",nx));
+         } else {
+            eprintln!("Specialised kernel for nx = {} does not match the \
+                                              dynamic version",nx);
+            verified = false;
+         }
+      } else {
+         info(config,&format!("No specialised kernel for nx = {}, the \
+                                         dynamic version was used",nx));
+      }
+   }
+
+   //  The chunks mode handles any elements left over at the end of a row
+   //  separately, and that code isn't used at all if nx is a multiple of
+   //  the chunk size. So it's always checked on a small array that does
+   //  have a remainder, as well as on the main array.
+
+   if config.mode == Mode::Chunks {
+      let (small_nx,small_ny) = (2 * crssub_chunks::CHUNK + 3,3);
+      let small_in =
+                  crsinput::make_input(small_nx,small_ny,Input::Standard,0)?;
+      let mut small_out = Array2D::new(small_nx,small_ny,0.0f32);
+      crssub_chunks::csub_chunks(&small_in,&mut small_out);
+      if crsverify::verify(&small_in,&small_out) {
+         info(config,&format!("Chunk remainder check on {} x {}: correct",
+                                                         small_nx,small_ny));
+      } else {
+         eprintln!("Chunk remainder check on {} x {}: INCORRECT",
+                                                         small_nx,small_ny);
+         verified = false;
+      }
+   }
+
+   //  The operation count is another separate, untimed, run, this time of
+   //  the generic kernel instantiated for the counting element type. Again,
+   //  a failure counts as a failure to verify the results.
+
+   if config.count_ops {
+      match count_ops(config,&in_array) {
+         Some(additions) => {
+            let per_element = match config.expression() {
+               Some(expr) => expr.additions(),
+               None if config.mode == Mode::Binary => crssub_binary::ADDITIONS,
+               None if config.mode == Mode::Affine => crssub_affine::ADDITIONS,
+               None if config.mode == Mode::Copy => 0,
+               None if config.mode == Mode::Prefix => crssub_prefix::ADDITIONS,
+               None => 1,
+            };
+            let expected = nx * ny * per_element;
+            if additions == expected {
+               info(config,&format!("Operation count: {} element additions, \
+                                     as expected for {} x {}",additions,nx,ny));
+            } else {
+               eprintln!("Operation count failed: {} element additions, \
+                                 expected {}",additions,expected);
+               verified = false;
+            }
+         }
+         None => eprintln!("--count-ops only applies to the modes that use \
+                                                        a flat Array2D"),
+      }
+   }
+
+   //  The incremental check reruns the kernel, and only reports on the
+   //  checks either side of the first failure.
+
+   if let Some(every) = config.verify_every {
+      match verify_every(config,&in_array,every)? {
+         None => info(config,&format!("Incremental check: correct after \
+                every {} calls, and after the last of {}",every,config.nrpt)),
+         Some((icall,what)) => {
+            let last_good = (icall - 1) / every * every;
+            let since = if last_good == 0 { "no earlier check".to_string() }
+                        else { format!("correct after call {}",last_good) };
+            eprintln!("Incremental check failed: the {} was wrong after \
+                                          call {} ({})",what,icall,since);
+            verified = false;
+         }
+      }
+   }
+
+   //  The row access timing is purely informational.
+
+   if config.row_access {
+      let (nested_ns,flat_ns) = time_row_access(config,&in_array);
+      let time = config.places(3);
+      info(config,&format!("Row access: min {} per row for vectors of \
+             vectors, {} per row for a flat Array2D, over {} rows",
+             config.time_text(nested_ns,time),config.time_text(flat_ns,time),
+             ny));
+   }
+
+   //  So is the per-row timing - the clock reads make it slower.
+
+   if config.per_row_timing {
+      let row_ns = time_per_row(config,&in_array)?;
+      report_per_row(config,&row_ns);
+   }
+
+   let (calls,min_ns,mean_ns) = per_call_times(config,&trials);
+   let (sem_ns,ci95_ns) = per_call_uncertainty(config,&trials);
+   if let Some(ref path) = config.svg_histogram {
+      write_histogram(config,&trials,path);
+   }
+   if let Some((window,stride)) = config.window_shape() {
+      report_window(config,window,stride,min_ns);
+   }
+   let (checksum_algo,checksum) = checksum_of(config,out_array.as_slice());
+   let result = BenchResult {
+      label: config.label.clone(),
+      mode: config.mode.name().to_string(),
+      ny,
+      nx,
+      nz: None,
+      repeats: config.nrpt,
+      trials: config.trials,
+      inner_repeat: config.inner_repeat,
+      threads: if config.mode.is_parallel() { config.threads } else { 1 },
+      input: config.input_label(),
+      seed: config.seed,
+      loop_order: config.loop_order.name().to_string(),
+      budget_ms: config.budget_ms,
+      out_stride: config.out_stride,
+      perm_seed: config.perm_seed,
+      window: config.window_shape().map(|shape| shape.0),
+      window_stride: config.window_shape().map(|shape| shape.1),
+      unroll: config.unroll_factor(),
+      expr: config.expression().map(|expr| expr.to_string()),
+      fresh_alloc: config.fresh_alloc,
+      hugepages: config.hugepages,
+      mlock: config.mlock,
+      time_source: config.time_source.effective().name().to_string(),
+      flops: config.flops(),
+      bytes: config.mode.info().bytes,
+      peak_bandwidth: config.peak_bandwidth,
+      calls,
+      min_ns_per_call: min_ns,
+      mean_ns_per_call: mean_ns,
+      sem_ns_per_call: sem_ns,
+      ci95_ns_per_call: ci95_ns,
+      verified,
+      checksum_algo,
+      checksum,
+      failed: false,
+      precision: config.precision,
+   };
+
+   if config.format == Format::Prose {
+      report_times(config,&result,&trials);
+      println!("Results {}",if verified { "correct" } else { "INCORRECT" });
+      report_checksum(&result);
+      #[cfg(feature = "extprec")]
+      if elementwise {
+         let report = crsprec::error_report(&in_array,&out_array);
+         println!("Compared with the extended precision reference: {} \
+                   elements inexact, max error {:e} at ({},{}), max \
+                   relative error {:e}",report.inexact,report.max_abs_error,
+                   report.worst_ix,report.worst_iy,report.max_rel_error);
+      }
+   }
+
+   if config.roofline { report_roofline(config,&result); }
+   if config.vs_memcpy { report_vs_memcpy(config,&in_array,&result)?; }
+
+   //  The accuracy check is purely informational.
+
+   if config.verify_both && !elementwise {
+      eprintln!("--verify-both only applies to kernels checked element by \
+                                                                  element");
+   } else if config.verify_both {
+      let check = crsverify::check_accuracy(&in_array,&out_array,
+                                                            config.tolerance);
+      info(config,&format!("Standard check (f32 arithmetic): {}",
+                                      if verified { "PASS" } else { "FAIL" }));
+      match check.first_inexact {
+         None => info(config,"Exact check (true sum): PASS"),
+         Some((ix,iy)) => info(config,&format!(
+            "Exact check (true sum): FAIL, {} elements inexact, first at \
+            ({},{}), index sum {}",check.inexact,ix,iy,ix + iy)),
+      }
+      info(config,&format!("Tolerant check (relative tolerance {:e}): {}, \
+            {} elements out of tolerance",check.tolerance,
+            if check.out_of_tolerance == 0 { "PASS" } else { "FAIL" },
+            check.out_of_tolerance));
+   }
+
+   if config.verify_shuffle && !elementwise {
+      eprintln!("--verify-shuffle only applies to kernels checked element by \
+                                                                  element");
+   } else if config.verify_shuffle {
+      report_shuffled(config,&in_array,&out_array);
+   }
+
+   Ok(result)
+}
+
+//  The number of the mismatches found by the shuffled check that are listed.
+
+const SHUFFLED_SHOWN: usize = 10;
+
+//  Reports the standard check made again in a shuffled order. Since the
+//  order is random, the mismatches listed are a fair sample of all of them,
+//  and the rows and columns they span show whether the damage is local.
+
+fn report_shuffled (config: &BenchConfig,in_array: &Array2D<f32>,
+                                                  out_array: &Array2D<f32>) {
+   let check = crsverify::check_shuffled(in_array,out_array,config.seed,
+                                                              SHUFFLED_SHOWN);
+   if check.mismatches == 0 {
+      info(config,&format!("Shuffled check (seed {}): PASS, all {} elements \
+                                 correct",config.seed,in_array.len()));
+      return;
+   }
+   let span = |range: Option<(usize,usize)>| match range {
+      Some((first,last)) if first == last => first.to_string(),
+      Some((first,last)) => format!("{}-{}",first,last),
+      None => String::new(),
+   };
+   info(config,&format!("Shuffled check (seed {}): FAIL, {} of {} elements \
+          wrong, in rows {} and columns {}",config.seed,check.mismatches,
+          in_array.len(),span(check.rows),span(check.columns)));
+   info(config,&format!("A random sample of {} of them:",check.sample.len()));
+   for (ix,iy,value,expected) in check.sample {
+      info(config,&format!("   ({},{}) is {}, should be {}",ix,iy,value,
+                                                                   expected));
+   }
+}
+
+//  The per-call times are in nanoseconds, and are for a single pass over
+//  the array, so with --inner-repeat they are the time for each actual
+//  call divided by the inner repeat count. The minimum over the trials is
+//  the figure to use - anything else is noise added by the system. Returns
+//  the total number of calls, and the minimum and mean times per call.
+
+fn per_call_times (config: &BenchConfig,trials: &[Trial]) -> (usize,f64,f64) {
+   let per_call = per_call_values(config,trials);
+   let calls: usize = trials.iter().map(|trial| trial.calls).sum();
+   let min_ns = per_call.iter().cloned().fold(f64::INFINITY,f64::min);
+   let mean_ns = per_call.iter().sum::<f64>() / per_call.len() as f64;
+   (calls,min_ns,mean_ns)
+}
+
+//  Returns the time per call for each trial, as per_call_times() uses them.
+
+fn per_call_values (config: &BenchConfig,trials: &[Trial]) -> Vec<f64> {
+   let inner_repeat = config.inner_repeat.unwrap_or(1);
+   trials.iter().map(|trial| {
+      let passes = (trial.calls * inner_repeat).max(1) as f64;
+      trial.time.as_secs_f64() * 1.0e9 / passes
+   }).collect()
+}
+
+//  Writes a histogram of the times per call of the trials to an SVG file,
+//  reporting where it went, or why it couldn't be written. Either way the
+//  run carries on, as it does for a difference map.
+
+fn write_histogram (config: &BenchConfig,trials: &[Trial],path: &str) {
+   let mut title = format!("{}: {} rows of {} columns, {} trials",
+                     config.mode.name(),config.ny,config.nx,trials.len());
+   if let Some(ref label) = config.label {
+      title = format!("{} - {}",label,title);
+   }
+   let svg = crssvg::histogram(&per_call_values(config,trials),&title);
+   match fs::write(path,svg) {
+      Ok(()) => info(config,&format!("Histogram of the {} trial times \
+                                       written to {}",trials.len(),path)),
+      Err(error) => eprintln!("{}",BenchError::Io { path: path.to_string(),
+                          action: "write", error: error.to_string() }),
+   }
+}
+
+//  Returns the standard error of the mean time per call, taking each trial
+//  as one measurement, and the half width of the 95% confidence interval
+//  for the mean, using Student's t (see crsstats.rs). Both are None if
+//  there's only the one trial.
+
+fn per_call_uncertainty (config: &BenchConfig,trials: &[Trial])
+                                                -> (Option<f64>,Option<f64>) {
+   let per_call = per_call_values(config,trials);
+   (crsstats::std_error(&per_call),crsstats::confidence_95(&per_call))
+}
+
+//  Reports the timing of a result, for the prose format.
+
+fn report_times (config: &BenchConfig,result: &BenchResult,trials: &[Trial]) {
+   let min_ns = result.min_ns_per_call;
+   let including = if config.fresh_alloc { ", including allocation" }
+                                                                else { "" };
+   let (time,ratio) = (config.places(3),config.places(4));
+   println!("Time per call{}: min {}, mean {} over {} trials, min {} per \
+            element",including,config.time_text(min_ns,time),
+            config.time_text(result.mean_ns_per_call,time),config.trials,
+            config.time_text(result.ns_per_element(),ratio));
+   if let (Some(sem),Some(ci95)) =
+                          (result.sem_ns_per_call,result.ci95_ns_per_call) {
+      println!("Mean time per call {} +/- {} (95% confidence interval, \
+                Student's t with {} degrees of freedom), standard error {}",
+                config.time_text(result.mean_ns_per_call,time),
+                config.time_text(ci95,time),config.trials - 1,
+                config.time_text(sem,time));
+   }
+   println!("Arithmetic intensity {:.*} flop/byte ({} flop, {} bytes per \
+             element), achieved {:.*} Gflop/s, {:.*} Gbyte/s",
+             ratio,result.intensity(),result.flops,result.bytes,
+             time,result.gflop_per_s(),time,result.gbyte_per_s());
+   if let (Some(peak),Some(fraction)) =
+                            (config.peak_bandwidth,result.peak_fraction()) {
+      println!("Achieved bandwidth is {:.*}% of the peak of {} Gbyte/s",
+                                  config.places(1),fraction * 100.0,peak);
+   }
+   if let Some(budget) = config.budget_ms {
+      let min_calls = trials.iter().map(|trial| trial.calls).min();
+      let max_calls = trials.iter().map(|trial| trial.calls).max();
+      println!("Budget of {} ms per trial: between {} and {} calls per \
+                trial, best throughput {:.*} calls/s, {:.*e} elements/s",
+                budget,min_calls.unwrap_or(0),max_calls.unwrap_or(0),
+                config.places(1),1.0e9 / min_ns,
+                ratio,1.0e9 * result.elements() as f64 / min_ns);
+   }
+   if let Some(inner_repeat) = config.inner_repeat {
+      println!("Each call made {} passes over the array, min {} per call \
+                including call overhead",inner_repeat,
+                config.time_text(min_ns * inner_repeat as f64,time));
+   }
+}
+
+//  Reports, for --roofline, the bandwidth-bound lower limit on the time per
+//  call - the bytes the mode declares for a pass, moved at the peak
+//  bandwidth - against the minimum time achieved. The efficiency is the
+//  limit as a percentage of the achieved time.
+
+fn report_roofline (config: &BenchConfig,result: &BenchResult) {
+   let peak = match config.peak_bandwidth {
+      Some(peak) => peak,
+      None => return,
+   };
+   let bytes = (result.elements() * result.bytes) as f64;
+   let bound_ns = bytes / peak;
+   let time = config.places(3);
+   info(config,&format!("Roofline: {} per call at the peak of {} Gbyte/s \
+         takes at least {}, against {} achieved - an efficiency of {:.*}%",
+         bytes_text(bytes),peak,config.time_text(bound_ns,time),
+         config.time_text(result.min_ns_per_call,time),config.places(1),
+         bound_ns * 100.0 / result.min_ns_per_call));
+   info(config,"Roofline: this is a bandwidth-bound lower limit, which only \
+         applies to a memory-bound kernel - not to one limited by its \
+         arithmetic, or to arrays small enough to stay in cache");
+}
+
+//  A kernel taking no more than this multiple of the time a memcpy() takes
+//  is reported as memory bound.
+
+const MEMCPY_BOUND_RATIO: f64 = 1.2;
+
+//  Reports, for --vs-memcpy, the kernel's minimum time per call as a
+//  multiple of the time copy_from_slice() takes to move the same bytes.
+//  The copy is of the input array into a scratch array, with the output
+//  passed through black_box() after each one so none can be dropped, and
+//  it's timed just as the kernel was. A copy moves 8 bytes an element,
+//  and its time is scaled by the bytes the mode declares.
+
+fn report_vs_memcpy (config: &BenchConfig,in_array: &Array2D<f32>,
+                             result: &BenchResult) -> Result<(),BenchError> {
+   let mut scratch = Array2D::try_new(config.nx,config.ny,0.0f32)?;
+   let trials = time_kernel(config,|| {
+      scratch.as_mut_slice().copy_from_slice(black_box(in_array.as_slice()));
+      black_box(&scratch);
+   });
+   let (_,copy_ns,_) = per_call_times(config,&trials);
+   let copy_bytes = 2 * std::mem::size_of::<f32>();
+   let scaled_ns = copy_ns * result.bytes as f64 / copy_bytes as f64;
+   let ratio = result.min_ns_per_call / scaled_ns;
+   let verdict = if ratio <= MEMCPY_BOUND_RATIO {
+      "so it's memory bound"
+   } else {
+      "so it does more than move the data"
+   };
+   let time = config.places(3);
+   info(config,&format!("Memcpy comparison: a memcpy of the {} per call the \
+         kernel moves takes min {}, against {} for the kernel - {:.*} times \
+         as long, {}",bytes_text((result.elements() * result.bytes) as f64),
+         config.time_text(scaled_ns,time),
+         config.time_text(result.min_ns_per_call,time),config.places(2),
+                                                             ratio,verdict));
+   Ok(())
+}
+
+//  Runs one configuration with a 3D array. This is a cut-down version of
+//  run_config(), since only the flat mode has a 3D kernel, and none of the
+//  2D diagnostics apply.
+
+fn run_config_3d (config: &BenchConfig) -> Result<BenchResult,BenchError> {
+
+   let (nx,ny,nz) = (config.nx,config.ny,config.nz);
+   let repeats = match config.budget_ms {
+      Some(budget) => format!("budget = {} ms",budget),
+      None if config.auto_repeats => format!("repeats = {} (automatic, for \
+         {} element operations a trial)",config.nrpt,config.repeat_ops),
+      None => format!("repeats = {}",config.nrpt),
+   };
+   if let Some(ref label) = config.label {
+      banner(config,&format!("Label: {}",label));
+   }
+   banner(config,&format!("Arrays have {} planes of {} rows of {} columns, \
+                   {}, mode = {}, 3D",nz,ny,nx,repeats,config.mode.name()));
+   report_time_source(config);
+
+   let in_array = crsinput::make_input_3d(nx,ny,nz,config.input,config.seed)?;
+   let mut out_array = Array3D::try_new(nx,ny,nz,0.0f32)?;
+   if config.input.is_random() {
+      info(config,&format!("Input {}, seed {}",config.input.describe(),
+                                                                config.seed));
+   }
+   let trials = time_kernel(config,
+                     || crssub3d::csub3d(black_box(&in_array),&mut out_array));
+   let verified = crsverify::verify_3d(&in_array,&out_array);
+
+   let (calls,min_ns,mean_ns) = per_call_times(config,&trials);
+   let (sem_ns,ci95_ns) = per_call_uncertainty(config,&trials);
+   let (checksum_algo,checksum) = checksum_of(config,out_array.as_slice());
+   let result = BenchResult {
+      label: config.label.clone(),
+      mode: config.mode.name().to_string(),
+      ny,
+      nx,
+      nz: Some(nz),
+      repeats: config.nrpt,
+      trials: config.trials,
+      inner_repeat: config.inner_repeat,
+      threads: 1,
+      input: config.input.name().to_string(),
+      seed: config.seed,
+      loop_order: config.loop_order.name().to_string(),
+      budget_ms: config.budget_ms,
+      out_stride: None,
+      perm_seed: None,
+      window: None,
+      window_stride: None,
+      unroll: None,
+      expr: None,
+      fresh_alloc: false,
+      hugepages: false,
+      mlock: false,
+      time_source: config.time_source.effective().name().to_string(),
+      flops: config.flops(),
+      bytes: config.mode.info().bytes,
+      peak_bandwidth: config.peak_bandwidth,
+      calls,
+      min_ns_per_call: min_ns,
+      mean_ns_per_call: mean_ns,
+      sem_ns_per_call: sem_ns,
+      ci95_ns_per_call: ci95_ns,
+      verified,
+      checksum_algo,
+      checksum,
+      failed: false,
+      precision: config.precision,
+   };
+   if config.format == Format::Prose {
+      report_times(config,&result,&trials);
+      println!("Results {}",if verified { "correct" } else { "INCORRECT" });
+      report_checksum(&result);
+   }
+   if config.roofline { report_roofline(config,&result); }
+   Ok(result)
+}
+
+//  Works out the checksum of the output, if one was asked for, returning
+//  the name of the algorithm and the checksum, as held in a BenchResult.
+
+fn checksum_of (config: &BenchConfig,values: &[f32])
+                                         -> (Option<String>,Option<String>) {
+   match config.checksum {
+      Some(checksum) => (Some(checksum.name().to_string()),
+                               Some(checksum.of(values,config.precision))),
+      None => (None,None),
+   }
+}
+
+//  Reports the checksum of the output, if there is one.
+
+fn report_checksum (result: &BenchResult) {
+   if let (Some(algo),Some(checksum)) =
+                                     (&result.checksum_algo,&result.checksum) {
+      println!("Output checksum ({}): {}",algo,checksum);
+   }
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                             D r i f t   C h e c k
+
+//  Looks for a trend in the minimum per-call times of each configuration
+//  over the repetitions of a drift check. The results are in order of
+//  repetition, with nconfigs results for each. The slope of the best fit
+//  line gives the change per repetition, and if that adds up to more than
+//  the regression threshold over the whole check, the machine is probably
+//  slowing down - usually because it's getting hot.
+
+fn report_drift (config: &BenchConfig,results: &[BenchResult],nconfigs: usize) {
+   for iconfig in 0..nconfigs {
+      let key = results[iconfig].config_key();
+      let repeats = results.iter().skip(iconfig).step_by(nconfigs);
+      if repeats.clone().any(|result| result.failed) {
+         info(config,&format!("Drift: no figure for {}, which failed at \
+                                            least once",key));
+         continue;
+      }
+      let times: Vec<f64> =
+                       repeats.map(|result| result.min_ns_per_call).collect();
+      let slope = crsstats::slope(&times);
+      let mean = crsstats::mean(&times);
+      let drift = if mean > 0.0 {
+         slope * (times.len() - 1) as f64 * 100.0 / mean
+      } else {
+         0.0
+      };
+      let (time,percent) = (config.places(3),config.places(2));
+      let sign = if slope >= 0.0 { "+" } else { "" };
+      info(config,&format!("Drift over {} repetitions of {}: slope {}{} \
+                  per repetition, {:+.*}% overall",times.len(),key,sign,
+                  config.time_text(slope,time),percent,drift));
+      if drift > config.threshold {
+         eprintln!("Warning: times for {} rose by {:.*}% over the drift \
+                    check, more than the {}% threshold - the machine may be \
+                    throttling",key,percent,drift,config.threshold);
+      }
+   }
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                          E q u i v a l e n c e
+
+//  Runs one call of the given mode, with everything else as in the
+//  configuration, untimed as far as anyone is concerned, and returns the
+//  output - with any scatter undone.
+
+fn run_once (config: &BenchConfig,mode: Mode,in_array: &Array2D<f32>)
+                                          -> Result<Array2D<f32>,BenchError> {
+   let once_config = match config.to_builder().mode(mode.name()).nrpt(1)
+              .auto_repeats(false).trials(1).warmup(0).warmup_seconds(None)
+              .warmup_to_freq(false).inner_repeat(None).budget_ms(None)
+              .fresh_alloc(false).build() {
+      Ok(once_config) => once_config,
+      Err(message) => usage_error(&format!("Mode {}: {}",mode.name(),message)),
+   };
+   let mut out_array = Array2D::try_new(config.nx,config.ny,0.0f32)?;
+   run_trials(&once_config,in_array,&mut out_array);
+   if let Some(stride) = once_config.out_stride {
+      out_array = crssub_strided::gather(&out_array,stride);
+   }
+   Ok(out_array)
+}
+
+//  Runs two modes on the same input and compares their outputs, returning
+//  the exit status - EXIT_OK if they agree to within the tolerance - or an
+//  error if the arrays can't be allocated.
+
+fn check_equiv (config: &BenchConfig,mode_a: Mode,mode_b: Mode)
+                                                   -> Result<i32,BenchError> {
+   let in_array = crsinput::make_input(config.nx,config.ny,config.input,
+                                                                 config.seed)?;
+   let out_a = run_once(config,mode_a,&in_array)?;
+   let out_b = run_once(config,mode_b,&in_array)?;
+   let comparison = crsverify::compare_outputs(&out_a,&out_b,config.tolerance);
+   let (name_a,name_b) = (mode_a.name(),mode_b.name());
+   println!("Comparing modes {} and {}, {} rows of {} columns, {} input",
+                 name_a,name_b,config.ny,config.nx,config.input.describe());
+   match comparison.first_difference {
+      None => println!("Outputs are bitwise identical"),
+      Some((ix,iy)) => {
+         println!("Outputs differ in {} elements, first at ({},{}): {} gives \
+                   {}, {} gives {}",comparison.differences,ix,iy,name_a,
+                   out_a[[ix,iy]],name_b,out_b[[ix,iy]]);
+         println!("{} elements differ by more than the relative tolerance \
+                   {:e}",comparison.out_of_tolerance,comparison.tolerance);
+      }
+   }
+   let equivalent = comparison.out_of_tolerance == 0;
+   Ok(if equivalent { EXIT_OK } else { EXIT_VERIFY_FAILED })
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                        T y p e   C o m p a r i s o n
+
+//  The element types a type comparison uses, the first being the one the
+//  others are compared with.
+
+const COMPARE_TYPES: [&str; 4] = ["f32","f64","i32","i64"];
+
+//  The timing of one element type in a type comparison.
+
+struct TypeTiming {
+   min_ns: f64,
+   mean_ns: f64,
+   verified: bool,
+}
+
+//  Times the selected mode with elements of type T, on the standard input,
+//  and checks the result.
+
+fn time_type<T: Element> (config: &BenchConfig)
+                                            -> Result<TypeTiming,BenchError> {
+   let (nx,ny) = (config.nx,config.ny);
+   let mut in_array = Array2D::try_new(nx,ny,T::from_index(0))?;
+   for iy in 0..ny {
+      for (ix,value) in in_array.row_mut(iy).iter_mut().enumerate() {
+         *value = T::from_index(nx - ix + ny - iy);
+      }
+   }
+   let mut out_array = Array2D::try_new(nx,ny,T::from_index(0))?;
+   let trials = run_generic_trials(config,&in_array,&mut out_array)
+                                                           .unwrap_or_default();
+   if let Some(stride) = config.out_stride {
+      out_array = crssub_strided::gather(&out_array,stride);
+   }
+   let (_,min_ns,mean_ns) = per_call_times(config,&trials);
+   Ok(TypeTiming { min_ns, mean_ns,
+             verified: config.check().verify_generic(&in_array,&out_array) })
+}
+
+//  Times the selected mode with each of the COMPARE_TYPES in turn, and
+//  reports the times, and each as a ratio to the time for f32, either as a
+//  table or as CSV. Returns the exit status.
+
+fn compare_types (config: &BenchConfig) -> Result<i32,BenchError> {
+   let timings = [time_type::<f32>(config)?,time_type::<f64>(config)?,
+                  time_type::<i32>(config)?,time_type::<i64>(config)?];
+   let reference = timings[0].min_ns;
+   let (time,ratio) = (config.places(3),config.places(4));
+   if config.format == Format::Csv {
+      println!("type,min_ns_per_call,mean_ns_per_call,ratio_to_f32,verified");
+      for (name,timing) in COMPARE_TYPES.iter().zip(timings.iter()) {
+         println!("{},{:.*},{:.*},{:.*},{}",name,time,timing.min_ns,time,
+             timing.mean_ns,ratio,timing.min_ns / reference,timing.verified);
+      }
+   } else {
+      let repeats = match config.budget_ms {
+         Some(budget) => format!("budget = {} ms",budget),
+         None => format!("repeats = {}",config.nrpt),
+      };
+      println!("Comparing element types, mode = {}, {} rows of {} columns, {}",
+                             config.mode.name(),config.ny,config.nx,repeats);
+      println!("   type {:>13} {:>13}  ratio to f32  results",
+               config.time_heading("min","call"),
+               config.time_heading("mean","call"));
+      for (name,timing) in COMPARE_TYPES.iter().zip(timings.iter()) {
+         println!("   {:<4} {:>13} {:>13} {:>13.*}  {}",name,
+                  config.time_cell(timing.min_ns,time),
+                  config.time_cell(timing.mean_ns,time),time,
+                  timing.min_ns / reference,
+                  if timing.verified { "correct" } else { "INCORRECT" });
+      }
+   }
+   let ratios: Vec<f64> = timings[1..].iter()
+                           .map(|timing| timing.min_ns / reference).collect();
+   report_geometric_mean(config,"f32",&ratios);
+   let all_verified = timings.iter().all(|timing| timing.verified);
+   Ok(if all_verified { EXIT_OK } else { EXIT_VERIFY_FAILED })
+}
+
+//  Reports the geometric mean of the ratios of a comparison to its
+//  reference, leaving out the reference itself, unless there's no sensible
+//  value - if a time was zero, for instance.
+
+fn report_geometric_mean (config: &BenchConfig,reference: &str,
+                                                            ratios: &[f64]) {
+   if let Some(value) = crsstats::geometric_mean(ratios) {
+      info(config,&format!("Geometric mean of the ratios to {}: {:.*}",
+                                          reference,config.places(4),value));
+   }
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                     U n r o l l   C o m p a r i s o n
+
+//  Runs an unrolling comparison: times the flat mode, and then the unrolled
+//  mode with each of its unroll factors, all with f32 elements on the
+//  standard input, just as a type comparison times each type, and prints
+//  the results as a table or as CSV. Returns the exit status.
+
+fn compare_unroll (config: &BenchConfig) -> Result<i32,BenchError> {
+   let base = || config.to_builder().compare_unroll(false);
+   let flat = base().mode("flat").build()?;
+   let mut timings = vec![(None,time_type::<f32>(&flat)?)];
+   for &factor in &crssub_unrolled::UNROLL_FACTORS {
+      let unrolled = base().unroll(Some(factor)).build()?;
+      timings.push((Some(factor),time_type::<f32>(&unrolled)?));
+   }
+   let reference = timings[0].1.min_ns;
+   let (time,ratio) = (config.places(3),config.places(4));
+   if config.format == Format::Csv {
+      println!("kernel,unroll,min_ns_per_call,mean_ns_per_call,ratio_to_flat,\
+                                                                  verified");
+      for &(factor,ref timing) in &timings {
+         println!("{},{},{:.*},{:.*},{:.*},{}",
+            if factor.is_some() { "unrolled" } else { "flat" },
+            factor.map_or("none".to_string(),|factor| factor.to_string()),
+            time,timing.min_ns,time,timing.mean_ns,ratio,
+            timing.min_ns / reference,timing.verified);
+      }
+   } else {
+      let repeats = match config.budget_ms {
+         Some(budget) => format!("budget = {} ms",budget),
+         None => format!("repeats = {}",config.nrpt),
+      };
+      println!("Comparing unroll factors, {} rows of {} columns, {}",
+                                                 config.ny,config.nx,repeats);
+      println!("   {:<13} {:>13} {:>13}  ratio to flat  results","kernel",
+               config.time_heading("min","call"),
+               config.time_heading("mean","call"));
+      for &(factor,ref timing) in &timings {
+         let kernel = match factor {
+            Some(factor) => format!("unrolled by {}",factor),
+            None => "flat".to_string(),
+         };
+         println!("   {:<13} {:>13} {:>13} {:>14.*}  {}",kernel,
+                  config.time_cell(timing.min_ns,time),
+                  config.time_cell(timing.mean_ns,time),ratio,
+                  timing.min_ns / reference,
+                  if timing.verified { "correct" } else { "INCORRECT" });
+      }
+   }
+   let ratios: Vec<f64> = timings[1..].iter()
+                           .map(|timing| timing.1.min_ns / reference).collect();
+   report_geometric_mean(config,"flat",&ratios);
+   let all_verified = timings.iter().all(|timing| timing.1.verified);
+   Ok(if all_verified { EXIT_OK } else { EXIT_VERIFY_FAILED })
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                  F a l s e   S h a r i n g   S t u d y
+
+//  Runs a false sharing study: times the interleaved kernel with chunks of
+//  a cache line, first with the chunks starting on the output's cache line
+//  boundaries and then half a line off them, with the same input and so on
+//  for both, and prints the results as a table or as CSV. Returns the exit
+//  status.
+
+fn false_share_study (config: &BenchConfig) -> Result<i32,BenchError> {
+   let (nx,ny) = (config.nx,config.ny);
+   let element = std::mem::size_of::<f32>();
+   let line = config.cache_line;
+   let chunk = line / element;
+   let in_array = crsinput::make_input(nx,ny,config.input,config.seed)?;
+   let mut out_array = Array2D::try_new(nx,ny,0.0f32)?;
+
+   //  The first chunk is cut short so that the rest start on a line
+   //  boundary, or shifted on half a line from there. Either way it has
+   //  from 1 to chunk elements, and the offset is where each boundary
+   //  falls within a line.
+
+   let address = out_array.as_slice().as_ptr() as usize;
+   let to_boundary = (line - address % line) % line / element;
+   let splits = [("aligned",to_boundary),
+                 ("misaligned",(to_boundary + chunk / 2) % chunk)];
+   let mut timings = Vec::new();
+   for &(name,lead) in &splits {
+      let first = if lead == 0 { chunk } else { lead };
+      let offset = (address + first * element) % line;
+      let trials = time_kernel(config,|| crssub_interleaved::csub_interleaved(
+               black_box(&in_array),&mut out_array,config.threads,chunk,first));
+      let (_,min_ns,mean_ns) = per_call_times(config,&trials);
+      let verified = config.check().verify(&in_array,&out_array);
+      timings.push((name,offset,TypeTiming { min_ns, mean_ns, verified }));
+   }
+   let reference = timings[0].2.min_ns;
+   let (time,ratio) = (config.places(3),config.places(4));
+   if config.format == Format::Csv {
+      println!("split,boundary_offset_bytes,min_ns_per_call,mean_ns_per_call,\
+                                                 ratio_to_aligned,verified");
+      for &(name,offset,ref timing) in &timings {
+         println!("{},{},{:.*},{:.*},{:.*},{}",name,offset,time,timing.min_ns,
+                  time,timing.mean_ns,ratio,timing.min_ns / reference,
+                                                            timing.verified);
+      }
+   } else {
+      let repeats = match config.budget_ms {
+         Some(budget) => format!("budget = {} ms",budget),
+         None => format!("repeats = {}",config.nrpt),
+      };
+      println!("False sharing study, {} threads, chunks of {} byte lines, {} \
+               rows of {} columns, {}",config.threads,line,ny,nx,repeats);
+      println!("   split       boundaries {:>12} {:>13}  ratio to aligned  \
+               results",config.time_heading("min","call"),
+               config.time_heading("mean","call"));
+      for &(name,offset,ref timing) in &timings {
+         println!("   {:<10} {:>5} bytes {:>12} {:>13} {:>17.*}  {}",name,
+                  offset,config.time_cell(timing.min_ns,time),
+                  config.time_cell(timing.mean_ns,time),ratio,
+                  timing.min_ns / reference,
+                  if timing.verified { "correct" } else { "INCORRECT" });
+      }
+   }
+   let penalty = (timings[1].2.min_ns / reference - 1.0) * 100.0;
+   info(config,&format!("False sharing penalty: with the chunk boundaries {} \
+      bytes into a line, the time per call changes by {:+.*}%",timings[1].1,
+                                                  config.places(1),penalty));
+   let all_verified = timings.iter().all(|timing| timing.2.verified);
+   Ok(if all_verified { EXIT_OK } else { EXIT_VERIFY_FAILED })
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                        O r d e r   S t u d y
+
+//  The sizes a loop order study times, the number of elements on a side of
+//  a square array, from one that fits easily in the L1 cache to one that
+//  fits in no cache at all.
+
+const ORDER_SIZES: [usize; 13] =
+                   [16,32,64,128,256,384,512,768,1024,1536,2048,3072,4096];
+
+//  As for a search for the cache cliff, without a time budget each trial
+//  at each size makes enough calls to handle about this many elements.
+
+const ORDER_ELEMENTS_PER_TRIAL: usize = 20_000_000;
+
+//  The ratio of the column order's time to the row order's at which the
+//  penalty for going down the columns counts as severe.
+
+const ORDER_PENALTY: f64 = 2.0;
+
+//  One of the sizes timed: the side of the array, the minimum times per
+//  element in ns along the rows and down the columns, and whether both
+//  results were correct.
+
+struct OrderPoint {
+   n: usize,
+   ij_ns: f64,
+   ji_ns: f64,
+   verified: bool,
+}
+
+//  Times the flat mode with both loop orders at each of the ORDER_SIZES,
+//  reporting each size as it's done, either as a table or as CSV, and then
+//  reports the crossover. Returns the exit status.
+
+fn order_study (config: &BenchConfig) -> Result<i32,BenchError> {
+   let bytes_per_element = config.mode.info().bytes;
+   let element = std::mem::size_of::<f32>();
+   let csv = config.format == Format::Csv;
+   if csv {
+      println!("n,working_set_bytes,column_stride_bytes,ij_ns_per_element,\
+                                  ji_ns_per_element,ratio_ji_to_ij,verified");
+   } else {
+      println!("Loop order study, mode = flat, square arrays of {} to {} on \
+                a side",ORDER_SIZES[0],ORDER_SIZES[ORDER_SIZES.len() - 1]);
+      println!("       n   working set  column stride  {:>14}  {:>14}   \
+               ratio  results",config.time_heading("ij","element"),
+               config.time_heading("ji","element"));
+   }
+   let mut points = Vec::new();
+   for &n in &ORDER_SIZES {
+      let elements = n * n;
+      let calls = (ORDER_ELEMENTS_PER_TRIAL / elements).max(1);
+      let time_order = |order: &str| -> Result<TypeTiming,BenchError> {
+         let order_config = config.to_builder().nx(n).ny(n).nrpt(calls)
+                              .auto_repeats(false).loop_order(order)
+                              .order_study(false).build()?;
+         time_type::<f32>(&order_config)
+      };
+      let (ij,ji) = (time_order("ij")?,time_order("ji")?);
+      let point = OrderPoint { n,
+                               ij_ns: ij.min_ns / elements as f64,
+                               ji_ns: ji.min_ns / elements as f64,
+                               verified: ij.verified && ji.verified };
+      let (bytes,stride) = (elements * bytes_per_element,n * element);
+      let (time,ratio) = (config.places(4),config.places(3));
+      if csv {
+         println!("{},{},{},{:.*},{:.*},{:.*},{}",n,bytes,stride,time,
+                  point.ij_ns,time,point.ji_ns,ratio,point.ji_ns / point.ij_ns,
+                  point.verified);
+      } else {
+         println!("   {:>5}  {:>12}  {:>13}  {:>14}  {:>14}  {:>6.*}  {}",
+                  n,bytes_text(bytes as f64),bytes_text(stride as f64),
+                  config.time_cell(point.ij_ns,time),
+                  config.time_cell(point.ji_ns,time),ratio,
+                  point.ji_ns / point.ij_ns,
+                  if point.verified { "correct" } else { "INCORRECT" });
+      }
+      points.push(point);
+   }
+
+   //  As for the cache cliff, the conclusion goes with the results for the
+   //  prose format, and is kept out of the data with CSV.
+
+   let report = |text: &str| if csv { info(config,text) }
+                                               else { println!("{}",text) };
+   let crossover = points.iter()
+                    .find(|point| point.ji_ns >= point.ij_ns * ORDER_PENALTY);
+   match crossover {
+      Some(point) => report(&format!("Crossover: from {} on a side - \
+            columns {} apart, a working set of {} - going down the columns \
+            takes {:.*} times as long as going along the rows",point.n,
+            bytes_text((point.n * element) as f64),
+            bytes_text((point.n * point.n * bytes_per_element) as f64),
+            config.places(2),
+            point.ji_ns / point.ij_ns)),
+      None => report(&format!("No crossover: going down the columns never \
+            took {} times as long as going along the rows",ORDER_PENALTY)),
+   }
+   let all_verified = points.iter().all(|point| point.verified);
+   Ok(if all_verified { EXIT_OK } else { EXIT_VERIFY_FAILED })
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                     B u i l d   C o m p a r i s o n
+
+//  Runs a build comparison: reads the result files written by different
+//  builds, and prints the best time for each configuration that's in all of
+//  them, and the speedup of each build over the first, as a table or as
+//  CSV. Each configuration is described by its mode and dimensions, and
+//  any other configuration fields whose values aren't the same throughout.
+//  Returns the exit status.
+
+fn compare_build (config: &BenchConfig,paths: &[&str])
+                                                  -> Result<i32,BenchError> {
+   let fields = failed_result(config).config_fields();
+   let names: Vec<&str> = fields.iter().map(|field| field.0).collect();
+   let builds = crsreport::read_builds(paths,&names)?;
+   let (first,others) = (&builds[0],&builds[1..]);
+   let common: Vec<&Vec<String>> = first.times.iter().map(|entry| &entry.0)
+        .filter(|key| others.iter().all(|build| build.time(key).is_some()))
+        .collect();
+   if common.is_empty() {
+      eprintln!("No configuration has results in all {} files",builds.len());
+      return Ok(EXIT_ERROR);
+   }
+   let shown: Vec<usize> = (0..names.len()).filter(|&index|
+              ["mode","ny","nx"].contains(&names[index])
+              || common.iter().any(|key| key[index] != common[0][index]))
+              .collect();
+   let describe = |key: &Vec<String>| {
+      let pairs: Vec<String> = shown.iter()
+             .map(|&index| format!("{}={}",names[index],key[index])).collect();
+      pairs.join(" ")
+   };
+   let (time,ratio) = (config.places(3),config.places(4));
+   if config.format == Format::Csv {
+      let headings: Vec<&str> = shown.iter().map(|&index| names[index])
+                                                                   .collect();
+      println!("{},opt_level,target_cpu,min_ns_per_call,speedup",
+                                                         headings.join(","));
+      for key in &common {
+         let values: Vec<&str> = shown.iter()
+                                 .map(|&index| key[index].as_str()).collect();
+         let reference = first.time(key).unwrap_or(0.0);
+         for build in &builds {
+            let ns = build.time(key).unwrap_or(0.0);
+            println!("{},{},{},{:.*},{:.*}",values.join(","),build.opt_level,
+                     build.target_cpu,time,ns,ratio,reference / ns);
+         }
+      }
+   } else {
+      println!("Comparing {} builds, {} configurations in every file",
+                                                  builds.len(),common.len());
+      for (index,build) in builds.iter().enumerate() {
+         println!("   build {}: {}, from {}",index + 1,build.describe(),
+                                                                 build.path);
+      }
+      let width = common.iter().map(|key| describe(key).len())
+                                      .max().unwrap_or(0).max(13);
+      let mut heading = format!("   {:<1$}","configuration",width);
+      for index in 0..builds.len() {
+         heading.push_str(&format!("  {:>16}",format!("build {} {}",
+                                 index + 1,config.time_heading("","call"))));
+      }
+      for index in 1..builds.len() {
+         heading.push_str(&format!("  {:>10}",format!("speedup {}",index + 1)));
+      }
+      println!("{}",heading);
+      for key in &common {
+         let times: Vec<f64> = builds.iter()
+                       .map(|build| build.time(key).unwrap_or(0.0)).collect();
+         let mut line = format!("   {:<1$}",describe(key),width);
+         for ns in &times {
+            line.push_str(&format!("  {:>16}",config.time_cell(*ns,time)));
+         }
+         for ns in &times[1..] {
+            line.push_str(&format!("  {:>10.*}",ratio,times[0] / ns));
+         }
+         println!("{}",line);
+      }
+   }
+   let mut left_out: Vec<&Vec<String>> = Vec::new();
+   for build in &builds {
+      for entry in &build.times {
+         if !common.contains(&&entry.0) && !left_out.contains(&&entry.0) {
+            left_out.push(&entry.0);
+         }
+      }
+   }
+   if !left_out.is_empty() {
+      info(config,&format!("Configurations not in every file, and left out: \
+                                                         {}",left_out.len()));
+   }
+   for (index,build) in others.iter().enumerate() {
+      let speedups: Vec<f64> = common.iter()
+         .map(|key| first.time(key).unwrap_or(0.0)
+                                   / build.time(key).unwrap_or(0.0)).collect();
+      if let Some(value) = crsstats::geometric_mean(&speedups) {
+         info(config,&format!("Geometric mean speedup of build {} over \
+                     build 1: {:.*}",index + 2,config.places(4),value));
+      }
+   }
+   Ok(EXIT_OK)
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                    C o n s t r u c t i o n   T i m i n g
+
+//  Runs a construction timing: checks each of the ways of building an
+//  array in crsconstruct.rs, and then times each of them building and
+//  dropping an array of the size given, with the usual trials, and prints
+//  the results as a table or as CSV. Returns the exit status.
+
+fn bench_construction (config: &BenchConfig) -> i32 {
+   let (nx,ny) = (config.nx,config.ny);
+   let mut timings = Vec::new();
+   let mut allocations = Vec::new();
+   for &strategy in &crsconstruct::STRATEGIES {
+      let verified = crsconstruct::build(strategy,nx,ny).is_initialised(nx,ny);
+      allocations.push(construction_allocations(strategy,nx,ny));
+      let trials = time_kernel(config,
+                   || { black_box(crsconstruct::build(strategy,nx,ny)); });
+      let (_,min_ns,mean_ns) = per_call_times(config,&trials);
+      timings.push((strategy,TypeTiming { min_ns, mean_ns, verified }));
+   }
+   let reference = timings[0].1.min_ns;
+   let (time,ratio) = (config.places(3),config.places(4));
+   let counted = allocations.iter().all(|counts| counts.is_some());
+   if config.format == Format::Csv {
+      println!("strategy,min_ns_per_call,mean_ns_per_call,ratio_to_flat,\
+             verified{}",if counted { ",allocations,peak_bytes" } else { "" });
+      for (&(strategy,ref timing),counts) in timings.iter().zip(&allocations) {
+         let extra = match *counts {
+            Some((made,peak)) => format!(",{},{}",made,peak),
+            None => String::new(),
+         };
+         println!("{},{:.*},{:.*},{:.*},{}{}",strategy.name(),time,
+               timing.min_ns,time,timing.mean_ns,ratio,
+               timing.min_ns / reference,timing.verified,extra);
+      }
+   } else {
+      let repeats = match config.budget_ms {
+         Some(budget) => format!("budget = {} ms",budget),
+         None => format!("repeats = {}",config.nrpt),
+      };
+      println!("Comparing ways of building an array, {} rows of {} columns, \
+                                           {}",config.ny,config.nx,repeats);
+      println!("   {:<11} {:>13} {:>13}  ratio to flat  results","strategy",
+               config.time_heading("min","call"),
+               config.time_heading("mean","call"));
+      for &(strategy,ref timing) in &timings {
+         println!("   {:<11} {:>13} {:>13} {:>14.*}  {}",strategy.name(),
+                  config.time_cell(timing.min_ns,time),
+                  config.time_cell(timing.mean_ns,time),ratio,
+                  timing.min_ns / reference,
+                  if timing.verified { "correct" } else { "INCORRECT" });
+      }
+      if counted {
+         println!("   strategy    allocations  peak in use");
+         for (&(strategy,_),counts) in timings.iter().zip(&allocations) {
+            if let Some((made,peak)) = *counts {
+               println!("   {:<11} {:>11}  {:>11}",strategy.name(),made,
+                                                     bytes_text(peak as f64));
+            }
+         }
+      }
+   }
+   let all_verified = timings.iter().all(|timing| timing.1.verified);
+   if all_verified { EXIT_OK } else { EXIT_VERIFY_FAILED }
+}
+
+//  Returns the allocations one build of an array makes, and the most
+//  bytes it has in use at once, counted by the tracking allocator - or
+//  None, in a build without it.
+
+#[cfg(feature = "trackalloc")]
+fn construction_allocations (strategy: crsconstruct::Strategy,nx: usize,
+                                         ny: usize) -> Option<(usize,usize)> {
+   let before = crsalloc::stats();
+   crsalloc::reset_peak();
+   drop(black_box(crsconstruct::build(strategy,nx,ny)));
+   let after = crsalloc::stats();
+   Some((after.allocations - before.allocations,after.peak - before.in_use))
+}
+
+#[cfg(not(feature = "trackalloc"))]
+fn construction_allocations (_strategy: crsconstruct::Strategy,_nx: usize,
+                                        _ny: usize) -> Option<(usize,usize)> {
+   None
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                           C a c h e   C l i f f
+
+//  The range of working sets a search for the cache cliff covers, in bytes,
+//  and the number of sizes tried for each doubling of the working set.
+
+const CLIFF_MIN_BYTES: usize = 16 * 1024;
+const CLIFF_MAX_BYTES: usize = 512 * 1024 * 1024;
+const CLIFF_STEPS_PER_DOUBLING: i32 = 4;
+
+//  Without a time budget, each trial at each size makes enough calls to
+//  handle about this many elements, so that every size gets much the same
+//  time.
+
+const CLIFF_ELEMENTS_PER_TRIAL: usize = 100_000_000;
+
+//  A step from one size to the next is part of a transition if the time per
+//  element goes up at least this fast, as a power of the working set - 0.5
+//  means as fast as its square root - and a run of such steps counts as a
+//  transition if the time goes up by at least this factor over the run, and
+//  stays up for the doubling after it.
+
+const CLIFF_SLOPE: f64 = 0.5;
+const CLIFF_RISE: f64 = 1.25;
+
+//  One of the sizes timed: the number of rows, the bytes the kernel touches
+//  in a pass, the minimum time per element in ns, and whether the result
+//  was correct.
+
+struct CliffPoint {
+   ny: usize,
+   bytes: usize,
+   ns_per_element: f64,
+   verified: bool,
+}
+
+//  A transition in the time per element. The steepest step is the one from
+//  point 'below' to the next, and the rise is the ratio of the lowest time
+//  in the doubling after the transition to the time at its start.
+
+struct Transition {
+   below: usize,
+   rise: f64,
+}
+
+//  Returns the row counts to time, for rows of nx elements, each element
+//  accounting for the given number of bytes. Small working sets may round
+//  to the same number of rows, and each is only used once.
+
+fn cliff_rows (nx: usize,bytes_per_element: usize) -> Vec<usize> {
+   let row_bytes = (nx * bytes_per_element).max(1) as f64;
+   let mut rows: Vec<usize> = Vec::new();
+   let mut step = 0;
+   loop {
+      let doublings = step as f64 / CLIFF_STEPS_PER_DOUBLING as f64;
+      let bytes = CLIFF_MIN_BYTES as f64 * 2.0f64.powf(doublings);
+      if bytes > CLIFF_MAX_BYTES as f64 * 1.0001 { break; }
+      let ny = ((bytes / row_bytes).round() as usize).max(1);
+      if rows.last() != Some(&ny) { rows.push(ny); }
+      step += 1;
+   }
+   rows
+}
+
+//  The rate at which the time per element goes up between two points, as a
+//  power of the working set.
+
+fn cliff_slope (lower: &CliffPoint,upper: &CliffPoint) -> f64 {
+   let time_ratio = upper.ns_per_element / lower.ns_per_element;
+   let size_ratio = upper.bytes as f64 / lower.bytes as f64;
+   if !(time_ratio > 0.0 && time_ratio.is_finite()) { return 0.0; }
+   time_ratio.ln() / size_ratio.ln()
+}
+
+//  Finds the transitions in a set of points, in order of working set. This
+//  is about as simple a detector as there is - a threshold on the slope,
+//  and another on the total rise - but the cliffs it looks for are big.
+//  Taking the lowest time after the run, rather than the time at its end,
+//  means a single slow size - an interrupt, say - isn't taken for a cliff.
+//  A run that ends within a doubling of the largest size can't be shown to
+//  stay up, and is ignored.
+
+fn find_transitions (points: &[CliffPoint]) -> Vec<Transition> {
+   let mut transitions = Vec::new();
+   let mut first = None;
+   for index in 0..points.len() {
+      let rising = index + 1 < points.len()
+               && cliff_slope(&points[index],&points[index + 1]) >= CLIFF_SLOPE;
+      match first {
+         None if rising => first = Some(index),
+         Some(start) if !rising => {
+            let after = index + CLIFF_STEPS_PER_DOUBLING as usize;
+            let lowest = points.get(index..=after).map(|following|
+                  following.iter().map(|point| point.ns_per_element)
+                                           .fold(f64::INFINITY,f64::min));
+            let rise = lowest.unwrap_or(0.0) / points[start].ns_per_element;
+            if rise >= CLIFF_RISE {
+               let slope = |below: usize|
+                                cliff_slope(&points[below],&points[below + 1]);
+               let mut below = start;
+               for step in start + 1..index {
+                  if slope(step) > slope(below) { below = step; }
+               }
+               transitions.push(Transition { below, rise });
+            }
+            first = None;
+         }
+         _ => {}
+      }
+   }
+   transitions
+}
+
+//  Formats a number of bytes in KiB, MiB or GiB, whichever suits.
+
+fn bytes_text (bytes: f64) -> String {
+   let mut value = bytes;
+   let mut unit = "bytes";
+   for next in ["KiB","MiB","GiB"].iter() {
+      if value < 1024.0 { break; }
+      value /= 1024.0;
+      unit = next;
+   }
+   if value < 10.0 && unit != "bytes" {
+      format!("{:.1} {}",value,unit)
+   } else {
+      format!("{:.0} {}",value,unit)
+   }
+}
+
+//  Times the selected mode at each of the sizes given by cliff_rows(),
+//  reporting each as it's done, either as a table or as CSV, and then
+//  reports the transitions found and the cliff. Returns the exit status.
+
+fn find_cliff (config: &BenchConfig) -> Result<i32,BenchError> {
+   let nx = config.nx;
+   let bytes_per_element = config.mode.info().bytes;
+   let rows = cliff_rows(nx,bytes_per_element);
+   let csv = config.format == Format::Csv;
+   if csv {
+      println!("nx,ny,working_set_bytes,min_ns_per_call,ns_per_element,\
+                                                                  verified");
+   } else {
+      println!("Looking for the cache cliff, mode = {}, rows of {} columns, \
+                          {} to {}",config.mode.name(),nx,
+                          bytes_text((nx * rows[0] * bytes_per_element) as f64),
+                          bytes_text(CLIFF_MAX_BYTES as f64));
+      println!("       rows   working set  {:>11}  results",
+                                         config.time_heading("","element"));
+   }
+   let mut points = Vec::new();
+   for &ny in &rows {
+      let elements = nx * ny;
+      let calls = (CLIFF_ELEMENTS_PER_TRIAL / elements).max(1);
+      let size_config = config.to_builder().ny(ny).nrpt(calls)
+                             .auto_repeats(false).find_cliff(false).build()?;
+      let timing = time_type::<f32>(&size_config)?;
+      let point = CliffPoint { ny, bytes: elements * bytes_per_element,
+                               ns_per_element: timing.min_ns / elements as f64,
+                               verified: timing.verified };
+      if csv {
+         println!("{},{},{},{:.*},{:.*},{}",nx,ny,point.bytes,
+                  config.places(3),timing.min_ns,config.places(6),
+                  point.ns_per_element,point.verified);
+      } else {
+         println!("   {:>8}  {:>12}  {:>11}  {}",point.ny,
+                  bytes_text(point.bytes as f64),
+                  config.time_cell(point.ns_per_element,config.places(4)),
+                  if point.verified { "correct" } else { "INCORRECT" });
+      }
+      points.push(point);
+   }
+
+   //  The conclusions are part of the results with the prose format, but
+   //  with CSV they are kept out of the data, like any other information.
+
+   let report = |text: &str| if csv { info(config,text) }
+                                               else { println!("{}",text) };
+   let transitions = find_transitions(&points);
+   let edges = |transition: &Transition|
+      (points[transition.below].bytes as f64,
+                                   points[transition.below + 1].bytes as f64);
+   if transitions.is_empty() {
+      report(&format!("No cliff found: the time per element never went up \
+            by {}% or more over a run of sizes",
+            ((CLIFF_RISE - 1.0) * 100.0).round()));
+   } else {
+      report("Transitions in the time per element:");
+      for transition in &transitions {
+         let (lower,upper) = edges(transition);
+         report(&format!("   between {} and {}, rising by a factor of {:.*}",
+                   bytes_text(lower),bytes_text(upper),config.places(2),
+                   transition.rise));
+      }
+      if let Some(cliff) = transitions.last() {
+         let (lower,upper) = edges(cliff);
+         report(&format!("Cliff: the working set stops fitting between {} \
+                and {}, implying a last level cache of about {}",
+                bytes_text(lower),bytes_text(upper),
+                bytes_text((lower * upper).sqrt())));
+      }
+   }
+   let all_verified = points.iter().all(|point| point.verified);
+   Ok(if all_verified { EXIT_OK } else { EXIT_VERIFY_FAILED })
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                        T o r t u r e   T e s t
+
+//  The dimensions a torture test uses for both the rows and the columns:
+//  the smallest there can be, some primes, and some either side of a power
+//  of two - the sizes that leave something over at the end of a row for a
+//  kernel that works in groups, chunks, tiles or threads.
+
+const TORTURE_SIZES: [usize; 9] = [1,3,13,17,127,257,1021,4095,4097];
+
+//  Combinations of the sizes with more elements than this are left out -
+//  the remainder paths are the same, and it keeps the test to seconds.
+
+const MAX_TORTURE_ELEMENTS: usize = 1 << 22;
+
+//  Runs a torture test: runs the selected mode once for each combination
+//  of TORTURE_SIZES as the number of columns and of rows, and checks each
+//  result just as a usual run would. Any shape that gets the wrong results,
+//  or that the mode can't be used for, is listed, followed by a summary,
+//  or with CSV output every shape gets a line. Returns the exit status,
+//  EXIT_OK if every shape that could be run got the right results.
+
+fn torture (config: &BenchConfig) -> Result<i32,BenchError> {
+   let mut shapes = Vec::new();
+   for &nx in &TORTURE_SIZES {
+      for &ny in &TORTURE_SIZES {
+         if nx * ny <= MAX_TORTURE_ELEMENTS { shapes.push((nx,ny)); }
+      }
+   }
+   let csv = config.format == Format::Csv;
+   if csv {
+      println!("nx,ny,result");
+   } else {
+      let sizes: Vec<String> =
+                       TORTURE_SIZES.iter().map(|n| n.to_string()).collect();
+      println!("Torture test, mode = {}, {} shapes, each dimension one of {}",
+                           config.mode.name(),shapes.len(),sizes.join(", "));
+   }
+   let (mut failed,mut skipped) = (0,0);
+   for &(nx,ny) in &shapes {
+      let result = match config.to_builder().nx(nx).ny(ny).torture(false)
+                                                                  .build() {
+         Ok(shape_config) => {
+            let in_array = crsinput::make_input(nx,ny,config.input,
+                                                             config.seed)?;
+            let out_array = run_once(&shape_config,config.mode,&in_array)?;
+            if shape_config.check().verify(&in_array,&out_array) {
+               "correct".to_string()
+            } else {
+               failed += 1;
+               "incorrect".to_string()
+            }
+         }
+         Err(message) => {
+            skipped += 1;
+            if !csv {
+               println!("   {} x {}: can't be run - {}",nx,ny,message);
+            }
+            "skipped".to_string()
+         }
+      };
+      if csv {
+         println!("{},{},{}",nx,ny,result);
+      } else if result == "incorrect" {
+         println!("   {} x {}: INCORRECT",nx,ny);
+      }
+   }
+   if !csv {
+      println!("Torture test: {} of {} shapes correct, {} incorrect, {} \
+               skipped",shapes.len() - failed - skipped,shapes.len(),failed,
+                                                                   skipped);
+   }
+   Ok(if failed == 0 { EXIT_OK } else { EXIT_VERIFY_FAILED })
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                    C o n c u r r e n t   A r r a y s
+
+//  What one thread of a concurrent arrays run did: the timing of each of
+//  its trials, and whether its output was correct.
+
+struct ThreadTiming {
+   trials: Vec<Trial>,
+   verified: bool,
+}
+
+//  Runs in each thread of a concurrent arrays run. Sets up the thread's own
+//  pair of arrays, then runs the trials one at a time, waiting at the
+//  barrier before each so that all the threads time each trial together,
+//  and finally checks its output. Any warmup is made before the first trial
+//  only. Every thread waits at the barrier once its arrays are set up, and
+//  if any of them couldn't allocate its arrays, none of them goes on to the
+//  trials - otherwise the rest would wait for it for ever.
+
+fn concurrent_thread (config: &BenchConfig,barrier: &Barrier,
+                     failed: &AtomicBool) -> Result<ThreadTiming,BenchError> {
+   let (nx,ny) = (config.nx,config.ny);
+   let arrays = crsinput::make_input(nx,ny,config.input,config.seed)
+        .and_then(|in_array| Ok((in_array,Array2D::try_new(nx,ny,0.0f32)?)));
+   if arrays.is_err() { failed.store(true,Ordering::Relaxed); }
+   barrier.wait();
+   let (in_array,mut out_array) = arrays?;
+   if failed.load(Ordering::Relaxed) {
+      return Ok(ThreadTiming { trials: Vec::new(), verified: false });
+   }
+   let mut trials = Vec::with_capacity(config.trials);
+   for itrial in 0..config.trials {
+      let mut trial_builder = config.to_builder().trials(1)
+                                                     .concurrent_arrays(None);
+      if itrial > 0 {
+         trial_builder = trial_builder.warmup(0).warmup_seconds(None)
+                                                       .warmup_to_freq(false);
+      }
+      let trial_config = trial_builder.build()?;
+      barrier.wait();
+      trials.extend(run_trials(&trial_config,&in_array,&mut out_array));
+   }
+   if let Some(stride) = config.out_stride {
+      out_array = crssub_strided::gather(&out_array,stride);
+   }
+   let verified = config.check().verify(&in_array,&out_array);
+   Ok(ThreadTiming { trials, verified })
+}
+
+//  Runs the selected mode in count threads at once, each with its own
+//  arrays, and reports the timing of each thread and the aggregate
+//  bandwidth, either as a table or as CSV. Returns the exit status.
+
+fn concurrent_arrays (config: &BenchConfig,count: usize)
+                                                  -> Result<i32,BenchError> {
+   let barrier = Barrier::new(count);
+   let failed = AtomicBool::new(false);
+   let outcomes: Vec<Result<ThreadTiming,BenchError>> =
+                                                    thread::scope(|scope| {
+      let handles: Vec<_> = (0..count).map(|_| scope.spawn(||
+                     concurrent_thread(config,&barrier,&failed))).collect();
+      handles.into_iter().map(|handle| handle.join()
+                   .unwrap_or_else(|panic| std::panic::resume_unwind(panic)))
+                                                                   .collect()
+   });
+   let timings = outcomes.into_iter().collect::<Result<Vec<_>,_>>()?;
+
+   //  The aggregate for a trial is the bytes moved by all the threads over
+   //  the time taken by the slowest, since they all started together.
+
+   let info = config.mode.info();
+   let elements = config.nx * config.ny;
+   let passes_per_call = config.inner_repeat.unwrap_or(1);
+   let aggregates: Vec<f64> = (0..config.trials).map(|itrial| {
+      let passes: usize = timings.iter().map(|timing|
+                         timing.trials[itrial].calls * passes_per_call).sum();
+      let slowest = timings.iter().map(|timing| timing.trials[itrial].time)
+                                                  .max().unwrap_or_default();
+      (passes * elements * info.bytes) as f64
+                                      / (slowest.as_secs_f64() * 1.0e9)
+   }).collect();
+   let best = aggregates.iter().cloned().fold(0.0,f64::max);
+   let mean = aggregates.iter().sum::<f64>() / aggregates.len() as f64;
+   let all_verified = timings.iter().all(|timing| timing.verified);
+   let (time,rate) = (config.places(3),config.places(4));
+
+   if config.format == Format::Csv {
+      println!("thread,min_ns_per_call,mean_ns_per_call,gbyte_per_s,verified");
+      for (ithread,timing) in timings.iter().enumerate() {
+         let (_,min_ns,mean_ns) = per_call_times(config,&timing.trials);
+         println!("{},{:.*},{:.*},{:.*},{}",ithread,time,min_ns,time,mean_ns,
+             rate,(elements * info.bytes) as f64 / min_ns,timing.verified);
+      }
+      println!("aggregate,,,{:.*},{}",rate,best,all_verified);
+   } else {
+      let repeats = match config.budget_ms {
+         Some(budget) => format!("budget = {} ms",budget),
+         None => format!("repeats = {}",config.nrpt),
+      };
+      println!("Concurrent arrays: {} threads, each with {} rows of {} \
+                columns, {}, mode = {}",count,config.ny,config.nx,repeats,
+                config.mode.name());
+      println!("   thread {:>13} {:>13}     Gbyte/s  results",
+               config.time_heading("min","call"),
+               config.time_heading("mean","call"));
+      for (ithread,timing) in timings.iter().enumerate() {
+         let (_,min_ns,mean_ns) = per_call_times(config,&timing.trials);
+         println!("   {:>6} {:>13} {:>13} {:>11.*}  {}",ithread,
+                  config.time_cell(min_ns,time),
+                  config.time_cell(mean_ns,time),time,
+                  (elements * info.bytes) as f64 / min_ns,
+                  if timing.verified { "correct" } else { "INCORRECT" });
+      }
+      println!("Aggregate bandwidth: best {:.*} Gbyte/s, mean {:.*} Gbyte/s \
+                over {} trials",time,best,time,mean,config.trials);
+      if let Some(peak) = config.peak_bandwidth {
+         println!("Best aggregate bandwidth is {:.*}% of the peak of {} \
+                     Gbyte/s",config.places(1),best / peak * 100.0,peak);
+      }
+      println!("Results {}",if all_verified { "correct" } else { "INCORRECT" });
+   }
+   Ok(if all_verified { EXIT_OK } else { EXIT_VERIFY_FAILED })
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                        A l i a s e d   O u t p u t
+
+//  The number of wrong elements listed by danger_alias().
+
+#[cfg(feature = "danger_alias")]
+const ALIAS_SHOWN: usize = 5;
+
+//  Used for --danger-alias. Puts the input and output arrays in one buffer,
+//  with the output starting offset elements after the input - or before it,
+//  if the offset is negative - runs csub_alias() over them once, and reports
+//  how many output elements are wrong, listing the first few. Then it does
+//  the same with separate arrays, through csub_rawptr(), for contrast.
+//  Nothing is timed, and nothing goes anywhere but the terminal. Returns the
+//  exit status, which is 1 if the aliased output is wrong.
+
+#[cfg(feature = "danger_alias")]
+fn danger_alias (config: &BenchConfig,offset: isize) -> Result<i32,BenchError> {
+   let (nx,ny) = (config.nx,config.ny);
+   let elements = nx * ny;
+   let shift = offset.unsigned_abs();
+   let mut in_array = Array2D::try_new(nx,ny,0.0f32)?;
+   crsinput::fill(&mut in_array,config.input,config.seed);
+   let mut buffer: Vec<f32> = Vec::new();
+   if buffer.try_reserve_exact(elements + shift).is_err() {
+      return Err(BenchError::Allocation { nx: nx + shift, ny });
+   }
+   buffer.resize(elements + shift,0.0);
+   let (in_start,out_start) = if offset < 0 { (shift,0) } else { (0,shift) };
+   buffer[in_start..in_start + elements].copy_from_slice(in_array.as_slice());
+
+   //  See the Safety section of crssub_alias.rs: both regions are nx * ny
+   //  values within the buffer, and nothing else touches the buffer until
+   //  the call returns.
+
+   let base = buffer.as_mut_ptr();
+   unsafe {
+      crssub_alias::csub_alias(base.add(in_start),base.add(out_start),nx,ny);
+   }
+   let output = &buffer[out_start..out_start + elements];
+   let mut wrong = 0;
+   let mut shown = Vec::new();
+   for iy in 0..ny {
+      for ix in 0..nx {
+         let expected = in_array[[ix,iy]] + f32::from_index(ix + iy);
+         let value = output[iy * nx + ix];
+         if value.to_bits() != expected.to_bits() {
+            if shown.len() < ALIAS_SHOWN {
+               shown.push(format!("   [{},{}]: {} rather than {}",ix,iy,
+                                                            value,expected));
+            }
+            wrong += 1;
+         }
+      }
+   }
+   let mut out_array = Array2D::try_new(nx,ny,0.0f32)?;
+   crssub_rawptr::csub_rawptr(&in_array,&mut out_array);
+   let separate = crsverify::verify(&in_array,&out_array);
+
+   println!("Aliased output: {} rows of {} columns, with the output {} \
+             elements {} the input in memory",ny,nx,shift,
+             if offset < 0 { "before" } else { "after" });
+   println!("Through csub_alias(): {} of the {} elements are wrong",wrong,
+                                                                   elements);
+   for line in &shown {
+      println!("{}",line);
+   }
+   if wrong == 0 {
+      println!("   Each element was read before it was overwritten - which \
+                            is luck, not something the kernel promises");
+   }
+   println!("Through csub_rawptr(), with separate arrays: results {}",
+                               if separate { "correct" } else { "INCORRECT" });
+   println!("With the same overlap, csub_rawptr(&array,&mut array) would \
+                                  not compile - the borrow checker stops it");
+   Ok(if wrong == 0 { EXIT_OK } else { EXIT_VERIFY_FAILED })
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                      P l u g g a b l e   K e r n e l s
+
+//  One of the built-in modes that uses a flat Array2D, as a Kernel (see
+//  crskernel.rs). The configuration says which mode, and supplies anything
+//  else the kernel needs - the number of threads, the loop order or the
+//  output stride. A strided output is put back in order before it's
+//  checked. Whatever the mode needs made - the gather mode's permutation
+//  index, say - is made once, when the kernel is. The self test uses this
+//  to run the built-in modes through run_benchmark(). The usual runs don't
+//  - they call each kernel directly (see with_kernel()).
+
+struct BuiltinKernel {
+   config: BenchConfig,
+   prepared: Prepared<f32>,
+}
+
+impl BuiltinKernel {
+
+   //  Returns None for the modes that use vectors of vectors.
+
+   fn new (config: &BenchConfig) -> Option<BuiltinKernel> {
+      if !config.mode.info().flat { return None; }
+      Some(BuiltinKernel { config: config.clone(),
+                                              prepared: prepare(config) })
+   }
+}
+
+impl Kernel for BuiltinKernel {
+   fn name (&self) -> &str {
+      self.config.mode.name()
+   }
+
+   fn run (&self,input: &Array2D<f32>,output: &mut Array2D<f32>) {
+      call_prepared(&self.config,input,output,&self.prepared);
+   }
+
+   fn verify (&self,input: &Array2D<f32>,output: &Array2D<f32>) -> bool {
+      let check = self.config.check();
+      match self.config.out_stride {
+         Some(stride) =>
+                  check.verify(input,&crssub_strided::gather(output,stride)),
+         None => check.verify(input,output),
+      }
+   }
+
+   fn flops (&self,nx: usize,ny: usize) -> usize {
+      self.config.flops() * nx * ny
+   }
+
+   fn bytes (&self,nx: usize,ny: usize) -> usize {
+      self.config.mode.info().bytes * nx * ny
+   }
+
+   fn threads (&self) -> usize {
+      if self.config.mode.is_parallel() { self.config.threads } else { 1 }
+   }
+}
+
+//  Runs and times any Kernel, with the array size, input, warmup, trials
+//  and so on given by the configuration - though not its mode, and none of
+//  the diagnostics - and checks the kernel's results the way the kernel
+//  says they should be checked. Nothing is reported. The result is just
+//  returned.
+
+pub fn run_benchmark (config: &BenchConfig,kernel: &dyn Kernel)
+                                          -> Result<BenchResult,BenchError> {
+   let (nx,ny) = (config.nx,config.ny);
+   let in_array = crsinput::make_input(nx,ny,config.input,config.seed)?;
+   let mut out_array = Array2D::try_new(nx,ny,0.0f32)?;
+   let trials = time_kernel(config,
+                        || kernel.run(black_box(&in_array),&mut out_array));
+   let verified = kernel.verify(&in_array,&out_array);
+   let (calls,min_ns,mean_ns) = per_call_times(config,&trials);
+   let (sem_ns,ci95_ns) = per_call_uncertainty(config,&trials);
+   let (checksum_algo,checksum) = checksum_of(config,out_array.as_slice());
+   Ok(BenchResult {
+      label: config.label.clone(),
+      mode: kernel.name().to_string(),
+      ny,
+      nx,
+      nz: None,
+      repeats: config.nrpt,
+      trials: config.trials,
+      inner_repeat: config.inner_repeat,
+      threads: kernel.threads(),
+      input: config.input.name().to_string(),
+      seed: config.seed,
+      loop_order: config.loop_order.name().to_string(),
+      budget_ms: config.budget_ms,
+      out_stride: config.out_stride,
+      perm_seed: config.perm_seed,
+      window: config.window_shape().map(|shape| shape.0),
+      window_stride: config.window_shape().map(|shape| shape.1),
+      unroll: config.unroll_factor(),
+      expr: config.expression().map(|expr| expr.to_string()),
+      fresh_alloc: false,
+      hugepages: false,
+      mlock: false,
+      time_source: config.time_source.effective().name().to_string(),
+      flops: kernel.flops(nx,ny) / (nx * ny),
+      bytes: kernel.bytes(nx,ny) / (nx * ny),
+      peak_bandwidth: config.peak_bandwidth,
+      calls,
+      min_ns_per_call: min_ns,
+      mean_ns_per_call: mean_ns,
+      sem_ns_per_call: sem_ns,
+      ci95_ns_per_call: ci95_ns,
+      verified,
+      checksum_algo,
+      checksum,
+      failed: false,
+      precision: config.precision,
+   })
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                      A s s e m b l y   R e p o r t
+
+//  The number of loops listed for each kernel, smallest first.
+
+const ASM_LOOPS_SHOWN: usize = 4;
+
+//  Reads an assembly listing (see crsasm.rs for how to get one), of this
+//  program or of one of the original test programs, and lists the kernels
+//  that the listing has as functions of their own - a generic kernel can
+//  have a copy for each element type - with their sizes and the sizes of
+//  their loops, and any main program, which is where the original programs'
+//  kernels end up when they're inlined. Then it lists the calls made to
+//  each kernel, and where from. Returns the exit status - EXIT_OK if there
+//  are no such calls, so every kernel was inlined wherever it's called
+//  directly, and EXIT_VERIFY_FAILED if there are.
+
+fn asm_report (path: &str) -> i32 {
+   let functions = match crsasm::read_listing(path) {
+      Ok(functions) => functions,
+      Err(error) => fail(&error),
+   };
+   let kernels = functions.iter().filter(|function| function.is_kernel())
+                                                                     .count();
+   println!("Assembly listing {}: {} functions, {} of them kernels",path,
+                                                     functions.len(),kernels);
+   let shown = functions.iter().filter(|function| function.is_kernel()
+                                         || function.name.ends_with("::main"));
+   for function in shown {
+      let loops: Vec<String> = function.loops.iter().take(ASM_LOOPS_SHOWN)
+                                    .map(|size| size.to_string()).collect();
+      println!("   {}",function.name);
+      println!("      {} instructions, {}, {} calls",function.instructions,
+         if loops.is_empty() { "no loops".to_string() }
+                  else { format!("loops of {} instructions",loops.join(", ")) },
+         function.calls.len());
+   }
+
+   //  The calls, collected by the kernel called, and then by the caller,
+   //  each in the order they're first found.
+
+   let mut calls: Vec<(&str,Vec<(&str,usize)>)> = Vec::new();
+   for function in &functions {
+      for callee in function.calls.iter()
+                                  .filter(|name| crsasm::is_kernel_name(name)) {
+         let index = match calls.iter().position(|call| call.0 == callee) {
+            Some(index) => index,
+            None => { calls.push((callee,Vec::new())); calls.len() - 1 }
+         };
+         let callers = &mut calls[index].1;
+         match callers.iter_mut().find(|caller| caller.0 == function.name) {
+            Some(caller) => caller.1 += 1,
+            None => callers.push((&function.name,1)),
+         }
+      }
+   }
+   if calls.is_empty() {
+      println!("No calls to kernels - every direct call was inlined");
+      return EXIT_OK;
+   }
+   println!("Calls to kernels that weren't inlined:");
+   let mut total = 0;
+   for (callee,callers) in &calls {
+      println!("   {}, called from",callee);
+      for &(caller,count) in callers {
+         println!("      {} ({})",caller,count);
+         total += count;
+      }
+   }
+   println!("{} call(s) to kernels that weren't inlined",total);
+   EXIT_VERIFY_FAILED
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                            S e l f   T e s t
+
+//  The array size used by the self test. The odd number of columns means
+//  the chunked kernel has a remainder, and the number of rows doesn't
+//  divide evenly between SELF_TEST_THREADS threads.
+
+const SELF_TEST_NX: usize = 19;
+const SELF_TEST_NY: usize = 7;
+const SELF_TEST_THREADS: usize = 3;
+
+//  The shapes, as (columns,rows), all with the same number of elements,
+//  that each mode is run on to check that its result doesn't depend on how
+//  the elements are split between rows and columns - a kernel that mixes
+//  up nx and ny, or assumes something about the aspect ratio, will get at
+//  least one of them wrong.
+
+const SELF_TEST_SHAPES: [(usize,usize); 4] =
+                               [(100,200),(200,100),(1,20000),(20000,1)];
+
+//  The random shapes each mode is also run on: SELF_TEST_RANDOM_CASES of
+//  them, each side from 1 to SELF_TEST_RANDOM_SIDE, drawn by a generator
+//  seeded with SELF_TEST_RANDOM_SEED, so every mode gets the same shapes,
+//  and every run of the self test the same ones again.
+
+const SELF_TEST_RANDOM_CASES: usize = 25;
+const SELF_TEST_RANDOM_SIDE: usize = 64;
+const SELF_TEST_RANDOM_SEED: u64 = 20191006;
+
+//  The random command lines the argument parser is given: case n is made by
+//  a generator seeded with SELF_TEST_FUZZ_SEED + n, and has up to
+//  FUZZ_MAX_ARGS arguments. The self test runs the first
+//  SELF_TEST_FUZZ_CASES, and --fuzz-args as many as it's asked for.
+
+const SELF_TEST_FUZZ_CASES: usize = 500;
+const SELF_TEST_FUZZ_SEED: u64 = 20191007;
+const FUZZ_MAX_ARGS: u64 = 8;
+
+//  The deepest an expression given to the parser is nested. Far more than
+//  the parser allows, it's enough to overflow the stack of a parser that
+//  didn't limit it. The depths are spread evenly over the powers of ten up
+//  to it, so most expressions are short, and the fuzz test stays quick.
+
+const FUZZ_MAX_NESTING: usize = 100000;
+
+//  The options the fuzz test leaves out - those that make the parser exit,
+//  and those that read or write a file.
+
+const FUZZ_EXCLUDED: [&str; 8] = ["--help","--list-modes","--selftest",
+   "--asm-report","--fuzz-args","--config","--export-config",
+   "--interactive"];
+
+//  The values the fuzz test picks from, along with the mode names and
+//  random strings - the sort of thing that catches out a parser.
+
+const FUZZ_VALUES: [&str; 50] = ["","0","1","-1","7","2000","1k","4Ki",
+   "3M","2Gi","Ki","18446744073709551615","18446744073709551616",
+   "99999999999Gi","0.5","-0.0","1e308","nan","inf","-inf","csv","prose",
+   "jsonl","prometheus","random","gaussian","standard","ij","ji","little",
+   "big","native","sum","fnv","crc32","monotonic","cputime","flat,index",
+   "flat,","10,20x30","x","1,2x","0/1","1/0","3/2","in + ix * 2","((in",
+   "-","--","\u{e9}"];
+
+//  The characters the random strings are made of.
+
+const FUZZ_CHARS: &[u8] = b"0123456789kMGi.-+,x/e";
+
+//  A builder for a self test configuration, with the size and everything
+//  else set for a single, quick, call.
+
+fn self_test_builder (mode: &str) -> BenchConfigBuilder {
+   BenchConfig::builder().mode(mode).nx(SELF_TEST_NX).ny(SELF_TEST_NY)
+                        .nrpt(1).trials(1).warmup(0).threads(SELF_TEST_THREADS)
+}
+
+//  Runs one self test variant with f32 elements, using run_trials() just as
+//  a real run would, on random input, and checks the result the way the
+//  mode says it should be checked. Returns true if it passed.
+
+fn self_test_f32 (builder: BenchConfigBuilder) -> bool {
+   self_test_seeded(builder,1)
+}
+
+//  As self_test_f32(), but with the random input made using the given seed.
+
+fn self_test_seeded (builder: BenchConfigBuilder,seed: u64) -> bool {
+   let config = match builder.build() {
+      Ok(config) => config,
+      Err(error) => { eprintln!("{}",error); return false; }
+   };
+   let in_array = match crsinput::make_input(config.nx,config.ny,
+                                                      Input::Random,seed) {
+      Ok(in_array) => in_array,
+      Err(error) => { eprintln!("{}",error); return false; }
+   };
+   match run_once(&config,config.mode,&in_array) {
+      Ok(out_array) => config.check().verify(&in_array,&out_array),
+      Err(error) => { eprintln!("{}",error); false }
+   }
+}
+
+//  Runs one mode with f32 elements on each of SELF_TEST_SHAPES, and
+//  returns true if its results were correct for all of them. Each failing
+//  shape is listed, since the outcome for the mode doesn't say which.
+
+fn self_test_shapes (mode: &str) -> bool {
+   let mut passed = true;
+   for &(nx,ny) in &SELF_TEST_SHAPES {
+      if !self_test_f32(self_test_builder(mode).nx(nx).ny(ny)) {
+         eprintln!("{} gets a {} x {} array wrong",mode,nx,ny);
+         passed = false;
+      }
+   }
+   passed
+}
+
+//  Runs one mode with f32 elements on the random shapes, each with its own
+//  random input, and returns true if its results were correct for all of
+//  them. This is a property test in a small way: whatever the shape and
+//  the values, the output must be what the mode's check says it should be
+//  - for most modes, out[iy][ix] == in[iy][ix] + (ix + iy) as f32. Each
+//  failing case is listed, with the command line that runs it again.
+
+fn self_test_random (mode: &str) -> bool {
+   let mut rng = crsinput::Rng::new(SELF_TEST_RANDOM_SEED);
+   let mut passed = true;
+   for _ in 0..SELF_TEST_RANDOM_CASES {
+      let nx = (rng.next_u64() % SELF_TEST_RANDOM_SIDE as u64) as usize + 1;
+      let ny = (rng.next_u64() % SELF_TEST_RANDOM_SIDE as u64) as usize + 1;
+      let seed = rng.next_u64() >> 32;
+      if !self_test_seeded(self_test_builder(mode).nx(nx).ny(ny),seed) {
+         eprintln!("{} gets a random {} x {} array wrong (generator seed {}) \
+            - to repeat it: crsbench 1 {} {} --mode {} --trials 1 \
+            --threads {} --input random --seed {}",mode,nx,ny,
+            SELF_TEST_RANDOM_SEED,ny,nx,mode,SELF_TEST_THREADS,seed);
+         passed = false;
+      }
+   }
+   passed
+}
+
+//  Runs the argument parser on the random command lines numbered from
+//  first up to, but not including, last, returning false if any of them
+//  failed - see fuzz_case().
+
+fn self_test_fuzz (first: usize,last: usize) -> bool {
+   let options = fuzz_options();
+   (first..last).filter(|&case| !fuzz_case(&options,case)).count() == 0
+}
+
+//  The options the fuzz test uses: all those in the usage summary, apart
+//  from the FUZZ_EXCLUDED ones.
+
+fn fuzz_options () -> Vec<&'static str> {
+   USAGE.lines().filter_map(|line| line.split_whitespace().next())
+      .filter(|word| word.starts_with("--") && !FUZZ_EXCLUDED.contains(word))
+                                                                  .collect()
+}
+
+//  Makes random command line number case from the options, the values,
+//  deeply nested expressions and random strings, and runs the parser on
+//  it, both with strict parsing and without. It fails if the parser
+//  panics, gives a configuration that doesn't build again, or accepts the
+//  command line with strict parsing but gives a different configuration
+//  without it, and the case is listed with its arguments. A long argument
+//  is listed by its length and how it starts.
+
+fn fuzz_case (options: &[&str],case: usize) -> bool {
+   let mut rng = crsinput::Rng::new(SELF_TEST_FUZZ_SEED + case as u64);
+   let mut args = vec!["crsbench".to_string()];
+   for _ in 0..rng.next_u64() % (FUZZ_MAX_ARGS + 1) {
+      let pick = rng.next_u64() as usize;
+      match rng.next_u64() % 6 {
+         0 | 1 => args.push(options[pick % options.len()].to_string()),
+         2 => args.push(FUZZ_VALUES[pick % FUZZ_VALUES.len()].to_string()),
+         3 => args.push(crsconfig::MODES[pick % crsconfig::MODES.len()].name
+                                                                 .to_string()),
+         4 => args.push((0..pick % 7).map(|_| FUZZ_CHARS[rng.next_u64()
+                             as usize % FUZZ_CHARS.len()] as char).collect()),
+         _ => {
+            let fraction = (pick % 1001) as f64 / 1000.0;
+            let depth = (FUZZ_MAX_NESTING as f64).powf(fraction) as usize;
+            let nested = match rng.next_u64() % 3 {
+               0 => format!("{}in{}","(".repeat(depth),")".repeat(depth)),
+               1 => format!("{}in","-".repeat(depth)),
+               _ => format!("{}in","(".repeat(depth)),
+            };
+            args.extend(["--mode".to_string(),"expr".to_string(),
+                                                 "--expr".to_string(),nested]);
+         }
+      }
+   }
+   let parse = |strict: bool| {
+      QUIET_WARNINGS.store(!strict,Ordering::Relaxed);
+      let parsed = std::panic::catch_unwind(|| parse_args(&args,strict));
+      QUIET_WARNINGS.store(false,Ordering::Relaxed);
+      parsed
+   };
+   let (strict,lenient) = (parse(true),parse(false));
+   let problem = match (&strict,&lenient) {
+      (Err(_),_) => Some("panics, with strict parsing".to_string()),
+      (_,Err(_)) => Some("panics, without strict parsing".to_string()),
+      (Ok(Ok(strict)),Ok(Ok(lenient))) if strict.fields() != lenient.fields() =>
+         Some("accepts the command line with strict parsing, but gives a \
+                        different configuration without it".to_string()),
+      (Ok(Ok(_)),Ok(Err(error))) => Some(format!("accepts the command line \
+                  with strict parsing, but not without it ({})",error)),
+      _ => [strict,lenient].iter().find_map(|parsed| match parsed {
+         Ok(Ok(config)) => config.to_builder().build().err().map(|error|
+               format!("accepts a configuration that doesn't build again \
+                                                            ({})",error)),
+         _ => None,
+      }),
+   };
+   match problem {
+      None => true,
+      Some(problem) => {
+         let quoted: Vec<String> = args[1..].iter().map(|arg|
+            if arg.len() <= 40 { format!("{:?}",arg) } else {
+               let start: String = arg.chars().take(20).collect();
+               format!("{:?}... ({} characters)",start,arg.len())
+            }).collect();
+         eprintln!("Fuzz case {}: the argument parser {}, given {}",case,
+                                                     problem,quoted.join(" "));
+         false
+      }
+   }
+}
+
+//  Runs the fuzz test of the argument parser, for --fuzz-args, on the first
+//  count random command lines, and reports how many failed. Returns the
+//  exit status.
+
+fn fuzz_args (count: usize) -> i32 {
+   let start = Instant::now();
+   let options = fuzz_options();
+   let failed = (0..count).filter(|&case| !fuzz_case(&options,case)).count();
+   println!("{}: {} random command lines, {} failed, in {:.1} ms",
+            if failed == 0 { "PASS" } else { "FAIL" },count,failed,
+            start.elapsed().as_secs_f64() * 1000.0);
+   if failed == 0 { EXIT_OK } else { EXIT_VERIFY_FAILED }
+}
+
+//  Runs one mode once with f32 elements, as self_test_f32() does, but
+//  checks the result bit for bit against strict IEEE arithmetic, as
+//  --strict-fp does.
+
+fn self_test_strict (builder: BenchConfigBuilder) -> bool {
+   let config = match builder.build() {
+      Ok(config) => config,
+      Err(error) => { eprintln!("{}",error); return false; }
+   };
+   let outputs = crsinput::make_input(config.nx,config.ny,Input::Random,1)
+      .and_then(|in_array| Ok((run_once(&config,config.mode,&in_array)?,
+                                  crsverify::strict_reference(&in_array)?)));
+   match outputs {
+      Ok((out_array,reference)) => crsverify::compare_outputs(&out_array,
+                             &reference,0.0).first_difference.is_none(),
+      Err(error) => { eprintln!("{}",error); false }
+   }
+}
+
+//  Runs one self test variant with elements of type T, which only works for
+//  the modes with generic kernels. The input values are a simple mix of
+//  the indices, so that they aren't the same along any row or column.
+
+fn self_test_generic<T: Element> (builder: BenchConfigBuilder) -> bool {
+   let config = match builder.build() {
+      Ok(config) => config,
+      Err(error) => { eprintln!("{}",error); return false; }
+   };
+   let in_array = Array2D::from_fn(config.nx,config.ny,
+                              |ix,iy| T::from_index((ix * 7 + iy * 13) % 31));
+   let mut out_array = Array2D::new(config.nx,config.ny,T::from_index(0));
+   if !call_generic(&config,&in_array,&mut out_array) { return false; }
+   if let Some(stride) = config.out_stride {
+      out_array = crssub_strided::gather(&out_array,stride);
+   }
+   config.check().verify_generic(&in_array,&out_array)
+}
+
+//  Checks that the tracking allocator counts what it should: a flat array
+//  is a single allocation, and a nested one is one for each row plus one
+//  for the vector of rows.
+
+#[cfg(feature = "trackalloc")]
+fn self_test_allocations () -> bool {
+   let (nx,ny) = (SELF_TEST_NX,SELF_TEST_NY);
+   let flat = construction_allocations(crsconstruct::Strategy::Flat,nx,ny);
+   let nested =
+            construction_allocations(crsconstruct::Strategy::Nested,nx,ny);
+   let bytes = nx * ny * std::mem::size_of::<f32>();
+   match (flat,nested) {
+      (Some((1,flat_peak)),Some((made,_))) => made == ny + 1 &&
+                                                      flat_peak >= bytes,
+      _ => false,
+   }
+}
+
+//  Checks that the expression parser gets precedence and associativity
+//  right, by parsing some expressions and checking that each is written
+//  out in the standard form it should be, that the standard form parses to
+//  the same thing, and that both evaluations give the value they should.
+//  Then checks that some expressions that make no sense are rejected.
+
+fn self_test_expr () -> bool {
+   let (value,ix,iy) = (1.5f32,4,2);
+   let cases = [
+      ("in+ix+iy","in + ix + iy",7.5),
+      ("in + ix * iy","in + ix * iy",9.5),
+      ("(in + ix) * iy","(in + ix) * iy",11.0),
+      ("in - (ix - iy)","in - (ix - iy)",-0.5),
+      ("in - ix - iy","in - ix - iy",-4.5),
+      ("-(in * 2) / ix","-(in * 2) / ix",-0.75),
+      ("((in))/(ix*iy)*8","in / (ix * iy) * 8",1.5),
+      ("- -in","--in",1.5),
+   ];
+   let mut passed = true;
+   for &(text,standard,wanted) in &cases {
+      let right = match Expr::parse(text) {
+         Ok(expr) => expr.to_string() == standard
+            && Expr::parse(standard) == Ok(expr)
+            && expr.eval(value,ix,iy) == wanted
+            && expr.reference(value,ix,iy) == wanted,
+         Err(_) => false,
+      };
+      if !right {
+         eprintln!("The expression '{}' isn't understood properly",text);
+         passed = false;
+      }
+   }
+   for text in &["","in +","in ix","(in","in)","x","in % 2","in * * 2"] {
+      if Expr::parse(text).is_ok() {
+         eprintln!("The expression '{}' is accepted, but shouldn't be",text);
+         passed = false;
+      }
+   }
+   passed
+}
+
+//  Checks that times are scaled to the unit they should be, including at
+//  the edges, where a time that rounds up to 1000 of one unit has to go in
+//  the next, and that with the scaling off they stay in ns.
+
+fn self_test_units () -> bool {
+   let cases = [
+      (0.0,3,"0.000 ns"),
+      (0.25,2,"0.25 ns"),
+      (999.0,1,"999.0 ns"),
+      (999.96,1,"1.0 µs"),
+      (3210.0,2,"3.21 µs"),
+      (-3210.0,2,"-3.21 µs"),
+      (999_999.0,3,"999.999 µs"),
+      (999_999.6,0,"1 ms"),
+      (1.5e6,1,"1.5 ms"),
+      (5.0e9,3,"5.000 s"),
+      (2.5e12,1,"2500.0 s"),
+      (f64::NAN,1,"NaN ns"),
+      (f64::INFINITY,1,"inf ns"),
+   ];
+   let mut passed = true;
+   for &(ns,places,wanted) in &cases {
+      let text = crsunits::time_text(ns,places,true);
+      if text != wanted {
+         eprintln!("{} ns to {} places is shown as '{}', not '{}'",ns,places,
+                                                                text,wanted);
+         passed = false;
+      }
+   }
+   if crsunits::time_text(3210.0,2,false) != "3210.00 ns" {
+      eprintln!("A time is scaled even with the scaling off");
+      passed = false;
+   }
+   passed
+}
+
+//  Runs the 3D kernel, with elements of type T, on a small 3D array, with
+//  input values mixed up from the indices as for self_test_generic().
+
+fn self_test_3d<T: Element> (nz: usize) -> bool {
+   let (nx,ny) = (SELF_TEST_NX,SELF_TEST_NY);
+   let in_array = Array3D::from_fn(nx,ny,nz,
+               |ix,iy,iz| T::from_index((ix * 7 + iy * 13 + iz * 5) % 31));
+   let mut out_array = match Array3D::try_new(nx,ny,nz,T::from_index(0)) {
+      Ok(out_array) => out_array,
+      Err(error) => { eprintln!("{}",error); return false; }
+   };
+   crssub3d::csub3d(&in_array,&mut out_array);
+   (0..nz).all(|iz| (0..ny).all(|iy| (0..nx).all(|ix| out_array[[ix,iy,iz]]
+                     == in_array[[ix,iy,iz]] + T::from_index(ix + iy + iz))))
+}
+
+//  The example kernel from the comments at the top of crskernel.rs, used to
+//  check that a kernel written against the Kernel trait, with nothing but
+//  the defaults, can be run by run_benchmark().
+
+struct ZipKernel;
+
+impl Kernel for ZipKernel {
+   fn name (&self) -> &str { "zip" }
+   fn run (&self,input: &Array2D<f32>,output: &mut Array2D<f32>) {
+      let nx = input.nx();
+      for (index,(out,value)) in output.as_mut_slice().iter_mut()
+                                       .zip(input.as_slice()).enumerate() {
+         *out = value + (index % nx + index / nx) as f32;
+      }
+   }
+}
+
+//  Runs a kernel through run_benchmark(), on random input, and returns true
+//  if its results were correct.
+
+fn self_test_kernel (kernel: &dyn Kernel) -> bool {
+   let config = match self_test_builder("flat").input("random").build() {
+      Ok(config) => config,
+      Err(error) => { eprintln!("{}",error); return false; }
+   };
+   match run_benchmark(&config,kernel) {
+      Ok(result) => result.verified,
+      Err(error) => { eprintln!("{}",error); false }
+   }
+}
+
+//  Writes a small array of random values to a raw file in the given byte
+//  order, checks that the file holds the bytes it should, worked out here
+//  from the bits of each value, and that it reads back exactly. The file
+//  goes in the temporary directory, and is removed afterwards.
+
+fn self_test_raw (endian: Endian) -> bool {
+   let (nx,ny) = (SELF_TEST_NX,SELF_TEST_NY);
+   let array = match crsinput::make_input(nx,ny,Input::Random,1) {
+      Ok(array) => array,
+      Err(error) => { eprintln!("{}",error); return false; }
+   };
+   let big = match endian {
+      Endian::Little => false,
+      Endian::Big => true,
+      Endian::Native => cfg!(target_endian = "big"),
+   };
+   let mut expected = Vec::with_capacity(nx * ny * 4);
+   for value in array.as_slice() {
+      let bits = value.to_bits();
+      let mut bytes = [bits as u8,(bits >> 8) as u8,(bits >> 16) as u8,
+                                                          (bits >> 24) as u8];
+      if big { bytes.reverse(); }
+      expected.extend_from_slice(&bytes);
+   }
+   let name = format!("crsbench_selftest_{}_{}.raw",process::id(),
+                                                               endian.name());
+   let path = env::temp_dir().join(name).display().to_string();
+   let passed = match crsverify::write_raw(&path,&array,endian)
+                .and_then(|_| crsverify::read_raw(&path,nx,ny,endian)) {
+      Ok(read_back) => fs::read(&path).ok() == Some(expected)
+         && read_back.as_slice().iter().zip(array.as_slice())
+                                       .all(|(a,b)| a.to_bits() == b.to_bits()),
+      Err(error) => { eprintln!("{}",error); false }
+   };
+   let _ = fs::remove_file(&path);
+   passed
+}
+
+//  Writes a small array of random values to a FITS file, in a different
+//  shape from the usual self test array, and checks that the file is a
+//  whole number of FITS blocks, that a configuration using it takes its
+//  dimensions from it, and that its pixel data loads as the input exactly
+//  and gives the flat mode's usual results. The file goes in the temporary
+//  directory, and is removed afterwards.
+
+#[cfg(feature = "fits")]
+fn self_test_fits () -> bool {
+   let (nx,ny) = (SELF_TEST_NX + 3,SELF_TEST_NY + 1);
+   let array = match crsinput::make_input(nx,ny,Input::Random,1) {
+      Ok(array) => array,
+      Err(error) => { eprintln!("{}",error); return false; }
+   };
+   let name = format!("crsbench_selftest_{}.fits",process::id());
+   let path = env::temp_dir().join(name).display().to_string();
+   let loaded = crsfits::write_image(&path,&array)
+      .and_then(|_| self_test_builder("flat").fits(&path).fits_data(true)
+                                                                   .build())
+      .and_then(|config| {
+         let mut in_array = Array2D::try_new(config.nx,config.ny,0.0f32)?;
+         load_fits(&config,&mut in_array)?;
+         Ok((config,in_array))
+      });
+   let blocks = fs::metadata(&path).is_ok_and(|file| file.len() % 2880 == 0);
+   let passed = match loaded {
+      Ok((config,in_array)) => blocks && config.nx == nx && config.ny == ny
+         && in_array.as_slice().iter().zip(array.as_slice())
+                                 .all(|(a,b)| a.to_bits() == b.to_bits())
+         && match run_once(&config,config.mode,&in_array) {
+               Ok(out_array) => config.check().verify(&in_array,&out_array),
+               Err(error) => { eprintln!("{}",error); false }
+            },
+      Err(error) => { eprintln!("{}",error); false }
+   };
+   let _ = fs::remove_file(&path);
+   passed
+}
+
+//  Runs every available mode, with each element type its kernel supports,
+//  and the variations on the flat mode, all on a tiny array, and reports
+//  the outcome of each. Returns the exit status - EXIT_OK if they all
+//  passed.
+
+fn self_test () -> i32 {
+   let start = Instant::now();
+   let mut outcomes: Vec<(String,bool)> = Vec::new();
+   let mut skipped = Vec::new();
+   for info in crsconfig::MODES {
+      if !info.available {
+         skipped.push(format!("{} (needs the {} feature)",info.name,
+                                                  info.feature.unwrap_or("?")));
+         continue;
+      }
+      outcomes.push((format!("{}, f32",info.name),
+                                  self_test_f32(self_test_builder(info.name))));
+      if info.flat {
+         outcomes.push((format!("{}, f64",info.name),
+                   self_test_generic::<f64>(self_test_builder(info.name))));
+         outcomes.push((format!("{}, i32",info.name),
+                   self_test_generic::<i32>(self_test_builder(info.name))));
+         outcomes.push((format!("{}, i64",info.name),
+                   self_test_generic::<i64>(self_test_builder(info.name))));
+      }
+   }
+
+   //  The same kernels, checked as --strict-fp checks them - except for
+   //  the expr mode, which evaluates in + ix + iy as (in + ix) + iy, and
+   //  isn't meant to give the usual results exactly, and the binary,
+   //  regtranspose, affine, copy and prefix modes, which don't leave the
+   //  usual results where the reference has them.
+
+   for info in crsconfig::MODES {
+      if !info.available || info.mode == Mode::Expr || info.mode == Mode::Binary
+                           || info.mode == Mode::RegTranspose
+                           || info.mode == Mode::Affine
+                           || info.mode == Mode::Copy
+                           || info.mode == Mode::Prefix { continue; }
+      outcomes.push((format!("{}, strict fp, f32",info.name),
+                              self_test_strict(self_test_builder(info.name))));
+   }
+
+   //  The same kernels, on arrays with the same number of elements in
+   //  different shapes.
+
+   let shapes: Vec<String> = SELF_TEST_SHAPES.iter()
+                            .map(|&(nx,ny)| format!("{}x{}",nx,ny)).collect();
+   for info in crsconfig::MODES {
+      if !info.available { continue; }
+      outcomes.push((format!("{}, shapes {}, f32",info.name,shapes.join(" ")),
+                                                 self_test_shapes(info.name)));
+   }
+
+   //  And on random shapes, with random input.
+
+   for info in crsconfig::MODES {
+      if !info.available { continue; }
+      outcomes.push((format!("{}, {} random shapes up to {}x{}, f32",
+                     info.name,SELF_TEST_RANDOM_CASES,SELF_TEST_RANDOM_SIDE,
+                     SELF_TEST_RANDOM_SIDE),self_test_random(info.name)));
+   }
+
+   //  The same kernels again, through the Kernel trait, and the example of
+   //  a kernel written against it.
+
+   for info in crsconfig::MODES {
+      if !info.available { continue; }
+      let config = match self_test_builder(info.name).input("random").build() {
+         Ok(config) => config,
+         Err(error) => { eprintln!("{}",error); continue; }
+      };
+      if let Some(kernel) = BuiltinKernel::new(&config) {
+         outcomes.push((format!("{}, as a Kernel",info.name),
+                                                 self_test_kernel(&kernel)));
+      }
+   }
+   outcomes.push(("zip, an example Kernel".to_string(),
+                                               self_test_kernel(&ZipKernel)));
+
+   //  The variations that need more than just the mode. The const mode
+   //  needs one of its specialised widths to use anything but the fallback.
+
+   outcomes.push(("flat, ji loop order, f32".to_string(),
+                 self_test_f32(self_test_builder("flat").loop_order("ji"))));
+   outcomes.push(("flat, output stride 5, f32".to_string(),
+                 self_test_f32(self_test_builder("flat").out_stride(Some(5)))));
+   outcomes.push(("flat, output stride 5, f64".to_string(),
+      self_test_generic::<f64>(self_test_builder("flat").out_stride(Some(5)))));
+   outcomes.push(("gather, shuffled, f32".to_string(),
+             self_test_f32(self_test_builder("gather").perm_seed(Some(3)))));
+   outcomes.push(("window, 3 rows moving on by 2, f32".to_string(),
+             self_test_f32(self_test_builder("window").window(Some(3))
+                                                  .window_stride(Some(2)))));
+   for &factor in &crssub_unrolled::UNROLL_FACTORS {
+      outcomes.push((format!("unrolled by {}, f32",factor),
+            self_test_f32(self_test_builder("unrolled").unroll(Some(factor)))));
+      outcomes.push((format!("unrolled by {}, i64",factor),
+            self_test_generic::<i64>(self_test_builder("unrolled")
+                                                     .unroll(Some(factor)))));
+   }
+   outcomes.push(("expr, in * 3 - ix / 2 + -iy, f32".to_string(),
+             self_test_f32(self_test_builder("expr")
+                          .expr(Some("in * 3 - ix / 2 + -iy".to_string())))));
+   outcomes.push(("expr, parsing and evaluation".to_string(),
+                                                        self_test_expr()));
+   outcomes.push(("const, 256 columns, f32".to_string(),
+                 self_test_f32(self_test_builder("const").nx(256).ny(3))));
+   outcomes.push(("threads, 1 thread, f32".to_string(),
+                 self_test_f32(self_test_builder("threads").threads(1))));
+   outcomes.push(("split, more threads than rows, f32".to_string(),
+                 self_test_f32(self_test_builder("split").threads(16))));
+   outcomes.push(("flat, 3D with 5 planes, f32".to_string(),
+                                                  self_test_3d::<f32>(5)));
+   outcomes.push(("flat, 3D with 5 planes, i64".to_string(),
+                                                  self_test_3d::<i64>(5)));
+   for &endian in &[Endian::Little,Endian::Big,Endian::Native] {
+      outcomes.push((format!("raw file round trip, {} endian",endian.name()),
+                                                      self_test_raw(endian)));
+   }
+   outcomes.push(("time scaling to ns, µs, ms and s".to_string(),
+                                                        self_test_units()));
+   #[cfg(feature = "fits")]
+   outcomes.push(("FITS file round trip, as dimensions and input".to_string(),
+                                                         self_test_fits()));
+   outcomes.push((format!("argument parser, {} random command lines",
+              SELF_TEST_FUZZ_CASES),self_test_fuzz(0,SELF_TEST_FUZZ_CASES)));
+   #[cfg(feature = "trackalloc")]
+   outcomes.push(("allocation counts for flat and nested builds".to_string(),
+                                                   self_test_allocations()));
+
+   println!("Self test, {} rows of {} columns unless noted:",SELF_TEST_NY,
+                                                                SELF_TEST_NX);
+   for &(ref label,passed) in &outcomes {
+      println!("   {}  {}",if passed { "PASS" } else { "FAIL" },label);
+   }
+   for label in &skipped {
+      println!("   SKIP  {}",label);
+   }
+   let failed = outcomes.iter().filter(|outcome| !outcome.1).count();
+   println!("{}: {} variants, {} failed, {} skipped, in {:.1} ms",
+            if failed == 0 { "PASS" } else { "FAIL" },outcomes.len(),failed,
+            skipped.len(),start.elapsed().as_secs_f64() * 1000.0);
+   if failed == 0 { EXIT_OK } else { EXIT_VERIFY_FAILED }
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                            R u n n i n g
+
+//  Runs a configuration: either one of the runs that replace the usual ones
+//  entirely, or the usual runs - the one configuration, or each of those
+//  in a sweep - reporting and recording the results as they are produced,
+//  followed by any drift report, frequency check, system information and
+//  comparison with a baseline. Returns the exit status, or an error if the
+//  run can't continue.
+
+fn run (config: &BenchConfig) -> Result<i32,BenchError> {
+
+   //  An equivalence check replaces the usual runs entirely.
+
+   if let Some((mode_a,mode_b)) = config.equiv {
+      return check_equiv(config,mode_a,mode_b);
+   }
+
+   //  So does a build comparison.
+
+   if let Some(paths) = config.build_comparison() {
+      return compare_build(config,&paths);
+   }
+
+   //  And a type comparison.
+
+   if config.compare_types {
+      return compare_types(config);
+   }
+
+   //  And so does a comparison of unroll factors.
+
+   if config.compare_unroll {
+      return compare_unroll(config);
+   }
+
+   //  And so does a false sharing study.
+
+   if config.false_share_study {
+      return false_share_study(config);
+   }
+
+   //  And so does a loop order study.
+
+   if config.order_study {
+      return order_study(config);
+   }
+
+   //  And so does a construction timing.
+
+   if config.bench_construction {
+      return Ok(bench_construction(config));
+   }
+
+   //  And so does a search for the cache cliff.
+
+   if config.find_cliff {
+      return find_cliff(config);
+   }
+
+   //  And so does a torture test.
+
+   if config.torture {
+      return torture(config);
+   }
+
+   //  As does a run with concurrent arrays.
+
+   if let Some(count) = config.concurrent_arrays {
+      return concurrent_arrays(config,count);
+   }
+
+   //  And, in a build that has it, the aliased output demonstration. It
+   //  never produces a result that could end up in a file.
+
+   #[cfg(feature = "danger_alias")]
+   if let Some(offset) = config.danger_alias {
+      eprintln!("Warning: --danger-alias deliberately breaks the rule that \
+          the output of the unsafe kernels doesn't overlap their input. Its \
+          results are meant to be wrong, and are for demonstration only");
+      return danger_alias(config,offset);
+   }
+
+   //  Work out the configurations to run - usually just the one.
+
+   let configs = match config.sweep_2d {
+      Some(ref sweep) => sweep_configs(config,sweep),
+      None => vec![config.clone()],
+   };
+
+   //  Run each configuration in turn. With CSV output the header is only
+   //  written once, and each line as soon as its result is available. A
+   //  drift check repeats the whole set of configurations, with a pause
+   //  between repetitions. In a sweep, a configuration that fails is
+   //  reported, and recorded as a failed result, and the sweep carries on.
+
+   let repetitions = config.drift_check.unwrap_or(1);
+   let mut results = Vec::new();
+   let mut append_path = config.append.clone();
+   #[cfg(feature = "sqlite")]
+   let mut db_path = config.sqlite.clone();
+   #[cfg(feature = "sqlite")]
+   let run_info = crsdb::run_info();
+   let mut stream = match config.report_tcp {
+      Some(ref address) => match ResultStream::connect(address) {
+         Ok(stream) => Some(stream),
+         Err(error) => {
+            eprintln!("Warning: {} - results won't be sent",error);
+            None
+         }
+      },
+      None => None,
+   };
+   for irep in 0..repetitions {
+      if irep > 0 && config.drift_pause_ms > 0 {
+         thread::sleep(Duration::from_millis(config.drift_pause_ms));
+      }
+      for one_config in &configs {
+         let result = match run_config(one_config) {
+            Ok(result) => result,
+            Err(error) if config.sweep_2d.is_some() => {
+               eprintln!("Warning: {} - carrying on with the rest of the \
+                                                               sweep",error);
+               failed_result(one_config)
+            }
+            Err(error) => return Err(error),
+         };
+         if config.format == Format::Csv {
+            if results.is_empty() { println!("{}",result.csv_header()); }
+            println!("{}",result.csv_row());
+         } else if config.format == Format::Jsonl {
+            println!("{}",result.json_line());
+            let _ = std::io::stdout().flush();
+         }
+         if let Some(path) = append_path.clone() {
+            if let Err(error) = crsreport::append_csv(&path,&result) {
+               eprintln!("Warning: {} - no more results will be appended",
+                                                                      error);
+               append_path = None;
+            }
+         }
+         #[cfg(feature = "sqlite")]
+         if let Some(path) = db_path.clone().filter(|_| !result.failed) {
+            if let Err(error) = crsdb::insert_result(&path,&run_info,&result) {
+               eprintln!("Warning: {} - no more results will be written to \
+                                                         the database",error);
+               db_path = None;
+            }
+         }
+         if let Some(ref mut connection) = stream {
+            if let Err(error) = connection.send(&result) {
+               eprintln!("Warning: {} - no more results will be sent",error);
+               stream = None;
+            }
+         }
+         results.push(result);
+      }
+   }
+   if config.format == Format::Prometheus {
+      print!("{}",crsreport::prometheus_text(&results));
+   }
+   if repetitions > 1 {
+      report_drift(config,&results,configs.len());
+   }
+
+   //  A sweep ends with a count of what did and didn't run.
+
+   let failures = results.iter().filter(|result| result.failed).count();
+   if config.sweep_2d.is_some() {
+      info(config,&format!("Sweep: {} run(s) succeeded, {} failed",
+                                          results.len() - failures,failures));
+   }
+
+   //  The frequency check is made straight after the timing, while the CPUs
+   //  are still likely to be running at the speed they were timed at.
+
+   if config.freq_check {
+      match crssysinfo::frequency_warnings() {
+         None => info(config,"Frequency check: no CPU frequency information \
+                                                               is available"),
+         Some(ref warnings) if warnings.is_empty() => info(config,
+            "Frequency check: no sign of boost, and every CPU uses the \
+                                                      performance governor"),
+         Some(warnings) => {
+            for warning in warnings {
+               eprintln!("Warning: {}",warning);
+            }
+         }
+      }
+   }
+
+   //  The system information comes at the end, as a footer, so it never
+   //  gets in the way of the results. It always goes in a meta file.
+
+   let meta_usable = config.meta_file.is_some()
+                                   && !META_FAILED.load(Ordering::Relaxed);
+   if config.sysinfo || meta_usable {
+      info(config,"System information:");
+      for (name,value) in crssysinfo::gather().fields() {
+         info(config,&format!("   {:<20} {}",name,value));
+      }
+   }
+
+   let all_verified = results.iter().filter(|result| !result.failed)
+                                              .all(|result| result.verified);
+   let mut status = if all_verified { EXIT_OK } else { EXIT_VERIFY_FAILED };
+
+   //  If there's a baseline, compare against it. A regression, a result
+   //  with nothing to compare it with, or a baseline that can't be used, is
+   //  reported through the exit status, but a verification failure takes
+   //  precedence.
+
+   if let Some(ref path) = config.baseline {
+      match crsreport::check_baseline(path,&results,config.threshold,
+                                                           config.si_times) {
+         Ok(check) => {
+            if check.regressions > 0 {
+               eprintln!("{} result(s) regressed by more than {}%",
+                                         check.regressions,config.threshold);
+            }
+            if check.unmatched > 0 {
+               eprintln!("{} result(s) had no baseline to compare against",
+                                                            check.unmatched);
+            }
+            if (check.regressions > 0 || check.unmatched > 0)
+                                                         && status == EXIT_OK {
+               status = EXIT_REGRESSION;
+            }
+         }
+         Err(message) => {
+            eprintln!("{}",message);
+            if status == EXIT_OK { status = EXIT_REGRESSION; }
+         }
+      }
+   }
+
+   //  A configuration that couldn't be run at all trumps everything.
+
+   if failures > 0 { status = EXIT_ERROR; }
+
+   Ok(status)
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                       I n t e r a c t i v e   M o d e
+
+//  The options 'set' won't take, since they do something other than set the
+//  configuration - the interactive mode has its own commands for those that
+//  make sense in a session.
+
+const SET_REFUSED: [&str; 6] = ["help","list-modes","selftest","asm-report",
+                                               "interactive","strict-args"];
+
+const COMMANDS: &str = "\
+Commands:
+   set OPTION [VALUE]  set an option, as on the command line but without the
+                       '--', eg 'set cols 4000', 'set verify-both'
+   mode NAME           the same as 'set mode NAME'
+   show                show the configuration
+   reset               go back to the configuration the session started with
+   run                 run the configuration, just as the program would
+   compare A B         check that modes A and B give the same output, then
+                       time each and show how their best times compare
+   modes               list the modes
+   help                show this list
+   quit                end the session, as does the end of the input
+";
+
+//  Runs an interactive session, starting from the configuration given by
+//  the command line, and reading commands from standard input until it
+//  ends or there's a 'quit'. The configuration is held as the command line
+//  that gives it, which each 'set' adds to, and which is parsed again -
+//  strictly, so a mistake is never replaced by a default - each time it's
+//  used. Returns the exit status, which is that of the last run or
+//  comparison, or EXIT_OK if there wasn't one.
+
+fn run_interactive (args: &[String]) -> i32 {
+   let start: Vec<String> = args.iter().filter(|arg| *arg != "--interactive")
+                                                           .cloned().collect();
+   let mut current = start.clone();
+   let mut status = EXIT_OK;
+   println!("Interactive mode - 'help' lists the commands");
+   let stdin = std::io::stdin();
+   loop {
+      print!("crsbench> ");
+      let _ = std::io::stdout().flush();
+      let mut line = String::new();
+      match stdin.lock().read_line(&mut line) {
+         Ok(0) | Err(_) => break,
+         Ok(_) => {}
+      }
+      let words: Vec<&str> = line.split_whitespace().collect();
+      match words.as_slice() {
+         [] => {}
+         ["help"] => print!("{}",COMMANDS),
+         ["quit"] | ["exit"] => break,
+         ["modes"] => list_modes(),
+         ["reset"] => current = start.clone(),
+         ["show"] => {
+            if let Some(config) = session_config(&current,&[]) {
+               for (name,value) in config.fields() {
+                  println!("   {:<20} {}",name,value);
+               }
+            }
+         }
+         ["run"] => {
+            if let Some(config) = session_config(&current,&[]) {
+               status = session_run(&config);
+            }
+         }
+         ["compare",mode_a,mode_b] => {
+            if let Some(compared) = session_compare(&current,mode_a,mode_b) {
+               status = compared;
+            }
+         }
+         ["mode",name] => set_option(&mut current,"mode",&[name]),
+         ["set",option,values @ ..] => set_option(&mut current,option,values),
+         _ => eprintln!("Unknown command '{}' - 'help' lists them",
+                                                                line.trim()),
+      }
+   }
+   status
+}
+
+//  Parses the session's command line, with any extra arguments added to
+//  the end, returning the configuration, or reporting why it can't be used
+//  and returning None.
+
+fn session_config (current: &[String],extra: &[&str]) -> Option<BenchConfig> {
+   let mut args = current.to_vec();
+   args.extend(extra.iter().map(|arg| arg.to_string()));
+   match parse_args(&args,true) {
+      Ok(config) => Some(config),
+      Err(error) => { eprintln!("{}",error); None }
+   }
+}
+
+//  Adds an option, and any values for it, to the session's command line, so
+//  long as the result can still be parsed.
+
+fn set_option (current: &mut Vec<String>,option: &str,values: &[&str]) {
+   let option = option.trim_start_matches('-');
+   if SET_REFUSED.contains(&option) {
+      eprintln!("'{}' can't be set - 'help' lists what the session can do",
+                                                                      option);
+      return;
+   }
+   let mut extra = vec![format!("--{}",option)];
+   extra.extend(values.iter().map(|value| value.to_string()));
+   let extra: Vec<&str> = extra.iter().map(|arg| arg.as_str()).collect();
+   if session_config(current,&extra).is_some() {
+      current.extend(extra.iter().map(|arg| arg.to_string()));
+   }
+}
+
+//  Compares two modes in a session: checks that they give the same output,
+//  as --equiv does, then times each of them with the session's
+//  configuration and shows the ratio of their best times per call. Returns
+//  the exit status - the worse of the check's and the runs' - or None if
+//  the configuration can't be used with one of the modes.
+
+fn session_compare (current: &[String],mode_a: &str,mode_b: &str)
+                                                              -> Option<i32> {
+   let modes = format!("{},{}",mode_a,mode_b);
+   let mut status = session_run(&session_config(current,&["--equiv",&modes])?);
+   let mut times = Vec::new();
+   for mode in [mode_a,mode_b] {
+      let config = session_config(current,&["--mode",mode])?;
+      match run_config(&config) {
+         Ok(result) => {
+            if !result.verified && status == EXIT_OK {
+               status = EXIT_VERIFY_FAILED;
+            }
+            times.push((config.time_text(result.min_ns_per_call,3),
+                                                     result.min_ns_per_call));
+         }
+         Err(error) => {
+            eprintln!("{}",error);
+            return Some(EXIT_ERROR);
+         }
+      }
+   }
+   let ((text_a,ns_a),(text_b,ns_b)) = (&times[0],&times[1]);
+   println!("Best times per call: {} {}, {} {} - {} takes {:.3} times as \
+             long as {}",mode_a,text_a,mode_b,text_b,mode_a,ns_a / ns_b,mode_b);
+   Some(status)
+}
+
+//  Runs a configuration in a session, reporting rather than exiting on an
+//  error, and returns the exit status the run would have had.
+
+fn session_run (config: &BenchConfig) -> i32 {
+   match run(config) {
+      Ok(status) => {
+         if status != EXIT_OK { eprintln!("Exit status would be {}",status); }
+         status
+      }
+      Err(error) => {
+         eprintln!("{}",error);
+         EXIT_ERROR
+      }
+   }
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                             M a i n  P r o g r a m
+
+fn main() {
+
+   MAIN_ENTERED.get_or_init(Instant::now);
+   let args: Vec<String> = env::args().collect();
+   let strict = args.iter().any(|arg| arg == "--strict-args");
+   let interactive = args.iter().any(|arg| arg == "--interactive");
+   let config = match parse_args(&args,strict) {
+      Ok(config) => config,
+      Err(error) => usage_error(&error.to_string()),
+   };
+
+   //  A meta file starts with the configuration, as given on the command
+   //  line. If it can't be created, that's reported and the run carries on
+   //  without it.
+
+   if let Some(ref path) = config.meta_file {
+      match File::create(path) {
+         Ok(_) => {
+            write_meta(&config,"Configuration:");
+            for (name,value) in config.fields() {
+               write_meta(&config,&format!("   {:<20} {}",name,value));
+            }
+         }
+         Err(error) => {
+            eprintln!("Warning: cannot create meta file {}: {}",path,error);
+            META_FAILED.store(true,Ordering::Relaxed);
+         }
+      }
+   }
+
+   //  An interactive session takes it from here, starting from this
+   //  configuration. Otherwise, it's run just the once.
+
+   if interactive {
+      process::exit(run_interactive(&args));
+   }
+   let status = match run(&config) {
+      Ok(status) => status,
+      Err(error) => fail(&error),
+   };
+   #[cfg(feature = "trackalloc")]
+   report_allocations(&config);
+   if config.report_startup { report_startup(&config); }
+   process::exit(status);
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                                 T e s t s
+
+#[cfg(test)]
+mod tests {
+   use super::{crsconfig, parse_args, parse_count, run_benchmark,
+               self_test_builder, self_test_fuzz, self_test_random,
+               self_test_shapes, SELF_TEST_FUZZ_CASES};
+   use crate::crsarray::Array2D;
+   use crate::crskernel::Kernel;
+
+   #[test]
+   fn parse_count_suffixes () {
+      assert_eq!(parse_count("1000"),Some(1000));
+      assert_eq!(parse_count("1k"),Some(1000));
+      assert_eq!(parse_count("4Ki"),Some(4096));
+      assert_eq!(parse_count("3M"),Some(3000000));
+      assert_eq!(parse_count("2Gi"),Some(2 << 30));
+   }
+
+   #[test]
+   fn parse_count_invalid () {
+      for value in ["","Ki","1ki","1K","1m","1.5k","-1k","+1k","+1"," 1k",
+                                                            "1k ","1 k"] {
+         assert_eq!(parse_count(value),None,"{:?}",value);
+      }
+   }
+
+   #[test]
+   fn parse_count_overflow () {
+      assert_eq!(parse_count("99999999999Gi"),None);
+      assert_eq!(parse_count(&format!("{}0",usize::MAX)),None);
+      assert_eq!(parse_count(&usize::MAX.to_string()),Some(usize::MAX));
+   }
+
+   //  An unknown option is reported as unknown, even when it comes last
+   //  with no value, and a known one as needing its value.
+
+   #[test]
+   fn unknown_option () {
+      let problem = |args: &[&str]| {
+         let mut line = vec!["crsbench".to_string()];
+         line.extend(args.iter().map(|arg| arg.to_string()));
+         parse_args(&line,true).err().map(|error| error.to_string())
+      };
+      let unknown = Some("Unknown option: --bogus".to_string());
+      assert_eq!(problem(&["--bogus"]),unknown);
+      assert_eq!(problem(&["--bogus","5"]),unknown);
+      assert_eq!(problem(&["--rows"]),Some("--rows needs a value".to_string()));
+      assert_eq!(problem(&["--input-dist"]),
+                                Some("--input-dist needs a value".to_string()));
+      assert_eq!(problem(&["--rows","5"]),None);
+   }
+
+   //  A kernel written against the Kernel trait alone, as a user of the
+   //  harness would write one - the standard operation a row at a time -
+   //  and a kernel that gets it wrong, which should fail the check.
+
+   struct RowKernel;
+
+   impl Kernel for RowKernel {
+      fn name (&self) -> &str { "rows" }
+      fn run (&self,input: &Array2D<f32>,output: &mut Array2D<f32>) {
+         for iy in 0..input.ny() {
+            for (ix,(out,value)) in output.row_mut(iy).iter_mut()
+                                            .zip(input.row(iy)).enumerate() {
+               *out = value + (ix + iy) as f32;
+            }
+         }
+      }
+   }
+
+   struct WrongKernel;
+
+   impl Kernel for WrongKernel {
+      fn name (&self) -> &str { "wrong" }
+      fn run (&self,input: &Array2D<f32>,output: &mut Array2D<f32>) {
+         output.as_mut_slice().copy_from_slice(input.as_slice());
+      }
+   }
+
+   #[test]
+   fn custom_kernel () {
+      let config = self_test_builder("flat").input("random").build().unwrap();
+      let result = run_benchmark(&config,&RowKernel).unwrap();
+      assert!(result.verified);
+      assert_eq!(result.mode,"rows");
+      assert_eq!((result.nx,result.ny),(config.nx,config.ny));
+      assert_eq!((result.flops,result.bytes,result.threads),(1,8,1));
+      assert_eq!(result.calls,config.nrpt * config.trials);
+      assert!(!run_benchmark(&config,&WrongKernel).unwrap().verified);
+   }
+
+   //  The self test's fuzz cases, which are always the same command lines.
+   //  A failure lists the case number and its arguments.
+
+   #[test]
+   fn fuzz_args () {
+      assert!(self_test_fuzz(0,SELF_TEST_FUZZ_CASES));
+   }
+
+   //  Every mode on arrays of the same size in different shapes, which it
+   //  should get right whichever way round the rows and columns are.
+
+   #[test]
+   fn shape_independence () {
+      for info in crsconfig::MODES {
+         if info.available {
+            assert!(self_test_shapes(info.name),"{}",info.name);
+         }
+      }
+   }
+
+   //  Every mode on the self test's random shapes, with random input. The
+   //  generator is always seeded with SELF_TEST_RANDOM_SEED, so these are
+   //  the same cases each time, and a failure lists the command line that
+   //  repeats it.
+
+   #[test]
+   fn random_shapes () {
+      for info in crsconfig::MODES {
+         if info.available {
+            assert!(self_test_random(info.name),"{}",info.name);
+         }
+      }
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o Each call to csub() is passed its input through std::hint::black_box().
+     Without it, the compiler is entitled to notice that the same call with
+     the same input is being made over and over, and only make it once. None
+     of the other test programs in the study bother with this, and so far as
+     I can tell rustc doesn't actually do that, but an optimiser that got
+     that clever would make the timings meaningless.
+
+   o The modes that use vectors of vectors work on copies of the input array
+     in that format. This costs time and memory, but it happens before the
+     timing starts, and it means the input values and the checking are the
+     same for all modes.
+
+   o As in crsmain.rs, an invalid number on the command line produces a
+     warning and the default value is used, unless --strict-args makes it
+     an error. Other problems, such as an unknown mode, or a zero
+     dimension, are found when the configuration is built, and are fatal.
+     parse_args() returns all of these as a ConfigError, and main() is the
+     one place that decides to exit on one.
+
+   o Warnings about invalid numbers go to standard error rather than standard
+     output, so that they don't end up mixed in with CSV output.
+
+   o An interactive session keeps the configuration as a command line, and
+     parses it again for every command that uses it, rather than keeping a
+     BenchConfig and changing it. That way 'set' takes exactly what the
+     command line takes, and a run in a session always does just what the
+     same command line would. Parsing is cheap next to a run. The one
+     drawback is that a flag, once set, can only be unset by 'reset'.
+
+   o A session ends, as a normal run would, on anything that calls fail()
+     rather than returning an error - a --config file that can't be read,
+     say, or an array in a fresh allocation run that can't be allocated.
+
+   o With --trial-fresh-thread the trials on the one thread are run after
+     those in fresh threads, so they have whatever the first set left
+     warm. Their spread is only there for comparison, and it's the fresh
+     thread trials that are reported, but a run with the two the other way
+     round would be fairer, and is simply a run without the option.
+
+*/
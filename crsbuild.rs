@@ -0,0 +1,86 @@
+//
+//                           c r s b u i l d . r s
+//
+// Summary:
+//    The build details recorded with the results of the Rust 2D array
+//    access benchmark harness.
+//
+// Introduction:
+//    One of the central questions of the study is how much the compiler
+//    options matter - how much faster a kernel runs built with -C
+//    opt-level=3 than with none, say, and how much more -C target-cpu=native
+//    adds. To answer that the results need to say how the program that
+//    produced them was built, but the compiler doesn't tell a program its
+//    own options - there's no cfg for the optimisation level or the target
+//    CPU. So they are given to it as environment variables when it's built,
+//    which option_env!() picks up at compile time:
+//
+//    CRS_OPT_LEVEL=3 CRS_TARGET_CPU=native \
+//             rustc -C opt-level=3 -C target-cpu=native crsbench.rs
+//
+//    If either isn't set, the description is 'unknown'. Nothing checks that
+//    it matches the options really used - it's up to whatever does the
+//    building, a script usually, to keep the two the same.
+//
+//    Every result is written with opt_level() and target_cpu() (see
+//    crsreport.rs), and --compare-build reads them back out of the result
+//    files from different builds.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//  The optimisation level the program was built with, as given in
+//  CRS_OPT_LEVEL - 0 to 3, s or z, as for -C opt-level.
+
+pub fn opt_level () -> &'static str {
+   option_env!("CRS_OPT_LEVEL").filter(|value| !value.is_empty())
+                                                        .unwrap_or("unknown")
+}
+
+//  The target CPU the program was built for, as given in CRS_TARGET_CPU -
+//  native, say, or generic.
+
+pub fn target_cpu () -> &'static str {
+   option_env!("CRS_TARGET_CPU").filter(|value| !value.is_empty())
+                                                        .unwrap_or("unknown")
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o A build script could find the optimisation level - Cargo gives one
+     OPT_LEVEL - but the harness is built by passing crsbench.rs straight
+     to rustc, without Cargo, and the environment is the only way in.
+
+   o Whether debug assertions are on is something a program can see, but
+     it's controlled separately from the optimisation level, so it says
+     nothing about it, and isn't used as a guess.
+
+   o The values are taken as they are, except that an empty one counts as
+     not set. They go into CSV files and database rows as text, and a
+     comma or a quote in one is quoted like any other.
+
+*/
@@ -0,0 +1,186 @@
+//
+//                         c r s c h e c k s u m . r s
+//
+// Summary:
+//    Output checksums for the Rust 2D array access benchmark harness.
+//
+// Introduction:
+//    The harness checks its own output element by element (see crsverify.rs),
+//    but the test programs in the other languages don't share that code, and
+//    comparing the output of, say, the C++ and Rust versions means comparing
+//    something small that each can print. This module provides three ways of
+//    reducing the output array to a single checksum, selected with the
+//    --checksum-algo option:
+//
+//    sum    the sum of the elements, in double precision. This is the quick
+//           sanity check - anything badly wrong will show up in it - but it
+//           is not an exact comparison. Floating point addition isn't
+//           associative, so a program that adds the elements up in a
+//           different order can get a slightly different sum from the same
+//           values, and quite different outputs can have the same sum.
+//    fnv    the 64-bit FNV-1a hash of the bytes of the elements.
+//    crc32  the CRC-32 of the bytes of the elements - the one used by zlib,
+//           gzip and PNG, so it's available almost everywhere, eg as
+//           zlib.crc32() in Python.
+//
+//    The two byte hashes depend on every bit of every element, so two
+//    programs with the same hash have, to all intents and purposes, bit for
+//    bit the same output. They are the ones to use to check that different
+//    implementations really agree exactly. For either, the bytes hashed are
+//    those of each element as an IEEE 754 single precision value, in little
+//    endian order, taking the elements in memory order - row by row, and
+//    for a 3D array plane by plane. For an output held as a NumPy float32
+//    array, that's zlib.crc32(array.astype('<f4').tobytes()).
+//
+//    Everything is done here, with no external crates, so the default build
+//    still needs nothing more than rustc.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//    14th Oct 2026. The sum can be given to a fixed number of decimal
+//                   places. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//  The ways the output can be checksummed.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Checksum {
+   Sum,
+   Fnv,
+   Crc32,
+}
+
+impl Checksum {
+   pub fn from_name (name: &str) -> Option<Checksum> {
+      match name {
+         "sum" => Some(Checksum::Sum),
+         "fnv" => Some(Checksum::Fnv),
+         "crc32" => Some(Checksum::Crc32),
+         _ => None,
+      }
+   }
+
+   pub fn name (&self) -> &'static str {
+      match *self {
+         Checksum::Sum => "sum",
+         Checksum::Fnv => "fnv",
+         Checksum::Crc32 => "crc32",
+      }
+   }
+
+   //  Returns the checksum of a set of values, in memory order, as it will
+   //  be printed. The sum is given to the number of decimal places given, or
+   //  if none is, as the shortest decimal that reads back as the same
+   //  double. The hashes are always in hex, with leading zeros.
+
+   pub fn of (&self,values: &[f32],places: Option<usize>) -> String {
+      match *self {
+         Checksum::Sum => {
+            let sum: f64 = values.iter().map(|&value| value as f64).sum();
+            match places {
+               Some(places) => format!("{:.*}",places,sum),
+               None => sum.to_string(),
+            }
+         }
+         Checksum::Fnv => format!("{:016x}",fnv1a(values)),
+         Checksum::Crc32 => format!("{:08x}",crc32(values)),
+      }
+   }
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                          F N V - 1 a   H a s h
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+fn fnv1a (values: &[f32]) -> u64 {
+   let mut hash = FNV_OFFSET_BASIS;
+   for value in values {
+      for byte in value.to_le_bytes().iter() {
+         hash ^= *byte as u64;
+         hash = hash.wrapping_mul(FNV_PRIME);
+      }
+   }
+   hash
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                               C R C - 3 2
+
+//  The reversed form of the IEEE 802.3 polynomial, and the table of the CRC
+//  of each possible byte, worked out when the program is compiled.
+
+const CRC32_POLYNOMIAL: u32 = 0xedb8_8320;
+
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+const fn crc32_table () -> [u32; 256] {
+   let mut table = [0u32; 256];
+   let mut index = 0;
+   while index < 256 {
+      let mut crc = index as u32;
+      let mut bit = 0;
+      while bit < 8 {
+         crc = if crc & 1 != 0 { (crc >> 1) ^ CRC32_POLYNOMIAL }
+                                                        else { crc >> 1 };
+         bit += 1;
+      }
+      table[index] = crc;
+      index += 1;
+   }
+   table
+}
+
+fn crc32 (values: &[f32]) -> u32 {
+   let mut crc = !0u32;
+   for value in values {
+      for byte in value.to_le_bytes().iter() {
+         crc = (crc >> 8) ^ CRC32_TABLE[((crc ^ *byte as u32) & 0xff) as usize];
+      }
+   }
+   !crc
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The bytes are always taken in little endian order, whatever the
+     machine, so the hashes can be compared between machines as well as
+     between languages. On the usual little endian machines the bytes are
+     the ones in memory, and to_le_bytes() costs nothing.
+
+   o A NaN can be held in more than one way, and the hashes will see the
+     difference where a comparison of values wouldn't. That's intended -
+     they are meant to say whether two outputs are identical - but it does
+     mean that two programs that both go wrong in the same way may not
+     agree about it.
+
+   o Nothing here is fast, by the standards of the kernels being timed, but
+     it's done once per run, outside the timing.
+
+*/
@@ -0,0 +1,190 @@
+//
+//                           c r s c l o c k . r s
+//
+// Summary:
+//    The clocks the Rust 2D array access benchmark harness can time with.
+//
+// Introduction:
+//    The trials are usually timed with Instant, the monotonic wall clock,
+//    which measures the time that passes, including any time the process
+//    wasn't running because the system was doing something else. On a busy
+//    shared machine that can be a good part of it. Process CPU time only
+//    counts the time the process was actually running, so timing with that
+//    instead leaves the scheduler out of it, and comparing the two shows
+//    how much the scheduler is getting in the way.
+//
+//    TimeSource says which clock to use, and Stopwatch reads it: start()
+//    reads the clock, and elapsed() the time that has gone by on it since.
+//    Process CPU time is read with clock_gettime(CLOCK_PROCESS_CPUTIME_ID),
+//    which this only uses on Linux. Anywhere else it isn't available, and
+//    the monotonic clock is used instead - effective() says which clock
+//    will actually be used, so that can be reported.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::time::{Duration, Instant};
+
+//  True if process CPU time can be read on this system.
+
+pub const CPU_TIME_AVAILABLE: bool = cfg!(target_os = "linux");
+
+//  The clocks there are to time with.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeSource {
+   Monotonic,
+   CpuTime,
+}
+
+impl TimeSource {
+   pub fn from_name (name: &str) -> Option<TimeSource> {
+      match name {
+         "monotonic" => Some(TimeSource::Monotonic),
+         "cputime" => Some(TimeSource::CpuTime),
+         _ => None,
+      }
+   }
+
+   pub fn name (&self) -> &'static str {
+      match *self {
+         TimeSource::Monotonic => "monotonic",
+         TimeSource::CpuTime => "cputime",
+      }
+   }
+
+   //  The clock that is actually used when this one is asked for - the
+   //  same one, unless it's process CPU time and that isn't available.
+
+   pub fn effective (&self) -> TimeSource {
+      if *self == TimeSource::CpuTime && !CPU_TIME_AVAILABLE {
+         TimeSource::Monotonic
+      } else {
+         *self
+      }
+   }
+
+   //  A description of the clock, for the banner.
+
+   pub fn description (&self) -> &'static str {
+      match *self {
+         TimeSource::Monotonic => "the monotonic wall clock",
+         TimeSource::CpuTime =>
+                          "process CPU time (CLOCK_PROCESS_CPUTIME_ID)",
+      }
+   }
+}
+
+//  A reading of one of the clocks, from which the time since can be found.
+
+#[derive(Clone, Copy, Debug)]
+pub enum Stopwatch {
+   Monotonic(Instant),
+   CpuTime(Duration),
+}
+
+impl Stopwatch {
+
+   //  Reads the clock to be used for the given source.
+
+   pub fn start (source: TimeSource) -> Stopwatch {
+      match source.effective() {
+         TimeSource::Monotonic => Stopwatch::Monotonic(Instant::now()),
+         TimeSource::CpuTime => Stopwatch::CpuTime(cpu_time()),
+      }
+   }
+
+   //  The time gone by on the same clock since start().
+
+   pub fn elapsed (&self) -> Duration {
+      match *self {
+         Stopwatch::Monotonic(started) => started.elapsed(),
+         Stopwatch::CpuTime(started) => cpu_time().saturating_sub(started),
+      }
+   }
+}
+
+//  The CPU time used by the process so far, all its threads together.
+
+#[cfg(target_os = "linux")]
+mod sys {
+   use std::os::raw::{c_int, c_long};
+
+   pub const CLOCK_PROCESS_CPUTIME_ID: c_int = 2;
+
+   #[repr(C)]
+   pub struct Timespec {
+      pub tv_sec: c_long,
+      pub tv_nsec: c_long,
+   }
+
+   extern "C" {
+      pub fn clock_gettime (clock: c_int,time: *mut Timespec) -> c_int;
+   }
+}
+
+#[cfg(target_os = "linux")]
+fn cpu_time () -> Duration {
+   let mut time = sys::Timespec { tv_sec: 0, tv_nsec: 0 };
+
+   //  Safety: time is a valid timespec for clock_gettime() to fill in, and
+   //  it only writes to that.
+
+   let status = unsafe {
+      sys::clock_gettime(sys::CLOCK_PROCESS_CPUTIME_ID,&mut time)
+   };
+   if status != 0 { return Duration::ZERO; }
+   Duration::new(time.tv_sec as u64,time.tv_nsec as u32)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn cpu_time () -> Duration {
+   Duration::ZERO
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o Process CPU time is the total for all the process's threads, so for
+     a parallel mode it's the CPU time of all the threads together, which
+     can be several times the wall clock time. That's not wrong, but it
+     isn't a time per call in the usual sense, and the throughputs worked
+     out from it mean something different.
+
+   o Reading the process CPU clock is a system call, where the monotonic
+     clock is read without one, so it takes longer - typically a few
+     hundred nanoseconds. The clock is only read either side of a trial,
+     or between batches with a time budget, so that's lost in the time of
+     all the calls in between.
+
+   o A timespec is declared here as two longs, since time_t is a long on
+     Linux - except on x32, which the harness isn't built for.
+
+   o If clock_gettime() fails, which it shouldn't for this clock, the time
+     reads as zero, so a trial would take no time at all, and the results
+     would be obviously wrong rather than quietly misleading.
+
+*/
@@ -0,0 +1,2828 @@
+//
+//                           c r s c o n f i g . r s
+//
+// Summary:
+//    Benchmark configuration for the Rust 2D array access benchmark harness.
+//
+// Introduction:
+//    The benchmark harness, crsbench.rs, has collected quite a few options,
+//    and the code that sets them up, supplies the defaults, and checks that
+//    the combination makes sense was getting spread around. This module
+//    brings that together. A BenchConfig holds a complete configuration, and
+//    is created using a BenchConfigBuilder, which starts with all the
+//    defaults and checks everything when build() is called, eg:
+//
+//       let config = BenchConfig::builder().nx(4000).ny(20)
+//                                         .mode("unsafe").build()?;
+//
+//    The command line parsing in crsbench.rs works by setting values in a
+//    builder, so a BenchConfig set up in code is checked in exactly the same
+//    way as one set up from the command line.
+//
+//    A configuration can also be written to a file, with write_file(), and
+//    read back into a builder with BenchConfigBuilder::from_file(). Files
+//    can just as well be written by hand, and kept under version control as
+//    the standard configurations for a study. Each line sets one of the
+//    names listed under Defaults, below, to a value written as fields()
+//    gives it, as 'name = value', eg:
+//
+//       # The configuration for the figures in section 3.
+//       nx = 4000
+//       ny = 20
+//       mode = "unsafe"        # any value can be quoted
+//       input = "gaussian, mean 0, stddev 1"
+//       label = "run \"B\"\tsecond try"
+//       budget_ms = 200
+//
+//    The '=' can be left out, as in 'nx 4000'. Blank lines, and anything
+//    after a '#' that isn't in a quoted value, are ignored, and anything
+//    not given keeps its default - which is how a file leaves a value
+//    unset. A bare none does the same, but a quoted "none" is the text
+//    none, so a label or a file name can be anything at all. A quoted
+//    value is taken exactly as it is, spaces and all, with \" and \\ for
+//    a quote and a backslash, and \n, \r, \t and \uXXXX for the
+//    characters they stand for in TOML. A name that isn't known only gets
+//    a warning, so a file still works with a version of the program that
+//    doesn't have all its settings, but a value that can't be read is an
+//    error. With the values that aren't numbers or true or false quoted, as
+//    write_file() writes them, the file is also valid TOML. What's read is
+//    only checked when build() is called, so anything set after reading
+//    the file is checked along with it.
+//
+//    write_file() leaves out the settings that say where the results go -
+//    the baseline, append, sqlite, report_tcp and meta_file files, and the
+//    diff_map, svg_histogram and emit_canonical outputs - so running from
+//    a saved configuration never writes over or adds to the results of the
+//    run that saved it. Those can still be set in a file written by hand.
+//
+// Defaults:
+//    nrpt            100000   calls to csub() per trial.
+//    ny              10       rows.
+//    nx              2000     columns.
+//    dims            2        a 2D array - 3 gives a 3D array.
+//    nz              10       planes, for a 3D array.
+//    mode            index    see Mode, below.
+//    trials          5        timed trials.
+//    warmup          0        untimed calls before the trials.
+//    warmup_seconds  none     the warmup is a count of calls.
+//    warmup_to_freq  false    the warmup doesn't wait for the CPU frequency.
+//    format          prose    see Format, below.
+//    baseline        none     no comparison with an earlier run.
+//    threshold       5.0      percent slowdown that counts as a regression.
+//    compare_build   none     no comparison of the results of builds.
+//    verify_both     false    no exact and tolerant accuracy checks.
+//    verify_shuffle  false    no check in a shuffled order.
+//    strict_fp       false    no bit for bit check against strict IEEE
+//                             arithmetic.
+//    tolerance       2 f32 epsilons, for the tolerant accuracy check.
+//    inner_repeat    none     csub() is called directly.
+//    threads         the number of CPUs available, for the parallel modes.
+//    check_coverage  false    no check on the parallel kernel's coverage.
+//    check_conflict  false    no check for the arrays conflicting in the
+//                             cache - see crsconflict.rs.
+//    cache_line      64       the bytes in a cache line, for check_conflict.
+//    cache_assoc     8        the ways in each cache set, for check_conflict.
+//    cache_size      32768    the bytes in the cache, for check_conflict.
+//    sweep_2d        none     just the one configuration is run.
+//    sweep_shard     none     all of a sweep is run.
+//    count_ops       false    no count of the kernel's element additions.
+//    verify_every    none     the output is only checked after the timing.
+//    row_access      false    no timing of row access on its own.
+//    per_row_timing  false    no timing of each row on its own.
+//    input           standard see crsinput.rs.
+//    seed            1        for the random input.
+//    seed_per_trial  false    every trial uses the same input.
+//    trial_fresh_thread
+//                    false    every trial runs on the same thread.
+//    mean, stddev    0, 1     for the gaussian input.
+//    diff_map        none     no difference map is written on a mismatch.
+//    svg_histogram   none     no histogram of the trial times is written.
+//    compare_with    none     the output isn't compared with another
+//                             implementation's.
+//    emit_canonical  none     the output isn't written out.
+//    label           none     the results have no label.
+//    endian          little   byte order for raw files - see Endian, in
+//                             crsverify.rs.
+//    loop_order      ij       see LoopOrder, below.
+//    sysinfo         false    no system information is reported.
+//    freq_check      false    no check of the CPU frequency settings.
+//    report_startup  false    no report of the time taken before the timing.
+//    budget_ms       none     each trial makes nrpt calls.
+//    auto_repeats    false    nrpt is as given, whatever the array size.
+//    repeat_ops      2G       with auto_repeats, nrpt is this many element
+//                             operations a trial divided by the elements in
+//                             the array - giving the default nrpt for the
+//                             default array size.
+//    time_source     monotonic the trials are timed with the wall clock -
+//                             see TimeSource, in crsclock.rs.
+//    meta_file       none     information goes to stdout or stderr.
+//    append          none     results aren't appended to a CSV file.
+//    sqlite          none     results aren't written to a database.
+//    fits            none     the dimensions are as given, not taken from
+//                             a FITS file - see crsfits.rs.
+//    fits_data       false    the input isn't read from the FITS file.
+//    report_tcp      none     results aren't sent to a collector.
+//    drift_check     none     everything is run once.
+//    drift_pause_ms  1000     between drift check repetitions.
+//    out_stride      none     the output is written contiguously.
+//    perm_seed       none     the gather mode uses the identity permutation.
+//    window          none     the window mode uses a window of 4 rows, or
+//                             all of them if there are fewer.
+//    window_stride   none     the window mode moves its window on by 1 row.
+//    unroll          none     the unrolled mode unrolls by 4.
+//    expr            none     the expr mode evaluates in + ix + iy.
+//    equiv           none     the selected mode is timed as usual.
+//    compare_types   false    only f32 elements are timed.
+//    compare_unroll  false    only the one unroll factor is timed.
+//    false_share_study
+//                    false    no timing of aligned and misaligned chunks.
+//    order_study     false    no comparison of the loop orders over sizes.
+//    bench_construction
+//                    false    no timing of the ways of building an array.
+//    find_cliff      false    no search for the cache cliff.
+//    torture         false    no run over awkward array sizes.
+//    concurrent_arrays
+//                    none     one thread times one pair of arrays.
+//    fresh_alloc     false    every call reuses the same output array.
+//    hugepages       false    the arrays use the system's usual pages.
+//    mlock           false    the arrays aren't locked into memory.
+//    protect_input   false    the input isn't made read-only.
+//    peak_bandwidth  none     no fraction of peak bandwidth is reported.
+//    roofline        false    no bandwidth-bound lower limit on the time is
+//                             reported.
+//    vs_memcpy       false    the time isn't compared with that of a copy.
+//    checksum        none     no checksum of the output is reported - see
+//                             crschecksum.rs.
+//    precision       none     each kind of result is printed with its usual
+//                             number of decimal places.
+//    si_times        false    times are shown in ns, not scaled.
+//    danger_alias    none     the output never overlaps the input. This one
+//                             can only be set on the command line - see
+//                             crssub_alias.rs.
+//
+// Validation:
+//    build() fails, with a BenchError saying why, if any of these rules is
+//    broken. Checking them all in build() means a configuration is checked
+//    the same way however it was set up.
+//
+//    o Names - of the mode, the equiv modes, the format, input, loop order,
+//      checksum, byte order and time source - must be recognised, and a
+//      mode must be available in this build.
+//    o Counts must not be zero: the array dimensions, repeats (unless they
+//      are automatic), trials, threads, inner repeats, calls between
+//      incremental checks, concurrent arrays, the time budget, the element
+//      operations for automatic repeats and the sweep dimensions. The array
+//      must not have too many elements to address, and a drift check needs
+//      at least two repetitions.
+//    o Only one study can be run at a time. The studies, which replace the
+//      usual runs with their own, are an equivalence check, a sweep, a
+//      build comparison, a type comparison, an unrolling comparison, a
+//      false sharing study, a loop order study, a construction timing, a
+//      search for the cache cliff, a torture test, concurrent arrays and an
+//      aliased output.
+//    o The options that only apply to the usual runs can't be given with a
+//      study: a fresh output array for each call, huge pages, locking the
+//      arrays into memory, protecting the input, a cache conflict check, a
+//      permutation seed, the pixel data of a FITS file, a fresh thread for
+//      each trial, a comparison with a memcpy, a histogram of the trial
+//      times, a comparison with another implementation's output, writing
+//      out the canonical output, and the jsonl and prometheus formats. A
+//      sweep is just a series of the usual runs, so all but the histogram
+//      and the two output comparisons can be given with one.
+//    o Options that belong to a mode need that mode: the ji loop order and
+//      an output stride the flat mode (a stride with the ij order, and one
+//      the array size allows - see crssub_strided.rs), a permutation seed
+//      the gather mode, a window and window stride the window mode (see
+//      crssub_window.rs), an unroll factor the unrolled mode, and an
+//      expression, which must make sense (see crsexpr.rs), the expr mode.
+//      The unroll factor must be 1, 2, 4 or 8 (see crssub_unrolled.rs),
+//      and the gather mode's index must be able to hold the array size
+//      (see crssub_gather.rs).
+//    o A fresh output array for each call, huge pages, locking the arrays
+//      into memory, protecting the input, a cache conflict check, a type
+//      comparison and a search for the cache cliff need a mode that uses a
+//      flat Array2D. A comparison with another output, and writing out the
+//      canonical one, need a mode whose results correspond element by
+//      element with the input.
+//    o The studies have their own needs: an unrolling comparison the
+//      unrolled mode with no unroll factor, a false sharing study the
+//      threads mode with at least two threads and a cache line of at least
+//      8 bytes, a loop order study the flat mode with no loop order or
+//      output stride, concurrent arrays a mode that isn't parallel, and a
+//      build comparison two or more result files and no baseline. A type
+//      comparison, an unrolling comparison and a search for the cache
+//      cliff only use the standard input, and a type comparison can't use
+//      the expr mode. An aliased output needs the danger_alias feature, and
+//      an offset that is nonzero and leaves the output overlapping the
+//      input.
+//    o A fresh output array for each call can't be combined with huge
+//      pages, locking the arrays into memory, a cache conflict check or a
+//      comparison with a memcpy. A seed per trial needs random or gaussian
+//      input, and can't be combined with concurrent arrays, protecting the
+//      input or a fresh thread for each trial. A histogram of the trial
+//      times can't be combined with a drift check.
+//    o A mean and standard deviation are only for the gaussian input, and
+//      the standard deviation must not be negative.
+//    o A warmup time must be a usable positive number of seconds, and
+//      can't be given with a warmup count, and a warmup to a steady
+//      frequency can't be given with either. Automatic repeats can't be
+//      given with a time budget. A roofline report needs the peak
+//      bandwidth, which must be a positive number.
+//    o A sweep shard needs a sweep, and must be one of its shards.
+//    o A database needs the sqlite feature, a collector address must be
+//      HOST:PORT, and a strict floating point check isn't available on a
+//      target that only has x87 floating point.
+//    o A FITS file needs the fits feature, must hold a 2D image (see
+//      crsfits.rs), and can't be given with a sweep. Its pixel data can
+//      only be the input if the image is of f32 values, and then no other
+//      input or seed per trial can be given.
+//    o A label can't contain a control character.
+//    o The cache line size must be a power of two, and the cache size a
+//      power of two number of sets of lines.
+//    o The number of dimensions must be 2 or 3. A 3D array must have some
+//      planes, not too many elements to address, the flat mode with the ij
+//      loop order, and none of the options that only work in 2D.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version, based on the configuration code from
+//                   crsbench.rs. agent.
+//    14th Oct 2026. Added count_ops. agent.
+//    14th Oct 2026. Added input, seed and seed_per_trial. agent.
+//    14th Oct 2026. Added diff_map. agent.
+//    14th Oct 2026. Added loop_order. agent.
+//    14th Oct 2026. Added sysinfo. agent.
+//    14th Oct 2026. Added budget_ms. agent.
+//    14th Oct 2026. Added meta_file, and fields(). agent.
+//    14th Oct 2026. Added the MODES table. agent.
+//    14th Oct 2026. Added mean and stddev, for the Gaussian input. agent.
+//    14th Oct 2026. Added drift_check and drift_pause_ms. agent.
+//    14th Oct 2026. Added out_stride. agent.
+//    14th Oct 2026. Added flops and bytes to the MODES table. agent.
+//    14th Oct 2026. Added equiv. agent.
+//    14th Oct 2026. Added check to the MODES table. agent.
+//    14th Oct 2026. Added warmup_seconds. agent.
+//    14th Oct 2026. build() now returns a BenchError. agent.
+//    14th Oct 2026. Added append. agent.
+//    14th Oct 2026. Added freq_check. agent.
+//    14th Oct 2026. Added the morton mode. agent.
+//    14th Oct 2026. Added peak_bandwidth. agent.
+//    14th Oct 2026. Added sqlite. agent.
+//    14th Oct 2026. Added dims and nz. agent.
+//    14th Oct 2026. Added row_access. agent.
+//    14th Oct 2026. Added compare_types. agent.
+//    14th Oct 2026. Added the checked mode. agent.
+//    14th Oct 2026. Added warmup_to_freq. agent.
+//    14th Oct 2026. Added checksum. agent.
+//    14th Oct 2026. Added sweep_shard. agent.
+//    14th Oct 2026. Added endian. agent.
+//    14th Oct 2026. Added fresh_alloc. agent.
+//    14th Oct 2026. Added verify_every. agent.
+//    14th Oct 2026. Added find_cliff. agent.
+//    14th Oct 2026. Added concurrent_arrays. agent.
+//    14th Oct 2026. Added strict_fp. agent.
+//    14th Oct 2026. Added write_file(), from_file() and reset_warmup(). agent.
+//    14th Oct 2026. Configuration files now use 'name = value', can have
+//                   quoted values and comments, and unknown names only get
+//                   a warning. agent.
+//    14th Oct 2026. Added the gather mode, perm_seed and check(). agent.
+//    14th Oct 2026. Added report_tcp. agent.
+//    14th Oct 2026. Added hugepages. agent.
+//    14th Oct 2026. Added precision, and places(). agent.
+//    14th Oct 2026. Added per_row_timing. agent.
+//    14th Oct 2026. Added danger_alias. agent.
+//    14th Oct 2026. Added the window mode, window, window_stride and
+//                   window_shape(). agent.
+//    14th Oct 2026. Added roofline. agent.
+//    14th Oct 2026. Added verify_shuffle. agent.
+//    14th Oct 2026. Added the jsonl format. agent.
+//    14th Oct 2026. Added the unrolled mode, unroll, compare_unroll and
+//                   unroll_factor(). agent.
+//    14th Oct 2026. Added bench_construction. agent.
+//    14th Oct 2026. Added mlock. agent.
+//    14th Oct 2026. Added the simd4 and fma4 modes. agent.
+//    14th Oct 2026. Added the expr mode, expr, expression() and flops().
+//                   agent.
+//    14th Oct 2026. Added compare_with. agent.
+//    14th Oct 2026. Added torture. agent.
+//    14th Oct 2026. Added label. agent.
+//    14th Oct 2026. Added the binary mode. agent.
+//    14th Oct 2026. Added time_source. agent.
+//    14th Oct 2026. Added the split mode. agent.
+//    14th Oct 2026. Added report_startup. agent.
+//    14th Oct 2026. Added the regtranspose mode. agent.
+//    14th Oct 2026. Added the prometheus format. agent.
+//    14th Oct 2026. Added check_conflict, cache_line, cache_assoc and
+//                   cache_size. agent.
+//    14th Oct 2026. Added protect_input. agent.
+//    14th Oct 2026. Added compare_build and build_comparison(). agent.
+//    14th Oct 2026. Added false_share_study. agent.
+//    14th Oct 2026. Added svg_histogram. agent.
+//    14th Oct 2026. Added the affine mode. agent.
+//    14th Oct 2026. Added the copy mode. agent.
+//    14th Oct 2026. Added trial_fresh_thread. agent.
+//    14th Oct 2026. Added vs_memcpy. agent.
+//    14th Oct 2026. Added order_study. agent.
+//    14th Oct 2026. Added si_times, time_text(), time_cell() and
+//                   time_heading(). agent.
+//    14th Oct 2026. Added the prefix mode. agent.
+//    14th Oct 2026. Added fits and fits_data, and input_label(). agent.
+//    14th Oct 2026. Added auto_repeats and repeat_ops. agent.
+//    14th Oct 2026. Added emit_canonical. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::fs;
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use crate::crschecksum::Checksum;
+use crate::crsclock::TimeSource;
+use crate::crsconflict::CacheGeometry;
+use crate::crserror::BenchError;
+use crate::crsinput::{self, Input};
+use crate::crssub_gather;
+use crate::crssub_strided;
+use crate::crssub_unrolled;
+use crate::crsexpr::{self, Expr};
+use crate::crssub_window;
+use crate::crsunits;
+use crate::crsverify::{self, Check, Endian};
+
+//  The default values, as listed above.
+
+pub const DEFAULT_NRPT: usize = 100000;
+pub const DEFAULT_NY: usize = 10;
+pub const DEFAULT_NX: usize = 2000;
+pub const DEFAULT_DIMS: usize = 2;
+pub const DEFAULT_NZ: usize = 10;
+pub const DEFAULT_TRIALS: usize = 5;
+pub const DEFAULT_WARMUP: usize = 0;
+pub const DEFAULT_THRESHOLD: f64 = 5.0;
+pub const DEFAULT_DRIFT_PAUSE_MS: u64 = 1000;
+pub const DEFAULT_REPEAT_OPS: usize = DEFAULT_NRPT * DEFAULT_NX * DEFAULT_NY;
+pub const DEFAULT_WINDOW: usize = 4;
+pub const DEFAULT_WINDOW_STRIDE: usize = 1;
+pub const DEFAULT_UNROLL: usize = 4;
+pub const DEFAULT_CACHE_LINE: usize = 64;
+pub const DEFAULT_CACHE_ASSOC: usize = 8;
+pub const DEFAULT_CACHE_SIZE: usize = 32768;
+
+//  True for a 32-bit x86 target without SSE2, where rustc has to use the
+//  x87 unit for floating point, and strict_fp can't be promised.
+
+const X87_ONLY: bool = cfg!(all(target_arch = "x86",
+                                          not(target_feature = "sse2")));
+
+//  ----------------------------------------------------------------------------
+//
+//                              M o d e s
+
+//  The different versions of csub() that can be tested.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mode {
+   Index,
+   Unsafe,
+   Checked,
+   Iter,
+   Flat,
+   Threads,
+   Split,
+   Const,
+   Chunks,
+   RawPtr,
+   Morton,
+   Gather,
+   Window,
+   Unrolled,
+   Simd4,
+   Fma4,
+   Expr,
+   Binary,
+   RegTranspose,
+   Affine,
+   Copy,
+   Prefix,
+}
+
+//  What is known about each mode. Everything that needs to know about the
+//  modes in general - the parsing of --mode, --list-modes, the usage text -
+//  works from the MODES table, so adding a mode means adding it to the enum
+//  and to the table, and then to the places in crsbench.rs that call it.
+
+pub struct ModeInfo {
+   pub mode: Mode,
+   pub name: &'static str,
+
+   //  A one line description, and the source file for the kernel.
+
+   pub description: &'static str,
+   pub source: &'static str,
+
+   //  True if the kernel uses a flat Array2D, rather than vectors of
+   //  vectors. These kernels are all generic over the element type.
+
+   pub flat: bool,
+
+   //  True if the kernel has no unsafe code.
+
+   pub safe: bool,
+
+   //  True if the kernel uses more than one thread.
+
+   pub parallel: bool,
+
+   //  The feature the kernel needs, if any, and whether it's available in
+   //  this build.
+
+   pub feature: Option<&'static str>,
+   pub available: bool,
+
+   //  The cost of the kernel for each array element, for placing it on a
+   //  roofline plot: the floating point operations it does, and the bytes
+   //  it moves to and from memory with f32 elements. For the add-the-indices
+   //  operation that's one add, and a four byte read and a four byte write.
+   //  The index sum itself is integer arithmetic, and isn't counted.
+
+   pub flops: usize,
+   pub bytes: usize,
+
+   //  How the kernel's results are checked - see crsverify.rs. Every kernel
+   //  but gather is a map, checked element by element, and even gather
+   //  is checked that way with the identity permutation - see
+   //  BenchConfig::check().
+
+   pub check: Check,
+}
+
+impl ModeInfo {
+
+   //  The arithmetic intensity, in floating point operations per byte.
+
+   pub fn intensity (&self) -> f64 {
+      self.flops as f64 / self.bytes as f64
+   }
+}
+
+pub const MODES: &[ModeInfo] = &[
+   ModeInfo { mode: Mode::Index, name: "index",
+      description: "vectors of vectors, array[iy][ix]",
+      source: "crssub.rs", flat: false, safe: true, parallel: false,
+      feature: None, available: true, flops: 1, bytes: 8,
+      check: Check::ElementWise },
+   ModeInfo { mode: Mode::Unsafe, name: "unsafe",
+      description: "vectors of vectors, unchecked access",
+      source: "crssub_unsafe.rs", flat: false, safe: false, parallel: false,
+      feature: None, available: true, flops: 1, bytes: 8,
+      check: Check::ElementWise },
+   ModeInfo { mode: Mode::Checked, name: "checked",
+      description: "vectors of vectors, the unsafe loops but checked",
+      source: "crssub_checked.rs", flat: false, safe: true, parallel: false,
+      feature: None, available: true, flops: 1, bytes: 8,
+      check: Check::ElementWise },
+   ModeInfo { mode: Mode::Iter, name: "iter",
+      description: "vectors of vectors, zipped iterators",
+      source: "crssub_iter.rs", flat: false, safe: true, parallel: false,
+      feature: None, available: true, flops: 1, bytes: 8,
+      check: Check::ElementWise },
+   ModeInfo { mode: Mode::Flat, name: "flat",
+      description: "a flat Array2D, array[[ix,iy]]",
+      source: "crssub1d.rs", flat: true, safe: true, parallel: false,
+      feature: None, available: true, flops: 1, bytes: 8,
+      check: Check::ElementWise },
+   ModeInfo { mode: Mode::Threads, name: "threads",
+      description: "a flat Array2D, bands of rows in parallel",
+      source: "crssub_threads.rs", flat: true, safe: true, parallel: true,
+      feature: None, available: true, flops: 1, bytes: 8,
+      check: Check::ElementWise },
+   ModeInfo { mode: Mode::Split, name: "split",
+      description: "a flat Array2D, rows halved recursively in parallel",
+      source: "crssub_split.rs", flat: true, safe: true, parallel: true,
+      feature: None, available: true, flops: 1, bytes: 8,
+      check: Check::ElementWise },
+   ModeInfo { mode: Mode::Const, name: "const",
+      description: "a flat Array2D, nx fixed at compile time if possible",
+      source: "crssub_const.rs", flat: true, safe: true, parallel: false,
+      feature: None, available: true, flops: 1, bytes: 8,
+      check: Check::ElementWise },
+   ModeInfo { mode: Mode::Chunks, name: "chunks",
+      description: "a flat Array2D, rows handled in chunks of 8",
+      source: "crssub_chunks.rs", flat: true, safe: true, parallel: false,
+      feature: None, available: true, flops: 1, bytes: 8,
+      check: Check::ElementWise },
+   ModeInfo { mode: Mode::RawPtr, name: "rawptr",
+      description: "a flat Array2D, walked with raw pointers",
+      source: "crssub_rawptr.rs", flat: true, safe: false, parallel: false,
+      feature: None, available: true, flops: 1, bytes: 8,
+      check: Check::ElementWise },
+   ModeInfo { mode: Mode::Morton, name: "morton",
+      description: "a flat Array2D, visited in Morton (Z) order",
+      source: "crssub_morton.rs", flat: true, safe: true, parallel: false,
+      feature: None, available: true, flops: 1, bytes: 8,
+      check: Check::ElementWise },
+   ModeInfo { mode: Mode::Gather, name: "gather",
+      description: "a flat Array2D, read through a permutation index",
+      source: "crssub_gather.rs", flat: true, safe: true, parallel: false,
+      feature: None, available: true, flops: 1, bytes: 16,
+      check: Check::Permutation },
+   ModeInfo { mode: Mode::Window, name: "window",
+      description: "a flat Array2D, a sliding window of rows at a time",
+      source: "crssub_window.rs", flat: true, safe: true, parallel: false,
+      feature: None, available: true, flops: 1, bytes: 8,
+      check: Check::ElementWise },
+   ModeInfo { mode: Mode::Unrolled, name: "unrolled",
+      description: "a flat Array2D, the inner loop unrolled by hand",
+      source: "crssub_unrolled.rs", flat: true, safe: true, parallel: false,
+      feature: None, available: true, flops: 1, bytes: 8,
+      check: Check::ElementWise },
+   ModeInfo { mode: Mode::Simd4, name: "simd4",
+      description: "a flat Array2D, in groups of four lanes",
+      source: "crssub_lanes.rs", flat: true, safe: true, parallel: false,
+      feature: None, available: true, flops: 1, bytes: 8,
+      check: Check::ElementWise },
+   ModeInfo { mode: Mode::Fma4, name: "fma4",
+      description: "as simd4, but in * 1 + addend, to test FMA contraction",
+      source: "crssub_lanes.rs", flat: true, safe: true, parallel: false,
+      feature: None, available: true, flops: 2, bytes: 8,
+      check: Check::ElementWise },
+   ModeInfo { mode: Mode::Expr, name: "expr",
+      description: "a flat Array2D, --expr interpreted - not a speed test",
+      source: "crsexpr.rs", flat: true, safe: true, parallel: false,
+      feature: None, available: true, flops: 2, bytes: 8,
+      check: Check::Expression(Expr::STANDARD) },
+   ModeInfo { mode: Mode::Binary, name: "binary",
+      description: "a flat Array2D, adding a second input array as well",
+      source: "crssub_binary.rs", flat: true, safe: true, parallel: false,
+      feature: None, available: true, flops: 2, bytes: 12,
+      check: Check::Binary },
+   ModeInfo { mode: Mode::RegTranspose, name: "regtranspose",
+      description: "a flat Array2D, written transposed in 4 x 4 blocks",
+      source: "crssub_regtranspose.rs", flat: true,
+      safe: !cfg!(all(feature = "simd", target_arch = "x86_64")),
+      parallel: false, feature: None, available: true, flops: 1, bytes: 8,
+      check: Check::Transpose },
+   ModeInfo { mode: Mode::Affine, name: "affine",
+      description: "a flat Array2D, each row times a gain plus an offset",
+      source: "crssub_affine.rs", flat: true, safe: true, parallel: false,
+      feature: None, available: true, flops: 2, bytes: 8,
+      check: Check::Affine },
+   ModeInfo { mode: Mode::Copy, name: "copy",
+      description: "a flat Array2D, copied with no arithmetic",
+      source: "crssub_copy.rs", flat: true, safe: true, parallel: false,
+      feature: None, available: true, flops: 0, bytes: 8,
+      check: Check::Copy },
+   ModeInfo { mode: Mode::Prefix, name: "prefix",
+      description: "a flat Array2D, with running sums along each row",
+      source: "crssub_prefix.rs", flat: true, safe: true, parallel: false,
+      feature: None, available: true, flops: 1, bytes: 8,
+      check: Check::Prefix },
+];
+
+impl Mode {
+
+   //  Returns the mode with the given name, whether or not it's available
+   //  in this build.
+
+   pub fn from_name (name: &str) -> Option<Mode> {
+      MODES.iter().find(|info| info.name == name).map(|info| info.mode)
+   }
+
+   pub fn info (&self) -> &'static ModeInfo {
+      match MODES.iter().find(|info| info.mode == *self) {
+         Some(info) => info,
+         None => panic!("Mode {:?} is missing from the MODES table",self),
+      }
+   }
+
+   pub fn name (&self) -> &'static str {
+      self.info().name
+   }
+
+   //  True for the modes that use more than one thread.
+
+   pub fn is_parallel (&self) -> bool {
+      self.info().parallel
+   }
+}
+
+//  The order in which the loops are nested. The storage is always row-major,
+//  so ij - iy in the outer loop and ix in the inner one - goes through
+//  memory in order, and ji jumps from row to row.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LoopOrder {
+   Ij,
+   Ji,
+}
+
+impl LoopOrder {
+   pub fn from_name (name: &str) -> Option<LoopOrder> {
+      match name {
+         "ij" => Some(LoopOrder::Ij),
+         "ji" => Some(LoopOrder::Ji),
+         _ => None,
+      }
+   }
+
+   pub fn name (&self) -> &'static str {
+      match *self {
+         LoopOrder::Ij => "ij",
+         LoopOrder::Ji => "ji",
+      }
+   }
+}
+
+//  The different ways the results can be written out. Jsonl is JSON Lines -
+//  one complete JSON object per result, on a line of its own. Prometheus
+//  is the Prometheus text exposition format, for monitoring.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+   Prose,
+   Csv,
+   Jsonl,
+   Prometheus,
+}
+
+impl Format {
+   pub fn from_name (name: &str) -> Option<Format> {
+      match name {
+         "prose" => Some(Format::Prose),
+         "csv" => Some(Format::Csv),
+         "jsonl" => Some(Format::Jsonl),
+         "prometheus" => Some(Format::Prometheus),
+         _ => None,
+      }
+   }
+
+   pub fn name (&self) -> &'static str {
+      match *self {
+         Format::Prose => "prose",
+         Format::Csv => "csv",
+         Format::Jsonl => "jsonl",
+         Format::Prometheus => "prometheus",
+      }
+   }
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                          B e n c h   C o n f i g
+
+//  A complete, checked, benchmark configuration. See the list of defaults
+//  at the top of this file for what each field means.
+
+#[derive(Clone, Debug)]
+pub struct BenchConfig {
+   pub nrpt: usize,
+   pub ny: usize,
+   pub nx: usize,
+   pub dims: usize,
+   pub nz: usize,
+   pub mode: Mode,
+   pub trials: usize,
+   pub warmup: usize,
+   pub warmup_seconds: Option<f64>,
+   pub warmup_to_freq: bool,
+   pub format: Format,
+   pub baseline: Option<String>,
+   pub threshold: f64,
+   pub compare_build: Option<String>,
+   pub verify_both: bool,
+   pub verify_shuffle: bool,
+   pub strict_fp: bool,
+   pub tolerance: f64,
+   pub inner_repeat: Option<usize>,
+   pub threads: usize,
+   pub check_coverage: bool,
+   pub check_conflict: bool,
+   pub cache_line: usize,
+   pub cache_assoc: usize,
+   pub cache_size: usize,
+   pub sweep_2d: Option<(Vec<usize>,Vec<usize>)>,
+   pub sweep_shard: Option<(usize,usize)>,
+   pub count_ops: bool,
+   pub verify_every: Option<usize>,
+   pub row_access: bool,
+   pub per_row_timing: bool,
+   pub input: Input,
+   pub seed: u64,
+   pub seed_per_trial: bool,
+   pub trial_fresh_thread: bool,
+   pub diff_map: Option<String>,
+   pub svg_histogram: Option<String>,
+   pub compare_with: Option<String>,
+   pub emit_canonical: Option<String>,
+   pub label: Option<String>,
+   pub endian: Endian,
+   pub time_source: TimeSource,
+   pub loop_order: LoopOrder,
+   pub sysinfo: bool,
+   pub freq_check: bool,
+   pub report_startup: bool,
+   pub budget_ms: Option<u64>,
+   pub auto_repeats: bool,
+   pub repeat_ops: usize,
+   pub meta_file: Option<String>,
+   pub append: Option<String>,
+   pub sqlite: Option<String>,
+   pub fits: Option<String>,
+   pub fits_data: bool,
+   pub report_tcp: Option<String>,
+   pub drift_check: Option<usize>,
+   pub drift_pause_ms: u64,
+   pub out_stride: Option<usize>,
+   pub perm_seed: Option<u64>,
+   pub window: Option<usize>,
+   pub window_stride: Option<usize>,
+   pub unroll: Option<usize>,
+   pub expr: Option<String>,
+   pub equiv: Option<(Mode,Mode)>,
+   pub compare_types: bool,
+   pub compare_unroll: bool,
+   pub false_share_study: bool,
+   pub order_study: bool,
+   pub bench_construction: bool,
+   pub find_cliff: bool,
+   pub torture: bool,
+   pub concurrent_arrays: Option<usize>,
+   pub fresh_alloc: bool,
+   pub hugepages: bool,
+   pub mlock: bool,
+   pub protect_input: bool,
+   pub peak_bandwidth: Option<f64>,
+   pub roofline: bool,
+   pub vs_memcpy: bool,
+   pub checksum: Option<Checksum>,
+   pub precision: Option<usize>,
+   pub si_times: bool,
+   pub danger_alias: Option<isize>,
+}
+
+impl BenchConfig {
+
+   //  Returns a builder with everything set to the defaults.
+
+   pub fn builder () -> BenchConfigBuilder {
+      BenchConfigBuilder {
+         config: BenchConfig {
+            nrpt: DEFAULT_NRPT,
+            ny: DEFAULT_NY,
+            nx: DEFAULT_NX,
+            dims: DEFAULT_DIMS,
+            nz: DEFAULT_NZ,
+            mode: Mode::Index,
+            trials: DEFAULT_TRIALS,
+            warmup: DEFAULT_WARMUP,
+            warmup_seconds: None,
+            warmup_to_freq: false,
+            format: Format::Prose,
+            baseline: None,
+            threshold: DEFAULT_THRESHOLD,
+            compare_build: None,
+            verify_both: false,
+            verify_shuffle: false,
+            strict_fp: false,
+            tolerance: crsverify::DEFAULT_TOLERANCE,
+            inner_repeat: None,
+            threads: default_threads(),
+            check_coverage: false,
+            check_conflict: false,
+            cache_line: DEFAULT_CACHE_LINE,
+            cache_assoc: DEFAULT_CACHE_ASSOC,
+            cache_size: DEFAULT_CACHE_SIZE,
+            sweep_2d: None,
+            sweep_shard: None,
+            count_ops: false,
+            verify_every: None,
+            row_access: false,
+            per_row_timing: false,
+            input: Input::Standard,
+            seed: crsinput::DEFAULT_SEED,
+            seed_per_trial: false,
+            trial_fresh_thread: false,
+            diff_map: None,
+            svg_histogram: None,
+            compare_with: None,
+            emit_canonical: None,
+            label: None,
+            endian: Endian::Little,
+            time_source: TimeSource::Monotonic,
+            loop_order: LoopOrder::Ij,
+            sysinfo: false,
+            freq_check: false,
+            report_startup: false,
+            budget_ms: None,
+            auto_repeats: false,
+            repeat_ops: DEFAULT_REPEAT_OPS,
+            meta_file: None,
+            append: None,
+            sqlite: None,
+            fits: None,
+            fits_data: false,
+            report_tcp: None,
+            drift_check: None,
+            drift_pause_ms: DEFAULT_DRIFT_PAUSE_MS,
+            out_stride: None,
+            perm_seed: None,
+            window: None,
+            window_stride: None,
+            unroll: None,
+            expr: None,
+            equiv: None,
+            compare_types: false,
+            compare_unroll: false,
+            false_share_study: false,
+            order_study: false,
+            bench_construction: false,
+            find_cliff: false,
+            torture: false,
+            concurrent_arrays: None,
+            fresh_alloc: false,
+            hugepages: false,
+            mlock: false,
+            protect_input: false,
+            peak_bandwidth: None,
+            roofline: false,
+            vs_memcpy: false,
+            checksum: None,
+            precision: None,
+            si_times: false,
+            danger_alias: None,
+         },
+         mode_name: None,
+         format_name: None,
+         input_name: None,
+         fits_path: None,
+         loop_order_name: None,
+         checksum_name: None,
+         endian_name: None,
+         time_source_name: None,
+         mean: None,
+         stddev: None,
+         equiv_names: None,
+         warmup_given: false,
+      }
+   }
+
+   //  Returns a builder with everything set as in this configuration, so a
+   //  variation on it can be built - a sweep uses this to set the dimensions
+   //  for each of its configurations.
+
+   pub fn to_builder (&self) -> BenchConfigBuilder {
+      BenchConfigBuilder { config: self.clone(), mode_name: None,
+                                   format_name: None, input_name: None,
+                                   fits_path: None,
+                                   loop_order_name: None, checksum_name: None,
+                                   endian_name: None, time_source_name: None,
+                                   mean: None, stddev: None, equiv_names: None,
+                                   warmup_given: false }
+   }
+
+   //  How the results of a run with this configuration are to be checked.
+   //  That's the mode's check, except that the gather mode with the
+   //  identity permutation gives the usual output, and can be checked
+   //  element by element, and the expr mode's results are checked against
+   //  the expression it was actually given.
+
+   pub fn check (&self) -> Check {
+      if self.mode == Mode::Gather && self.perm_seed.is_none() {
+         Check::ElementWise
+      } else if let Some(expr) = self.expression() {
+         Check::Expression(expr)
+      } else {
+         self.mode.info().check
+      }
+   }
+
+   //  The result files for a build comparison, if there is one.
+
+   pub fn build_comparison (&self) -> Option<Vec<&str>> {
+      self.compare_build.as_ref()
+                        .map(|paths| paths.split(',').map(str::trim).collect())
+   }
+
+   //  The cache geometry to use for a cache conflict check.
+
+   pub fn cache_geometry (&self) -> CacheGeometry {
+      CacheGeometry { line: self.cache_line, assoc: self.cache_assoc,
+                                                     size: self.cache_size }
+   }
+
+   //  The number of decimal places a result is printed with - the
+   //  precision, if one was given, or else the usual number for that kind
+   //  of value.
+
+   pub fn places (&self,usual: usize) -> usize {
+      self.precision.unwrap_or(usual)
+   }
+
+   //  A time in ns, as it's shown in the prose output, to the given number
+   //  of decimal places - scaled to a suitable unit with si_times, and in
+   //  ns otherwise (see crsunits.rs).
+
+   pub fn time_text (&self,ns: f64,places: usize) -> String {
+      crsunits::time_text(ns,places,self.si_times)
+   }
+
+   //  A time in ns as it's shown in a column of a table headed by
+   //  time_heading() - with its unit with si_times, and as a plain number
+   //  of ns otherwise, since the heading says they're ns.
+
+   pub fn time_cell (&self,ns: f64,places: usize) -> String {
+      if self.si_times { self.time_text(ns,places) }
+      else { format!("{:.*}",places,ns) }
+   }
+
+   //  The heading for a column of times in a table, such as 'min ns/call',
+   //  for times of the given kind each per the given thing. With si_times
+   //  each time carries its own unit, and it's 'min per call'.
+
+   pub fn time_heading (&self,kind: &str,per: &str) -> String {
+      let heading = if self.si_times { format!("{} per {}",kind,per) }
+                    else { format!("{} ns/{}",kind,per) };
+      heading.trim_start().to_string()
+   }
+
+   //  The name of the input, as it goes into the results - the input's
+   //  own name, with '_per_trial' added for a seed per trial, or 'fits' if
+   //  the input is the pixel data from a FITS file.
+
+   pub fn input_label (&self) -> String {
+      if self.fits_data {
+         "fits".to_string()
+      } else if self.seed_per_trial {
+         format!("{}_per_trial",self.input.name())
+      } else {
+         self.input.name().to_string()
+      }
+   }
+
+   //  The height of the window mode's window, in rows, and the number of
+   //  rows it moves on by, with the defaults filled in. None for every
+   //  other mode.
+
+   pub fn window_shape (&self) -> Option<(usize,usize)> {
+      if self.mode != Mode::Window { return None; }
+      Some((self.window.unwrap_or(DEFAULT_WINDOW.min(self.ny)),
+                      self.window_stride.unwrap_or(DEFAULT_WINDOW_STRIDE)))
+   }
+
+   //  The unrolled mode's unroll factor, with the default filled in. None
+   //  for every other mode.
+
+   pub fn unroll_factor (&self) -> Option<usize> {
+      if self.mode != Mode::Unrolled { return None; }
+      Some(self.unroll.unwrap_or(DEFAULT_UNROLL))
+   }
+
+   //  The expr mode's expression, compiled, with the default filled in.
+   //  None for every other mode. The expression has been checked by
+   //  build(), so it can always be compiled.
+
+   pub fn expression (&self) -> Option<Expr> {
+      if self.mode != Mode::Expr { return None; }
+      let text = self.expr.as_deref().unwrap_or(crsexpr::STANDARD);
+      Some(Expr::parse(text).unwrap_or(Expr::STANDARD))
+   }
+
+   //  The floating point operations the kernel does for each element -
+   //  the mode's own figure, or for the expr mode the operations in its
+   //  expression.
+
+   pub fn flops (&self) -> usize {
+      match self.expression() {
+         Some(expr) => expr.flops(),
+         None => self.mode.info().flops,
+      }
+   }
+
+   //  The configuration as (name,value) pairs, in the order of the list of
+   //  defaults, for reporting. Anything not set is shown as 'none'.
+
+   pub fn fields (&self) -> Vec<(&'static str,String)> {
+      self.settings().into_iter().map(|(name,value)|
+                   (name,value.unwrap_or_else(|| "none".to_string()))).collect()
+   }
+
+   //  The same pairs, but with None for anything not set, for write_file(),
+   //  which leaves those out.
+
+   fn settings (&self) -> Vec<(&'static str,Option<String>)> {
+      vec![
+         ("nrpt",Some(self.nrpt.to_string())),
+         ("ny",Some(self.ny.to_string())),
+         ("nx",Some(self.nx.to_string())),
+         ("dims",Some(self.dims.to_string())),
+         ("nz",Some(self.nz.to_string())),
+         ("mode",Some(self.mode.name().to_string())),
+         ("trials",Some(self.trials.to_string())),
+         ("warmup",Some(self.warmup.to_string())),
+         ("warmup_seconds",self.warmup_seconds.map(|t| t.to_string())),
+         ("warmup_to_freq",Some(self.warmup_to_freq.to_string())),
+         ("format",Some(self.format.name().to_string())),
+         ("baseline",self.baseline.clone()),
+         ("threshold",Some(self.threshold.to_string())),
+         ("compare_build",self.compare_build.clone()),
+         ("verify_both",Some(self.verify_both.to_string())),
+         ("verify_shuffle",Some(self.verify_shuffle.to_string())),
+         ("strict_fp",Some(self.strict_fp.to_string())),
+         ("tolerance",Some(format!("{:e}",self.tolerance))),
+         ("inner_repeat",self.inner_repeat.map(|n| n.to_string())),
+         ("threads",Some(self.threads.to_string())),
+         ("check_coverage",Some(self.check_coverage.to_string())),
+         ("check_conflict",Some(self.check_conflict.to_string())),
+         ("cache_line",Some(self.cache_line.to_string())),
+         ("cache_assoc",Some(self.cache_assoc.to_string())),
+         ("cache_size",Some(self.cache_size.to_string())),
+         ("sweep_2d",self.sweep_2d.as_ref().map(|sweep| {
+            let list = |values: &Vec<usize>| values.iter()
+                         .map(|n| n.to_string()).collect::<Vec<_>>().join(",");
+            format!("{}x{}",list(&sweep.0),list(&sweep.1))
+         })),
+         ("sweep_shard",self.sweep_shard.map(|(shard,shards)|
+                                             format!("{}/{}",shard,shards))),
+         ("count_ops",Some(self.count_ops.to_string())),
+         ("verify_every",self.verify_every.map(|n| n.to_string())),
+         ("row_access",Some(self.row_access.to_string())),
+         ("per_row_timing",Some(self.per_row_timing.to_string())),
+         ("input",Some(self.input.describe())),
+         ("seed",Some(self.seed.to_string())),
+         ("seed_per_trial",Some(self.seed_per_trial.to_string())),
+         ("trial_fresh_thread",Some(self.trial_fresh_thread.to_string())),
+         ("diff_map",self.diff_map.clone()),
+         ("svg_histogram",self.svg_histogram.clone()),
+         ("compare_with",self.compare_with.clone()),
+         ("emit_canonical",self.emit_canonical.clone()),
+         ("label",self.label.clone()),
+         ("endian",Some(self.endian.name().to_string())),
+         ("loop_order",Some(self.loop_order.name().to_string())),
+         ("sysinfo",Some(self.sysinfo.to_string())),
+         ("freq_check",Some(self.freq_check.to_string())),
+         ("report_startup",Some(self.report_startup.to_string())),
+         ("budget_ms",self.budget_ms.map(|n| n.to_string())),
+         ("auto_repeats",Some(self.auto_repeats.to_string())),
+         ("repeat_ops",Some(self.repeat_ops.to_string())),
+         ("time_source",Some(self.time_source.name().to_string())),
+         ("meta_file",self.meta_file.clone()),
+         ("append",self.append.clone()),
+         ("sqlite",self.sqlite.clone()),
+         ("fits",self.fits.clone()),
+         ("fits_data",Some(self.fits_data.to_string())),
+         ("report_tcp",self.report_tcp.clone()),
+         ("drift_check",self.drift_check.map(|n| n.to_string())),
+         ("drift_pause_ms",Some(self.drift_pause_ms.to_string())),
+         ("out_stride",self.out_stride.map(|n| n.to_string())),
+         ("perm_seed",self.perm_seed.map(|n| n.to_string())),
+         ("window",self.window.map(|n| n.to_string())),
+         ("window_stride",self.window_stride.map(|n| n.to_string())),
+         ("unroll",self.unroll.map(|n| n.to_string())),
+         ("expr",self.expr.clone()),
+         ("equiv",self.equiv.map(|(mode_a,mode_b)|
+                               format!("{},{}",mode_a.name(),mode_b.name()))),
+         ("compare_types",Some(self.compare_types.to_string())),
+         ("compare_unroll",Some(self.compare_unroll.to_string())),
+         ("false_share_study",Some(self.false_share_study.to_string())),
+         ("order_study",Some(self.order_study.to_string())),
+         ("bench_construction",Some(self.bench_construction.to_string())),
+         ("find_cliff",Some(self.find_cliff.to_string())),
+         ("torture",Some(self.torture.to_string())),
+         ("concurrent_arrays",self.concurrent_arrays.map(|n| n.to_string())),
+         ("fresh_alloc",Some(self.fresh_alloc.to_string())),
+         ("hugepages",Some(self.hugepages.to_string())),
+         ("mlock",Some(self.mlock.to_string())),
+         ("protect_input",Some(self.protect_input.to_string())),
+         ("peak_bandwidth",self.peak_bandwidth.map(|gbs| gbs.to_string())),
+         ("roofline",Some(self.roofline.to_string())),
+         ("vs_memcpy",Some(self.vs_memcpy.to_string())),
+         ("checksum",self.checksum.map(|checksum|
+                                                checksum.name().to_string())),
+         ("precision",self.precision.map(|n| n.to_string())),
+         ("si_times",Some(self.si_times.to_string())),
+         ("danger_alias",self.danger_alias.map(|n| n.to_string())),
+      ]
+   }
+}
+
+//  The default number of threads is the number of CPUs the program can use.
+
+pub fn default_threads () -> usize {
+   thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                 B e n c h   C o n f i g   B u i l d e r
+
+//  Builds a BenchConfig. Each setter takes the builder by value and returns
+//  it, so calls can be chained. The mode, format, input, loop order,
+//  checksum and byte order are given by name, and are only checked by
+//  build(), and a FITS file is only read, for its dimensions, by build().
+//  The mean and standard deviation are only used with the Gaussian input,
+//  so are also held separately until build() knows which input is
+//  being used.
+
+pub struct BenchConfigBuilder {
+   config: BenchConfig,
+   mode_name: Option<String>,
+   format_name: Option<String>,
+   input_name: Option<String>,
+   fits_path: Option<String>,
+   loop_order_name: Option<String>,
+   checksum_name: Option<String>,
+   endian_name: Option<String>,
+   time_source_name: Option<String>,
+   mean: Option<f64>,
+   stddev: Option<f64>,
+   equiv_names: Option<(String,String)>,
+
+   //  Set if a warmup count is given explicitly, since it can't be combined
+   //  with a warmup time.
+
+   warmup_given: bool,
+}
+
+impl BenchConfigBuilder {
+
+   pub fn nrpt (mut self,nrpt: usize) -> Self {
+      self.config.nrpt = nrpt; self
+   }
+
+   pub fn ny (mut self,ny: usize) -> Self {
+      self.config.ny = ny; self
+   }
+
+   pub fn nx (mut self,nx: usize) -> Self {
+      self.config.nx = nx; self
+   }
+
+   pub fn dims (mut self,dims: usize) -> Self {
+      self.config.dims = dims; self
+   }
+
+   pub fn nz (mut self,nz: usize) -> Self {
+      self.config.nz = nz; self
+   }
+
+   pub fn mode (mut self,name: &str) -> Self {
+      self.mode_name = Some(name.to_string()); self
+   }
+
+   pub fn trials (mut self,trials: usize) -> Self {
+      self.config.trials = trials; self
+   }
+
+   pub fn warmup (mut self,warmup: usize) -> Self {
+      self.config.warmup = warmup; self.warmup_given = true; self
+   }
+
+   pub fn warmup_seconds (mut self,seconds: Option<f64>) -> Self {
+      self.config.warmup_seconds = seconds; self
+   }
+
+   pub fn warmup_to_freq (mut self,warmup_to_freq: bool) -> Self {
+      self.config.warmup_to_freq = warmup_to_freq; self
+   }
+
+   pub fn format (mut self,name: &str) -> Self {
+      self.format_name = Some(name.to_string()); self
+   }
+
+   pub fn baseline (mut self,path: &str) -> Self {
+      self.config.baseline = Some(path.to_string()); self
+   }
+
+   pub fn threshold (mut self,threshold: f64) -> Self {
+      self.config.threshold = threshold; self
+   }
+
+   pub fn compare_build (mut self,paths: &str) -> Self {
+      self.config.compare_build = Some(paths.to_string()); self
+   }
+
+   pub fn verify_both (mut self,verify_both: bool) -> Self {
+      self.config.verify_both = verify_both; self
+   }
+
+   pub fn verify_shuffle (mut self,verify_shuffle: bool) -> Self {
+      self.config.verify_shuffle = verify_shuffle; self
+   }
+
+   pub fn strict_fp (mut self,strict_fp: bool) -> Self {
+      self.config.strict_fp = strict_fp; self
+   }
+
+   pub fn tolerance (mut self,tolerance: f64) -> Self {
+      self.config.tolerance = tolerance; self
+   }
+
+   pub fn inner_repeat (mut self,inner_repeat: Option<usize>) -> Self {
+      self.config.inner_repeat = inner_repeat; self
+   }
+
+   pub fn threads (mut self,threads: usize) -> Self {
+      self.config.threads = threads; self
+   }
+
+   pub fn check_coverage (mut self,check_coverage: bool) -> Self {
+      self.config.check_coverage = check_coverage; self
+   }
+
+   pub fn check_conflict (mut self,check_conflict: bool) -> Self {
+      self.config.check_conflict = check_conflict; self
+   }
+
+   pub fn cache_line (mut self,cache_line: usize) -> Self {
+      self.config.cache_line = cache_line; self
+   }
+
+   pub fn cache_assoc (mut self,cache_assoc: usize) -> Self {
+      self.config.cache_assoc = cache_assoc; self
+   }
+
+   pub fn cache_size (mut self,cache_size: usize) -> Self {
+      self.config.cache_size = cache_size; self
+   }
+
+   pub fn sweep_2d (mut self,sweep: Option<(Vec<usize>,Vec<usize>)>) -> Self {
+      self.config.sweep_2d = sweep; self
+   }
+
+   pub fn sweep_shard (mut self,shard: Option<(usize,usize)>) -> Self {
+      self.config.sweep_shard = shard; self
+   }
+
+   pub fn count_ops (mut self,count_ops: bool) -> Self {
+      self.config.count_ops = count_ops; self
+   }
+
+   pub fn verify_every (mut self,calls: Option<usize>) -> Self {
+      self.config.verify_every = calls; self
+   }
+
+   pub fn row_access (mut self,row_access: bool) -> Self {
+      self.config.row_access = row_access; self
+   }
+
+   pub fn per_row_timing (mut self,per_row_timing: bool) -> Self {
+      self.config.per_row_timing = per_row_timing; self
+   }
+
+   pub fn input (mut self,name: &str) -> Self {
+      self.input_name = Some(name.to_string()); self
+   }
+
+   pub fn fits (mut self,path: &str) -> Self {
+      self.fits_path = Some(path.to_string()); self
+   }
+
+   pub fn fits_data (mut self,fits_data: bool) -> Self {
+      self.config.fits_data = fits_data; self
+   }
+
+   pub fn mean (mut self,mean: f64) -> Self {
+      self.mean = Some(mean); self
+   }
+
+   pub fn stddev (mut self,stddev: f64) -> Self {
+      self.stddev = Some(stddev); self
+   }
+
+   pub fn seed (mut self,seed: u64) -> Self {
+      self.config.seed = seed; self
+   }
+
+   pub fn seed_per_trial (mut self,seed_per_trial: bool) -> Self {
+      self.config.seed_per_trial = seed_per_trial; self
+   }
+
+   pub fn trial_fresh_thread (mut self,trial_fresh_thread: bool) -> Self {
+      self.config.trial_fresh_thread = trial_fresh_thread; self
+   }
+
+   pub fn diff_map (mut self,path: &str) -> Self {
+      self.config.diff_map = Some(path.to_string()); self
+   }
+
+   pub fn svg_histogram (mut self,path: &str) -> Self {
+      self.config.svg_histogram = Some(path.to_string()); self
+   }
+
+   pub fn compare_with (mut self,path: &str) -> Self {
+      self.config.compare_with = Some(path.to_string()); self
+   }
+
+   pub fn emit_canonical (mut self,path: &str) -> Self {
+      self.config.emit_canonical = Some(path.to_string()); self
+   }
+
+   pub fn label (mut self,label: &str) -> Self {
+      self.config.label = Some(label.to_string()); self
+   }
+
+   pub fn endian (mut self,name: &str) -> Self {
+      self.endian_name = Some(name.to_string()); self
+   }
+
+   pub fn time_source (mut self,name: &str) -> Self {
+      self.time_source_name = Some(name.to_string()); self
+   }
+
+   pub fn loop_order (mut self,name: &str) -> Self {
+      self.loop_order_name = Some(name.to_string()); self
+   }
+
+   pub fn sysinfo (mut self,sysinfo: bool) -> Self {
+      self.config.sysinfo = sysinfo; self
+   }
+
+   pub fn freq_check (mut self,freq_check: bool) -> Self {
+      self.config.freq_check = freq_check; self
+   }
+
+   pub fn report_startup (mut self,report_startup: bool) -> Self {
+      self.config.report_startup = report_startup; self
+   }
+
+   pub fn budget_ms (mut self,budget_ms: Option<u64>) -> Self {
+      self.config.budget_ms = budget_ms; self
+   }
+
+   pub fn auto_repeats (mut self,auto_repeats: bool) -> Self {
+      self.config.auto_repeats = auto_repeats; self
+   }
+
+   pub fn repeat_ops (mut self,repeat_ops: usize) -> Self {
+      self.config.repeat_ops = repeat_ops; self
+   }
+
+   pub fn meta_file (mut self,path: &str) -> Self {
+      self.config.meta_file = Some(path.to_string()); self
+   }
+
+   pub fn append (mut self,path: &str) -> Self {
+      self.config.append = Some(path.to_string()); self
+   }
+
+   pub fn sqlite (mut self,path: &str) -> Self {
+      self.config.sqlite = Some(path.to_string()); self
+   }
+
+   pub fn report_tcp (mut self,address: &str) -> Self {
+      self.config.report_tcp = Some(address.to_string()); self
+   }
+
+   pub fn drift_check (mut self,repetitions: Option<usize>) -> Self {
+      self.config.drift_check = repetitions; self
+   }
+
+   pub fn drift_pause_ms (mut self,pause_ms: u64) -> Self {
+      self.config.drift_pause_ms = pause_ms; self
+   }
+
+   pub fn out_stride (mut self,stride: Option<usize>) -> Self {
+      self.config.out_stride = stride; self
+   }
+
+   pub fn perm_seed (mut self,seed: Option<u64>) -> Self {
+      self.config.perm_seed = seed; self
+   }
+
+   pub fn window (mut self,rows: Option<usize>) -> Self {
+      self.config.window = rows; self
+   }
+
+   pub fn window_stride (mut self,rows: Option<usize>) -> Self {
+      self.config.window_stride = rows; self
+   }
+
+   pub fn unroll (mut self,factor: Option<usize>) -> Self {
+      self.config.unroll = factor; self
+   }
+
+   pub fn expr (mut self,expr: Option<String>) -> Self {
+      self.config.expr = expr; self
+   }
+
+   pub fn peak_bandwidth (mut self,gbytes_per_s: Option<f64>) -> Self {
+      self.config.peak_bandwidth = gbytes_per_s; self
+   }
+
+   pub fn roofline (mut self,roofline: bool) -> Self {
+      self.config.roofline = roofline; self
+   }
+
+   pub fn vs_memcpy (mut self,vs_memcpy: bool) -> Self {
+      self.config.vs_memcpy = vs_memcpy; self
+   }
+
+   pub fn checksum (mut self,name: &str) -> Self {
+      self.checksum_name = Some(name.to_string()); self
+   }
+
+   pub fn precision (mut self,places: Option<usize>) -> Self {
+      self.config.precision = places; self
+   }
+
+   pub fn si_times (mut self,si_times: bool) -> Self {
+      self.config.si_times = si_times; self
+   }
+
+   pub fn danger_alias (mut self,offset: Option<isize>) -> Self {
+      self.config.danger_alias = offset; self
+   }
+
+   pub fn equiv (mut self,name_a: &str,name_b: &str) -> Self {
+      self.equiv_names = Some((name_a.to_string(),name_b.to_string())); self
+   }
+
+   pub fn compare_types (mut self,compare_types: bool) -> Self {
+      self.config.compare_types = compare_types; self
+   }
+
+   pub fn compare_unroll (mut self,compare_unroll: bool) -> Self {
+      self.config.compare_unroll = compare_unroll; self
+   }
+
+   pub fn false_share_study (mut self,false_share_study: bool) -> Self {
+      self.config.false_share_study = false_share_study; self
+   }
+
+   pub fn order_study (mut self,order_study: bool) -> Self {
+      self.config.order_study = order_study; self
+   }
+
+   pub fn bench_construction (mut self,bench_construction: bool) -> Self {
+      self.config.bench_construction = bench_construction; self
+   }
+
+   pub fn find_cliff (mut self,find_cliff: bool) -> Self {
+      self.config.find_cliff = find_cliff; self
+   }
+
+   pub fn torture (mut self,torture: bool) -> Self {
+      self.config.torture = torture; self
+   }
+
+   pub fn concurrent_arrays (mut self,count: Option<usize>) -> Self {
+      self.config.concurrent_arrays = count; self
+   }
+
+   pub fn fresh_alloc (mut self,fresh_alloc: bool) -> Self {
+      self.config.fresh_alloc = fresh_alloc; self
+   }
+
+   pub fn hugepages (mut self,hugepages: bool) -> Self {
+      self.config.hugepages = hugepages; self
+   }
+
+   pub fn mlock (mut self,mlock: bool) -> Self {
+      self.config.mlock = mlock; self
+   }
+
+   pub fn protect_input (mut self,protect_input: bool) -> Self {
+      self.config.protect_input = protect_input; self
+   }
+
+   //  Puts the warmup back to the default, forgetting any count, time or
+   //  wait for the frequency - so that a warmup given on the command line
+   //  replaces one read from a file, rather than clashing with it.
+
+   pub fn reset_warmup (mut self) -> Self {
+      self.config.warmup = DEFAULT_WARMUP;
+      self.config.warmup_seconds = None;
+      self.config.warmup_to_freq = false;
+      self.warmup_given = false;
+      self
+   }
+
+   //  Checks the configuration, returning it if it makes sense, or a message
+   //  explaining the problem if not.
+
+   pub fn build (self) -> Result<BenchConfig,BenchError> {
+      self.check().map_err(BenchError::Config)
+   }
+
+   //  Does the work for build(), returning a message saying what's wrong
+   //  with the configuration if anything is.
+
+   fn check (self) -> Result<BenchConfig,String> {
+      let mut config = self.config;
+      if let Some(name) = self.mode_name {
+         config.mode = available_mode(&name)?;
+      }
+      if let Some((name_a,name_b)) = self.equiv_names {
+         config.equiv = Some((available_mode(&name_a)?,
+                                                   available_mode(&name_b)?));
+      }
+      if let Some(name) = self.format_name {
+         config.format = match Format::from_name(&name) {
+            Some(format) => format,
+            None => return Err(format!("Unknown format '{}'",name)),
+         };
+      }
+      if let Some(name) = self.input_name {
+         config.input = match Input::from_name(&name) {
+            Some(input) => input,
+            None => return Err(format!("Unknown input '{}'",name)),
+         };
+      }
+      if self.mean.is_some() || self.stddev.is_some() {
+         match config.input {
+            Input::Gaussian { ref mut mean, ref mut stddev } => {
+               *mean = self.mean.unwrap_or(*mean);
+               *stddev = self.stddev.unwrap_or(*stddev);
+            }
+            _ => return Err("A mean and standard deviation can only be \
+                                 given for the gaussian input".to_string()),
+         }
+      }
+      if let Input::Gaussian { stddev, .. } = config.input {
+         if stddev.is_nan() || stddev < 0.0 {
+            return Err("The standard deviation must not be negative"
+                                                                .to_string());
+         }
+      }
+      if let Some(name) = self.loop_order_name {
+         config.loop_order = match LoopOrder::from_name(&name) {
+            Some(loop_order) => loop_order,
+            None => return Err(format!("Unknown loop order '{}'",name)),
+         };
+      }
+      if let Some(name) = self.checksum_name {
+         config.checksum = match Checksum::from_name(&name) {
+            Some(checksum) => Some(checksum),
+            None => return Err(format!("Unknown checksum '{}'",name)),
+         };
+      }
+      if let Some(name) = self.endian_name {
+         config.endian = match Endian::from_name(&name) {
+            Some(endian) => endian,
+            None => return Err(format!("Unknown byte order '{}'",name)),
+         };
+      }
+      if let Some(name) = self.time_source_name {
+         config.time_source = match TimeSource::from_name(&name) {
+            Some(time_source) => time_source,
+            None => return Err(format!("Unknown time source '{}' - it can \
+                                  be monotonic or cputime",name)),
+         };
+      }
+      if let Some(path) = self.fits_path {
+         let (nx,ny,bitpix) = fits_shape(&path)?;
+         if config.fits_data && bitpix != -32 {
+            return Err(format!("The FITS file {} has BITPIX = {}, but its \
+               pixel data can only be the input if it's of f32 values, the \
+               element type of the arrays, with BITPIX = -32",path,bitpix));
+         }
+         config.nx = nx;
+         config.ny = ny;
+         config.fits = Some(path);
+      }
+      if config.nx == 0 || config.ny == 0 {
+         return Err("Array dimensions must not be zero".to_string());
+      }
+      if config.nx.checked_mul(config.ny).is_none() {
+         return Err("The array has too many elements to be addressed"
+                                                                .to_string());
+      }
+      if config.nrpt == 0 && !config.auto_repeats {
+         return Err("The number of repeats must not be zero".to_string());
+      }
+      if config.trials == 0 {
+         return Err("The number of trials must not be zero".to_string());
+      }
+      if config.threads == 0 {
+         return Err("The number of threads must not be zero".to_string());
+      }
+      if config.inner_repeat == Some(0) {
+         return Err("The inner repeat count must not be zero".to_string());
+      }
+      if config.concurrent_arrays == Some(0) {
+         return Err("The number of concurrent arrays must not be zero"
+                                                                .to_string());
+      }
+      if config.verify_every == Some(0) {
+         return Err("The number of calls between checks must not be zero"
+                                                                .to_string());
+      }
+      if let Some(repetitions) = config.drift_check {
+         if repetitions < 2 {
+            return Err("A drift check needs at least two repetitions"
+                                                                .to_string());
+         }
+      }
+      if let Some(seconds) = config.warmup_seconds {
+         if self.warmup_given {
+            return Err("A warmup count and a warmup time can't both be \
+                                                           given".to_string());
+         }
+         if seconds.is_nan() || seconds <= 0.0
+                          || Duration::try_from_secs_f64(seconds).is_err() {
+            return Err("The warmup time must be a positive number of seconds"
+                                                                .to_string());
+         }
+      }
+      let warmup_fixed = self.warmup_given || config.warmup_seconds.is_some();
+      if config.warmup_to_freq && warmup_fixed {
+         return Err("A warmup to a steady frequency can't be combined with \
+                            a warmup count or a warmup time".to_string());
+      }
+      if let Some(peak) = config.peak_bandwidth {
+         if !(peak > 0.0 && peak.is_finite()) {
+            return Err("The peak bandwidth must be a positive number"
+                                                                .to_string());
+         }
+      }
+      if config.roofline && config.peak_bandwidth.is_none() {
+         return Err("A roofline report needs the peak bandwidth, given \
+                                    with --peak-bandwidth".to_string());
+      }
+      if config.sqlite.is_some() && !cfg!(feature = "sqlite") {
+         return Err("Writing results to a database needs the program to be \
+                            built with the 'sqlite' feature".to_string());
+      }
+      if config.fits.is_some() && !cfg!(feature = "fits") {
+         return Err("Reading a FITS file needs the program to be built \
+                                 with the 'fits' feature".to_string());
+      }
+      if config.fits.is_some() && config.sweep_2d.is_some() {
+         return Err("A FITS file sets the array dimensions, so can't be \
+                                         combined with a sweep".to_string());
+      }
+      if config.fits_data {
+         if config.fits.is_none() {
+            return Err("Using the pixel data of a FITS file as the input \
+                             needs the file, given with --fits".to_string());
+         }
+         if config.input != Input::Standard || config.seed_per_trial {
+            return Err("The pixel data of a FITS file is the input, so \
+               another input, or a seed per trial, can't be given as \
+                                                       well".to_string());
+         }
+      }
+      if let Some(ref address) = config.report_tcp {
+         let usable = match address.rsplit_once(':') {
+            Some((host,port)) =>
+                               !host.is_empty() && port.parse::<u16>().is_ok(),
+            None => false,
+         };
+         if !usable {
+            return Err(format!("The collector address '{}' should be given \
+                                          as HOST:PORT",address));
+         }
+      }
+      if config.strict_fp && X87_ONLY {
+         return Err("Strict floating point isn't available for this \
+            target, where f32 arithmetic uses the x87 unit and so can't be \
+            relied on to round every result to single precision".to_string());
+      }
+      if config.budget_ms == Some(0) {
+         return Err("The time budget must not be zero".to_string());
+      }
+      if config.auto_repeats {
+         if config.repeat_ops == 0 {
+            return Err("The element operations for automatic repeats must \
+                                                not be zero".to_string());
+         }
+         if config.budget_ms.is_some() {
+            return Err("Automatic repeats set the number of calls, and a \
+               time budget replaces it, so the two can't be combined"
+                                                                .to_string());
+         }
+      }
+      if let Some((ref nx_list,ref ny_list)) = config.sweep_2d {
+         if nx_list.contains(&0) || ny_list.contains(&0) {
+            return Err("Sweep dimensions must not be zero".to_string());
+         }
+      }
+      if let Some((shard,shards)) = config.sweep_shard {
+         if config.sweep_2d.is_none() {
+            return Err("A sweep shard can only be given for a sweep"
+                                                                .to_string());
+         }
+         if shard >= shards {
+            return Err(format!("Sweep shard {}/{} doesn't exist - the shards \
+                      of N are numbered from 0 to N - 1",shard,shards));
+         }
+      }
+      if config.seed_per_trial && !config.input.is_random() {
+         return Err("A seed per trial needs random or gaussian input"
+                                                                .to_string());
+      }
+      if let Some(stride) = config.out_stride {
+         if config.mode != Mode::Flat || config.loop_order != LoopOrder::Ij {
+            return Err("A strided output is only available for the flat \
+                                   mode, with the ij loop order".to_string());
+         }
+         let len = config.nx * config.ny;
+         if let Some(problem) = crssub_strided::stride_problem(stride,len) {
+            return Err(problem);
+         }
+      }
+      if config.mode == Mode::Gather {
+         if let Some(problem) =
+                        crssub_gather::size_problem(config.nx,config.ny) {
+            return Err(problem);
+         }
+      }
+      if config.perm_seed.is_some() && config.mode != Mode::Gather {
+         return Err("A permutation seed is only used by the gather mode"
+                                                                .to_string());
+      }
+      if (config.window.is_some() || config.window_stride.is_some())
+                                           && config.mode != Mode::Window {
+         return Err("A window and a window stride are only used by the \
+                                              window mode".to_string());
+      }
+      if let Some((window,stride)) = config.window_shape() {
+         if let Some(problem) =
+                     crssub_window::window_problem(config.ny,window,stride) {
+            return Err(problem);
+         }
+      }
+      if config.unroll.is_some() && config.mode != Mode::Unrolled {
+         return Err("An unroll factor is only used by the unrolled mode"
+                                                                .to_string());
+      }
+      if let Some(factor) = config.unroll_factor() {
+         if !crssub_unrolled::UNROLL_FACTORS.contains(&factor) {
+            return Err(format!("There is no version unrolled by {} - the \
+                  unroll factor must be one of {:?}",factor,
+                                           crssub_unrolled::UNROLL_FACTORS));
+         }
+      }
+      if config.expr.is_some() && config.mode != Mode::Expr {
+         return Err("An expression is only used by the expr mode"
+                                                                .to_string());
+      }
+      if let Some(ref text) = config.expr {
+         if let Err(problem) = Expr::parse(text) {
+            return Err(format!("The expression '{}' can't be used: {}",text,
+                                                                   problem));
+         }
+      }
+      if config.mode == Mode::Expr && config.compare_types {
+         return Err("The expr mode's results are only checked with f32 \
+                       elements, so it can't be used for a type comparison"
+                                                                .to_string());
+      }
+      if config.compare_unroll {
+         if config.mode != Mode::Unrolled || config.unroll.is_some() {
+            return Err("An unrolling comparison needs the unrolled mode, \
+                with no unroll factor, since it times them all".to_string());
+         }
+         if config.input != Input::Standard {
+            return Err("An unrolling comparison only uses the standard \
+                                                         input".to_string());
+         }
+      }
+      if let Some(ref label) = config.label {
+         if label.contains(char::is_control) {
+            return Err("A label can't contain a line break, a tab or any \
+                             other control character".to_string());
+         }
+      }
+      if config.loop_order == LoopOrder::Ji && config.mode != Mode::Flat {
+         return Err("The ji loop order is only available for the flat mode"
+                                                                .to_string());
+      }
+      if config.compare_types {
+         if !config.mode.info().flat {
+            return Err(format!("A type comparison needs a mode with a \
+                  generic kernel, which '{}' doesn't have - --list-modes \
+                  shows which modes use a flat Array2D",config.mode.name()));
+         }
+         if config.input != Input::Standard {
+            return Err("A type comparison only uses the standard input"
+                                                                .to_string());
+         }
+      }
+      if config.find_cliff {
+         if !config.mode.info().flat {
+            return Err(format!("A search for the cache cliff needs a mode \
+                  that uses a flat Array2D, which '{}' doesn't - \
+                  --list-modes shows which do",config.mode.name()));
+         }
+         if config.input != Input::Standard {
+            return Err("A search for the cache cliff only uses the standard \
+                                                         input".to_string());
+         }
+      }
+      if config.concurrent_arrays.is_some() {
+         if config.mode.is_parallel() {
+            return Err(format!("Concurrent arrays run one thread per pair \
+                  of arrays, and can't use the parallel '{}' mode",
+                                                      config.mode.name()));
+         }
+         if config.seed_per_trial {
+            return Err("Concurrent arrays can't use a seed per trial"
+                                                                .to_string());
+         }
+      }
+      if config.fresh_alloc && !config.mode.info().flat {
+         return Err(format!("A fresh output array for each call needs a \
+               mode that uses a flat Array2D, which '{}' doesn't - \
+               --list-modes shows which do",config.mode.name()));
+      }
+      if config.hugepages {
+         if !config.mode.info().flat {
+            return Err(format!("Huge pages need a mode that uses a flat \
+                  Array2D, which '{}' doesn't - --list-modes shows which do",
+                                                      config.mode.name()));
+         }
+         if config.fresh_alloc {
+            return Err("Huge pages can't be combined with a fresh output \
+                array for each call".to_string());
+         }
+      }
+      if config.compare_with.is_some() && !config.check().is_elementwise() {
+         return Err(format!("A comparison with another implementation's \
+               output needs a mode whose results correspond element by \
+               element with the input, which '{}' doesn't",
+                                                      config.mode.name()));
+      }
+      if config.emit_canonical.is_some() && !config.check().is_elementwise() {
+         return Err(format!("Writing out the canonical output needs a \
+               mode whose results correspond element by element with the \
+               input, so they can be compared with --compare-with, which \
+               '{}' doesn't",config.mode.name()));
+      }
+      let geometry = config.cache_geometry();
+      let possible = match geometry.line.checked_mul(geometry.assoc) {
+         Some(set_bytes) if set_bytes > 0 => geometry.line.is_power_of_two()
+                  && geometry.size.is_multiple_of(set_bytes)
+                  && geometry.sets().is_power_of_two(),
+         _ => false,
+      };
+      if !possible {
+         return Err(format!("A cache of {} bytes with {} byte lines and {} \
+             ways isn't possible - the line size must be a power of two, \
+             and the cache a power of two number of sets of that many \
+             lines",geometry.size,geometry.line,geometry.assoc));
+      }
+      if config.check_conflict {
+         if !config.mode.info().flat {
+            return Err(format!("A cache conflict check needs a mode that \
+                  uses a flat Array2D, which '{}' doesn't - --list-modes \
+                  shows which do",config.mode.name()));
+         }
+         if config.fresh_alloc {
+            return Err("A cache conflict check can't be combined with a \
+                fresh output array for each call".to_string());
+         }
+      }
+      if config.mlock {
+         if !config.mode.info().flat {
+            return Err(format!("Locking the arrays into memory needs a mode \
+                  that uses a flat Array2D, which '{}' doesn't - \
+                  --list-modes shows which do",config.mode.name()));
+         }
+         if config.fresh_alloc {
+            return Err("Locking the arrays into memory can't be combined \
+                with a fresh output array for each call".to_string());
+         }
+      }
+      if config.protect_input {
+         if !config.mode.info().flat {
+            return Err(format!("Protecting the input needs a mode that uses \
+                  a flat Array2D, which '{}' doesn't - --list-modes shows \
+                  which do",config.mode.name()));
+         }
+         if config.seed_per_trial {
+            return Err("Protecting the input can't be combined with a seed \
+                per trial, which refills the input between trials"
+                                                               .to_string());
+         }
+      }
+      if let Some(offset) = config.danger_alias {
+         if !cfg!(feature = "danger_alias") {
+            return Err("An aliased output needs the program to be built \
+                         with the 'danger_alias' feature".to_string());
+         }
+         if offset == 0 || offset.unsigned_abs() >= config.nx * config.ny {
+            return Err(format!("An aliased output must overlap the input \
+                  without starting at the same place, so its offset must \
+                  be nonzero and less than {} elements either way",
+                                                     config.nx * config.ny));
+         }
+      }
+      if let Some(paths) = config.build_comparison() {
+         if paths.len() < 2 || paths.iter().any(|path| path.is_empty()) {
+            return Err("A build comparison needs two or more result files, \
+                separated by commas".to_string());
+         }
+         if config.baseline.is_some() {
+            return Err("A build comparison can't be combined with a \
+                baseline".to_string());
+         }
+      }
+      if config.false_share_study {
+         if config.mode != Mode::Threads || config.threads < 2 {
+            return Err("A false sharing study needs the threads mode, with \
+                at least two threads".to_string());
+         }
+         if config.cache_line < 8 {
+            return Err("A false sharing study needs a cache line of at least \
+                8 bytes, to be able to shift the chunks half a line"
+                                                               .to_string());
+         }
+      }
+      if config.order_study && (config.mode != Mode::Flat
+                                       || config.loop_order != LoopOrder::Ij
+                                       || config.out_stride.is_some()) {
+         return Err("A loop order study needs the flat mode, which is \
+             the one with both loop orders, with no loop order or output \
+             stride given - it times both orders itself".to_string());
+      }
+      if config.trial_fresh_thread && config.seed_per_trial {
+         return Err("A fresh thread for each trial can't be combined with \
+             a seed per trial".to_string());
+      }
+      if config.vs_memcpy && config.fresh_alloc {
+         return Err("A comparison with a memcpy can't be combined with a \
+             fresh output array for each call, whose times include the \
+             allocation".to_string());
+      }
+      if config.svg_histogram.is_some() && config.drift_check.is_some() {
+         return Err("A histogram of the trial times is for a single run, \
+             so it can't be combined with a drift check".to_string());
+      }
+      check_studies(&config)?;
+      if config.dims != 2 && config.dims != 3 {
+         return Err("The number of dimensions must be 2 or 3".to_string());
+      }
+      if config.dims == 3 {
+         check_3d(&config)?;
+      }
+
+      //  Automatic repeats replace any repeat count given, and are worked
+      //  out again for each configuration built from this one, so each of
+      //  a sweep's configurations gets the count for its own size.
+
+      if config.auto_repeats {
+         let planes = if config.dims == 3 { config.nz } else { 1 };
+         let elements = config.nx.saturating_mul(config.ny)
+                                                   .saturating_mul(planes);
+         config.nrpt = (config.repeat_ops / elements.max(1)).max(1);
+      }
+      Ok(config)
+   }
+}
+
+//  The columns and rows of the image in a FITS file, and its BITPIX, from
+//  its header. Without the fits feature there's nothing to read it with.
+
+#[cfg(feature = "fits")]
+fn fits_shape (path: &str) -> Result<(usize,usize,i32),String> {
+   let header = crate::crsfits::read_header(path)
+                                         .map_err(|error| error.to_string())?;
+   Ok((header.nx,header.ny,header.bitpix))
+}
+
+#[cfg(not(feature = "fits"))]
+fn fits_shape (_path: &str) -> Result<(usize,usize,i32),String> {
+   Err("Reading a FITS file needs the program to be built with the 'fits' \
+                                                     feature".to_string())
+}
+
+//  The studies, each of which replaces the usual runs with its own, and the
+//  options that only apply to the usual runs, which none of the studies
+//  use. Only one study can be run at a time, and none of those options can
+//  be given with one - except that a sweep is just a series of the usual
+//  runs, so the options marked as working with a sweep can be.
+
+fn check_studies (config: &BenchConfig) -> Result<(),String> {
+   let studies = [
+      (config.equiv.is_some(),"An equivalence check"),
+      (config.sweep_2d.is_some(),"A sweep"),
+      (config.compare_build.is_some(),"A build comparison"),
+      (config.compare_types,"A type comparison"),
+      (config.compare_unroll,"An unrolling comparison"),
+      (config.false_share_study,"A false sharing study"),
+      (config.order_study,"A loop order study"),
+      (config.bench_construction,"A construction timing"),
+      (config.find_cliff,"A search for the cache cliff"),
+      (config.torture,"A torture test"),
+      (config.concurrent_arrays.is_some(),"Concurrent arrays"),
+      (config.danger_alias.is_some(),"An aliased output"),
+   ];
+   let given: Vec<&str> = studies.iter().filter(|study| study.0)
+                                             .map(|study| study.1).collect();
+   if given.len() > 1 {
+      return Err(format!("{} can't be combined with {} - only one study can \
+                       be run at a time",given[0],lower_first(given[1])));
+   }
+   let study = match given.first() {
+      Some(&study) => lower_first(study),
+      None => return Ok(()),
+   };
+   let sweep = config.sweep_2d.is_some();
+   let run_options = [
+      (config.fresh_alloc,true,"A fresh output array for each call"),
+      (config.hugepages,true,"Huge pages"),
+      (config.mlock,true,"Locking the arrays into memory"),
+      (config.protect_input,true,"Protecting the input"),
+      (config.check_conflict,true,"A cache conflict check"),
+      (config.perm_seed.is_some(),true,"A permutation seed"),
+      (config.fits_data,true,"The pixel data of a FITS file"),
+      (config.trial_fresh_thread,true,"A fresh thread for each trial"),
+      (config.vs_memcpy,true,"A comparison with a memcpy"),
+      (config.svg_histogram.is_some(),false,"A histogram of the trial times"),
+      (config.compare_with.is_some(),false,
+                     "A comparison with another implementation's output"),
+      (config.emit_canonical.is_some(),false,
+                                          "Writing out the canonical output"),
+   ];
+   for &(option_given,with_sweep,what) in &run_options {
+      if option_given && !(with_sweep && sweep) {
+         return Err(format!("{} can't be combined with {}",what,study));
+      }
+   }
+   let results_only = config.format == Format::Jsonl
+                                      || config.format == Format::Prometheus;
+   if results_only && !sweep {
+      return Err(format!("The {} format is only for the usual results, and \
+                 can't be used for {}",config.format.name(),study));
+   }
+   Ok(())
+}
+
+//  A name from one of the tables above, as it reads in the middle of a
+//  sentence.
+
+fn lower_first (text: &str) -> String {
+   let mut chars = text.chars();
+   match chars.next() {
+      Some(first) => first.to_lowercase().chain(chars).collect(),
+      None => String::new(),
+   }
+}
+
+//  The extra checks for a 3D array. Only the flat layout has a 3D version,
+//  and the options that look at rows and columns, or that run a separate
+//  2D kernel, don't apply.
+
+fn check_3d (config: &BenchConfig) -> Result<(),String> {
+   if config.nz == 0 {
+      return Err("Array dimensions must not be zero".to_string());
+   }
+   if config.nx.checked_mul(config.ny)
+                   .and_then(|len| len.checked_mul(config.nz)).is_none() {
+      return Err("The array has too many elements to be addressed"
+                                                                .to_string());
+   }
+   if config.mode != Mode::Flat || config.loop_order != LoopOrder::Ij {
+      return Err("A 3D array is only available for the flat mode, with the \
+                                                 ij loop order".to_string());
+   }
+   let unavailable = [
+      (config.out_stride.is_some(),"A strided output"),
+      (config.sweep_2d.is_some(),"A 2D sweep"),
+      (config.equiv.is_some(),"An equivalence check"),
+      (config.compare_types,"A type comparison"),
+      (config.compare_unroll,"An unrolling comparison"),
+      (config.false_share_study,"A false sharing study"),
+      (config.order_study,"A loop order study"),
+      (config.fits.is_some(),"A FITS image"),
+      (config.bench_construction,"A construction timing"),
+      (config.find_cliff,"A search for the cache cliff"),
+      (config.torture,"A torture test"),
+      (config.concurrent_arrays.is_some(),"Concurrent arrays"),
+      (config.seed_per_trial,"A seed per trial"),
+      (config.trial_fresh_thread,"A fresh thread for each trial"),
+      (config.count_ops,"An operation count"),
+      (config.verify_every.is_some(),"An incremental check"),
+      (config.row_access,"A row access timing"),
+      (config.per_row_timing,"A per-row timing"),
+      (config.danger_alias.is_some(),"An aliased output"),
+      (config.check_coverage,"A coverage check"),
+      (config.check_conflict,"A cache conflict check"),
+      (config.diff_map.is_some(),"A difference map"),
+      (config.svg_histogram.is_some(),"A histogram of the trial times"),
+      (config.vs_memcpy,"A comparison with a memcpy"),
+      (config.compare_with.is_some(),"A comparison with another output"),
+      (config.emit_canonical.is_some(),"Writing out the canonical output"),
+      (config.verify_both,"An accuracy check"),
+      (config.verify_shuffle,"A shuffled check"),
+      (config.strict_fp,"A strict floating point check"),
+      (config.fresh_alloc,"A fresh output array for each call"),
+      (config.hugepages,"A huge page allocation"),
+      (config.mlock,"Locking the arrays into memory"),
+      (config.protect_input,"Protecting the input"),
+   ];
+   for &(given,what) in &unavailable {
+      if given {
+         return Err(format!("{} isn't available for a 3D array",what));
+      }
+   }
+   Ok(())
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                 C o n f i g u r a t i o n   F i l e s
+
+impl BenchConfig {
+
+   //  Writes the configuration to a file that from_file() can read back -
+   //  a comment saying what it is, then each of settings(), one to a line,
+   //  with anything that isn't a number or true or false quoted. Anything
+   //  not set, and the DESTINATIONS, are left out.
+
+   pub fn write_file (&self,path: &str) -> Result<(),BenchError> {
+      let mut text = String::from("# crsbench configuration - read it back \
+                                          with --config\n");
+      for (name,value) in self.settings() {
+         if DESTINATIONS.contains(&name) { continue; }
+         let value = match value {
+            Some(value) => value,
+            None => continue,
+         };
+         let bare = value.parse::<f64>().is_ok() || value == "true"
+                                                         || value == "false";
+         if bare {
+            text.push_str(&format!("{:<18}= {}\n",name,value));
+         } else {
+            text.push_str(&format!("{:<18}= {}\n",name,quoted(&value)));
+         }
+      }
+      fs::write(path,text).map_err(|error| BenchError::Io {
+         path: path.to_string(), action: "write", error: error.to_string() })
+   }
+}
+
+impl BenchConfigBuilder {
+
+   //  Reads a configuration file, in the format described at the top of
+   //  this file, returning a builder with the defaults overridden by
+   //  whatever the file sets, and a warning for each name that isn't known.
+   //  A value that can't be read is an error, reported with the line
+   //  number. Nothing else is checked until build() is called.
+
+   pub fn from_file (path: &str)
+                    -> Result<(BenchConfigBuilder,Vec<String>),BenchError> {
+      let text = fs::read_to_string(path).map_err(|error| BenchError::Io {
+         path: path.to_string(), action: "read", error: error.to_string() })?;
+      let mut builder = BenchConfig::builder();
+      let mut warnings = Vec::new();
+      let names = file_names();
+      for (index,line) in text.lines().enumerate() {
+         let line = line.trim();
+         if line.is_empty() || line.starts_with('#') { continue; }
+         let split = line.find(|c: char| c.is_whitespace() || c == '=')
+                                                        .unwrap_or(line.len());
+         let name = &line[..split];
+         let rest = line[split..].trim_start();
+         let format_error = |message: String| BenchError::Format {
+            path: path.to_string(),
+                           message: format!("line {}: {}",index + 1,message) };
+         let (value,was_quoted) =
+              file_value(rest.strip_prefix('=').unwrap_or(rest))
+                                                        .map_err(format_error)?;
+         if !names.contains(&name) {
+            warnings.push(format!("{} line {}: unknown setting '{}' ignored",
+                                                       path,index + 1,name));
+            continue;
+         }
+         let unset = !was_quoted && value == "none";
+         builder = builder.set_field(name,&value,unset)
+                                                        .map_err(format_error)?;
+      }
+      Ok((builder,warnings))
+   }
+
+   //  Sets one value, given by name and as the text fields() shows for it,
+   //  or unsets it if unset is true. The names are checked when the
+   //  configuration is built, as they would be for values set any other
+   //  way. A warmup count is set directly, so it doesn't count as given and
+   //  a warmup time can still replace it.
+
+   fn set_field (mut self,name: &str,value: &str,unset: bool)
+                                                     -> Result<Self,String> {
+      let config = &mut self.config;
+      match name {
+         "nrpt" => config.nrpt = setting(value)?,
+         "ny" => config.ny = setting(value)?,
+         "nx" => config.nx = setting(value)?,
+         "dims" => config.dims = setting(value)?,
+         "nz" => config.nz = setting(value)?,
+         "mode" => self.mode_name = Some(value.to_string()),
+         "trials" => config.trials = setting(value)?,
+         "warmup" => config.warmup = setting(value)?,
+         "warmup_seconds" => config.warmup_seconds = optional(value,unset)?,
+         "warmup_to_freq" => config.warmup_to_freq = setting(value)?,
+         "format" => self.format_name = Some(value.to_string()),
+         "baseline" => config.baseline = optional(value,unset)?,
+         "threshold" => config.threshold = setting(value)?,
+         "compare_build" => config.compare_build = optional(value,unset)?,
+         "verify_both" => config.verify_both = setting(value)?,
+         "verify_shuffle" => config.verify_shuffle = setting(value)?,
+         "strict_fp" => config.strict_fp = setting(value)?,
+         "tolerance" => config.tolerance = setting(value)?,
+         "inner_repeat" => config.inner_repeat = optional(value,unset)?,
+         "threads" => config.threads = setting(value)?,
+         "check_coverage" => config.check_coverage = setting(value)?,
+         "check_conflict" => config.check_conflict = setting(value)?,
+         "cache_line" => config.cache_line = setting(value)?,
+         "cache_assoc" => config.cache_assoc = setting(value)?,
+         "cache_size" => config.cache_size = setting(value)?,
+         "sweep_2d" => config.sweep_2d = if unset { None } else {
+            match value.split_once('x') {
+               Some((nx_list,ny_list)) => Some((list(nx_list)?,list(ny_list)?)),
+               None => return Err(bad_value(value)),
+            }
+         },
+         "sweep_shard" => config.sweep_shard = if unset { None } else {
+            match value.split_once('/') {
+               Some((shard,shards)) => Some((setting(shard)?,setting(shards)?)),
+               None => return Err(bad_value(value)),
+            }
+         },
+         "count_ops" => config.count_ops = setting(value)?,
+         "verify_every" => config.verify_every = optional(value,unset)?,
+         "row_access" => config.row_access = setting(value)?,
+         "per_row_timing" => config.per_row_timing = setting(value)?,
+         "input" => config.input = input_setting(value)?,
+         "seed" => config.seed = setting(value)?,
+         "seed_per_trial" => config.seed_per_trial = setting(value)?,
+         "trial_fresh_thread" => config.trial_fresh_thread = setting(value)?,
+         "diff_map" => config.diff_map = optional(value,unset)?,
+         "svg_histogram" => config.svg_histogram = optional(value,unset)?,
+         "compare_with" => config.compare_with = optional(value,unset)?,
+         "emit_canonical" => config.emit_canonical = optional(value,unset)?,
+         "label" => config.label = optional(value,unset)?,
+         "endian" => self.endian_name = Some(value.to_string()),
+         "time_source" => self.time_source_name = Some(value.to_string()),
+         "loop_order" => self.loop_order_name = Some(value.to_string()),
+         "sysinfo" => config.sysinfo = setting(value)?,
+         "freq_check" => config.freq_check = setting(value)?,
+         "report_startup" => config.report_startup = setting(value)?,
+         "budget_ms" => config.budget_ms = optional(value,unset)?,
+         "auto_repeats" => config.auto_repeats = setting(value)?,
+         "repeat_ops" => config.repeat_ops = setting(value)?,
+         "meta_file" => config.meta_file = optional(value,unset)?,
+         "append" => config.append = optional(value,unset)?,
+         "sqlite" => config.sqlite = optional(value,unset)?,
+         "fits" => config.fits = optional(value,unset)?,
+         "fits_data" => config.fits_data = setting(value)?,
+         "report_tcp" => config.report_tcp = optional(value,unset)?,
+         "drift_check" => config.drift_check = optional(value,unset)?,
+         "drift_pause_ms" => config.drift_pause_ms = setting(value)?,
+         "out_stride" => config.out_stride = optional(value,unset)?,
+         "perm_seed" => config.perm_seed = optional(value,unset)?,
+         "window" => config.window = optional(value,unset)?,
+         "window_stride" => config.window_stride = optional(value,unset)?,
+         "unroll" => config.unroll = optional(value,unset)?,
+         "expr" => config.expr = optional(value,unset)?,
+         "equiv" => self.equiv_names = if unset { None } else {
+            match value.split_once(',') {
+               Some((mode_a,mode_b)) =>
+                                  Some((mode_a.to_string(),mode_b.to_string())),
+               None => return Err(bad_value(value)),
+            }
+         },
+         "compare_types" => config.compare_types = setting(value)?,
+         "compare_unroll" => config.compare_unroll = setting(value)?,
+         "false_share_study" => config.false_share_study = setting(value)?,
+         "order_study" => config.order_study = setting(value)?,
+         "bench_construction" => config.bench_construction = setting(value)?,
+         "find_cliff" => config.find_cliff = setting(value)?,
+         "torture" => config.torture = setting(value)?,
+         "concurrent_arrays" =>
+                         config.concurrent_arrays = optional(value,unset)?,
+         "fresh_alloc" => config.fresh_alloc = setting(value)?,
+         "hugepages" => config.hugepages = setting(value)?,
+         "mlock" => config.mlock = setting(value)?,
+         "protect_input" => config.protect_input = setting(value)?,
+         "peak_bandwidth" => config.peak_bandwidth = optional(value,unset)?,
+         "roofline" => config.roofline = setting(value)?,
+         "vs_memcpy" => config.vs_memcpy = setting(value)?,
+         "checksum" => if unset {
+            config.checksum = None;
+         } else {
+            self.checksum_name = Some(value.to_string());
+         },
+         "precision" => config.precision = optional(value,unset)?,
+         "si_times" => config.si_times = setting(value)?,
+         "danger_alias" => if !unset {
+            return Err("danger_alias can only be given on the command line, \
+                                             as --danger-alias".to_string());
+         },
+         _ => return Err(format!("unknown setting '{}'",name)),
+      }
+      Ok(self)
+   }
+}
+
+//  The names a configuration file can set - those of fields(), and so of
+//  the list of defaults.
+
+fn file_names () -> Vec<&'static str> {
+   BenchConfig::builder().config.fields().iter().map(|field| field.0).collect()
+}
+
+//  The settings write_file() leaves out, because they say where the
+//  results of a run go rather than how it's run.
+
+const DESTINATIONS: [&str; 8] = ["baseline","append","sqlite","report_tcp",
+                 "meta_file","diff_map","svg_histogram","emit_canonical"];
+
+//  Returns a value quoted as a TOML basic string, with a backslash before
+//  any quote or backslash, and any control character escaped.
+
+fn quoted (value: &str) -> String {
+   let mut text = String::from("\"");
+   for c in value.chars() {
+      match c {
+         '"' => text.push_str("\\\""),
+         '\\' => text.push_str("\\\\"),
+         '\n' => text.push_str("\\n"),
+         '\r' => text.push_str("\\r"),
+         '\t' => text.push_str("\\t"),
+         c if c.is_control() => text.push_str(&format!("\\u{:04X}",c as u32)),
+         c => text.push(c),
+      }
+   }
+   text.push('"');
+   text
+}
+
+//  Returns the value part of a line from a configuration file, and whether
+//  it was quoted. A quoted value is what's inside the quotes, unescaped as
+//  quoted() escapes it, with nothing but a comment allowed after the
+//  closing quote. Otherwise it's everything up to any comment.
+
+fn file_value (text: &str) -> Result<(String,bool),String> {
+   let text = text.trim();
+   let inside = match text.strip_prefix('"') {
+      Some(inside) => inside,
+      None => {
+         let value = match text.find('#') {
+            Some(comment) => text[..comment].trim_end(),
+            None => text,
+         };
+         return Ok((value.to_string(),false));
+      }
+   };
+   let mut value = String::new();
+   let mut chars = inside.chars();
+   loop {
+      match chars.next() {
+         None => return Err("missing closing quote".to_string()),
+         Some('"') => break,
+         Some('\\') => value.push(match chars.next() {
+            Some('"') => '"',
+            Some('\\') => '\\',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('u') => {
+               let hex: String = chars.by_ref().take(4).collect();
+               let digits = hex.bytes().all(|byte| byte.is_ascii_hexdigit());
+               let code = if hex.len() == 4 && digits {
+                  u32::from_str_radix(&hex,16).ok().and_then(char::from_u32)
+               } else {
+                  None
+               };
+               match code {
+                  Some(c) => c,
+                  None => return Err(format!("invalid escape '\\u{}'",hex)),
+               }
+            }
+            Some(c) => return Err(format!("invalid escape '\\{}'",c)),
+            None => return Err("missing closing quote".to_string()),
+         }),
+         Some(c) => value.push(c),
+      }
+   }
+   let rest = chars.as_str().trim_start();
+   if !rest.is_empty() && !rest.starts_with('#') {
+      return Err(format!("unexpected '{}' after the closing quote",rest));
+   }
+   Ok((value,true))
+}
+
+//  The helpers for set_field(), which read a value of any type that can be
+//  parsed, a value that can be unset, a list of sweep dimensions, and an
+//  input, which for the Gaussian input includes its mean and standard
+//  deviation. A value is used just as it is - a bare one has already been
+//  trimmed, and the spaces in a quoted one are meant.
+
+fn bad_value (value: &str) -> String {
+   format!("invalid value '{}'",value)
+}
+
+fn setting<T: FromStr> (value: &str) -> Result<T,String> {
+   value.parse().map_err(|_| bad_value(value))
+}
+
+fn optional<T: FromStr> (value: &str,unset: bool) -> Result<Option<T>,String> {
+   if unset { Ok(None) } else { setting(value).map(Some) }
+}
+
+fn list (value: &str) -> Result<Vec<usize>,String> {
+   value.split(',').map(|item| setting(item.trim())).collect()
+}
+
+fn input_setting (value: &str) -> Result<Input,String> {
+   if let Some(rest) = value.strip_prefix("gaussian, mean ") {
+      if let Some((mean,stddev)) = rest.split_once(", stddev ") {
+         return Ok(Input::Gaussian { mean: setting(mean)?,
+                                                 stddev: setting(stddev)? });
+      }
+   }
+   Input::from_name(value).ok_or_else(|| bad_value(value))
+}
+
+//  Returns the mode with the given name, checking that it's available in
+//  this build.
+
+fn available_mode (name: &str) -> Result<Mode,String> {
+   let mode = match Mode::from_name(name) {
+      Some(mode) => mode,
+      None => return Err(format!("Unknown mode '{}' - --list-modes lists \
+                                                the modes available",name)),
+   };
+   if let Some(feature) = mode.info().feature {
+      if !mode.info().available {
+         return Err(format!("Mode '{}' needs the program to be built with \
+                                              the '{}' feature",name,feature));
+      }
+   }
+   Ok(mode)
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                                 T e s t s
+
+#[cfg(test)]
+mod tests {
+   use std::fs;
+
+   use super::{file_value, quoted, BenchConfig, BenchConfigBuilder};
+   use crate::crssub_unrolled::UNROLL_FACTORS;
+
+   fn temp_path (name: &str) -> String {
+      let mut path = std::env::temp_dir();
+      path.push(format!("crsconfig_{}_{}.toml",name,std::process::id()));
+      path.to_string_lossy().into_owned()
+   }
+
+   //  A quoted value is kept exactly, escapes and all, and only a bare none
+   //  means unset.
+
+   #[test]
+   fn file_values () {
+      let value = |text: &str| file_value(text).unwrap();
+      assert_eq!(value("4000  # columns"),("4000".to_string(),false));
+      assert_eq!(value("none"),("none".to_string(),false));
+      assert_eq!(value("\"none\""),("none".to_string(),true));
+      assert_eq!(value("\"  a # b  \"  # c"),("  a # b  ".to_string(),true));
+      assert_eq!(value(r#""say \"hi\" \\ \t\u0041""#),
+                                        ("say \"hi\" \\ \tA".to_string(),true));
+      for text in ["\"open","\"a\" b","\"\\x\"","\"\\u00G1\"","\"\\u+041\""] {
+         assert!(file_value(text).is_err(),"{:?}",text);
+      }
+      let text = " \"quoted\" \\ \t\r\n\u{1} # not a comment ";
+      assert_eq!(file_value(&quoted(text)).unwrap(),(text.to_string(),true));
+   }
+
+   //  Written and read back, a configuration comes out the same, except that
+   //  where the results go isn't saved.
+
+   #[test]
+   fn file_round_trip () {
+      let path = temp_path("round_trip");
+      let label = " run \"none\" # 2 \\ ";
+      let config = BenchConfig::builder().nx(100).label(label)
+                      .append("results.csv").baseline("old.csv")
+                      .meta_file("meta.json").report_tcp("localhost:9000")
+                                                          .build().unwrap();
+      config.write_file(&path).unwrap();
+      let text = fs::read_to_string(&path).unwrap();
+      for name in ["append","baseline","meta_file","report_tcp","sqlite",
+                                                   "warmup_seconds","expr"] {
+         assert!(!text.lines().any(|line| line.starts_with(name)),"{}",name);
+      }
+      let (builder,warnings) = BenchConfigBuilder::from_file(&path).unwrap();
+      let read = builder.build().unwrap();
+      assert!(warnings.is_empty());
+      assert_eq!(read.label.as_deref(),Some(label));
+      assert_eq!(read.nx,100);
+      assert_eq!(read.append,None);
+      assert_eq!(read.baseline,None);
+      let unsaved = ["append","baseline","meta_file","report_tcp"];
+      let saved = |config: &BenchConfig| -> Vec<_> {
+         config.fields().into_iter()
+                         .filter(|field| !unsaved.contains(&field.0)).collect()
+      };
+      assert_eq!(saved(&read),saved(&config));
+      fs::remove_file(&path).unwrap();
+   }
+
+   //  The first problem build() finds, which is expected to be the one with
+   //  the rule being tested.
+
+   fn problem (builder: BenchConfigBuilder) -> String {
+      match builder.build() {
+         Ok(_) => String::new(),
+         Err(error) => error.to_string(),
+      }
+   }
+
+   fn flat () -> BenchConfigBuilder {
+      BenchConfig::builder().mode("flat")
+   }
+
+   //  The defaults are a usable configuration, with every rule kept.
+
+   #[test]
+   fn defaults () {
+      assert!(BenchConfig::builder().build().is_ok());
+      assert!(flat().build().is_ok());
+   }
+
+   #[test]
+   fn names () {
+      assert!(problem(flat().mode("nosuch")).contains("Unknown mode"));
+      assert!(problem(flat().equiv("flat","nosuch")).contains("Unknown mode"));
+      assert!(problem(flat().format("x")).contains("Unknown format"));
+      assert!(problem(flat().input("x")).contains("Unknown input"));
+      assert!(problem(flat().loop_order("x")).contains("Unknown loop order"));
+      assert!(problem(flat().checksum("x")).contains("Unknown checksum"));
+      assert!(problem(flat().endian("x")).contains("Unknown byte order"));
+      assert!(problem(flat().time_source("x")).contains("Unknown time source"));
+   }
+
+   #[test]
+   fn counts () {
+      let zero = "must not be zero";
+      assert!(problem(flat().nx(0)).contains(zero));
+      assert!(problem(flat().ny(0)).contains(zero));
+      assert!(problem(flat().nrpt(0)).contains("repeats must not be zero"));
+      assert!(flat().nrpt(0).auto_repeats(true).build().is_ok());
+      assert!(problem(flat().trials(0)).contains("trials must not be zero"));
+      assert!(problem(flat().threads(0)).contains("threads must not be zero"));
+      assert!(problem(flat().inner_repeat(Some(0))).contains(zero));
+      assert!(problem(flat().verify_every(Some(0))).contains(zero));
+      assert!(problem(flat().concurrent_arrays(Some(0))).contains(zero));
+      assert!(problem(flat().budget_ms(Some(0))).contains(zero));
+      assert!(problem(flat().auto_repeats(true).repeat_ops(0)).contains(zero));
+      assert!(problem(flat().sweep_2d(Some((vec![10,0],vec![5]))))
+                                                            .contains(zero));
+      assert!(problem(flat().nx(usize::MAX).ny(2)).contains("too many"));
+      assert!(problem(flat().drift_check(Some(1))).contains("two repetitions"));
+      assert!(flat().drift_check(Some(2)).build().is_ok());
+   }
+
+   //  Only one study at a time, and the options for the usual runs only
+   //  with a sweep, and then only those that work with one.
+
+   #[test]
+   fn studies () {
+      let flat = || BenchConfig::builder().mode("flat");
+      let sweep = || Some((vec![10,20],vec![5]));
+      let message = |builder: BenchConfigBuilder|
+                                    builder.build().err().unwrap().to_string();
+      assert!(flat().torture(true).build().is_ok());
+      assert!(message(flat().torture(true).compare_types(true))
+                                        .contains("only one study can be run"));
+      assert!(message(flat().equiv("flat","index").sweep_2d(sweep()))
+                                        .contains("only one study can be run"));
+      assert!(flat().fresh_alloc(true).vs_memcpy(false).sweep_2d(sweep())
+                                                           .build().is_ok());
+      assert!(message(flat().fresh_alloc(true).find_cliff(true))
+          .contains("A fresh output array for each call can't be combined \
+                                         with a search for the cache cliff"));
+      assert!(message(flat().emit_canonical("out.raw").sweep_2d(sweep()))
+                                .contains("can't be combined with a sweep"));
+      assert!(flat().format("jsonl").sweep_2d(sweep()).build().is_ok());
+      assert!(flat().format("jsonl").torture(true).build().is_err());
+   }
+
+   #[test]
+   fn mode_options () {
+      let index = || BenchConfig::builder().mode("index");
+      assert!(problem(index().loop_order("ji")).contains("flat mode"));
+      assert!(problem(index().out_stride(Some(3))).contains("flat mode"));
+      assert!(problem(flat().loop_order("ji").out_stride(Some(3)))
+                                                    .contains("ij loop order"));
+      assert!(problem(flat().out_stride(Some(0))).contains("between 1 and"));
+      assert!(problem(flat().nx(10).ny(10).out_stride(Some(2)))
+                                                  .contains("common factor"));
+      assert!(flat().nx(10).ny(10).out_stride(Some(3)).build().is_ok());
+      assert!(problem(flat().perm_seed(Some(1))).contains("gather mode"));
+      assert!(problem(flat().window(Some(2))).contains("window mode"));
+      assert!(problem(flat().window_stride(Some(2))).contains("window mode"));
+      assert!(problem(flat().unroll(Some(2))).contains("unrolled mode"));
+      assert!(problem(flat().expr(Some("in".to_string())))
+                                                     .contains("expr mode"));
+      assert!(problem(BenchConfig::builder().mode("expr")
+                    .expr(Some("in +".to_string()))).contains("can't be used"));
+      if usize::MAX > u32::MAX as usize {
+         assert!(problem(BenchConfig::builder().mode("gather")
+                     .nx(usize::MAX).ny(1)).contains("rows and columns"));
+      }
+   }
+
+   #[test]
+   fn flat_only () {
+      let needs = |builder: BenchConfigBuilder|
+                  problem(builder.mode("index")).contains("flat Array2D");
+      assert!(needs(BenchConfig::builder().fresh_alloc(true)));
+      assert!(needs(BenchConfig::builder().hugepages(true)));
+      assert!(needs(BenchConfig::builder().mlock(true)));
+      assert!(needs(BenchConfig::builder().protect_input(true)));
+      assert!(needs(BenchConfig::builder().check_conflict(true)));
+      assert!(needs(BenchConfig::builder().compare_types(true)));
+      assert!(needs(BenchConfig::builder().find_cliff(true)));
+      let prefix = || BenchConfig::builder().mode("prefix");
+      assert!(problem(prefix().compare_with("out.raw"))
+                                               .contains("element by element"));
+      assert!(problem(prefix().emit_canonical("out.raw"))
+                                               .contains("element by element"));
+      assert!(flat().emit_canonical("out.raw").build().is_ok());
+   }
+
+   #[test]
+   fn study_needs () {
+      let unrolled = || BenchConfig::builder().mode("unrolled");
+      let threads = || BenchConfig::builder().mode("threads");
+      assert!(problem(flat().compare_unroll(true)).contains("unrolled mode"));
+      assert!(problem(unrolled().unroll(Some(2)).compare_unroll(true))
+                                                .contains("no unroll factor"));
+      assert!(unrolled().compare_unroll(true).build().is_ok());
+      assert!(problem(flat().false_share_study(true)).contains("threads mode"));
+      assert!(problem(threads().threads(1).false_share_study(true))
+                                                  .contains("two threads"));
+      assert!(problem(threads().threads(2).cache_line(4)
+                        .false_share_study(true)).contains("at least 8 bytes"));
+      assert!(threads().threads(2).false_share_study(true).build().is_ok());
+      assert!(problem(flat().loop_order("ji").order_study(true))
+                                                      .contains("loop order"));
+      assert!(problem(threads().concurrent_arrays(Some(2)))
+                                                      .contains("parallel"));
+      assert!(problem(flat().compare_build("a.csv")).contains("two or more"));
+      assert!(problem(flat().compare_build("a.csv,b.csv").baseline("c.csv"))
+                                                        .contains("baseline"));
+      assert!(problem(flat().input("random").compare_types(true))
+                                                  .contains("standard input"));
+      assert!(problem(unrolled().input("random").compare_unroll(true))
+                                                  .contains("standard input"));
+      assert!(problem(flat().input("random").find_cliff(true))
+                                                  .contains("standard input"));
+      assert!(problem(BenchConfig::builder().mode("expr").compare_types(true))
+                                                  .contains("type comparison"));
+      assert!(flat().danger_alias(Some(0)).build().is_err());
+      assert!(flat().nx(10).ny(10).danger_alias(Some(100)).build().is_err());
+      assert_eq!(flat().nx(10).ny(10).danger_alias(Some(1)).build().is_ok(),
+                                               cfg!(feature = "danger_alias"));
+   }
+
+   #[test]
+   fn combinations () {
+      let fresh = || flat().fresh_alloc(true);
+      let fresh_no = "fresh output array for each call";
+      assert!(problem(fresh().hugepages(true)).contains(fresh_no));
+      assert!(problem(fresh().mlock(true)).contains(fresh_no));
+      assert!(problem(fresh().check_conflict(true)).contains(fresh_no));
+      assert!(problem(fresh().vs_memcpy(true)).contains(fresh_no));
+      let seeded = || flat().input("random").seed_per_trial(true);
+      assert!(problem(flat().seed_per_trial(true))
+                                             .contains("random or gaussian"));
+      assert!(seeded().build().is_ok());
+      assert!(problem(seeded().concurrent_arrays(Some(2)))
+                                                   .contains("seed per trial"));
+      assert!(problem(seeded().protect_input(true)).contains("seed per trial"));
+      assert!(problem(seeded().trial_fresh_thread(true))
+                                                   .contains("seed per trial"));
+      assert!(problem(flat().svg_histogram("t.svg").drift_check(Some(2)))
+                                                      .contains("drift check"));
+   }
+
+   #[test]
+   fn gaussian () {
+      assert!(problem(flat().mean(1.0)).contains("gaussian input"));
+      assert!(problem(flat().input("gaussian").stddev(-1.0))
+                                                      .contains("negative"));
+      assert!(problem(flat().input("gaussian").stddev(f64::NAN))
+                                                      .contains("negative"));
+      assert!(flat().input("gaussian").mean(1.0).stddev(2.0).build().is_ok());
+   }
+
+   #[test]
+   fn timing () {
+      assert!(problem(flat().warmup_seconds(Some(0.0))).contains("positive"));
+      assert!(problem(flat().warmup_seconds(Some(f64::NAN)))
+                                                        .contains("positive"));
+      assert!(problem(flat().warmup(5).warmup_seconds(Some(1.0)))
+                                                    .contains("both be given"));
+      assert!(problem(flat().warmup(5).warmup_to_freq(true))
+                                                 .contains("steady frequency"));
+      assert!(flat().warmup_to_freq(true).build().is_ok());
+      assert!(problem(flat().auto_repeats(true).budget_ms(Some(10)))
+                                                .contains("can't be combined"));
+      assert!(problem(flat().roofline(true)).contains("peak bandwidth"));
+      assert!(problem(flat().peak_bandwidth(Some(-1.0)))
+                                                        .contains("positive"));
+      assert!(flat().roofline(true).peak_bandwidth(Some(20.0)).build().is_ok());
+   }
+
+   #[test]
+   fn sweep_shards () {
+      let sweep = Some((vec![10,20],vec![5]));
+      assert!(problem(flat().sweep_shard(Some((0,2)))).contains("for a sweep"));
+      assert!(problem(flat().sweep_2d(sweep.clone()).sweep_shard(Some((2,2))))
+                                                    .contains("doesn't exist"));
+      assert!(flat().sweep_2d(sweep).sweep_shard(Some((1,2))).build().is_ok());
+   }
+
+   #[test]
+   fn destinations () {
+      assert_eq!(flat().sqlite("results.db").build().is_ok(),
+                                                     cfg!(feature = "sqlite"));
+      assert!(problem(flat().report_tcp("localhost")).contains("HOST:PORT"));
+      assert!(problem(flat().report_tcp(":9000")).contains("HOST:PORT"));
+      assert!(problem(flat().report_tcp("localhost:99999"))
+                                                        .contains("HOST:PORT"));
+      assert!(flat().report_tcp("localhost:9000").build().is_ok());
+   }
+
+   #[test]
+   fn fits () {
+      assert!(flat().fits("nosuch.fits").build().is_err());
+      assert!(problem(flat().fits_data(true)).contains("needs the file"));
+   }
+
+   #[test]
+   fn labels () {
+      assert!(problem(flat().label("a\nb")).contains("control character"));
+      assert!(flat().label("run 1").build().is_ok());
+   }
+
+   #[test]
+   fn cache_geometry () {
+      assert!(problem(flat().cache_line(48)).contains("isn't possible"));
+      assert!(problem(flat().cache_size(1000)).contains("isn't possible"));
+      assert!(problem(flat().cache_assoc(0)).contains("isn't possible"));
+   }
+
+   #[test]
+   fn three_d () {
+      let cube = || flat().dims(3).nz(2);
+      assert!(problem(flat().dims(4)).contains("2 or 3"));
+      assert!(problem(flat().dims(3).nz(0)).contains("must not be zero"));
+      assert!(problem(cube().mode("index")).contains("3D array"));
+      assert!(problem(cube().loop_order("ji")).contains("3D array"));
+      assert!(problem(cube().row_access(true)).contains("3D array"));
+      assert!(problem(cube().nx(usize::MAX).ny(1).nz(2))
+                                                      .contains("too many"));
+      assert!(cube().build().is_ok());
+   }
+
+   //  Only the factors crssub_unrolled.rs has versions for get through, so
+   //  no other can reach its panic.
+
+   #[test]
+   fn unroll_factors () {
+      for factor in 0..=16 {
+         let built = BenchConfig::builder().mode("unrolled")
+                                            .unroll(Some(factor)).build();
+         assert_eq!(built.is_ok(),UNROLL_FACTORS.contains(&factor),
+                                                      "factor {}",factor);
+      }
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The fields of BenchConfig are public so the harness can read them
+     easily. Anything that wants to change a configuration should go through
+     to_builder() and build(), so the result is checked again.
+
+*/
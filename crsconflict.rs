@@ -0,0 +1,167 @@
+//
+//                        c r s c o n f l i c t . r s
+//
+// Summary:
+//    Works out whether the input and output arrays of the Rust 2D array
+//    access benchmark harness are likely to conflict in the cache.
+//
+// Introduction:
+//    A set associative cache can only hold a few lines - its associativity
+//    - that map to any one set, and which set a line maps to depends only
+//    on its address. Two arrays whose addresses differ by a multiple of the
+//    cache size divided by the associativity - the way span - map to the
+//    same sets, element for element, and rows whose length is a multiple
+//    of it map each column onto just a few sets. Either can make a kernel
+//    surprisingly slow for some array sizes, or some alignments, and not
+//    for others only slightly different, which is very confusing if you
+//    don't know it's happening.
+//
+//    analyse() takes the cache geometry, the base addresses of the arrays
+//    and the length of a row, and works out what the addresses imply. It
+//    doesn't measure anything: it assumes the cache is indexed by the
+//    address bits just above the offset within a line, as L1 data caches
+//    are, and that's as far as it goes. The harness uses it for
+//    --check-conflict.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//  The shape of a cache: the bytes in a line, the number of ways each set
+//  has, and the total size in bytes. BenchConfig checks that the line size
+//  and the number of sets are powers of two.
+
+#[derive(Clone, Copy, Debug)]
+pub struct CacheGeometry {
+   pub line: usize,
+   pub assoc: usize,
+   pub size: usize,
+}
+
+impl CacheGeometry {
+
+   //  The number of sets.
+
+   pub fn sets (&self) -> usize {
+      self.size / (self.line * self.assoc)
+   }
+
+   //  The way span - the bytes that map onto each set once. Addresses that
+   //  differ by a multiple of this map to the same set.
+
+   pub fn way_span (&self) -> usize {
+      self.size / self.assoc
+   }
+
+   //  The set the byte at an address maps to.
+
+   pub fn set_of (&self,address: usize) -> usize {
+      (address / self.line) % self.sets()
+   }
+}
+
+//  What analyse() found.
+
+#[derive(Clone, Copy, Debug)]
+pub struct Conflicts {
+
+   //  How far the output starts past the input, in bytes, modulo the way
+   //  span, and whether that's close enough to zero, either way, for
+   //  corresponding elements of the two to be in the same set.
+
+   pub offset: usize,
+   pub arrays_collide: bool,
+
+   //  For one column of both arrays - the same element of every row - the
+   //  lines it takes, the number of different sets they fall in, and the
+   //  most lines that fall in any one set.
+
+   pub column_lines: usize,
+   pub column_sets: usize,
+   pub worst_set: usize,
+}
+
+impl Conflicts {
+
+   //  True if one column of both arrays crowds into fewer sets than it
+   //  could, with more lines in one of them than it has ways, so the rows
+   //  will evict each other going down a column.
+
+   pub fn columns_collide (&self,geometry: &CacheGeometry) -> bool {
+      self.worst_set > geometry.assoc
+                  && self.column_sets < self.column_lines.min(geometry.sets())
+   }
+}
+
+//  Works out the conflicts for an input and an output array starting at the
+//  given addresses, each ny rows of row_bytes bytes.
+
+pub fn analyse (geometry: &CacheGeometry,in_base: usize,out_base: usize,
+                                   row_bytes: usize,ny: usize) -> Conflicts {
+   let span = geometry.way_span();
+   let offset = out_base.wrapping_sub(in_base) % span;
+   let arrays_collide =
+                offset < geometry.line || span - offset < geometry.line;
+
+   let mut counts = vec![0usize; geometry.sets()];
+   for iy in 0..ny {
+      for &base in &[in_base,out_base] {
+         counts[geometry.set_of(base + iy * row_bytes)] += 1;
+      }
+   }
+   Conflicts {
+      offset,
+      arrays_collide,
+      column_lines: 2 * ny,
+      column_sets: counts.iter().filter(|&&count| count > 0).count(),
+      worst_set: counts.iter().cloned().max().unwrap_or(0),
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o Only the first element of each row is looked at for the column check.
+     Every other column is the same pattern shifted along, except where a
+     row straddles a line boundary differently, which doesn't change the
+     picture.
+
+   o Real caches complicate this. Some L2 and L3 caches hash the address
+     to pick a set, the addresses here are virtual while the larger caches
+     are indexed physically, and prefetchers and replacement policies vary.
+     The defaults describe a typical L1 data cache - 32 KiB, 8 ways and
+     64 byte lines - where none of that applies, and it's the cache where
+     the effect is usually seen.
+
+   o With those defaults the way span is 4 KiB, which is also the distance
+     at which an x86 processor can mistake a load for one depending on an
+     earlier store (4K aliasing). So arrays whose addresses collide here
+     may also be slowed down for that quite different reason.
+
+   o The column check counts lines, not rows, so a column with two rows in
+     the same line is counted twice. That's only possible for rows shorter
+     than a line, which don't conflict with anything to speak of.
+
+*/
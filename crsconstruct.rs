@@ -0,0 +1,162 @@
+//
+//                         c r s c o n s t r u c t . r s
+//
+// Summary:
+//    Ways of building an array, for the Rust 2D array access benchmark
+//    harness.
+//
+// Introduction:
+//    The study is about the time taken to access the elements of a 2D array,
+//    but before an array can be accessed it has to be built, and how that's
+//    done can matter just as much - particularly for the vector of vectors
+//    the original Rust test programs use, where every row is a separate
+//    allocation. This module has a number of ways of building an array of
+//    nx columns and ny rows with every element zero, each written the way
+//    it might well be written in a real program:
+//
+//    flat         a single vector, vec![0.0; nx * ny], as the 1D program
+//                 crsmain1d.rs does.
+//    flat-resize  a single vector, reserved and then resized, as
+//                 Array2D::try_new() in crsarray.rs does.
+//    nested       a vector of vectors, vec![vec![0.0; nx]; ny], as the
+//                 original crsmain.rs does.
+//    row-loop     a vector of vectors, with each row made as a vector of its
+//                 own and pushed on in a loop.
+//    collect      a vector of vectors, collected from an iterator over the
+//                 rows, each collected from an iterator over the columns.
+//
+//    The harness (see --bench-construction in crsbench.rs) checks that each
+//    of them does build the array it should, and then times each of them,
+//    including the time to drop the array again, and reports the times side
+//    by side.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//  The ways an array can be built.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Strategy {
+   Flat,
+   FlatResize,
+   Nested,
+   RowLoop,
+   Collect,
+}
+
+//  All of them, in the order they are reported. The first is the one the
+//  others are compared with.
+
+pub const STRATEGIES: [Strategy; 5] = [Strategy::Flat,Strategy::FlatResize,
+               Strategy::Nested,Strategy::RowLoop,Strategy::Collect];
+
+impl Strategy {
+   pub fn name (&self) -> &'static str {
+      match *self {
+         Strategy::Flat => "flat",
+         Strategy::FlatResize => "flat-resize",
+         Strategy::Nested => "nested",
+         Strategy::RowLoop => "row-loop",
+         Strategy::Collect => "collect",
+      }
+   }
+}
+
+//  An array, as built by one of the strategies - either a single vector
+//  holding the rows one after the other, or a vector of rows.
+
+pub enum Built {
+   Flat(Vec<f32>),
+   Nested(Vec<Vec<f32>>),
+}
+
+impl Built {
+
+   //  Returns true if this is an array of nx columns and ny rows, with every
+   //  element zero - what every strategy is meant to build.
+
+   pub fn is_initialised (&self,nx: usize,ny: usize) -> bool {
+      match *self {
+         Built::Flat(ref data) => {
+            data.len() == nx * ny && data.iter().all(|&value| value == 0.0)
+         }
+         Built::Nested(ref rows) => {
+            rows.len() == ny && rows.iter().all(|row| row.len() == nx
+                                    && row.iter().all(|&value| value == 0.0))
+         }
+      }
+   }
+}
+
+//  Builds an array of nx columns and ny rows, with every element zero, in
+//  the way given.
+
+pub fn build (strategy: Strategy,nx: usize,ny: usize) -> Built {
+   match strategy {
+      Strategy::Flat => Built::Flat(vec![0.0; nx * ny]),
+      Strategy::FlatResize => {
+         let mut data = Vec::new();
+         data.reserve_exact(nx * ny);
+         data.resize(nx * ny,0.0);
+         Built::Flat(data)
+      }
+      Strategy::Nested => Built::Nested(vec![vec![0.0; nx]; ny]),
+      Strategy::RowLoop => {
+         let mut rows = Vec::with_capacity(ny);
+         for _iy in 0..ny {
+            rows.push(vec![0.0; nx]);
+         }
+         Built::Nested(rows)
+      }
+      Strategy::Collect => {
+         Built::Nested((0..ny).map(|_| (0..nx).map(|_| 0.0).collect())
+                                                                   .collect())
+      }
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o vec![0.0; n] is a special case. The standard library knows that
+     zero is all zero bits, and asks the allocator for zeroed memory, which
+     for a large array means fresh pages from the system - pages that are
+     only really provided, and zeroed, when they are first touched. So flat
+     can look far faster than flat-resize, which writes every element, but
+     only because the cost has been moved to the first pass of whatever
+     uses the array. The same goes for the rows of row-loop, but not for
+     nested, where vec![row; ny] clones the first row for all but the last,
+     copying it into memory that is written as it goes.
+
+   o Each timed call builds the array and then drops it, so the time
+     includes giving the memory back. For a large array that's a call to
+     the system for each allocation big enough to have been given its own
+     pages, which for the nested strategies is one per row.
+
+   o The built array is passed through black_box() by the caller, so the
+     optimiser can't see that it's never used and leave out the building.
+
+*/
@@ -0,0 +1,475 @@
+//
+//                              c r s d b . r s
+//
+// Summary:
+//    SQLite result database for the Rust 2D array access benchmark harness.
+//
+// Introduction:
+//    A CSV file per run is fine for looking at one set of results, but the
+//    numbers for this study get collected over months, on several machines,
+//    and a pile of CSV files gets hard to manage. This module writes each
+//    result as a row in an SQLite database, along with details of the
+//    machine and when it was run, so the whole history can be queried - to
+//    spot a regression, say, or compare machines for the same configuration.
+//
+//    The program has no dependencies on other crates, and this doesn't add
+//    one. It calls the SQLite C library directly, declaring just the handful
+//    of functions it uses.
+//
+// Building:
+//    This is only used if crsbench.rs is built with the 'sqlite' feature,
+//    and the program then has to be linked with the SQLite library, which
+//    must be installed (on Debian or Ubuntu, the libsqlite3-dev package):
+//
+//    rustc -O --cfg 'feature="sqlite"' crsbench.rs
+//
+// Schema:
+//    The results go in a single table, called results, created if it isn't
+//    already in the database (see SCHEMA, below). Each row is one result.
+//    The columns are:
+//
+//    id               a unique row number, assigned by SQLite.
+//    run_time         when the run started, as a Unix time in seconds. All
+//                     the results from one run have the same run_time, so
+//                     together with host it identifies a run.
+//    host             the host name.
+//    cpu_model, logical_cores, physical_cores, os, os_release, arch
+//                     the system details, as reported by --sysinfo (see
+//                     crssysinfo.rs). A core count that can't be found is
+//                     NULL.
+//    opt_level, target_cpu
+//                     how the program was built, as in the CSV output (see
+//                     crsbuild.rs) - 'unknown' if the build didn't say.
+//    mode ... peak_bandwidth
+//                     the configuration fields, as in the CSV output (see
+//                     crsreport.rs), with NULL for an optional field that
+//                     wasn't set - nz, for example, is NULL for a 2D array.
+//                     fresh_alloc is 1 if the times include allocating the
+//                     output array, 0 if not, and hugepages is 1 if huge
+//                     pages were asked for - whether or not they were
+//                     obtained. In the same way, mlock is 1 if locking the
+//                     arrays into memory was asked for.
+//    calls ... peak_fraction
+//                     the result fields, as in the CSV output, except that
+//                     aspect and intensity are left out, since they are just
+//                     nx / ny and flops / bytes. Times are in ns, throughputs
+//                     in Gflop/s and Gbyte/s. sem_ns_per_call and
+//                     ci95_ns_per_call are NULL for a single trial.
+//    verified         1 if the results were correct, 0 if not. There's no
+//                     status column, since a configuration that couldn't
+//                     be run has no results, and isn't written at all.
+//    checksum_algo, checksum
+//                     the checksum of the output, and how it was worked out
+//                     (see crschecksum.rs), or NULL if none was asked for.
+//
+//    So, for example, the best time seen for each machine for the default
+//    configuration of the flat mode is given by:
+//
+//    SELECT host, cpu_model, MIN(min_ns_per_call) FROM results
+//       WHERE mode = 'flat' AND nx = 2000 AND ny = 10 GROUP BY host;
+//
+// Safety:
+//    The calls to the SQLite library are unsafe, and rely on the following:
+//
+//    o Every handle passed to SQLite is one it returned, and is still open.
+//      The handles are only held by Database and Statement, which close
+//      them when they are dropped, and never give them out.
+//    o Every string passed as a C string is a CString, so is terminated
+//      properly. Text values are passed with their length instead, and
+//      SQLite is told to take its own copy of them (SQLITE_TRANSIENT).
+//    o Strings returned by SQLite are copied before the next call into the
+//      library, which might invalidate them.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//    14th Oct 2026. Added the nz column. agent.
+//    14th Oct 2026. Added the checksum_algo and checksum columns. agent.
+//    14th Oct 2026. Added the fresh_alloc column. agent.
+//    14th Oct 2026. Added the perm_seed column. agent.
+//    14th Oct 2026. Added the hugepages column. agent.
+//    14th Oct 2026. Failed results aren't written. agent.
+//    14th Oct 2026. Added the window and window_stride columns. agent.
+//    14th Oct 2026. Added the ns_per_element column. agent.
+//    14th Oct 2026. Added the unroll column. agent.
+//    14th Oct 2026. Added the sem_ns_per_call and ci95_ns_per_call
+//                   columns. agent.
+//    14th Oct 2026. Added the mlock column. agent.
+//    14th Oct 2026. Added the expr column. agent.
+//    14th Oct 2026. Added the label column. agent.
+//    14th Oct 2026. Added the time_source column. agent.
+//    14th Oct 2026. Added the opt_level and target_cpu columns. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::ffi::{CStr, CString};
+use std::fs;
+use std::os::raw::{c_char, c_int, c_void};
+use std::ptr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::crsbuild;
+use crate::crserror::BenchError;
+use crate::crsreport::BenchResult;
+use crate::crssysinfo::{self, SysInfo};
+
+//  The results table. The column names for the configuration and result
+//  fields are the same as in the CSV output. The index is on the fields
+//  most queries will select on.
+
+const SCHEMA: &str = "
+   CREATE TABLE IF NOT EXISTS results (
+      id               INTEGER PRIMARY KEY,
+      run_time         INTEGER NOT NULL,
+      host             TEXT NOT NULL,
+      cpu_model        TEXT NOT NULL,
+      logical_cores    INTEGER,
+      physical_cores   INTEGER,
+      os               TEXT NOT NULL,
+      os_release       TEXT NOT NULL,
+      arch             TEXT NOT NULL,
+      label            TEXT,
+      opt_level        TEXT NOT NULL,
+      target_cpu       TEXT NOT NULL,
+      mode             TEXT NOT NULL,
+      ny               INTEGER NOT NULL,
+      nx               INTEGER NOT NULL,
+      nz               INTEGER,
+      repeats          INTEGER NOT NULL,
+      trials           INTEGER NOT NULL,
+      inner_repeat     INTEGER,
+      threads          INTEGER NOT NULL,
+      input            TEXT NOT NULL,
+      seed             INTEGER NOT NULL,
+      loop_order       TEXT NOT NULL,
+      budget_ms        INTEGER,
+      out_stride       INTEGER,
+      perm_seed        INTEGER,
+      window           INTEGER,
+      window_stride    INTEGER,
+      unroll           INTEGER,
+      expr             TEXT,
+      fresh_alloc      INTEGER NOT NULL,
+      hugepages        INTEGER NOT NULL,
+      mlock            INTEGER NOT NULL,
+      time_source      TEXT NOT NULL,
+      flops            INTEGER NOT NULL,
+      bytes            INTEGER NOT NULL,
+      peak_bandwidth   REAL,
+      calls            INTEGER NOT NULL,
+      min_ns_per_call  REAL NOT NULL,
+      mean_ns_per_call REAL NOT NULL,
+      sem_ns_per_call  REAL,
+      ci95_ns_per_call REAL,
+      ns_per_element   REAL NOT NULL,
+      gflop_per_s      REAL NOT NULL,
+      gbyte_per_s      REAL NOT NULL,
+      peak_fraction    REAL,
+      verified         INTEGER NOT NULL,
+      checksum_algo    TEXT,
+      checksum         TEXT);
+   CREATE INDEX IF NOT EXISTS results_config ON results (mode,ny,nx);";
+
+//  How long to wait, in ms, if another program has the database locked -
+//  two benchmarks might finish at the same time.
+
+const BUSY_TIMEOUT_MS: c_int = 5000;
+
+//  ----------------------------------------------------------------------------
+//
+//                           R u n   D e t a i l s
+
+//  The details recorded with every result from a run, gathered once at the
+//  start.
+
+pub struct RunInfo {
+   pub run_time: i64,
+   pub host: String,
+   pub system: SysInfo,
+}
+
+pub fn run_info () -> RunInfo {
+   let run_time = SystemTime::now().duration_since(UNIX_EPOCH)
+                                   .map(|since| since.as_secs() as i64);
+   let host = fs::read_to_string("/proc/sys/kernel/hostname")
+                                   .map(|name| name.trim().to_string());
+   RunInfo {
+      run_time: run_time.unwrap_or(0),
+      host: host.unwrap_or_else(|_| "unknown".to_string()),
+      system: crssysinfo::gather(),
+   }
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                         W r i t i n g   R e s u l t s
+
+//  Writes a result to the database at path, creating the database and the
+//  results table if they don't exist. A database whose results table has
+//  different columns - made by a version of the program with other fields -
+//  is left alone, and an error is returned.
+
+pub fn insert_result (path: &str,run: &RunInfo,result: &BenchResult)
+                                                     -> Result<(),BenchError> {
+   let database = match Database::open(path) {
+      Ok(database) => database,
+      Err(error) => return Err(BenchError::Io { path: path.to_string(),
+                                               action: "open", error }),
+   };
+   let optional =
+               |value: Option<usize>| value.map(|n| Value::Integer(n as i64));
+   let text = |text: &str| Some(Value::Text(text.to_string()));
+   let integer = |value: usize| Some(Value::Integer(value as i64));
+   let real = |value: f64| Some(Value::Real(value));
+   let columns = vec![
+      ("run_time",Some(Value::Integer(run.run_time))),
+      ("host",text(&run.host)),
+      ("cpu_model",text(&run.system.cpu_model)),
+      ("logical_cores",optional(run.system.logical_cores)),
+      ("physical_cores",optional(run.system.physical_cores)),
+      ("os",text(&run.system.os)),
+      ("os_release",text(&run.system.os_release)),
+      ("arch",text(&run.system.arch)),
+      ("label",result.label.clone().map(Value::Text)),
+      ("opt_level",text(crsbuild::opt_level())),
+      ("target_cpu",text(crsbuild::target_cpu())),
+      ("mode",text(&result.mode)),
+      ("ny",integer(result.ny)),
+      ("nx",integer(result.nx)),
+      ("nz",optional(result.nz)),
+      ("repeats",integer(result.repeats)),
+      ("trials",integer(result.trials)),
+      ("inner_repeat",optional(result.inner_repeat)),
+      ("threads",integer(result.threads)),
+      ("input",text(&result.input)),
+      ("seed",Some(Value::Integer(result.seed as i64))),
+      ("loop_order",text(&result.loop_order)),
+      ("budget_ms",result.budget_ms.map(|n| Value::Integer(n as i64))),
+      ("out_stride",optional(result.out_stride)),
+      ("perm_seed",result.perm_seed.map(|n| Value::Integer(n as i64))),
+      ("window",optional(result.window)),
+      ("window_stride",optional(result.window_stride)),
+      ("unroll",optional(result.unroll)),
+      ("expr",result.expr.clone().map(Value::Text)),
+      ("fresh_alloc",Some(Value::Integer(result.fresh_alloc as i64))),
+      ("hugepages",Some(Value::Integer(result.hugepages as i64))),
+      ("mlock",Some(Value::Integer(result.mlock as i64))),
+      ("time_source",text(&result.time_source)),
+      ("flops",integer(result.flops)),
+      ("bytes",integer(result.bytes)),
+      ("peak_bandwidth",result.peak_bandwidth.map(Value::Real)),
+      ("calls",integer(result.calls)),
+      ("min_ns_per_call",real(result.min_ns_per_call)),
+      ("mean_ns_per_call",real(result.mean_ns_per_call)),
+      ("sem_ns_per_call",result.sem_ns_per_call.map(Value::Real)),
+      ("ci95_ns_per_call",result.ci95_ns_per_call.map(Value::Real)),
+      ("ns_per_element",real(result.ns_per_element())),
+      ("gflop_per_s",real(result.gflop_per_s())),
+      ("gbyte_per_s",real(result.gbyte_per_s())),
+      ("peak_fraction",result.peak_fraction().map(Value::Real)),
+      ("verified",Some(Value::Integer(result.verified as i64))),
+      ("checksum_algo",result.checksum_algo.clone().map(Value::Text)),
+      ("checksum",result.checksum.clone().map(Value::Text)),
+   ];
+   database.execute(SCHEMA)
+      .and_then(|_| database.insert("results",&columns))
+      .map_err(|error| BenchError::Io { path: path.to_string(),
+                                         action: "write", error })
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                        S Q L i t e   I n t e r f a c e
+
+//  The opaque types SQLite uses for a database connection and a prepared
+//  statement.
+
+#[repr(C)]
+struct Sqlite3 { _private: [u8; 0] }
+
+#[repr(C)]
+struct Sqlite3Stmt { _private: [u8; 0] }
+
+const SQLITE_OK: c_int = 0;
+const SQLITE_DONE: c_int = 101;
+
+//  Passed as the destructor for a bound value, this tells SQLite to make
+//  its own copy of it.
+
+const SQLITE_TRANSIENT: isize = -1;
+
+#[link(name = "sqlite3")]
+extern "C" {
+   fn sqlite3_open (filename: *const c_char,db: *mut *mut Sqlite3) -> c_int;
+   fn sqlite3_close (db: *mut Sqlite3) -> c_int;
+   fn sqlite3_errmsg (db: *mut Sqlite3) -> *const c_char;
+   fn sqlite3_busy_timeout (db: *mut Sqlite3,ms: c_int) -> c_int;
+   fn sqlite3_exec (db: *mut Sqlite3,sql: *const c_char,
+                    callback: *mut c_void,arg: *mut c_void,
+                    errmsg: *mut *mut c_char) -> c_int;
+   fn sqlite3_prepare_v2 (db: *mut Sqlite3,sql: *const c_char,bytes: c_int,
+             stmt: *mut *mut Sqlite3Stmt,tail: *mut *const c_char) -> c_int;
+   fn sqlite3_bind_int64 (stmt: *mut Sqlite3Stmt,index: c_int,value: i64)
+                                                                      -> c_int;
+   fn sqlite3_bind_double (stmt: *mut Sqlite3Stmt,index: c_int,value: f64)
+                                                                      -> c_int;
+   fn sqlite3_bind_text (stmt: *mut Sqlite3Stmt,index: c_int,
+              value: *const c_char,bytes: c_int,destructor: isize) -> c_int;
+   fn sqlite3_bind_null (stmt: *mut Sqlite3Stmt,index: c_int) -> c_int;
+   fn sqlite3_step (stmt: *mut Sqlite3Stmt) -> c_int;
+   fn sqlite3_finalize (stmt: *mut Sqlite3Stmt) -> c_int;
+}
+
+//  A value to be written to a column. A column with no value is NULL.
+
+enum Value {
+   Integer(i64),
+   Real(f64),
+   Text(String),
+}
+
+//  An open database connection, closed when it's dropped. Errors are
+//  returned as SQLite's own description of what went wrong.
+
+struct Database {
+   handle: *mut Sqlite3,
+}
+
+impl Database {
+
+   fn open (path: &str) -> Result<Database,String> {
+      let c_path = match CString::new(path) {
+         Ok(c_path) => c_path,
+         Err(_) => return Err("the name contains a nul character".to_string()),
+      };
+      let mut handle = ptr::null_mut();
+      let status = unsafe { sqlite3_open(c_path.as_ptr(),&mut handle) };
+
+      //  Even a failed open can return a handle, which has to be closed,
+      //  and it's then where the error message comes from.
+
+      let database = Database { handle };
+      if status != SQLITE_OK { return Err(database.message()); }
+      unsafe { sqlite3_busy_timeout(database.handle,BUSY_TIMEOUT_MS); }
+      Ok(database)
+   }
+
+   //  The description of the most recent error.
+
+   fn message (&self) -> String {
+      if self.handle.is_null() { return "out of memory".to_string(); }
+      let message = unsafe { CStr::from_ptr(sqlite3_errmsg(self.handle)) };
+      message.to_string_lossy().into_owned()
+   }
+
+   //  Executes one or more SQL statements that don't take any values.
+
+   fn execute (&self,sql: &str) -> Result<(),String> {
+      let c_sql = CString::new(sql).map_err(|error| error.to_string())?;
+      let status = unsafe { sqlite3_exec(self.handle,c_sql.as_ptr(),
+                    ptr::null_mut(),ptr::null_mut(),ptr::null_mut()) };
+      if status != SQLITE_OK { return Err(self.message()); }
+      Ok(())
+   }
+
+   //  Inserts a row into a table, setting the named columns.
+
+   fn insert (&self,table: &str,columns: &[(&str,Option<Value>)])
+                                                        -> Result<(),String> {
+      let names: Vec<&str> = columns.iter().map(|column| column.0).collect();
+      let places = vec!["?"; columns.len()];
+      let sql = format!("INSERT INTO {} ({}) VALUES ({})",table,
+                                           names.join(","),places.join(","));
+      let c_sql = CString::new(sql).map_err(|error| error.to_string())?;
+      let mut handle = ptr::null_mut();
+      let status = unsafe { sqlite3_prepare_v2(self.handle,c_sql.as_ptr(),-1,
+                                               &mut handle,ptr::null_mut()) };
+      let statement = Statement { handle };
+      if status != SQLITE_OK { return Err(self.message()); }
+      for (index,column) in columns.iter().enumerate() {
+         let index = index as c_int + 1;
+         let status = unsafe {
+            match column.1 {
+               None => sqlite3_bind_null(statement.handle,index),
+               Some(Value::Integer(value)) =>
+                           sqlite3_bind_int64(statement.handle,index,value),
+               Some(Value::Real(value)) =>
+                           sqlite3_bind_double(statement.handle,index,value),
+               Some(Value::Text(ref value)) => sqlite3_bind_text(
+                     statement.handle,index,value.as_ptr() as *const c_char,
+                     value.len() as c_int,SQLITE_TRANSIENT),
+            }
+         };
+         if status != SQLITE_OK { return Err(self.message()); }
+      }
+      if unsafe { sqlite3_step(statement.handle) } != SQLITE_DONE {
+         return Err(self.message());
+      }
+      Ok(())
+   }
+}
+
+impl Drop for Database {
+   fn drop (&mut self) {
+      unsafe { sqlite3_close(self.handle); }
+   }
+}
+
+//  A prepared statement, finalized when it's dropped.
+
+struct Statement {
+   handle: *mut Sqlite3Stmt,
+}
+
+impl Drop for Statement {
+   fn drop (&mut self) {
+      unsafe { sqlite3_finalize(self.handle); }
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The database is opened, and the table creation run, for every result,
+     rather than once for the run. That costs a few ms, outside any timing,
+     and means a sweep that fails part way through still leaves its earlier
+     results in the database, just as for --append.
+
+   o Passing null to sqlite3_close() or sqlite3_finalize() is harmless, so
+     the Drop implementations don't need to check for a failed open or
+     prepare.
+
+   o SQLITE_TRANSIENT is really a function pointer with the value -1, which
+     can't be written as a Rust fn. Declaring the parameter as an isize
+     passes the same bits on every platform SQLite supports.
+
+   o A Statement is dropped before its Database, since it's declared later,
+     so nothing tries to close a connection with a statement still open.
+
+   o Any new configuration or result field needs a column in SCHEMA and an
+     entry in insert_result(). An existing database won't have the new
+     column, and inserts into it will then fail with SQLite's message
+     saying which column is missing - better that than quietly recording
+     results that can't be told apart.
+
+*/
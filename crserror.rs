@@ -0,0 +1,179 @@
+//
+//                           c r s e r r o r . r s
+//
+// Summary:
+//    Errors for the Rust 2D array access benchmark harness.
+//
+// Introduction:
+//    The harness started as a single program, where the simplest thing to do
+//    when something went wrong was to report it and exit. As the modules get
+//    used by other programs, that isn't good enough - a larger test runner
+//    that sets up a configuration with a bad dimension, or one too big to
+//    allocate, wants to be told, not to have its process brought down. So
+//    the modules return a BenchError for anything that can reasonably go
+//    wrong, and leave it to the caller to decide what to do about it.
+//
+// Panics:
+//    A panic is kept for things that can only happen if the code itself is
+//    wrong - an invariant that's been broken, rather than bad input. The
+//    functions that can panic are:
+//
+//    o Array2D::new() and Array2D::from_fn(), if nx * ny overflows - and if
+//      the memory can't be allocated, the program aborts. Array2D::try_new()
+//      returns an error in both cases, and is what the harness uses for its
+//      arrays. The same goes for Array3D.
+//    o Indexing an Array2D or Array3D, or row() and row_mut(), with an index
+//      out of range.
+//    o The kernels, if the input and output arrays have different
+//      dimensions, or (for csub_strided()) if the stride hasn't been checked
+//      using stride_problem(). The harness always sets the arrays up to
+//      match and checks the configuration before running anything.
+//    o Mode::info(), if a mode is missing from the MODES table.
+//
+//    Everything else - building a configuration, making the input, the
+//    verification routines, and reading and writing files - either can't
+//    fail or returns a Result.
+//
+// Command line errors:
+//    The command line is a little different, since an argument that can't
+//    be read has traditionally - as in crsmain.rs - been reported with a
+//    warning and replaced by the default. That's kept as the default, but
+//    the parsing in crsbench.rs returns a ConfigError, so that the program
+//    can be asked (with --strict-args) to treat the same thing as an error.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//    14th Oct 2026. Added Allocation3D. agent.
+//    14th Oct 2026. Io is also used for network connections. agent.
+//    14th Oct 2026. Added ConfigError. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BenchError {
+
+   //  A configuration that doesn't make sense. The message says why - see
+   //  the Validation section of crsconfig.rs.
+
+   Config(String),
+
+   //  An array of nx columns and ny rows that couldn't be allocated, either
+   //  because nx * ny overflows or because the memory isn't available.
+
+   Allocation { nx: usize, ny: usize },
+
+   //  The same, for a 3D array of nz planes, each of ny rows of nx columns.
+
+   Allocation3D { nx: usize, ny: usize, nz: usize },
+
+   //  A file that couldn't be read or written. The action is 'open',
+   //  'read', 'create' or 'write', and the error is the system's description.
+   //  The same is used for a network connection, with the HOST:PORT address
+   //  as the path, and 'connect to' or 'send to' as the action.
+
+   Io { path: String, action: &'static str, error: String },
+
+   //  A file that could be read, but whose contents can't be used.
+
+   Format { path: String, message: String },
+}
+
+impl fmt::Display for BenchError {
+   fn fmt (&self,formatter: &mut fmt::Formatter) -> fmt::Result {
+      match *self {
+         BenchError::Config(ref message) => write!(formatter,"{}",message),
+         BenchError::Allocation { nx, ny } => write!(formatter,
+                  "Cannot allocate an array of {} rows of {} columns",ny,nx),
+         BenchError::Allocation3D { nx, ny, nz } => write!(formatter,
+                  "Cannot allocate an array of {} planes of {} rows of {} \
+                                                        columns",nz,ny,nx),
+         BenchError::Io { ref path, action, ref error } =>
+                  write!(formatter,"Cannot {} {}: {}",action,path,error),
+         BenchError::Format { ref path, ref message } =>
+                  write!(formatter,"{} {}",path,message),
+      }
+   }
+}
+
+impl Error for BenchError {}
+
+//  ----------------------------------------------------------------------------
+//
+//                      C o m m a n d   L i n e   E r r o r s
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfigError {
+
+   //  A value given on the command line, for the setting described by
+   //  'what', that can't be read as one. Unless the parsing is strict, this
+   //  is only a warning, and the default is used instead.
+
+   Value { what: String, value: String },
+
+   //  Anything else wrong with the command line - an unknown option, an
+   //  option without its value, or a configuration that doesn't make sense.
+   //  This is always an error.
+
+   Usage(String),
+}
+
+impl fmt::Display for ConfigError {
+   fn fmt (&self,formatter: &mut fmt::Formatter) -> fmt::Result {
+      match *self {
+         ConfigError::Value { ref what, ref value } =>
+                  write!(formatter,"{} '{}' is invalid",what,value),
+         ConfigError::Usage(ref message) => write!(formatter,"{}",message),
+      }
+   }
+}
+
+impl Error for ConfigError {}
+
+impl From<BenchError> for ConfigError {
+   fn from (error: BenchError) -> ConfigError {
+      ConfigError::Usage(error.to_string())
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o Allocation failure is only part of the story on Linux, which will
+     usually hand out more memory than it has and only find out when it's
+     used. try_new() catches the sizes that are plainly impossible, and any
+     the allocator does refuse, but a run that's too big for the machine
+     can still be killed once the array is filled.
+
+   o The vectors of vectors that the nested modes work on are allocated in
+     the usual way, which aborts if it fails, but only once try_new() has
+     succeeded for arrays of the same size.
+
+   o The errors hold strings rather than the io::Error itself so that they
+     can be cloned and compared, which is handy for a caller that collects
+     them.
+
+*/
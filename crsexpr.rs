@@ -0,0 +1,527 @@
+//
+//                            c r s e x p r . r s
+//
+// Summary:
+//    A tiny expression interpreter, for the expr mode of the Rust 2D array
+//    access benchmark harness.
+//
+// Introduction:
+//    Every kernel in this study sets each output element to the input
+//    element plus its index sum, and trying anything else - an operation
+//    with more arithmetic in it, say, to move the kernel along the roofline
+//    - means writing and compiling a new kernel. The expr mode instead sets
+//    each element to the value of an expression given on the command line
+//    (--expr), which is parsed once and then interpreted for every element.
+//    That makes it possible to try out a new kernel shape, and see that it
+//    gives the results it should, before writing it properly.
+//
+//    It is a flexibility mode, not a speed mode. The interpretation costs
+//    far more than the arithmetic itself, so its times say very little
+//    about how fast a compiled kernel doing the same thing would be. They
+//    do grow with the number of operations, though, so the relative cost of
+//    two expressions is some guide.
+//
+// Expressions:
+//    An expression is made up of the three variables, whole numbers, the
+//    four operators + - * /, unary minus, and parentheses. The variables
+//    are:
+//
+//    in    the input element.
+//    ix    the column number.
+//    iy    the row number.
+//
+//    * and / bind more tightly than + and -, and operators of the same
+//    precedence are applied left to right, so 'in + ix + iy' is
+//    '(in + ix) + iy' - which, in floating point, isn't quite the same as
+//    the usual in + (ix + iy). Spaces are optional. The arithmetic is done
+//    in the element type: ix, iy and the numbers are converted to it just
+//    as the index sum is by the compiled kernels, unary minus is a
+//    subtraction from zero, and with integer elements a division is an
+//    integer division. An expression can have at most MAX_OPS variables,
+//    numbers and operators in all, and parentheses and minus signs can only
+//    be nested MAX_OPS deep.
+//
+//    The results are checked against a second, separate, evaluation of the
+//    same expression - see Expr::reference() - rather than the usual
+//    check, and must match it bit for bit.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::fmt;
+
+use crate::crsarray::{Array2D, Element};
+
+//  The most variables, numbers and operators an expression can have.
+
+pub const MAX_OPS: usize = 32;
+
+//  The expression the expr mode uses if none is given - the usual
+//  operation, as closely as an expression can give it.
+
+pub const STANDARD: &str = "in + ix + iy";
+
+//  One step of a compiled expression. The steps are in postfix order, each
+//  variable or number pushing a value, and each operator replacing the
+//  one or two values on top with its result.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Op {
+   In,
+   Ix,
+   Iy,
+   Number(u32),
+   Add,
+   Sub,
+   Mul,
+   Div,
+   Neg,
+}
+
+//  A compiled expression. It's held in a fixed size array, rather than a
+//  vector, so that it can be copied as freely as the Check that holds it
+//  (see crsverify.rs).
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Expr {
+   ops: [Op; MAX_OPS],
+   len: usize,
+}
+
+impl Expr {
+
+   //  The STANDARD expression, compiled.
+
+   pub const STANDARD: Expr =
+                   Expr::from_ops(&[Op::In,Op::Ix,Op::Add,Op::Iy,Op::Add]);
+
+   const fn from_ops (ops: &[Op]) -> Expr {
+      let mut expr = Expr { ops: [Op::In; MAX_OPS], len: ops.len() };
+      let mut index = 0;
+      while index < ops.len() {
+         expr.ops[index] = ops[index];
+         index += 1;
+      }
+      expr
+   }
+
+   //  Parses and compiles an expression, returning a description of the
+   //  first problem with it if it can't be.
+
+   pub fn parse (text: &str) -> Result<Expr,String> {
+      let tokens = tokenise(text)?;
+      if tokens.is_empty() { return Err("it's empty".to_string()); }
+      let mut parser = Parser { tokens: &tokens, next: 0, depth: 0,
+                          expr: Expr { ops: [Op::In; MAX_OPS], len: 0 } };
+      parser.sum()?;
+      match parser.peek() {
+         None => Ok(parser.expr),
+         Some(token) => Err(format!("'{}' wasn't expected there",token)),
+      }
+   }
+
+   //  The number of additions each evaluation makes, which is what
+   //  --count-ops counts.
+
+   pub fn additions (&self) -> usize {
+      self.ops[..self.len].iter().filter(|&&op| op == Op::Add).count()
+   }
+
+   //  The number of arithmetic operations each evaluation makes. As for the
+   //  compiled kernels, converting ix, iy and the numbers to the element
+   //  type isn't counted.
+
+   pub fn flops (&self) -> usize {
+      self.ops[..self.len].iter().filter(|&&op|
+            !matches!(op,Op::In | Op::Ix | Op::Iy | Op::Number(_))).count()
+   }
+
+   //  Evaluates the expression for the element at (ix,iy), whose input
+   //  value is given. This is the interpreter the expr mode times, working
+   //  through the steps with a stack of values.
+
+   #[inline(always)]
+   pub fn eval<T: Element> (&self,value: T,ix: usize,iy: usize) -> T {
+      let mut stack = [value; MAX_OPS];
+      let mut depth = 0;
+      for &op in &self.ops[..self.len] {
+         let operand = match op {
+            Op::In => value,
+            Op::Ix => T::from_index(ix),
+            Op::Iy => T::from_index(iy),
+            Op::Number(number) => T::from_index(number as usize),
+            Op::Neg => {
+               stack[depth - 1] = T::from_index(0) - stack[depth - 1];
+               continue;
+            }
+            _ => {
+               depth -= 1;
+               let (left,right) = (stack[depth - 1],stack[depth]);
+               stack[depth - 1] = match op {
+                  Op::Add => left + right,
+                  Op::Sub => left - right,
+                  Op::Mul => left * right,
+                  _ => left / right,
+               };
+               continue;
+            }
+         };
+         stack[depth] = operand;
+         depth += 1;
+      }
+      stack[0]
+   }
+
+   //  Evaluates the expression for an f32 element as eval() does, but
+   //  separately, working back from the last step by recursion, with no
+   //  stack of values. This is the reference the expr mode's results are
+   //  checked against.
+
+   pub fn reference (&self,value: f32,ix: usize,iy: usize) -> f32 {
+      self.reference_to(self.len - 1,value,ix,iy).0
+   }
+
+   //  Evaluates the part of the expression that ends with the given step,
+   //  returning its value and the step it starts with.
+
+   fn reference_to (&self,last: usize,value: f32,ix: usize,iy: usize)
+                                                              -> (f32,usize) {
+      match self.ops[last] {
+         Op::In => (value,last),
+         Op::Ix => (ix as f32,last),
+         Op::Iy => (iy as f32,last),
+         Op::Number(number) => (number as f32,last),
+         Op::Neg => {
+            let (operand,first) = self.reference_to(last - 1,value,ix,iy);
+            (0.0 - operand,first)
+         }
+         op => {
+            let (right,middle) = self.reference_to(last - 1,value,ix,iy);
+            let (left,first) = self.reference_to(middle - 1,value,ix,iy);
+            let result = match op {
+               Op::Add => left + right,
+               Op::Sub => left - right,
+               Op::Mul => left * right,
+               _ => left / right,
+            };
+            (result,first)
+         }
+      }
+   }
+}
+
+//  The expression written out in a standard form, with spaces around each
+//  operator and only the parentheses that are needed, so that expressions
+//  that only differ in the way they are written are reported the same way.
+
+impl fmt::Display for Expr {
+   fn fmt (&self,formatter: &mut fmt::Formatter) -> fmt::Result {
+
+      //  Each entry is a part of the expression, with the precedence of its
+      //  outermost operator - 4 for something that never needs parentheses.
+
+      let wrap = |text: String,needed: bool|
+                       if needed { format!("({})",text) } else { text };
+      let mut parts: Vec<(String,u8)> = Vec::new();
+      for &op in &self.ops[..self.len] {
+         let part = match op {
+            Op::In => ("in".to_string(),4),
+            Op::Ix => ("ix".to_string(),4),
+            Op::Iy => ("iy".to_string(),4),
+            Op::Number(number) => (number.to_string(),4),
+            Op::Neg => {
+               let (text,precedence) = parts.pop().unwrap_or_default();
+               (format!("-{}",wrap(text,precedence < 3)),3)
+            }
+            _ => {
+               let (right,right_precedence) = parts.pop().unwrap_or_default();
+               let (left,left_precedence) = parts.pop().unwrap_or_default();
+               let (symbol,precedence) = match op {
+                  Op::Add => ("+",1),
+                  Op::Sub => ("-",1),
+                  Op::Mul => ("*",2),
+                  _ => ("/",2),
+               };
+               let text = format!("{} {} {}",
+                              wrap(left,left_precedence < precedence),symbol,
+                              wrap(right,right_precedence <= precedence));
+               (text,precedence)
+            }
+         };
+         parts.push(part);
+      }
+      write!(formatter,"{}",parts.pop().unwrap_or_default().0)
+   }
+}
+
+//  Sets each element of the output array to the value of the expression
+//  for the corresponding input element.
+
+pub fn csub_expr<T: Element> (input_array: &Array2D<T>,
+                                output_array: &mut Array2D<T>,expr: &Expr) {
+   for iy in 0..input_array.ny() {
+      let in_row = input_array.row(iy);
+      let out_row = output_array.row_mut(iy);
+      for (ix,(out,value)) in out_row.iter_mut().zip(in_row).enumerate() {
+         *out = expr.eval(*value,ix,iy);
+      }
+   }
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                               P a r s i n g
+
+//  The pieces an expression is made of - a variable or a number, already
+//  in the form of the step that pushes it, or one of the symbols.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Token {
+   Value(Op),
+   Symbol(char),
+}
+
+impl fmt::Display for Token {
+   fn fmt (&self,formatter: &mut fmt::Formatter) -> fmt::Result {
+      match *self {
+         Token::Value(Op::In) => write!(formatter,"in"),
+         Token::Value(Op::Ix) => write!(formatter,"ix"),
+         Token::Value(Op::Iy) => write!(formatter,"iy"),
+         Token::Value(Op::Number(number)) => write!(formatter,"{}",number),
+         Token::Value(_) => write!(formatter,"?"),
+         Token::Symbol(symbol) => write!(formatter,"{}",symbol),
+      }
+   }
+}
+
+//  Splits an expression into its tokens.
+
+fn tokenise (text: &str) -> Result<Vec<Token>,String> {
+   let mut tokens = Vec::new();
+   let chars: Vec<char> = text.chars().collect();
+   let mut index = 0;
+   while index < chars.len() {
+      let c = chars[index];
+      if c.is_whitespace() {
+         index += 1;
+      } else if "+-*/()".contains(c) {
+         tokens.push(Token::Symbol(c));
+         index += 1;
+      } else if c.is_ascii_alphanumeric() {
+         let start = index;
+         while index < chars.len() && chars[index].is_ascii_alphanumeric() {
+            index += 1;
+         }
+         let word: String = chars[start..index].iter().collect();
+         let op = match word.as_str() {
+            "in" => Op::In,
+            "ix" => Op::Ix,
+            "iy" => Op::Iy,
+            _ => match word.parse::<u32>() {
+               Ok(number) => Op::Number(number),
+               Err(_) => return Err(format!("'{}' is neither one of the \
+                      variables in, ix and iy nor a whole number",word)),
+            },
+         };
+         tokens.push(Token::Value(op));
+      } else {
+         return Err(format!("'{}' can't be used - only the variables in, \
+              ix and iy, whole numbers, + - * / and parentheses can",c));
+      }
+   }
+   Ok(tokens)
+}
+
+//  A recursive descent parser, which compiles the expression as it goes,
+//  appending each step to expr once its operands are in place. depth is
+//  how many parentheses and unary minus signs the parser is inside, which
+//  is how deep the recursion has gone.
+
+struct Parser<'a> {
+   tokens: &'a [Token],
+   next: usize,
+   depth: usize,
+   expr: Expr,
+}
+
+impl<'a> Parser<'a> {
+   fn peek (&self) -> Option<Token> {
+      self.tokens.get(self.next).copied()
+   }
+
+   fn emit (&mut self,op: Op) -> Result<(),String> {
+      if self.expr.len == MAX_OPS {
+         return Err(format!("it has more than {} variables, numbers and \
+                                                    operators",MAX_OPS));
+      }
+      self.expr.ops[self.expr.len] = op;
+      self.expr.len += 1;
+      Ok(())
+   }
+
+   //  sum := product (('+' | '-') product)*
+
+   fn sum (&mut self) -> Result<(),String> {
+      self.product()?;
+      loop {
+         let op = match self.peek() {
+            Some(Token::Symbol('+')) => Op::Add,
+            Some(Token::Symbol('-')) => Op::Sub,
+            _ => return Ok(()),
+         };
+         self.next += 1;
+         self.product()?;
+         self.emit(op)?;
+      }
+   }
+
+   //  product := factor (('*' | '/') factor)*
+
+   fn product (&mut self) -> Result<(),String> {
+      self.factor()?;
+      loop {
+         let op = match self.peek() {
+            Some(Token::Symbol('*')) => Op::Mul,
+            Some(Token::Symbol('/')) => Op::Div,
+            _ => return Ok(()),
+         };
+         self.next += 1;
+         self.factor()?;
+         self.emit(op)?;
+      }
+   }
+
+   //  factor := '-' factor | '(' sum ')' | variable | number
+
+   fn factor (&mut self) -> Result<(),String> {
+      let token = match self.peek() {
+         Some(token) => token,
+         None => return Err("it ends too soon".to_string()),
+      };
+      self.next += 1;
+      if let Token::Symbol('-') | Token::Symbol('(') = token {
+         if self.depth == MAX_OPS {
+            return Err(format!("it nests parentheses and minus signs more \
+                                                 than {} deep",MAX_OPS));
+         }
+      }
+      match token {
+         Token::Value(op) => self.emit(op),
+         Token::Symbol('-') => {
+            self.depth += 1;
+            self.factor()?;
+            self.depth -= 1;
+            self.emit(Op::Neg)
+         }
+         Token::Symbol('(') => {
+            self.depth += 1;
+            self.sum()?;
+            self.depth -= 1;
+            match self.peek() {
+               Some(Token::Symbol(')')) => { self.next += 1; Ok(()) }
+               _ => Err("a '(' has no matching ')'".to_string()),
+            }
+         }
+         Token::Symbol(symbol) => Err(format!("'{}' wasn't expected there",
+                                                                     symbol)),
+      }
+   }
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                                 T e s t s
+
+#[cfg(test)]
+mod tests {
+   use super::{Expr, MAX_OPS};
+
+   //  Nesting up to MAX_OPS deep is fine, one more is an error, and so is
+   //  nesting far deeper than the stack could take.
+
+   #[test]
+   fn nesting_limit () {
+      let nested = |depth: usize,open: &str,close: &str|
+                    format!("{}in{}",open.repeat(depth),close.repeat(depth));
+      assert!(Expr::parse(&nested(MAX_OPS,"(",")")).is_ok());
+      assert!(Expr::parse(&nested(MAX_OPS + 1,"(",")")).is_err());
+      assert!(Expr::parse(&nested(50000,"(",")")).is_err());
+      assert!(Expr::parse(&nested(50000,"(","")).is_err());
+      assert!(Expr::parse(&nested(MAX_OPS - 1,"-","")).is_ok());
+      assert!(Expr::parse(&nested(50000,"-","")).is_err());
+      assert!(Expr::parse(&nested(50000,"-(",")")).is_err());
+   }
+
+   //  Depth is counted from the outside in, not in total, so parentheses
+   //  one after another don't add up.
+
+   #[test]
+   fn nesting_is_not_summed () {
+      let group = format!("{}in{}","(".repeat(MAX_OPS),")".repeat(MAX_OPS));
+      assert!(Expr::parse(&format!("{} + {}",group,group)).is_ok());
+   }
+
+   #[test]
+   fn too_many_ops () {
+      let long = vec!["in"; MAX_OPS / 2 + 1].join(" + ");
+      assert!(Expr::parse(&long).is_err());
+      let short = vec!["in"; MAX_OPS / 2].join(" + ");
+      assert!(Expr::parse(&short).is_ok());
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The reference evaluation does the same operations, in the same order,
+     as the interpreter, so the two should agree bit for bit, even when
+     the expression divides by zero and gives an infinity or a NaN. What it
+     checks is the machinery - the compiled steps, the stack and the loop
+     over the array - rather than the arithmetic, which is the hardware's.
+     Whether the parser has the precedence right is checked by the self
+     test, which compares the standard form of some expressions with what
+     they should be.
+
+   o Unary minus is a subtraction from zero, rather than a negation,
+     because that's what Element provides. The one difference is in the
+     sign of a zero result - 0 - 0 is +0, where -0 is -0.
+
+   o Integer division by zero panics, so an expression that can divide by
+     zero - 'in / ix', at the first column - can only be used with floating
+     point elements. The expr mode is only ever run with f32 elements,
+     except by the self test, with the standard expression.
+
+   o eval() is marked inline(always), so that csub_expr() is a single loop
+     with the interpreter inside it, as it would be written by hand. The
+     match on each step is still made for every element, which is most of
+     the cost.
+
+   o Without the limit on nesting, an expression of a few tens of thousands
+     of '(' - which can come from a --config file as easily as from the
+     command line - would overflow the parser's stack, and that aborts the
+     whole program rather than giving an error.
+
+*/
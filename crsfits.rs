@@ -0,0 +1,329 @@
+//
+//                            c r s f i t s . r s
+//
+// Summary:
+//    Minimal FITS image reading for the Rust 2D array access benchmark
+//    harness.
+//
+// Introduction:
+//    Most of the people this study is aimed at are astronomers, whose 2D
+//    arrays are images, and whose images are FITS files. Rather than have
+//    them work out the dimensions of their data and type them in, the
+//    harness can take them from a FITS file with --fits, and with
+//    --fits-data it can use the pixel values themselves as the input, so a
+//    kernel can be timed on exactly the shape - and the values - it will
+//    meet in practice.
+//
+//    This is nothing like a FITS library. It reads the primary header of a
+//    file - the 2880 byte blocks of 80 character cards up to the END card -
+//    and looks at just the cards that say what the primary data array is:
+//    SIMPLE, BITPIX, NAXIS and NAXISn, and BSCALE and BZERO. read_header()
+//    returns what they say, and read_image() also reads the data array,
+//    which has to be of 32 bit IEEE floating point values (BITPIX = -32),
+//    unscaled, since those are what the harness's usual run works with.
+//    write_image() writes the simplest possible FITS file holding an array
+//    of f32 values, which is what the self test uses to check the reading.
+//
+// Building:
+//    This is only used if crsbench.rs is built with the 'fits' feature:
+//
+//    rustc -O --cfg 'feature="fits"' crsbench.rs
+//
+//    It needs nothing beyond the standard library.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Read, Write};
+
+use crate::crsarray::Array2D;
+use crate::crserror::BenchError;
+
+//  The size of a FITS block, and of a header card.
+
+const BLOCK: usize = 2880;
+const CARD: usize = 80;
+
+//  The BITPIX value for 32 bit IEEE floating point data.
+
+pub const BITPIX_F32: i32 = -32;
+
+//  What the primary header says about the data array: the bits per value
+//  (negative for floating point), the length of the first two axes - the
+//  columns and the rows - and the scaling to be applied to the values. The
+//  offset is where the data starts in the file, just past the header.
+
+#[derive(Clone, Debug)]
+pub struct FitsHeader {
+   pub bitpix: i32,
+   pub nx: usize,
+   pub ny: usize,
+   pub bscale: f64,
+   pub bzero: f64,
+   offset: usize,
+}
+
+impl FitsHeader {
+
+   //  The name of the element type the data is held as, for messages.
+
+   pub fn type_name (&self) -> &'static str {
+      match self.bitpix {
+         8 => "u8",
+         16 => "i16",
+         32 => "i32",
+         64 => "i64",
+         -32 => "f32",
+         _ => "f64",
+      }
+   }
+
+   //  The bytes in the data array.
+
+   fn data_bytes (&self) -> usize {
+      self.nx * self.ny * (self.bitpix.unsigned_abs() as usize / 8)
+   }
+}
+
+//  Reads the primary header of a FITS file. Only the header blocks are
+//  read, so this is quick however big the image is.
+
+pub fn read_header (path: &str) -> Result<FitsHeader,BenchError> {
+   let mut file = File::open(path).map_err(|error| BenchError::Io {
+         path: path.to_string(), action: "open", error: error.to_string() })?;
+   let mut header = Vec::new();
+   loop {
+      let mut block = [0u8; BLOCK];
+      if let Err(error) = file.read_exact(&mut block) {
+         return Err(if error.kind() == std::io::ErrorKind::UnexpectedEof {
+            format_error(path,"ends before the END card of its header")
+         } else {
+            BenchError::Io { path: path.to_string(), action: "read",
+                                                 error: error.to_string() }
+         });
+      }
+      header.extend_from_slice(&block);
+      if block.chunks(CARD).any(|card| keyword(card) == "END") { break; }
+   }
+   parse_header(path,&header)
+}
+
+//  Reads the data array of a FITS file, which has to be of unscaled f32
+//  values, as an array of its NAXIS1 columns and NAXIS2 rows, with the
+//  first row of the image as row 0. Returns the header too.
+
+pub fn read_image (path: &str)
+                         -> Result<(FitsHeader,Array2D<f32>),BenchError> {
+   let bytes = fs::read(path).map_err(|error| BenchError::Io {
+         path: path.to_string(), action: "read", error: error.to_string() })?;
+   let header = parse_header(path,&bytes)?;
+   if header.bitpix != BITPIX_F32 {
+      return Err(format_error(path,&format!("has BITPIX = {}, {} values, \
+         but the benchmark's input is f32, which needs BITPIX = -32",
+         header.bitpix,header.type_name())));
+   }
+   if header.bscale != 1.0 || header.bzero != 0.0 {
+      return Err(format_error(path,"has scaled data (BSCALE or BZERO), \
+                                   which isn't supported"));
+   }
+   if bytes.len() < header.offset + header.data_bytes() {
+      return Err(format_error(path,&format!("is too short to hold {} rows \
+                               of {} values",header.ny,header.nx)));
+   }
+   let data = &bytes[header.offset..];
+   let nx = header.nx;
+   let image = Array2D::from_fn(nx,header.ny,|ix,iy| {
+      let at = (iy * nx + ix) * 4;
+      f32::from_be_bytes([data[at],data[at + 1],data[at + 2],data[at + 3]])
+   });
+   Ok((header,image))
+}
+
+//  Writes an array to a FITS file, as a primary data array of f32 values,
+//  with the minimal header the standard requires.
+
+pub fn write_image (path: &str,array: &Array2D<f32>) -> Result<(),BenchError> {
+   let io_error = |action: &'static str,error: std::io::Error| BenchError::Io {
+         path: path.to_string(), action, error: error.to_string() };
+   let mut bytes: Vec<u8> = Vec::new();
+   let cards = [
+      format!("{:<8}= {:>20}","SIMPLE","T"),
+      format!("{:<8}= {:>20}","BITPIX",BITPIX_F32),
+      format!("{:<8}= {:>20}","NAXIS",2),
+      format!("{:<8}= {:>20}","NAXIS1",array.nx()),
+      format!("{:<8}= {:>20}","NAXIS2",array.ny()),
+      "END".to_string(),
+   ];
+   for card in &cards {
+      bytes.extend_from_slice(format!("{:<1$}",card,CARD).as_bytes());
+   }
+   bytes.resize(whole_blocks(bytes.len()),b' ');
+   for value in array.as_slice() {
+      bytes.extend_from_slice(&value.to_be_bytes());
+   }
+   bytes.resize(whole_blocks(bytes.len()),0);
+   let file = File::create(path).map_err(|error| io_error("create",error))?;
+   let mut writer = BufWriter::new(file);
+   writer.write_all(&bytes).map_err(|error| io_error("write",error))?;
+   writer.flush().map_err(|error| io_error("write",error))
+}
+
+//  Works out what a primary header says, from the bytes of the file - or
+//  at least of all its header blocks.
+
+fn parse_header (path: &str,bytes: &[u8]) -> Result<FitsHeader,BenchError> {
+   let mut simple = false;
+   let mut bitpix = None;
+   let mut axes: Vec<Option<usize>> = Vec::new();
+   let (mut bscale,mut bzero) = (1.0,0.0);
+   let mut end = None;
+   for (index,card) in bytes.chunks(CARD).enumerate() {
+      let name = keyword(card);
+      if index == 0 && !(name == "SIMPLE" && value(card) == Some("T")) {
+         return Err(format_error(path,"isn't a FITS file - it doesn't \
+                                            start with SIMPLE = T"));
+      }
+      if name == "END" {
+         end = Some(index);
+         break;
+      }
+      let integer = || value(card).and_then(|text| text.parse::<i64>().ok());
+      let real = || value(card).and_then(|text|
+                                   text.replace('D',"E").parse::<f64>().ok());
+      match name {
+         "SIMPLE" => simple = true,
+         "BITPIX" => bitpix = integer(),
+         "NAXIS" => {
+            let count = integer().filter(|&count| (0..=999).contains(&count));
+            match count {
+               Some(count) => axes = vec![None; count as usize],
+               None => return Err(format_error(path,"has an invalid NAXIS")),
+            }
+         }
+         "BSCALE" => bscale = real().unwrap_or(f64::NAN),
+         "BZERO" => bzero = real().unwrap_or(f64::NAN),
+         _ => {
+            let number = name.strip_prefix("NAXIS")
+                             .and_then(|digits| digits.parse::<usize>().ok());
+            if let Some(number) = number.filter(|&number| number >= 1) {
+               if let Some(axis) = axes.get_mut(number - 1) {
+                  *axis = integer()
+                             .and_then(|length| usize::try_from(length).ok());
+               }
+            }
+         }
+      }
+   }
+   let end = match end {
+      Some(end) if simple => end,
+      _ => return Err(format_error(path,"has no END card in its header")),
+   };
+   let bitpix = match bitpix {
+      Some(bits) if [8,16,32,64,-32,-64].contains(&bits) => bits as i32,
+      _ => return Err(format_error(path,"has no valid BITPIX")),
+   };
+   if axes.len() < 2 || axes.iter().any(|axis| axis.is_none()) {
+      return Err(format_error(path,"doesn't hold a 2D image - it needs \
+                               NAXIS of at least 2, and each NAXISn"));
+   }
+   let lengths: Vec<usize> =
+                      axes.iter().map(|axis| axis.unwrap_or(0)).collect();
+   if lengths[2..].iter().any(|&length| length != 1) {
+      return Err(format_error(path,&format!("holds a {}D array, not a 2D \
+                                               image",lengths.len())));
+   }
+   let (nx,ny) = (lengths[0],lengths[1]);
+   if nx == 0 || ny == 0 {
+      return Err(format_error(path,"has an empty image"));
+   }
+   let bytes_per_value = bitpix.unsigned_abs() as usize / 8;
+   if nx.checked_mul(ny).and_then(|len| len.checked_mul(bytes_per_value))
+                                                                 .is_none() {
+      return Err(format_error(path,"has an image too big to address"));
+   }
+   let offset = whole_blocks((end + 1) * CARD);
+   Ok(FitsHeader { bitpix, nx, ny, bscale, bzero, offset })
+}
+
+//  The keyword of a header card - its first eight characters, without the
+//  trailing spaces.
+
+fn keyword (card: &[u8]) -> &str {
+   std::str::from_utf8(&card[..card.len().min(8)]).unwrap_or("").trim_end()
+}
+
+//  The value of a header card, if it has one - the text after the '= '
+//  value indicator in columns 9 and 10, up to any comment, trimmed.
+
+fn value (card: &[u8]) -> Option<&str> {
+   if card.len() < 10 || &card[8..10] != b"= " { return None; }
+   let text = std::str::from_utf8(&card[10..]).ok()?;
+   Some(text.split('/').next().unwrap_or("").trim())
+}
+
+//  The bytes taken up by the given number of bytes, rounded up to a whole
+//  number of FITS blocks.
+
+fn whole_blocks (bytes: usize) -> usize {
+   bytes.div_ceil(BLOCK) * BLOCK
+}
+
+fn format_error (path: &str,message: &str) -> BenchError {
+   BenchError::Format { path: path.to_string(), message: message.to_string() }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o FITS stores the first axis, NAXIS1, fastest, so a row of the image
+     is NAXIS1 values, and there are NAXIS2 rows - which is just how an
+     Array2D is laid out, nx columns of ny rows. Astronomers usually
+     display row 1 at the bottom, but that only matters for display; here
+     the first row in the file is row 0.
+
+   o Values are big endian, as the standard requires, whatever machine
+     wrote them.
+
+   o Only the primary data array is read. Any extensions after it are
+     ignored, and a file whose primary array is empty and whose image is
+     in an extension - common for compressed images, and for data from
+     some instruments - is rejected, since NAXIS is 0. Converting it to a
+     simple image first, with any of the usual tools, gets round that.
+
+   o Blank pixels in floating point images are NaNs. They go through the
+     kernels like any other value, but NaN is never equal to NaN, so the
+     check of the results will fail for them.
+
+   o The commentary and string-valued cards are never looked at, so a '/'
+     in a string value, which would confuse value(), doesn't matter - only
+     numeric and logical values are read.
+
+   o The header has to fit in memory, which it always will; read_image()
+     reads the whole file in one go, which for a very large image needs
+     memory for the file as well as for the array.
+
+*/
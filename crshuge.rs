@@ -0,0 +1,259 @@
+//
+//                           c r s h u g e . r s
+//
+// Summary:
+//    Huge page support for the Rust 2D array access benchmark harness.
+//
+// Introduction:
+//    Every access to memory goes through the TLB, which caches the
+//    translations from virtual to physical addresses, one entry per page.
+//    With the usual 4 KiB pages, even a few MBytes of array needs far more
+//    entries than the TLB has, and once the arrays are big enough the TLB
+//    misses can cost as much as the accesses themselves. Linux can back
+//    memory with transparent huge pages - usually 2 MiB each - if asked to
+//    with madvise(MADV_HUGEPAGE), and comparing the timings with and
+//    without them (--hugepages) shows how much of the time goes on the TLB.
+//
+//    HugePages collects the advice for the arrays of a run. advise() is
+//    given the storage of each array as it's allocated, before any of it
+//    has been touched - see Array2D::try_new_prepared() - and asks for the
+//    whole huge pages that lie within it to be huge. The system is free to
+//    ignore the advice, and may not have the huge pages to spare, so
+//    backing() then looks in /proc/self/smaps to see how much of that
+//    memory really did end up in huge pages.
+//
+//    Anything that stops huge pages being used - a system other than
+//    Linux, transparent huge pages being turned off, an array too small to
+//    hold a whole huge page - is recorded as a problem, to be reported as a
+//    warning, and the arrays are simply used with the usual pages.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::fs;
+
+//  The size of a huge page if the system doesn't say, which is what it is
+//  on x86-64, and on most ARM64 systems.
+
+const DEFAULT_HUGE_PAGE: usize = 2 * 1024 * 1024;
+
+//  The sysfs files that say whether transparent huge pages can be used, and
+//  how big they are.
+
+const THP_ENABLED: &str = "/sys/kernel/mm/transparent_hugepage/enabled";
+const THP_PAGE_SIZE: &str =
+                        "/sys/kernel/mm/transparent_hugepage/hpage_pmd_size";
+
+//  How much of the advised memory is in huge pages, the size of the huge
+//  pages, and the size of the pages used for the rest.
+
+#[derive(Clone, Copy, Debug)]
+pub struct Backing {
+   pub huge_bytes: usize,
+   pub total_bytes: usize,
+   pub huge_page_size: usize,
+   pub base_page_size: usize,
+}
+
+//  The memory that's been advised to use huge pages, as (start,end)
+//  address ranges, and anything that's got in the way.
+
+pub struct HugePages {
+   page_size: usize,
+   ranges: Vec<(usize,usize)>,
+   total_bytes: usize,
+   problems: Vec<String>,
+}
+
+impl HugePages {
+   pub fn new () -> HugePages {
+      let page_size = fs::read_to_string(THP_PAGE_SIZE).ok()
+                .and_then(|text| text.trim().parse::<usize>().ok())
+                .filter(|&size| size.is_power_of_two())
+                .unwrap_or(DEFAULT_HUGE_PAGE);
+      let mut huge = HugePages { page_size, ranges: Vec::new(),
+                                 total_bytes: 0, problems: Vec::new() };
+      if let Some(problem) = thp_problem() {
+         huge.problems.push(problem);
+      }
+      huge
+   }
+
+   //  Advises the system to use huge pages for as much of the bytes of
+   //  memory starting at address as are in whole huge pages. The memory
+   //  must not have been touched yet, or it will already have its pages.
+
+   pub fn advise (&mut self,address: *const u8,bytes: usize) {
+      self.total_bytes += bytes;
+      if !self.problems.is_empty() { return; }
+      let start = (address as usize).saturating_add(self.page_size - 1)
+                                                     & !(self.page_size - 1);
+      let end = (address as usize).saturating_add(bytes)
+                                                     & !(self.page_size - 1);
+      if end <= start {
+         self.problems.push(format!("An array of {} bytes is too small to \
+                           hold a whole huge page of {} bytes",bytes,
+                                                            self.page_size));
+         return;
+      }
+      match madvise_huge(start,end - start) {
+         Ok(()) => self.ranges.push((start,end)),
+         Err(problem) => self.problems.push(problem),
+      }
+   }
+
+   //  What's got in the way of using huge pages, if anything. Each is a
+   //  complete sentence, to be reported as a warning.
+
+   pub fn problems (&self) -> &[String] {
+      &self.problems
+   }
+
+   //  How much of the advised memory is in huge pages now - which, once
+   //  the arrays have been filled, is how much they have. Returns None if
+   //  nothing was advised, or /proc/self/smaps can't be read.
+
+   pub fn backing (&self) -> Option<Backing> {
+      if self.ranges.is_empty() { return None; }
+      let smaps = fs::read_to_string("/proc/self/smaps").ok()?;
+      let mut backing = Backing { huge_bytes: 0,
+                         total_bytes: self.total_bytes,
+                         huge_page_size: self.page_size, base_page_size: 0 };
+
+      //  Each mapping starts with a line giving its address range, followed
+      //  by lines of 'Name: value kB'. The huge pages found in a mapping
+      //  are only counted up to the size of its overlap with the advised
+      //  memory, in case it holds something else as well.
+
+      let mut overlap = 0;
+      for line in smaps.lines() {
+         if let Some((start,end)) = mapping_range(line) {
+            overlap = self.ranges.iter().map(|&(low,high)|
+                     high.min(end).saturating_sub(low.max(start))).sum();
+            continue;
+         }
+         if overlap == 0 { continue; }
+         let mut words = line.split_whitespace();
+         let (name,value) = match (words.next(),words.next()) {
+            (Some(name),Some(value)) => (name,value),
+            _ => continue,
+         };
+         let bytes = match value.parse::<usize>() {
+            Ok(kbytes) => kbytes * 1024,
+            Err(_) => continue,
+         };
+         match name {
+            "AnonHugePages:" => backing.huge_bytes += bytes.min(overlap),
+            "KernelPageSize:" => backing.base_page_size = bytes,
+            _ => {}
+         }
+      }
+      Some(backing)
+   }
+}
+
+//  Returns the address range of a mapping from the line at the start of its
+//  entry in /proc/self/smaps - eg '7f1c9a000000-7f1c9b000000 rw-p ...' - or
+//  None if the line is anything else.
+
+fn mapping_range (line: &str) -> Option<(usize,usize)> {
+   let range = line.split_whitespace().next()?;
+   let (start,end) = range.split_once('-')?;
+   let start = usize::from_str_radix(start,16).ok()?;
+   let end = usize::from_str_radix(end,16).ok()?;
+   Some((start,end))
+}
+
+//  Returns a description of why transparent huge pages can't be used, if
+//  they can't. The enabled file shows the current setting in brackets, eg
+//  'always [madvise] never', and both 'always' and 'madvise' honour the
+//  advice.
+
+fn thp_problem () -> Option<String> {
+   if !cfg!(target_os = "linux") {
+      return Some("Huge pages are only available on Linux".to_string());
+   }
+   match fs::read_to_string(THP_ENABLED) {
+      Ok(ref text) if text.contains("[never]") => Some(format!("Transparent \
+                   huge pages are turned off ({} is 'never')",THP_ENABLED)),
+      Ok(_) => None,
+      Err(_) => Some(format!("This system doesn't seem to support \
+                   transparent huge pages ({} can't be read)",THP_ENABLED)),
+   }
+}
+
+//  The madvise() call itself, which only exists in this form on Linux.
+
+#[cfg(target_os = "linux")]
+fn madvise_huge (start: usize,bytes: usize) -> Result<(),String> {
+   use std::os::raw::{c_int, c_void};
+   const MADV_HUGEPAGE: c_int = 14;
+   extern "C" {
+      fn madvise (address: *mut c_void,length: usize,advice: c_int) -> c_int;
+   }
+
+   //  Safety: the range is page aligned, and lies within an allocation
+   //  that is still live. The advice only changes how the memory is backed,
+   //  not its contents or whether it can be accessed.
+
+   let status = unsafe { madvise(start as *mut c_void,bytes,MADV_HUGEPAGE) };
+   if status == 0 { return Ok(()); }
+   Err(format!("The system refused to use huge pages: {}",
+                                          std::io::Error::last_os_error()))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn madvise_huge (_start: usize,_bytes: usize) -> Result<(),String> {
+   Err("Huge pages are only available on Linux".to_string())
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The arrays are only aligned to the size of an element - or to a
+     page, for an input that's to be protected - so it's only the whole
+     huge pages inside each one that are advised. For the large arrays
+     where huge pages matter, the part of a huge page at either end that's
+     left out makes very little difference, but it does mean an array needs
+     to be at least 4 MiB or so to be sure of getting any huge pages at all.
+
+   o madvise() splits the allocation's mapping so that the advised part has
+     one of its own, but two advised ranges next to each other - the input
+     and output arrays can easily end up that way - get merged into one
+     mapping. That's why backing() adds up the overlap with every advised
+     range, rather than expecting one mapping per array.
+
+   o Explicit huge pages, from hugetlbfs or mmap(MAP_HUGETLB), would be
+     certain to be huge, but need pages to have been set aside by root
+     beforehand, and an allocation the Vec couldn't own. Transparent huge
+     pages need nothing set up, which suits a benchmark that's meant to be
+     run anywhere.
+
+   o KernelPageSize in smaps is the base page size - 4 KiB on x86-64 - even
+     for a mapping that's partly in huge pages, which is what's wanted for
+     describing the rest of the memory.
+
+*/
@@ -0,0 +1,283 @@
+//
+//                           c r s i n p u t . r s
+//
+// Summary:
+//    Input array generation for the Rust 2D array access benchmark harness.
+//
+// Introduction:
+//    All the test programs in this study set up the input array with the sum
+//    of the row and column indices in descending order, so element (ix,iy)
+//    of an Ny by Nx array is (Nx - ix) + (Ny - iy). That makes the results
+//    easy to check, and it's what the harness does by default, but it's a
+//    very regular pattern. This module can also fill the array with pseudo
+//    random values, from a given seed, so that runs can use different data
+//    while still being reproducible.
+//
+//    The uniform random values are distributed over the same range as the
+//    standard values, 0 to Nx + Ny, so the results have much the same
+//    magnitude either way. Real detector data is closer to Gaussian noise
+//    about some mean level, so normally distributed values, with a given
+//    mean and standard deviation, are also available. The checks in
+//    crsverify.rs work from the input array, so they work just as well
+//    whatever the values are.
+//
+// Generator:
+//    The generator is SplitMix64, which is tiny, fast, and more than good
+//    enough for making test data. It's written out here rather than using
+//    the rand crate so that the harness still builds with nothing more than
+//    rustc. Gaussian values come from the same generator, using the
+//    Box-Muller transform.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//    14th Oct 2026. Added the Gaussian input. agent.
+//    14th Oct 2026. make_input() now returns an error if the array can't be
+//                   allocated. agent.
+//    14th Oct 2026. Added make_input_3d(), for 3D arrays. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::crsarray::Array2D;
+use crate::crsarray3d::Array3D;
+use crate::crserror::BenchError;
+
+//  The seed used if none is specified.
+
+pub const DEFAULT_SEED: u64 = 1;
+
+//  The default mean and standard deviation for the Gaussian input.
+
+pub const DEFAULT_MEAN: f64 = 0.0;
+pub const DEFAULT_STDDEV: f64 = 1.0;
+
+//  ----------------------------------------------------------------------------
+//
+//                         I n p u t   K i n d s
+
+//  The different ways the input array can be filled.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Input {
+   Standard,
+   Random,
+   Gaussian { mean: f64, stddev: f64 },
+}
+
+impl Input {
+
+   //  Returns the input with the given name. The Gaussian input has the
+   //  default mean and standard deviation.
+
+   pub fn from_name (name: &str) -> Option<Input> {
+      match name {
+         "standard" => Some(Input::Standard),
+         "random" => Some(Input::Random),
+         "gaussian" => Some(Input::Gaussian { mean: DEFAULT_MEAN,
+                                               stddev: DEFAULT_STDDEV }),
+         _ => None,
+      }
+   }
+
+   pub fn name (&self) -> &'static str {
+      match *self {
+         Input::Standard => "standard",
+         Input::Random => "random",
+         Input::Gaussian { .. } => "gaussian",
+      }
+   }
+
+   //  The name, with any parameters.
+
+   pub fn describe (&self) -> String {
+      match *self {
+         Input::Gaussian { mean, stddev } =>
+                           format!("gaussian, mean {}, stddev {}",mean,stddev),
+         _ => self.name().to_string(),
+      }
+   }
+
+   //  True for the inputs that use the random number generator.
+
+   pub fn is_random (&self) -> bool {
+      *self != Input::Standard
+   }
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                             G e n e r a t o r
+
+//  A SplitMix64 pseudo random number generator.
+
+pub struct Rng {
+   state: u64,
+}
+
+impl Rng {
+   pub fn new (seed: u64) -> Rng {
+      Rng { state: seed }
+   }
+
+   pub fn next_u64 (&mut self) -> u64 {
+      self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+      let mut value = self.state;
+      value = (value ^ (value >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+      value = (value ^ (value >> 27)).wrapping_mul(0x94d049bb133111eb);
+      value ^ (value >> 31)
+   }
+
+   //  Returns a value uniformly distributed in [0,1). The top 24 bits are
+   //  used, which is all an f32 significand can hold, so every value is
+   //  exactly representable and none of them round up to 1.
+
+   pub fn next_f32 (&mut self) -> f32 {
+      (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+   }
+
+   //  Returns a value uniformly distributed in [0,1), using the top 53
+   //  bits, in the same way.
+
+   pub fn next_f64 (&mut self) -> f64 {
+      (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+   }
+
+   //  Returns a pair of independent values from a normal distribution with
+   //  zero mean and unit standard deviation, using the Box-Muller transform.
+   //  The first uniform value is taken from (0,1] rather than [0,1), so its
+   //  logarithm is always finite.
+
+   pub fn next_normal_pair (&mut self) -> (f64,f64) {
+      let u1 = 1.0 - self.next_f64();
+      let u2 = self.next_f64();
+      let radius = (-2.0 * u1.ln()).sqrt();
+      let angle = 2.0 * std::f64::consts::PI * u2;
+      (radius * angle.cos(),radius * angle.sin())
+   }
+}
+
+//  The seed to use for a given trial when each trial gets fresh input. The
+//  seeds simply count up from the base seed, so the sequence is easy to
+//  reproduce.
+
+pub fn trial_seed (seed: u64,itrial: usize) -> u64 {
+   seed.wrapping_add(itrial as u64)
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                              F i l l i n g
+
+//  Fills an existing array with the given kind of input. The seed is
+//  ignored for the standard input.
+
+pub fn fill (array: &mut Array2D<f32>,input: Input,seed: u64) {
+   let nx = array.nx();
+   let ny = array.ny();
+   if input == Input::Standard {
+      for iy in 0..ny {
+         for (ix,value) in array.row_mut(iy).iter_mut().enumerate() {
+            *value = (nx - ix + ny - iy) as f32;
+         }
+      }
+   } else {
+      fill_random(array.as_mut_slice(),input,seed,(nx + ny) as f32);
+   }
+}
+
+//  Fills a set of values with random or Gaussian input. Uniform values are
+//  spread over 0 to range.
+
+fn fill_random (values: &mut [f32],input: Input,seed: u64,range: f32) {
+   let mut rng = Rng::new(seed);
+   match input {
+      Input::Standard => {}
+      Input::Random => {
+         for value in values {
+            *value = rng.next_f32() * range;
+         }
+      }
+      Input::Gaussian { mean, stddev } => {
+         for pair in values.chunks_mut(2) {
+            let (first,second) = rng.next_normal_pair();
+            pair[0] = (mean + stddev * first) as f32;
+            if pair.len() > 1 {
+               pair[1] = (mean + stddev * second) as f32;
+            }
+         }
+      }
+   }
+}
+
+//  Returns a new nx by ny array filled with the given kind of input, or an
+//  error if the array can't be allocated.
+
+pub fn make_input (nx: usize,ny: usize,input: Input,seed: u64)
+                                         -> Result<Array2D<f32>,BenchError> {
+   let mut array = Array2D::try_new(nx,ny,0.0f32)?;
+   fill(&mut array,input,seed);
+   Ok(array)
+}
+
+//  The same for a 3D array of nz planes, each of ny rows of nx columns. The
+//  standard input extends naturally, element (ix,iy,iz) being (Nx - ix) +
+//  (Ny - iy) + (Nz - iz), and the uniform random values are spread over 0
+//  to Nx + Ny + Nz.
+
+pub fn make_input_3d (nx: usize,ny: usize,nz: usize,input: Input,seed: u64)
+                                         -> Result<Array3D<f32>,BenchError> {
+   let mut array = Array3D::try_new(nx,ny,nz,0.0f32)?;
+   if input == Input::Standard {
+      for iz in 0..nz {
+         for iy in 0..ny {
+            for (ix,value) in array.row_mut(iy,iz).iter_mut().enumerate() {
+               *value = (nx - ix + ny - iy + nz - iz) as f32;
+            }
+         }
+      }
+   } else {
+      fill_random(array.as_mut_slice(),input,seed,(nx + ny + nz) as f32);
+   }
+   Ok(array)
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o fill() works on an existing array so that the harness can give each
+     trial fresh input without allocating a new array each time - the
+     timed kernel then always sees the same addresses, and only the data
+     changes.
+
+   o The random values are generated in memory order, so the same seed
+     gives the same array whatever the code that reads it does.
+
+   o Box-Muller produces values in pairs, and both are used, so with an odd
+     number of elements the second value of the last pair is thrown away.
+
+   o Unlike the standard values, the random values have fractional parts,
+     so adding the index sum generally has to round. The standard check
+     doesn't mind, since it rounds in just the same way, but the exact
+     check done by --verify-both will report many elements as inexact.
+
+*/
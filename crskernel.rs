@@ -0,0 +1,154 @@
+//
+//                          c r s k e r n e l . r s
+//
+// Summary:
+//    The Kernel trait, for plugging new kernels into the Rust 2D array
+//    access benchmark harness.
+//
+// Introduction:
+//    The versions of csub() that make up the study are listed in the MODES
+//    table in crsconfig.rs, and the harness calls each of them directly,
+//    through with_kernel() in crsbench.rs, so that the kernel can be inlined
+//    into the timing loop exactly as it would be in the original test
+//    programs. That's the right way to time them, but it means a new kernel
+//    can only be tried that way by adding a mode.
+//
+//    Kernel is a simpler way in. Anything that implements it can be passed
+//    to run_benchmark() in crsbench.rs, which sets up the arrays as for any
+//    other run, times the kernel using the same warmup, trials and budget
+//    as the configuration, checks its results, and returns a BenchResult
+//    that can be reported, compared with a baseline, or written to a CSV
+//    file just as the results for the built-in modes are. Each of the
+//    built-in modes that uses a flat Array2D is also available as a Kernel
+//    (see BuiltinKernel in crsbench.rs), so a new kernel can be timed
+//    alongside them under the same conditions.
+//
+//    The kernel can be in a module added to crsbench.rs, or in a program
+//    of its own. run_benchmark() is public, as are the modules it needs -
+//    this one, crsarray, crsconfig, crserror and crsreport - so the harness
+//    can be built as a library, with something like
+//
+//       rustc -O --crate-type lib -A dead_code crsbench.rs
+//
+//    and used from a program built with '--extern crsbench=libcrsbench.rlib'.
+//    The dead code is everything that only main() uses.
+//
+//    A kernel only has to say what it's called and how to make one pass
+//    over the array. The check and the operation counts have defaults that
+//    suit any kernel doing the standard operation - adding the index sum to
+//    each element - so, for example, this is a complete kernel:
+//
+//       struct ZipKernel;
+//
+//       impl Kernel for ZipKernel {
+//          fn name (&self) -> &str { "zip" }
+//          fn run (&self,input: &Array2D<f32>,output: &mut Array2D<f32>) {
+//             let nx = input.nx();
+//             for (index,(out,value)) in output.as_mut_slice().iter_mut()
+//                                  .zip(input.as_slice()).enumerate() {
+//                *out = value + (index % nx + index / nx) as f32;
+//             }
+//          }
+//       }
+//
+//    The self test (--selftest) runs this very kernel, along with the
+//    built-in kernels, through run_benchmark(), and the tests in crsbench.rs
+//    have another example.
+//
+// Timing:
+//    run_benchmark() calls the kernel through a &dyn Kernel, so there is a
+//    virtual call, which can't be inlined, for every pass over the array,
+//    and nothing the kernel does can be inlined into the timing loop. For
+//    a small array that's a real cost - for the flat mode, with one row of
+//    8 columns, it put the time per pass up from about 13.5 ns to around
+//    18 ns. It's lost in the noise for a large array, but run_benchmark()
+//    results should be compared with each other, including a built-in mode
+//    run as a BuiltinKernel, rather than with the results of the usual
+//    runs.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::crsarray::Array2D;
+use crate::crsverify;
+
+pub trait Kernel {
+
+   //  The name the kernel's results are reported under - the equivalent of
+   //  the mode name for a built-in kernel.
+
+   fn name (&self) -> &str;
+
+   //  Makes one pass over the array, setting each element of the output to
+   //  the corresponding input element plus the sum of its indices.
+
+   fn run (&self,input: &Array2D<f32>,output: &mut Array2D<f32>);
+
+   //  Checks the output of a pass. The default is the standard element by
+   //  element check, which is right for any kernel that does the standard
+   //  operation in single precision.
+
+   fn verify (&self,input: &Array2D<f32>,output: &Array2D<f32>) -> bool {
+      crsverify::verify(input,output)
+   }
+
+   //  The floating point operations and the bytes of memory traffic for a
+   //  pass over an array of nx columns and ny rows. The defaults are for
+   //  the standard operation - one addition per element, with each element
+   //  read once and written once.
+
+   fn flops (&self,nx: usize,ny: usize) -> usize {
+      nx * ny
+   }
+
+   fn bytes (&self,nx: usize,ny: usize) -> usize {
+      8 * nx * ny
+   }
+
+   //  The number of threads the kernel uses, for reporting.
+
+   fn threads (&self) -> usize {
+      1
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The trait only deals in f32 arrays. The built-in flat kernels are
+     generic over the element type, but a generic method would stop the
+     trait being used as a &dyn Kernel, and f32 is what the study is about.
+
+   o The modes that use vectors of vectors aren't available as Kernels,
+     since converting to and from an Array2D on every pass would swamp the
+     time for the pass itself.
+
+   o BenchResult holds the flops and bytes per element, so those are worked
+     out from the totals given here by dividing by the number of elements,
+     rounding down. That's exact for anything that does the same work on
+     every element.
+
+*/
@@ -0,0 +1,193 @@
+//
+//                           c r s l o c k . r s
+//
+// Summary:
+//    Memory locking for the Rust 2D array access benchmark harness.
+//
+// Introduction:
+//    Once an array has been filled, all its pages are in memory, and the
+//    timed loop shouldn't take a page fault. But nothing stops the system
+//    taking pages away again - swapping them out under memory pressure, or
+//    moving them to compact memory - and the next access to one of those
+//    is a fault that can cost many microseconds, in the middle of a trial.
+//    mlock() locks a range of memory into RAM, faulting in any pages that
+//    aren't there yet, so that none of it is paged out until it's unlocked.
+//    Asking for that (--mlock) takes paging out of the timings entirely.
+//
+//    MemoryLock collects the locks for the arrays of a run. lock() is given
+//    the storage of each array once it's been allocated and filled, and
+//    locks it, and the locks are all released when the MemoryLock is
+//    dropped, at the end of the run.
+//
+//    Locking memory needs permission. An unprivileged process can only lock
+//    as much as its RLIMIT_MEMLOCK allows - often only 64 KiB, or 8 MiB on
+//    newer systems - and anything that stops an array being locked, that
+//    limit included, is recorded as a problem, to be reported as a warning,
+//    after which the arrays are simply used unlocked. 'ulimit -l' shows the
+//    limit, and can raise it as far as the hard limit allows.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::fs;
+
+//  The memory that's been locked, as (address,bytes) ranges, and anything
+//  that's got in the way.
+
+pub struct MemoryLock {
+   ranges: Vec<(usize,usize)>,
+   problems: Vec<String>,
+}
+
+impl MemoryLock {
+   pub fn new () -> MemoryLock {
+      MemoryLock { ranges: Vec::new(), problems: Vec::new() }
+   }
+
+   //  Locks the bytes of memory starting at address into RAM. If a lock
+   //  fails, any already taken are released, so it's all or nothing, and
+   //  no more are tried, since the rest would only fail for the same reason.
+
+   pub fn lock (&mut self,address: *const u8,bytes: usize) {
+      if !self.problems.is_empty() || bytes == 0 { return; }
+      match mlock_range(address as usize,bytes) {
+         Ok(()) => self.ranges.push((address as usize,bytes)),
+         Err(problem) => {
+            self.problems.push(problem);
+            for (address,bytes) in self.ranges.drain(..) {
+               munlock_range(address,bytes);
+            }
+         }
+      }
+   }
+
+   //  What's got in the way of locking the memory, if anything. Each is a
+   //  complete sentence, to be reported as a warning.
+
+   pub fn problems (&self) -> &[String] {
+      &self.problems
+   }
+
+   //  The number of bytes locked.
+
+   pub fn locked_bytes (&self) -> usize {
+      self.ranges.iter().map(|range| range.1).sum()
+   }
+}
+
+impl Drop for MemoryLock {
+   fn drop (&mut self) {
+      for &(address,bytes) in &self.ranges {
+         munlock_range(address,bytes);
+      }
+   }
+}
+
+//  Returns the soft limit on locked memory, as /proc/self/limits gives it -
+//  eg '8388608 bytes' or 'unlimited' - or None if it can't be read.
+
+fn memlock_limit () -> Option<String> {
+   let limits = fs::read_to_string("/proc/self/limits").ok()?;
+   let name = "Max locked memory";
+   let line = limits.lines().find(|line| line.starts_with(name))?;
+   let mut words = line[name.len()..].split_whitespace();
+   let soft = words.next()?;
+   let _hard = words.next()?;
+   match words.next() {
+      Some(units) => Some(format!("{} {}",soft,units)),
+      None => Some(soft.to_string()),
+   }
+}
+
+//  The mlock() and munlock() calls themselves, which are the same on any
+//  Unix system.
+
+#[cfg(unix)]
+mod sys {
+   use std::os::raw::{c_int, c_void};
+   extern "C" {
+      pub fn mlock (address: *const c_void,length: usize) -> c_int;
+      pub fn munlock (address: *const c_void,length: usize) -> c_int;
+   }
+}
+
+#[cfg(unix)]
+fn mlock_range (address: usize,bytes: usize) -> Result<(),String> {
+
+   //  Safety: the range lies within an allocation that is still live, and
+   //  locking only changes whether its pages can be paged out, not their
+   //  contents or whether they can be accessed.
+
+   let status = unsafe { sys::mlock(address as *const _,bytes) };
+   if status == 0 { return Ok(()); }
+   let error = std::io::Error::last_os_error();
+   let limit = match memlock_limit() {
+      Some(limit) => format!(" - the limit on locked memory (ulimit -l) is \
+                                                               {}",limit),
+      None => String::new(),
+   };
+   Err(format!("The system refused to lock {} bytes of array into memory: \
+                                                  {}{}",bytes,error,limit))
+}
+
+#[cfg(unix)]
+fn munlock_range (address: usize,bytes: usize) {
+
+   //  Safety: as for mlock_range(). If the memory has been freed by now,
+   //  munlock() just fails, which does no harm.
+
+   unsafe { sys::munlock(address as *const _,bytes); }
+}
+
+#[cfg(not(unix))]
+fn mlock_range (_address: usize,_bytes: usize) -> Result<(),String> {
+   Err("Memory locking is only available on Unix systems".to_string())
+}
+
+#[cfg(not(unix))]
+fn munlock_range (_address: usize,_bytes: usize) {}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o mlock() rounds the range out to whole pages, so the pages at either
+     end of an array can be shared with something else, which gets locked
+     too. That does no harm, and munlock() rounds the same way.
+
+   o Locks don't nest - a page locked twice is unlocked by one munlock() -
+     so if the input and output arrays share a page, unlocking either one
+     unlocks it. That only happens at the end of the run, when all the
+     locks are released together, so it doesn't matter.
+
+   o The locks are released when the MemoryLock is dropped, which
+     run_config() arranges to be before the arrays are. A process's locks
+     all go when it exits in any case, even if it never gets that far.
+
+   o Rust's own allocator has no way to ask for locked memory, which is why
+     the locking is done afterwards, on the Vec's storage, just as the huge
+     page advice in crshuge.rs is given on it.
+
+*/
@@ -1,7 +1,16 @@
 use std::env;
 
+// This only uses part of what crsarray and crserror provide - the rest is
+// for crsbench - so the allows stop the dead code warnings for the rest.
+
+#[allow(dead_code)]
+mod crsarray;
+#[allow(dead_code)]
+mod crserror;
 mod crssub1d;
 
+use crsarray::Array2D;
+
 fn main() {
     let mut nrpt = 100;
     let mut rows = 5;
@@ -30,26 +39,22 @@ fn main() {
     assert_ne!(rows, 0, "rows were zero");
     assert_ne!(cols, 0, "cols were zero");
 
-    let mut in_array = vec![0.0f32; cols * rows];
-    let mut out_array = vec![0.0f32; cols * rows];
-    for iy in 0..rows {
-       for ix in 0..cols {
-          in_array[iy * cols + ix] = (cols - ix + rows - iy) as f32;
-       }
-    }
+    let in_array = Array2D::from_fn(cols,rows,
+                                 |ix,iy| (cols - ix + rows - iy) as f32);
+    let mut out_array = Array2D::new(cols,rows,0.0f32);
 
    println! ("Calling");
     for _irpt in 1..=nrpt {
-       crssub1d::csub1d (&mut in_array,cols,rows,&mut out_array);
+       crssub1d::csub1d (&in_array,&mut out_array);
     }
     println! ("Called");
 
     'check_loop :
     for iy in 0..rows {
        for ix in 0..cols {
-          if out_array[iy * cols + ix] != (in_array[iy * cols + ix] + (ix + iy) as f32) {
-             println! ("Error {} {} {} {}",ix,iy,out_array[iy * cols + ix],
-                                                             in_array[iy * cols + ix]);
+          if out_array[[ix,iy]] != (in_array[[ix,iy]] + (ix + iy) as f32) {
+             println! ("Error {} {} {} {}",ix,iy,out_array[[ix,iy]],
+                                                             in_array[[ix,iy]]);
              break 'check_loop;
           }
        }
@@ -0,0 +1,132 @@
+//
+//                         c r s o p c o u n t . r s
+//
+// Summary:
+//    An instrumented element type that counts the additions made with it.
+//
+// Introduction:
+//    Where hardware performance counters can't be used - which is often the
+//    case on shared or virtual machines - it's still useful to confirm that
+//    a kernel did all the work it was supposed to, and no more. The kernel
+//    here does one addition per element, so the number of additions should
+//    be exactly nx * ny. A kernel that skipped part of the array, or did some
+//    rows twice, could still produce the right values, but it would get this
+//    count wrong.
+//
+//    CountedF32 behaves just like an f32, except that every addition also
+//    increments a global counter. Running one of the generic kernels with
+//    CountedF32 elements, instead of f32, gives an instrumented version of
+//    exactly the same code. That version is only ever run once, outside the
+//    timed part of the test; the timed version uses plain f32 and has no
+//    counting code in it at all.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//    14th Oct 2026. CountedF32 can be multiplied, uncounted. agent.
+//    14th Oct 2026. CountedF32 can be subtracted and divided, uncounted.
+//                   agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::ops::{Add, Div, Mul, Sub};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::crsarray::Element;
+
+//  The count of additions made using CountedF32 values.
+
+static ADDITIONS: AtomicUsize = AtomicUsize::new(0);
+
+//  An f32 that counts its additions.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CountedF32(pub f32);
+
+impl Add for CountedF32 {
+   type Output = CountedF32;
+
+   fn add (self,other: CountedF32) -> CountedF32 {
+      ADDITIONS.fetch_add(1,Ordering::Relaxed);
+      CountedF32(self.0 + other.0)
+   }
+}
+
+//  The other operations are only there because Element needs them, and
+//  aren't counted.
+
+impl Sub for CountedF32 {
+   type Output = CountedF32;
+
+   fn sub (self,other: CountedF32) -> CountedF32 {
+      CountedF32(self.0 - other.0)
+   }
+}
+
+impl Mul for CountedF32 {
+   type Output = CountedF32;
+
+   fn mul (self,other: CountedF32) -> CountedF32 {
+      CountedF32(self.0 * other.0)
+   }
+}
+
+impl Div for CountedF32 {
+   type Output = CountedF32;
+
+   fn div (self,other: CountedF32) -> CountedF32 {
+      CountedF32(self.0 / other.0)
+   }
+}
+
+impl Element for CountedF32 {
+   fn from_index (index: usize) -> CountedF32 { CountedF32(index as f32) }
+}
+
+//  Resets the count of additions to zero.
+
+pub fn reset () {
+   ADDITIONS.store(0,Ordering::SeqCst);
+}
+
+//  Returns the number of additions made since the last reset().
+
+pub fn additions () -> usize {
+   ADDITIONS.load(Ordering::SeqCst)
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The counter is an atomic so that the parallel kernels can be counted
+     too. Relaxed ordering is enough for the increments, since nothing reads
+     the count until the threads have all been joined.
+
+   o Only additions are counted. Converting the index sum to an element, with
+     Element::from_index(), isn't counted - it isn't an operation on the
+     array data. Nor are subtractions, multiplications or divisions, which
+     only the fma4 kernel and the expr mode make - the fma4 kernel always
+     multiplying by one - so that every kernel should count nx * ny, the
+     expr mode with the standard expression included.
+
+*/
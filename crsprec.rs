@@ -0,0 +1,164 @@
+//
+//                           c r s p r e c . r s
+//
+// Summary:
+//    Extended precision reference values for the Rust 2D array access test.
+//
+// Introduction:
+//    The normal check on the results of csub() compares each output element
+//    with the input element plus the sum of its indices, calculated in single
+//    precision exactly as csub() itself does it. That shows that csub() did
+//    what it was supposed to, but says nothing about how accurate the result
+//    is. Once ix + iy gets beyond 2^24 it can no longer be held exactly in an
+//    f32, and neither can the sum, so the 'correct' answer itself has errors.
+//
+//    This module calculates the expected values in a higher precision, so the
+//    actual error in each output element can be reported. Rust doesn't have
+//    an equivalent of the 80-bit long double that C compilers provide on x86
+//    (and f128 is only available on nightly builds), so this uses a 'double
+//    double' - an unevaluated sum of two f64 values, hi + lo, with lo no more
+//    than half an ulp of hi. That gives a significand of about 106 bits, which
+//    is far more than needed to hold the exact sum of an f32 and an index sum
+//    of any size that will fit in memory. In practice the reference values
+//    calculated here are exact.
+//
+//    The aim is accurate error reporting, not speed. None of this is used in
+//    the timed part of the code.
+//
+// Building:
+//    This is only used if crsbench.rs is built with the 'extprec' feature:
+//
+//    rustc -O --cfg 'feature="extprec"' crsbench.rs
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::crsarray::Array2D;
+
+//  ----------------------------------------------------------------------------
+//
+//                          D o u b l e   D o u b l e
+
+//  A double double value, hi + lo.
+
+#[derive(Clone, Copy, Debug)]
+pub struct DoubleDouble {
+   hi: f64,
+   lo: f64,
+}
+
+impl DoubleDouble {
+
+   pub fn from_f32 (value: f32) -> DoubleDouble {
+      DoubleDouble { hi: value as f64, lo: 0.0 }
+   }
+
+   //  Converts an index value exactly. An f64 only holds integers exactly up
+   //  to 2^53, so anything beyond that is split into the nearest f64 and the
+   //  (small) remainder.
+
+   pub fn from_usize (value: usize) -> DoubleDouble {
+      let hi = value as f64;
+      let lo = (value as i128 - hi as i128) as f64;
+      DoubleDouble { hi, lo }
+   }
+
+   //  Adds two double doubles, using Knuth's two-sum to get the exact sum of
+   //  the two hi parts.
+
+   pub fn add (self,other: DoubleDouble) -> DoubleDouble {
+      let sum = self.hi + other.hi;
+      let bv = sum - self.hi;
+      let error = (self.hi - (sum - bv)) + (other.hi - bv);
+      let lo = error + self.lo + other.lo;
+      let hi = sum + lo;
+      DoubleDouble { hi, lo: lo - (hi - sum) }
+   }
+
+   //  The absolute difference between this value and an f32, as an f64.
+
+   pub fn abs_diff (self,value: f32) -> f64 {
+      ((value as f64 - self.hi) - self.lo).abs()
+   }
+
+   pub fn to_f64 (self) -> f64 {
+      self.hi + self.lo
+   }
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                          E r r o r   R e p o r t
+
+//  Summarises the errors in an output array compared with the extended
+//  precision reference values.
+
+pub struct ErrorReport {
+   pub max_abs_error: f64,
+   pub max_rel_error: f64,
+   pub worst_ix: usize,
+   pub worst_iy: usize,
+   pub inexact: usize,
+}
+
+//  Compares each element of the output array with the exact value of the
+//  input element plus the sum of its indices.
+
+pub fn error_report (in_array: &Array2D<f32>,out_array: &Array2D<f32>)
+                                                              -> ErrorReport {
+   let mut report = ErrorReport { max_abs_error: 0.0, max_rel_error: 0.0,
+                                  worst_ix: 0, worst_iy: 0, inexact: 0 };
+   for iy in 0..in_array.ny() {
+      for ix in 0..in_array.nx() {
+         let expected = DoubleDouble::from_f32(in_array[[ix,iy]])
+                                          .add(DoubleDouble::from_usize(ix + iy));
+         let error = expected.abs_diff(out_array[[ix,iy]]);
+         if error > 0.0 {
+            report.inexact += 1;
+            let magnitude = expected.to_f64().abs();
+            if magnitude > 0.0 && error / magnitude > report.max_rel_error {
+               report.max_rel_error = error / magnitude;
+            }
+         }
+         if error > report.max_abs_error {
+            report.max_abs_error = error;
+            report.worst_ix = ix;
+            report.worst_iy = iy;
+         }
+      }
+   }
+   report
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The double double arithmetic here is only the addition needed for this
+     test. It is the standard algorithm (see, for example, Shewchuk's paper
+     on adaptive precision arithmetic), and relies on the compiler not being
+     allowed to reassociate floating point operations, which Rust doesn't.
+
+*/
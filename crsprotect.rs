@@ -0,0 +1,259 @@
+//
+//                         c r s p r o t e c t . r s
+//
+// Summary:
+//    Write protection of the input array for the Rust 2D array access
+//    benchmark harness.
+//
+// Introduction:
+//    Every kernel reads the input array and writes the output array, and
+//    none of them should ever write the input. For the safe kernels the
+//    compiler sees to that, since they only get a shared reference to it,
+//    but the unsafe ones work through raw pointers, and a mistake in the
+//    arithmetic could write anywhere. The results checks would usually
+//    notice, but not necessarily - a stray write to an element that has
+//    already been read leaves the output right. Asking the system to make
+//    the input's pages read-only while the kernel runs turns any such write
+//    into an immediate segmentation fault, at the instruction that made it,
+//    which is about as strong a check as there could be (--protect-input).
+//
+//    ReadOnly does that with mprotect(). protect() is given the storage of
+//    the input array once it has been filled, and makes the whole pages that
+//    lie within it read-only, and release() - or dropping the ReadOnly -
+//    makes them writable again. mprotect() works on whole pages, so the
+//    input is allocated page aligned and padded to a whole number of pages
+//    (see Array2D::try_new_aligned() in crsarray.rs), with page_size()
+//    giving the alignment. That way none of its pages is shared with
+//    anything else, and all of the array is protected, however small it
+//    is. protected_bytes() says how much was.
+//
+//    Anything that stops the input being protected - a system other than
+//    Linux, storage that doesn't hold a whole page, mprotect() failing -
+//    is recorded as a problem, to be reported as a warning, and the input
+//    is then used unprotected.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//    14th Oct 2026. page_size() is public, for allocating the input. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//  The page size if the system doesn't say.
+
+const DEFAULT_PAGE_SIZE: usize = 4096;
+
+//  The memory that's been made read-only, as a (start,end) address range,
+//  and anything that's got in the way.
+
+pub struct ReadOnly {
+   range: Option<(usize,usize)>,
+   problems: Vec<String>,
+}
+
+impl ReadOnly {
+   pub fn new () -> ReadOnly {
+      ReadOnly { range: None, problems: Vec::new() }
+   }
+
+   //  Makes as much of the bytes of memory starting at address as are in
+   //  whole pages read-only. Only one range can be protected at a time.
+
+   pub fn protect (&mut self,address: *const u8,bytes: usize) {
+      if self.range.is_some() || !self.problems.is_empty() { return; }
+      let page_size = page_size();
+      let start = (address as usize).saturating_add(page_size - 1)
+                                                        & !(page_size - 1);
+      let end = (address as usize).saturating_add(bytes) & !(page_size - 1);
+      if end <= start {
+         self.problems.push(format!("Input storage of {} bytes doesn't \
+                            hold a whole page of {} bytes",bytes,page_size));
+         return;
+      }
+      match mprotect_range(start,end - start,false) {
+         Ok(()) => self.range = Some((start,end)),
+         Err(problem) => self.problems.push(problem),
+      }
+   }
+
+   //  Makes the protected memory writable again.
+
+   pub fn release (&mut self) {
+      if let Some((start,end)) = self.range.take() {
+         if let Err(problem) = mprotect_range(start,end - start,true) {
+            eprintln!("Warning: {}",problem);
+         }
+      }
+   }
+
+   //  What's got in the way of protecting the memory, if anything. Each is
+   //  a complete sentence, to be reported as a warning.
+
+   pub fn problems (&self) -> &[String] {
+      &self.problems
+   }
+
+   //  The number of bytes protected.
+
+   pub fn protected_bytes (&self) -> usize {
+      self.range.map_or(0,|(start,end)| end - start)
+   }
+}
+
+impl Drop for ReadOnly {
+   fn drop (&mut self) {
+      self.release();
+   }
+}
+
+//  The mprotect() and sysconf() calls themselves. mprotect() exists on any
+//  Unix system, but the constants used here are those for Linux.
+
+#[cfg(target_os = "linux")]
+mod sys {
+   use std::os::raw::{c_int, c_long, c_void};
+
+   pub const PROT_READ: c_int = 1;
+   pub const PROT_WRITE: c_int = 2;
+   pub const SC_PAGESIZE: c_int = 30;
+
+   extern "C" {
+      pub fn mprotect (address: *mut c_void,length: usize,prot: c_int)
+                                                                    -> c_int;
+      pub fn sysconf (name: c_int) -> c_long;
+   }
+}
+
+//  The size of a page, which the storage of an input to be protected
+//  should be aligned to and padded to a multiple of.
+
+#[cfg(target_os = "linux")]
+pub fn page_size () -> usize {
+
+   //  Safety: sysconf() only returns a value.
+
+   let size = unsafe { sys::sysconf(sys::SC_PAGESIZE) };
+   if size > 0 && (size as usize).is_power_of_two() {
+      size as usize
+   } else {
+      DEFAULT_PAGE_SIZE
+   }
+}
+
+#[cfg(target_os = "linux")]
+fn mprotect_range (start: usize,bytes: usize,writable: bool)
+                                                        -> Result<(),String> {
+   let prot = if writable { sys::PROT_READ | sys::PROT_WRITE }
+              else { sys::PROT_READ };
+
+   //  Safety: the range is page aligned, and lies within an allocation
+   //  that is still live, and only holds the array, which nothing writes
+   //  while it's protected - unless something is wrong, which is the
+   //  point. Making it readable and writable again restores the access it
+   //  had before.
+
+   let status = unsafe { sys::mprotect(start as *mut _,bytes,prot) };
+   if status == 0 { return Ok(()); }
+   Err(format!("The system refused to make {} bytes of the input {}: {}",
+         bytes,if writable { "writable again" } else { "read-only" },
+                                          std::io::Error::last_os_error()))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn page_size () -> usize {
+   DEFAULT_PAGE_SIZE
+}
+
+#[cfg(not(target_os = "linux"))]
+fn mprotect_range (_start: usize,_bytes: usize,_writable: bool)
+                                                        -> Result<(),String> {
+   Err("Protecting the input is only available on Linux".to_string())
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                                 T e s t s
+
+#[cfg(test)]
+mod tests {
+   use super::{page_size, ReadOnly};
+   use crate::crsarray::Array2D;
+
+   //  An input far smaller than a page, allocated as run_config() does,
+   //  has the whole of its storage protected, and can still be read.
+
+   #[cfg(target_os = "linux")]
+   #[test]
+   fn small_input () {
+      let array = Array2D::try_new_aligned(3,2,2.0f32,page_size(),|_,_| {})
+                                                                  .unwrap();
+      let mut read_only = ReadOnly::new();
+      read_only.protect(array.as_slice().as_ptr() as *const u8,
+                                                      array.storage_bytes());
+      assert!(read_only.problems().is_empty(),"{:?}",read_only.problems());
+      assert_eq!(read_only.protected_bytes(),page_size());
+      assert_eq!(array.as_slice().iter().sum::<f32>(),12.0);
+      read_only.release();
+      assert_eq!(read_only.protected_bytes(),0);
+   }
+
+   //  Storage that doesn't hold a whole page is a problem, not a panic.
+
+   #[test]
+   fn partial_page () {
+      let array = Array2D::new(3,2,0.0f32);
+      let mut read_only = ReadOnly::new();
+      read_only.protect(array.as_slice().as_ptr() as *const u8,
+                                                      array.storage_bytes());
+      assert_eq!(read_only.problems().len(),1);
+      assert_eq!(read_only.protected_bytes(),0);
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The input stays protected from before the warmup to the end of the
+     timed trials, rather than being protected and unprotected around each
+     call. mprotect() is a system call that changes the page tables, and
+     doing that for each call would add its cost - and the TLB flushes it
+     causes - to the times.
+
+   o A stray write to a protected page raises SIGSEGV, and the harness
+     doesn't catch it, so the program stops with the usual 'Segmentation
+     fault' and no results. A debugger run with the same arguments stops at
+     the write itself.
+
+   o An ordinary Vec would only be aligned to the size of an element. For
+     a large array that would still leave all but the part of a page at
+     each end protectable, but a small one in the middle of the heap might
+     not hold a whole page at all, and the partial pages are shared with
+     whatever else is there. Padding the input to whole pages costs at
+     most a page of memory.
+
+   o The protection must be released before the array is freed, since the
+     allocator may write to the memory when it's given back. run_config()
+     releases it as soon as the trials are done, and the Drop
+     implementation is there in case anything returns early.
+
+*/
@@ -0,0 +1,1033 @@
+//
+//                           c r s r e p o r t . r s
+//
+// Summary:
+//    Result reporting for the Rust 2D array access benchmark harness.
+//
+// Introduction:
+//    The original test programs in this study just did the work and left it
+//    to the caller - usually Run.py - to time them. The benchmark harness,
+//    crsbench.rs, does its own timing, and this module holds its idea of what
+//    a result is, and the code that writes results out and reads them back.
+//
+// Results:
+//    Each result is made up of two sets of fields. The configuration fields
+//    describe what was run - the mode, the array dimensions, the number of
+//    repeats and so on - and the result fields describe what happened. When
+//    results from different runs are compared, it is the full set of
+//    configuration fields that is used to decide if two results describe the
+//    same test. Any new field that changes what is measured should be added
+//    to config_fields(), and any new measurement to result_fields() - and
+//    both need a column in the database schema in crsdb.rs.
+//
+//    Ahead of both comes the label, free-form text given with --label to
+//    say where a result came from - a machine name, a compiler version,
+//    some notes. It's written with every result, but it isn't part of the
+//    configuration, so results with different labels can still be compared.
+//    After it come the build fields, opt_level and target_cpu, which say
+//    how the program that produced the result was built (see crsbuild.rs).
+//    They aren't part of the configuration either, so that the results of
+//    different builds for the same configuration can be compared - which
+//    is what read_builds(), below, is for.
+//
+// CSV format:
+//    When written as CSV, the first line is a header giving the field names,
+//    configuration fields first, and each following line is one result. Code
+//    reading the CSV back finds the fields it wants using the header, so it
+//    doesn't depend on the order of the columns. The separator is always a
+//    comma. The label and the build fields are the only ones that can
+//    contain a comma or a quote, and a value that does is quoted, with any
+//    quotes inside it doubled, as RFC 4180 has it. read_csv() reads that
+//    back.
+//
+//    A configuration in a sweep that couldn't be run - because its arrays
+//    couldn't be allocated, say - still gets a line, so the gap in the
+//    results is visible. Its status field is 'failed' rather than 'ok',
+//    and its other result fields are 'none'.
+//
+// JSON Lines format:
+//    Results can also be written as JSON Lines, one complete JSON object
+//    per result on a line of its own, with no enclosing array, so each line
+//    can be parsed as soon as it arrives. Each object has every field the
+//    CSV has, with the same names and in the same order. Counts, times and
+//    rates are JSON numbers, the flags are true or false, the names and the
+//    checksum are strings, and anything that is 'none' in the CSV - or
+//    isn't a finite number - is null. The checksum is always a string, even
+//    when it's a sum, since a hex hash can look like a number.
+//
+// Streaming:
+//    Results can also be sent, as they are produced, to a collector
+//    listening on a TCP port - see ResultStream, below. What's sent is
+//    exactly the CSV described above, the header line and then one line
+//    per result, each line ending with a newline, so a collector can be as
+//    simple as 'nc -lk 9000 >> results.csv'. Nothing is ever read back.
+//    Each connection gets its own header, so a collector taking results
+//    from many machines can tell the streams apart by the connection they
+//    arrive on.
+//
+// Prometheus format:
+//    Results can also be written in the Prometheus text exposition format,
+//    for a node_exporter textfile collector to pick up - see
+//    prometheus_text(), below. Each measurement is a gauge, named csub_
+//    and then what it is, in the base units Prometheus prefers - seconds
+//    rather than nanoseconds, bytes and operations per second rather than
+//    gigabytes and gigaflops. Each sample has the label, the build fields
+//    and the configuration fields as its labels, leaving out any that are
+//    'none', so the samples for different configurations can be told
+//    apart. Prometheus insists that all the samples of a metric come
+//    together, after a single HELP and TYPE line, so nothing is written
+//    until every configuration has been run. A configuration that couldn't
+//    be run has a csub_ok sample of 0, and no others.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//    14th Oct 2026. Added the input field. agent.
+//    14th Oct 2026. Added the loop_order field. agent.
+//    14th Oct 2026. Added the budget_ms and calls fields. agent.
+//    14th Oct 2026. Added the out_stride field. agent.
+//    14th Oct 2026. Added the flops and bytes fields, and the intensity and
+//                   throughput results. agent.
+//    14th Oct 2026. Errors are now returned as a BenchError. agent.
+//    14th Oct 2026. Added append_csv(). agent.
+//    14th Oct 2026. Added the peak_bandwidth field, and the peak_fraction
+//                   result. agent.
+//    14th Oct 2026. Added the nz field, for 3D arrays. agent.
+//    14th Oct 2026. Added the checksum_algo and checksum results. agent.
+//    14th Oct 2026. Added the fresh_alloc field. agent.
+//    14th Oct 2026. Added the perm_seed field. agent.
+//    14th Oct 2026. Added ResultStream. agent.
+//    14th Oct 2026. Added the hugepages field. agent.
+//    14th Oct 2026. Added precision, for the number of decimal places the
+//                   result fields and the baseline report use. agent.
+//    14th Oct 2026. Added failed, and the status result. agent.
+//    14th Oct 2026. Added the window and window_stride fields. agent.
+//    14th Oct 2026. Added the ns_per_element result. agent.
+//    14th Oct 2026. Added json_line(). agent.
+//    14th Oct 2026. Added the unroll field. agent.
+//    14th Oct 2026. Added the sem_ns_per_call and ci95_ns_per_call
+//                   results. agent.
+//    14th Oct 2026. Added the mlock field. agent.
+//    14th Oct 2026. Added the expr field. agent.
+//    14th Oct 2026. Added the label, and quoting of CSV values that need
+//                   it. agent.
+//    14th Oct 2026. Added the time_source field. agent.
+//    14th Oct 2026. Added prometheus_text(). agent.
+//    14th Oct 2026. Added the opt_level and target_cpu build fields, and
+//                   read_builds(). agent.
+//    14th Oct 2026. check_baseline() can show its times scaled. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use crate::crsbuild;
+use crate::crserror::BenchError;
+use crate::crsunits;
+
+//  ----------------------------------------------------------------------------
+//
+//                          B e n c h   R e s u l t
+
+//  The result of timing one configuration.
+
+#[derive(Clone, Debug)]
+pub struct BenchResult {
+   pub label: Option<String>,
+   pub mode: String,
+   pub ny: usize,
+   pub nx: usize,
+   pub nz: Option<usize>,
+   pub repeats: usize,
+   pub trials: usize,
+   pub inner_repeat: Option<usize>,
+   pub threads: usize,
+   pub input: String,
+   pub seed: u64,
+   pub loop_order: String,
+   pub budget_ms: Option<u64>,
+   pub out_stride: Option<usize>,
+   pub perm_seed: Option<u64>,
+   pub window: Option<usize>,
+   pub window_stride: Option<usize>,
+   pub unroll: Option<usize>,
+   pub expr: Option<String>,
+   pub fresh_alloc: bool,
+   pub hugepages: bool,
+   pub mlock: bool,
+   pub time_source: String,
+   pub flops: usize,
+   pub bytes: usize,
+   pub peak_bandwidth: Option<f64>,
+   pub calls: usize,
+   pub min_ns_per_call: f64,
+   pub mean_ns_per_call: f64,
+
+   //  The standard error of the mean time per call, and the half width of
+   //  the 95% confidence interval for it, from Student's t. None if there
+   //  was only one trial.
+
+   pub sem_ns_per_call: Option<f64>,
+   pub ci95_ns_per_call: Option<f64>,
+   pub verified: bool,
+   pub checksum_algo: Option<String>,
+   pub checksum: Option<String>,
+
+   //  Set if the configuration couldn't be run at all, in which case none
+   //  of the other result fields mean anything.
+
+   pub failed: bool,
+
+   //  The number of decimal places the results are written with, if not
+   //  the usual number for each field. This isn't part of the result, and
+   //  isn't written out.
+
+   pub precision: Option<usize>,
+}
+
+impl BenchResult {
+
+   //  The fields that describe what was run, as (name,value) pairs. Taken
+   //  together these are the key used to match results from different runs.
+
+   pub fn config_fields (&self) -> Vec<(&'static str,String)> {
+      vec![
+         ("mode",self.mode.clone()),
+         ("ny",self.ny.to_string()),
+         ("nx",self.nx.to_string()),
+         ("nz",match self.nz {
+            Some(nz) => nz.to_string(),
+            None => "none".to_string(),
+         }),
+         ("repeats",self.repeats.to_string()),
+         ("trials",self.trials.to_string()),
+         ("inner_repeat",match self.inner_repeat {
+            Some(inner_repeat) => inner_repeat.to_string(),
+            None => "none".to_string(),
+         }),
+         ("threads",self.threads.to_string()),
+         ("input",self.input.clone()),
+         ("seed",self.seed.to_string()),
+         ("loop_order",self.loop_order.clone()),
+         ("budget_ms",match self.budget_ms {
+            Some(budget_ms) => budget_ms.to_string(),
+            None => "none".to_string(),
+         }),
+         ("out_stride",match self.out_stride {
+            Some(out_stride) => out_stride.to_string(),
+            None => "none".to_string(),
+         }),
+         ("perm_seed",match self.perm_seed {
+            Some(perm_seed) => perm_seed.to_string(),
+            None => "none".to_string(),
+         }),
+         ("window",match self.window {
+            Some(window) => window.to_string(),
+            None => "none".to_string(),
+         }),
+         ("window_stride",match self.window_stride {
+            Some(window_stride) => window_stride.to_string(),
+            None => "none".to_string(),
+         }),
+         ("unroll",match self.unroll {
+            Some(unroll) => unroll.to_string(),
+            None => "none".to_string(),
+         }),
+         ("expr",match self.expr {
+            Some(ref expr) => expr.clone(),
+            None => "none".to_string(),
+         }),
+         ("fresh_alloc",self.fresh_alloc.to_string()),
+         ("hugepages",self.hugepages.to_string()),
+         ("mlock",self.mlock.to_string()),
+         ("time_source",self.time_source.clone()),
+      ]
+   }
+
+   //  The fields that describe what happened, as (name,value) pairs. Times
+   //  are per call, which means per pass over the array - with an inner
+   //  repeat count of K, each actual call makes K passes. Times have three
+   //  decimal places, and everything else four, unless a precision says
+   //  otherwise - except the time per element, which is usually below a
+   //  nanosecond, and gets four like everything else. For a configuration
+   //  that failed, only the status says anything.
+
+   pub fn result_fields (&self) -> Vec<(&'static str,String)> {
+      let time = self.precision.unwrap_or(3);
+      let other = self.precision.unwrap_or(4);
+      let mut fields = vec![
+         ("aspect",format!("{:.*}",other,self.nx as f64 / self.ny as f64)),
+         ("calls",self.calls.to_string()),
+         ("min_ns_per_call",format!("{:.*}",time,self.min_ns_per_call)),
+         ("mean_ns_per_call",format!("{:.*}",time,self.mean_ns_per_call)),
+         ("sem_ns_per_call",match self.sem_ns_per_call {
+            Some(sem) => format!("{:.*}",time,sem),
+            None => "none".to_string(),
+         }),
+         ("ci95_ns_per_call",match self.ci95_ns_per_call {
+            Some(ci95) => format!("{:.*}",time,ci95),
+            None => "none".to_string(),
+         }),
+         ("ns_per_element",format!("{:.*}",other,self.ns_per_element())),
+         ("intensity",format!("{:.*}",other,self.intensity())),
+         ("gflop_per_s",format!("{:.*}",other,self.gflop_per_s())),
+         ("gbyte_per_s",format!("{:.*}",other,self.gbyte_per_s())),
+         ("peak_fraction",match self.peak_fraction() {
+            Some(fraction) => format!("{:.*}",other,fraction),
+            None => "none".to_string(),
+         }),
+         ("verified",self.verified.to_string()),
+         ("checksum_algo",match self.checksum_algo {
+            Some(ref algo) => algo.clone(),
+            None => "none".to_string(),
+         }),
+         ("checksum",match self.checksum {
+            Some(ref checksum) => checksum.clone(),
+            None => "none".to_string(),
+         }),
+      ];
+      if self.failed {
+         for field in fields.iter_mut() {
+            field.1 = "none".to_string();
+         }
+      }
+      fields.push(("status",
+                       if self.failed { "failed" } else { "ok" }.to_string()));
+      fields
+   }
+
+   //  The arithmetic intensity, in floating point operations per byte, and
+   //  the achieved throughputs, based on the minimum time per call. The
+   //  flops and bytes are per element, as declared in the MODES table.
+
+   pub fn intensity (&self) -> f64 {
+      self.flops as f64 / self.bytes as f64
+   }
+
+   pub fn gflop_per_s (&self) -> f64 {
+      (self.elements() * self.flops) as f64 / self.min_ns_per_call
+   }
+
+   pub fn gbyte_per_s (&self) -> f64 {
+      (self.elements() * self.bytes) as f64 / self.min_ns_per_call
+   }
+
+   //  The minimum time per call divided by the number of elements, which
+   //  unlike the time per call can be compared between array sizes.
+
+   pub fn ns_per_element (&self) -> f64 {
+      self.min_ns_per_call / self.elements() as f64
+   }
+
+   //  The number of elements in the array - for a 3D array, nz is the
+   //  number of planes.
+
+   pub fn elements (&self) -> usize {
+      self.nx * self.ny * self.nz.unwrap_or(1)
+   }
+
+   //  The achieved bandwidth as a fraction of the peak bandwidth, if one
+   //  was given.
+
+   pub fn peak_fraction (&self) -> Option<f64> {
+      self.peak_bandwidth.map(|peak| self.gbyte_per_s() / peak)
+   }
+
+   //  Every field that's written out: the label, then the build fields,
+   //  then the configuration fields, then the result fields.
+
+   pub fn fields (&self) -> Vec<(&'static str,String)> {
+      let mut fields = vec![("label",match self.label {
+         Some(ref label) => label.clone(),
+         None => "none".to_string(),
+      })];
+      fields.extend(build_fields());
+      fields.extend(self.config_fields());
+      fields.extend(self.result_fields());
+      fields
+   }
+
+   //  The CSV header line, listing the names of all the fields.
+
+   pub fn csv_header (&self) -> String {
+      let names: Vec<&str> =
+                        self.fields().iter().map(|field| field.0).collect();
+      names.join(",")
+   }
+
+   //  The result as a single line of CSV, matching csv_header().
+
+   pub fn csv_row (&self) -> String {
+      let values: Vec<String> = self.fields().iter()
+                                   .map(|field| csv_value(&field.1)).collect();
+      values.join(",")
+   }
+
+   //  The result as a single line of JSON, an object with the same fields
+   //  as csv_row(), typed as described under JSON Lines format, above.
+
+   pub fn json_line (&self) -> String {
+      let members: Vec<String> = self.fields().into_iter()
+         .map(|(name,value)| format!("\"{}\":{}",name,json_value(name,&value)))
+         .collect();
+      format!("{{{}}}",members.join(","))
+   }
+
+   //  The value of one of the PROMETHEUS_METRICS for the result, or None
+   //  if it doesn't have one - which for a failed configuration is all of
+   //  them but csub_ok.
+
+   fn prometheus_value (&self,name: &str) -> Option<f64> {
+      if name == "csub_ok" {
+         return Some(if self.failed { 0.0 } else { 1.0 });
+      }
+      if self.failed { return None; }
+      match name {
+         "csub_verified" => Some(if self.verified { 1.0 } else { 0.0 }),
+         "csub_calls" => Some(self.calls as f64),
+         "csub_min_seconds_per_call" => Some(self.min_ns_per_call * 1.0e-9),
+         "csub_mean_seconds_per_call" => Some(self.mean_ns_per_call * 1.0e-9),
+         "csub_seconds_per_element" => Some(self.ns_per_element() * 1.0e-9),
+         "csub_flops_per_second" => Some(self.gflop_per_s() * 1.0e9),
+         "csub_bytes_per_second" => Some(self.gbyte_per_s() * 1.0e9),
+         _ => None,
+      }
+   }
+
+   //  The labels for the result's Prometheus samples, as they go between
+   //  the braces: the label, the build fields and the configuration fields
+   //  that aren't 'none', each value quoted and escaped.
+
+   fn prometheus_labels (&self) -> String {
+      let mut fields = Vec::new();
+      if let Some(ref label) = self.label {
+         fields.push(("label",label.clone()));
+      }
+      fields.extend(build_fields());
+      fields.extend(self.config_fields());
+      let labels: Vec<String> = fields.iter()
+            .filter(|field| field.1 != "none")
+            .map(|field| format!("{}=\"{}\"",field.0,
+                                                 prometheus_escape(&field.1)))
+            .collect();
+      labels.join(",")
+   }
+
+   //  The configuration key for the result, as a single printable string.
+
+   pub fn config_key (&self) -> String {
+      let pairs: Vec<String> = self.config_fields().iter()
+             .map(|field| format!("{}={}",field.0,field.1)).collect();
+      pairs.join(" ")
+   }
+}
+
+//  The fields whose values are always written to JSON as strings. Every
+//  other field is a number or a flag.
+
+const JSON_STRINGS: [&str; 11] = ["label","opt_level","target_cpu","mode",
+                     "input","loop_order","expr","time_source","checksum_algo",
+                     "checksum","status"];
+
+//  The build fields, which are the same for every result of a run.
+
+fn build_fields () -> Vec<(&'static str,String)> {
+   vec![("opt_level",crsbuild::opt_level().to_string()),
+        ("target_cpu",crsbuild::target_cpu().to_string())]
+}
+
+//  The metrics written in the Prometheus format, with their help text. All
+//  of them are gauges.
+
+const PROMETHEUS_METRICS: [(&str,&str); 8] = [
+   ("csub_ok","1 if the configuration could be run, 0 if it couldn't"),
+   ("csub_verified","1 if the results were correct, 0 if they weren't"),
+   ("csub_calls","Calls to the kernel in each timed trial"),
+   ("csub_min_seconds_per_call","Minimum time per call over the trials"),
+   ("csub_mean_seconds_per_call","Mean time per call over the trials"),
+   ("csub_seconds_per_element","Minimum time per call per array element"),
+   ("csub_flops_per_second",
+                  "Floating point operations per second, at the minimum time"),
+   ("csub_bytes_per_second",
+                      "Bytes read and written per second, at the minimum time"),
+];
+
+//  Returns the results as a complete Prometheus text exposition, each
+//  metric with its help and type, then a sample for each result.
+
+pub fn prometheus_text (results: &[BenchResult]) -> String {
+   let mut text = String::new();
+   for &(name,help) in &PROMETHEUS_METRICS {
+      text.push_str(&format!("# HELP {} {}\n# TYPE {} gauge\n",name,help,name));
+      for result in results {
+         if let Some(value) = result.prometheus_value(name) {
+            text.push_str(&format!("{}{{{}}} {}\n",name,
+                          result.prometheus_labels(),prometheus_number(value)));
+         }
+      }
+   }
+   text
+}
+
+//  Returns a label value escaped as Prometheus needs it, with a backslash
+//  before any backslash or double quote, and any line break as \n.
+
+fn prometheus_escape (value: &str) -> String {
+   let mut text = String::new();
+   for c in value.chars() {
+      match c {
+         '\\' => text.push_str("\\\\"),
+         '"' => text.push_str("\\\""),
+         '\n' => text.push_str("\\n"),
+         c => text.push(c),
+      }
+   }
+   text
+}
+
+//  Returns a sample value as Prometheus writes it, which is as Rust does,
+//  except for infinities and NaN.
+
+fn prometheus_number (value: f64) -> String {
+   if value.is_nan() {
+      "NaN".to_string()
+   } else if value.is_infinite() {
+      if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+   } else {
+      value.to_string()
+   }
+}
+
+//  Returns a field value as it's written to CSV - quoted, with any quotes
+//  in it doubled, if it contains a comma, a quote or a line break, and
+//  otherwise as it is.
+
+fn csv_value (value: &str) -> String {
+   if value.contains([',', '"', '\n', '\r']) {
+      format!("\"{}\"",value.replace('"',"\"\""))
+   } else {
+      value.to_string()
+   }
+}
+
+//  Splits a line of CSV into its values, undoing any quoting csv_value()
+//  did. A quote only starts a quoted value at the start of a field. Returns
+//  None if a quoted value isn't closed.
+
+fn split_csv (line: &str) -> Option<Vec<String>> {
+   let mut values = Vec::new();
+   let mut value = String::new();
+   let mut chars = line.chars().peekable();
+   let mut quoted = false;
+   while let Some(c) = chars.next() {
+      if quoted {
+         if c != '"' {
+            value.push(c);
+         } else if chars.peek() == Some(&'"') {
+            value.push(chars.next().unwrap_or('"'));
+         } else {
+            quoted = false;
+         }
+      } else if c == '"' && value.is_empty() {
+         quoted = true;
+      } else if c == ',' {
+         values.push(std::mem::take(&mut value));
+      } else {
+         value.push(c);
+      }
+   }
+   if quoted { return None; }
+   values.push(value);
+   Some(values)
+}
+
+//  Returns a field value, as held for the CSV, as a JSON value.
+
+fn json_value (name: &str,value: &str) -> String {
+   if value == "none" {
+      "null".to_string()
+   } else if JSON_STRINGS.contains(&name) {
+      let mut text = String::from("\"");
+      for c in value.chars() {
+         match c {
+            '"' => text.push_str("\\\""),
+            '\\' => text.push_str("\\\\"),
+            c if (c as u32) < 0x20 => text.push_str(&format!("\\u{:04x}",
+                                                                  c as u32)),
+            c => text.push(c),
+         }
+      }
+      text.push('"');
+      text
+   } else if value == "true" || value == "false" {
+      value.to_string()
+   } else {
+      match value.parse::<f64>() {
+         Ok(number) if number.is_finite() => value.to_string(),
+         _ => "null".to_string(),
+      }
+   }
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                            R e a d i n g   C S V
+
+//  Reads a CSV result file, as written using csv_header() and csv_row(), and
+//  returns each result line as a map from field name to value, with any
+//  quoting undone. The header is split just as the lines are. Blank lines
+//  are ignored, but a line that doesn't have the same number of fields as
+//  the header, or has a quoted value that isn't closed, is an error, giving
+//  its line number, rather than being left out.
+
+pub fn read_csv (path: &str)
+                         -> Result<Vec<HashMap<String,String>>,BenchError> {
+   let text = match fs::read_to_string(path) {
+      Ok(text) => text,
+      Err(error) => return Err(BenchError::Io { path: path.to_string(),
+                                 action: "read", error: error.to_string() }),
+   };
+   let format_error = |message: String| BenchError::Format {
+                                          path: path.to_string(), message };
+   let mut lines = text.lines().enumerate()
+                                  .filter(|line| !line.1.trim().is_empty());
+   let header: Vec<String> = match lines.next() {
+      Some((_,line)) => match split_csv(line) {
+         Some(names) => names.iter().map(|name| name.trim().to_string())
+                                                                  .collect(),
+         None => return Err(format_error(
+                         "has a quoted name in its header that isn't closed"
+                                                             .to_string())),
+      },
+      None => return Err(format_error("is empty".to_string())),
+   };
+   let mut rows = Vec::new();
+   for (index,line) in lines {
+      let values = match split_csv(line) {
+         Some(values) => values,
+         None => return Err(format_error(format!("has a quoted value that \
+                                isn't closed on line {}",index + 1))),
+      };
+      if values.len() != header.len() {
+         return Err(format_error(format!("has {} fields on line {}, where \
+             the header has {}",values.len(),index + 1,header.len())));
+      }
+      let mut row = HashMap::new();
+      for (name,value) in header.iter().zip(values.iter()) {
+         row.insert(name.clone(),value.trim().to_string());
+      }
+      rows.push(row);
+   }
+   Ok(rows)
+}
+
+//  Appends a result to a CSV file, as a line written by csv_row(). If the
+//  file doesn't exist or is empty, the header is written first, so a file
+//  built up over many runs has the header just once. If the file already
+//  has a different header - written by a version of the program with other
+//  fields - nothing is appended and an error is returned, rather than
+//  leaving a file whose lines don't match its header.
+
+pub fn append_csv (path: &str,result: &BenchResult) -> Result<(),BenchError> {
+   let io_error = |action: &'static str,error: std::io::Error| BenchError::Io {
+         path: path.to_string(), action, error: error.to_string() };
+   let mut file = match OpenOptions::new().read(true).append(true)
+                                                      .create(true).open(path) {
+      Ok(file) => file,
+      Err(error) => return Err(io_error("open",error)),
+   };
+   let header = result.csv_header();
+   let mut first_line = String::new();
+   if let Err(error) = BufReader::new(&file).read_line(&mut first_line) {
+      return Err(io_error("read",error));
+   }
+   let mut text = String::new();
+   if first_line.trim().is_empty() {
+      text.push_str(&header);
+      text.push('\n');
+   } else if first_line.trim() != header {
+      return Err(BenchError::Format { path: path.to_string(),
+         message: "has a different header, so the result wasn't appended"
+                                                              .to_string() });
+   }
+   text.push_str(&result.csv_row());
+   text.push('\n');
+   file.write_all(text.as_bytes()).map_err(|error| io_error("write",error))
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                     S t r e a m i n g   R e s u l t s
+
+//  How long to wait for a connection to the collector, or for it to accept
+//  a line, before giving up on it.
+
+const STREAM_TIMEOUT: Duration = Duration::from_secs(5);
+
+//  A connection to a collector, to which each result is sent as a line of
+//  CSV, as it's produced, with the header line sent before the first.
+
+pub struct ResultStream {
+   address: String,
+   stream: TcpStream,
+   header_sent: bool,
+}
+
+impl ResultStream {
+
+   //  Connects to a collector at an address given as HOST:PORT, trying
+   //  each of the addresses the host name resolves to in turn.
+
+   pub fn connect (address: &str) -> Result<ResultStream,BenchError> {
+      let io_error = |error: std::io::Error| BenchError::Io {
+         path: address.to_string(), action: "connect to",
+                                                    error: error.to_string() };
+      let mut last_error = None;
+      for socket_address in address.to_socket_addrs().map_err(&io_error)? {
+         match TcpStream::connect_timeout(&socket_address,STREAM_TIMEOUT) {
+            Ok(stream) => {
+               stream.set_write_timeout(Some(STREAM_TIMEOUT))
+                                                        .map_err(&io_error)?;
+               stream.set_nodelay(true).map_err(&io_error)?;
+               return Ok(ResultStream { address: address.to_string(),
+                                     stream, header_sent: false });
+            }
+            Err(error) => last_error = Some(error),
+         }
+      }
+      Err(match last_error {
+         Some(error) => io_error(error),
+         None => BenchError::Io { path: address.to_string(),
+               action: "connect to", error: "no such host".to_string() },
+      })
+   }
+
+   //  Sends a result, as a line written by csv_row(), preceded by the
+   //  header if it's the first.
+
+   pub fn send (&mut self,result: &BenchResult) -> Result<(),BenchError> {
+      let mut text = String::new();
+      if !self.header_sent {
+         text.push_str(&result.csv_header());
+         text.push('\n');
+      }
+      text.push_str(&result.csv_row());
+      text.push('\n');
+      match self.stream.write_all(text.as_bytes()) {
+         Ok(()) => { self.header_sent = true; Ok(()) }
+         Err(error) => Err(BenchError::Io { path: self.address.clone(),
+                               action: "send to", error: error.to_string() }),
+      }
+   }
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                        B a s e l i n e   C h e c k
+
+//  Compares a set of results against those in a baseline CSV file, written
+//  by an earlier run. Each result is matched against the baseline line with
+//  exactly the same configuration fields, and the minimum per-call times are
+//  compared. Any result more than threshold percent slower than its baseline
+//  is a regression. A report line is written to standard error for each
+//  result, so it doesn't get mixed up with any CSV output, and the
+//  function returns the number of regressions found, and the number of
+//  results with no match in the baseline - which weren't checked at all.
+//  Results for configurations that failed have no time to compare, and
+//  are left out, and nor is a failed line in the baseline used. A baseline
+//  with no lines that can be used is an error. The times are shown scaled
+//  to a suitable unit if si_times is true, and in ns otherwise.
+
+pub struct BaselineCheck {
+   pub regressions: usize,
+   pub unmatched: usize,
+}
+
+pub fn check_baseline (path: &str,results: &[BenchResult],threshold: f64,
+                           si_times: bool) -> Result<BaselineCheck,BenchError> {
+   let baseline: Vec<HashMap<String,String>> = read_csv(path)?.into_iter()
+       .filter(|row| row.get("status").is_none_or(|status| status != "failed"))
+                                                                   .collect();
+   if baseline.is_empty() {
+      return Err(BenchError::Format { path: path.to_string(),
+                 message: "has no results to compare against".to_string() });
+   }
+   let mut regressions = 0;
+   let mut unmatched = 0;
+   for result in results.iter().filter(|result| !result.failed) {
+      let config = result.config_fields();
+      for &(name,_) in &config {
+         if !baseline[0].contains_key(name) {
+            return Err(BenchError::Format { path: path.to_string(),
+                        message: format!("has no '{}' field",name) });
+         }
+      }
+      let matched = baseline.iter().find(|row|
+        config.iter().all(|&(name,ref value)| row.get(name) == Some(value)));
+      let row = match matched {
+         Some(row) => row,
+         None => {
+            eprintln!("NO BASELINE {}",result.config_key());
+            unmatched += 1;
+            continue;
+         }
+      };
+      let base_ns = match row.get("min_ns_per_call")
+                                    .and_then(|value| value.parse::<f64>().ok()) {
+         Some(ns) if ns > 0.0 => ns,
+         _ => return Err(BenchError::Format { path: path.to_string(),
+                message: format!("has an invalid time for {}",
+                                                   result.config_key()) }),
+      };
+      let change = (result.min_ns_per_call - base_ns) * 100.0 / base_ns;
+      let regressed = change > threshold;
+      let (time,percent) = (result.precision.unwrap_or(3),
+                                              result.precision.unwrap_or(2));
+      eprintln!("{} {}: baseline {}, now {}, change {:+.*}%",
+               if regressed { "REGRESSION" } else { "ok" },
+               result.config_key(),crsunits::time_text(base_ns,time,si_times),
+               crsunits::time_text(result.min_ns_per_call,time,si_times),
+               percent,change);
+      if regressed { regressions += 1; }
+   }
+   Ok(BaselineCheck { regressions, unmatched })
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                    B u i l d   C o m p a r i s o n
+
+//  The results read from one file for a build comparison: the build fields
+//  they were written with, and the minimum time per call for each
+//  configuration, in the order the configurations first appear. Each key is
+//  the values of the configuration fields, in the order they were asked for.
+
+pub struct BuildTimes {
+   pub path: String,
+   pub opt_level: String,
+   pub target_cpu: String,
+   pub times: Vec<(Vec<String>,f64)>,
+}
+
+impl BuildTimes {
+
+   //  A description of the build, eg 'opt-level 3, target-cpu native'.
+
+   pub fn describe (&self) -> String {
+      format!("opt-level {}, target-cpu {}",self.opt_level,self.target_cpu)
+   }
+
+   //  The time for a configuration, if the file had one for it.
+
+   pub fn time (&self,key: &[String]) -> Option<f64> {
+      self.times.iter().find(|entry| entry.0 == key).map(|entry| entry.1)
+   }
+}
+
+//  Reads a set of CSV result files, each written by one build of the
+//  program, and returns the times in each, keyed by the named configuration
+//  fields. Every line of a file must come from the same build - lines
+//  written before the build fields were added count as 'unknown' - and a
+//  configuration that appears more than once is given its best time. As
+//  for a baseline, failed lines are ignored.
+
+pub fn read_builds (paths: &[&str],names: &[&str])
+                                     -> Result<Vec<BuildTimes>,BenchError> {
+   let mut builds = Vec::new();
+   for &path in paths {
+      let format_error = |message: String| BenchError::Format {
+                                  path: path.to_string(), message };
+      let mut build: Option<(String,String)> = None;
+      let mut times: Vec<(Vec<String>,f64)> = Vec::new();
+      for row in read_csv(path)? {
+         if row.get("status").is_some_and(|status| status == "failed") {
+            continue;
+         }
+         let mut key = Vec::new();
+         for &name in names {
+            match row.get(name) {
+               Some(value) => key.push(value.clone()),
+               None => return Err(format_error(
+                                       format!("has no '{}' field",name))),
+            }
+         }
+         let field = |name: &str| row.get(name)
+                   .map_or("unknown".to_string(),|value| value.to_string());
+         let this_build = (field("opt_level"),field("target_cpu"));
+         if *build.get_or_insert_with(|| this_build.clone()) != this_build {
+            return Err(format_error(
+                         "has results from more than one build".to_string()));
+         }
+         let time = row.get("min_ns_per_call")
+                                   .and_then(|value| value.parse::<f64>().ok());
+         let ns = match time {
+            Some(ns) if ns > 0.0 => ns,
+            _ => {
+               let pairs: Vec<String> = names.iter().zip(key.iter())
+                         .map(|(name,value)| format!("{}={}",name,value))
+                         .collect();
+               return Err(format_error(format!("has an invalid time for {}",
+                                                         pairs.join(" "))));
+            }
+         };
+         match times.iter_mut().find(|entry| entry.0 == key) {
+            Some(entry) => entry.1 = entry.1.min(ns),
+            None => times.push((key,ns)),
+         }
+      }
+      let (opt_level,target_cpu) = match build {
+         Some(build) => build,
+         None => return Err(format_error("has no results".to_string())),
+      };
+      builds.push(BuildTimes { path: path.to_string(), opt_level,
+                               target_cpu, times });
+   }
+   Ok(builds)
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                                 T e s t s
+
+#[cfg(test)]
+mod tests {
+   use std::fs;
+
+   use super::{check_baseline, read_csv, BenchResult};
+
+   fn temp_path (name: &str) -> String {
+      let mut path = std::env::temp_dir();
+      path.push(format!("crsreport_{}_{}.csv",name,std::process::id()));
+      path.to_string_lossy().into_owned()
+   }
+
+   //  Writes a CSV file and reads it back, removing the file.
+
+   fn read_text (name: &str,text: &str) -> Result<usize,String> {
+      let path = temp_path(name);
+      fs::write(&path,text).unwrap();
+      let rows = read_csv(&path);
+      fs::remove_file(&path).unwrap();
+      rows.map(|rows| rows.len()).map_err(|error| error.to_string())
+   }
+
+   fn result (nx: usize,min_ns: f64) -> BenchResult {
+      BenchResult { label: None, mode: "flat".to_string(), ny: 10, nx,
+         nz: None, repeats: 100, trials: 5, inner_repeat: None, threads: 1,
+         input: "standard".to_string(), seed: 1, loop_order: "ij".to_string(),
+         budget_ms: None, out_stride: None, perm_seed: None, window: None,
+         window_stride: None, unroll: None, expr: None, fresh_alloc: false,
+         hugepages: false, mlock: false, time_source: "monotonic".to_string(),
+         flops: 1, bytes: 8, peak_bandwidth: None, calls: 100,
+         min_ns_per_call: min_ns, mean_ns_per_call: min_ns,
+         sem_ns_per_call: None, ci95_ns_per_call: None, verified: true,
+         checksum_algo: None, checksum: None, failed: false, precision: None }
+   }
+
+   //  A baseline file holding the given results.
+
+   fn baseline (name: &str,results: &[BenchResult]) -> String {
+      let path = temp_path(name);
+      let mut text = results[0].csv_header();
+      for result in results {
+         text.push('\n');
+         text.push_str(&result.csv_row());
+      }
+      fs::write(&path,text).unwrap();
+      path
+   }
+
+   #[test]
+   fn csv_lines () {
+      assert_eq!(read_text("good","mode,\"a,b\"\n\nflat,\"1,2\"\n"),Ok(1));
+      let short = read_text("short","mode,nx\nflat,10\nflat\n").unwrap_err();
+      assert!(short.contains("1 fields on line 3"),"{}",short);
+      let open = read_text("open","mode,nx\nflat,\"10\n").unwrap_err();
+      assert!(open.contains("isn't closed on line 2"),"{}",open);
+      assert!(read_text("header","mode,\"nx\n").is_err());
+      assert!(read_text("empty","\n\n").is_err());
+   }
+
+   //  A result with no baseline line isn't a pass - it's counted, so the
+   //  gate can fail on it - and a baseline with nothing usable is an error.
+
+   #[test]
+   fn baseline_matching () {
+      let path = baseline("matching",&[result(100,50.0)]);
+      let check = |results: &[BenchResult]|
+                             check_baseline(&path,results,5.0,false).unwrap();
+      let same = check(&[result(100,51.0)]);
+      assert_eq!((same.regressions,same.unmatched),(0,0));
+      let slower = check(&[result(100,60.0)]);
+      assert_eq!((slower.regressions,slower.unmatched),(1,0));
+      let other = check(&[result(100,50.0),result(200,50.0)]);
+      assert_eq!((other.regressions,other.unmatched),(0,1));
+      let mut reseeded = result(100,50.0);
+      reseeded.seed = 2;
+      assert_eq!(check(&[reseeded]).unmatched,1);
+      fs::remove_file(&path).unwrap();
+      let mut failed = result(100,50.0);
+      failed.failed = true;
+      let path = baseline("failed",&[failed]);
+      assert!(check_baseline(&path,&[result(100,50.0)],5.0,false).is_err());
+      fs::remove_file(&path).unwrap();
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The CSV handling is deliberately minimal. Only the label can need
+     quoting, and the label can't contain a line break (crsconfig.rs checks
+     that), so every result is still one line, and the reader can go a line
+     at a time. A label that is 'none' can't be told from no label at all.
+
+   o The stream is written to directly, without a BufWriter, so that each
+     line goes out as soon as its result is known, and set_nodelay() stops
+     the system holding a short line back to see if more is coming. The
+     write timeout means a collector that has stopped reading can hold up
+     a run by a few seconds, but no more.
+
+   o The Prometheus values are written with all the digits Rust needs to
+     give them exactly, rather than with the precision, since they're for
+     a program to read. The label names are the field names, which are
+     already valid Prometheus label names, and none of them is job or
+     instance, the labels Prometheus adds itself when it scrapes.
+
+   o A build comparison matches configurations on the values of their
+     fields as they're written, so a file from a version of the program
+     with different fields won't match - read_builds() fails if a field is
+     missing altogether, and any field a newer version adds has to be
+     there in all the files. The build fields themselves aren't checked
+     against anything: two files can say they're the same build, and the
+     comparison still goes ahead.
+
+   o A positive change is a slowdown and a negative one an improvement. The
+     threshold is a percentage of the baseline time, so a threshold of 5
+     allows the new time to be up to 5% longer than the baseline.
+
+*/
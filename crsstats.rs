@@ -0,0 +1,163 @@
+//
+//                           c r s s t a t s . r s
+//
+// Summary:
+//    Simple statistics for the Rust 2D array access benchmark harness.
+//
+// Introduction:
+//    The benchmark harness, crsbench.rs, mostly reports the minimum time over
+//    a number of trials, which needs nothing more than a fold. Some of its
+//    diagnostics need a little more than that, and this module collects the
+//    routines they use. None of it is clever, and none of it is used in the
+//    timed part of the code.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version, with mean() and slope(). agent.
+//    14th Oct 2026. Added std_dev(), std_error() and confidence_95(). agent.
+//    14th Oct 2026. Added geometric_mean(). agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//  The mean of a set of values, or zero if there aren't any.
+
+pub fn mean (values: &[f64]) -> f64 {
+   if values.is_empty() { return 0.0; }
+   values.iter().sum::<f64>() / values.len() as f64
+}
+
+//  The geometric mean of a set of values - the nth root of their product -
+//  or None if there aren't any, or any isn't a positive finite number. This
+//  is the one to use for ratios, such as speedups - see the programming
+//  notes.
+
+pub fn geometric_mean (values: &[f64]) -> Option<f64> {
+   if values.is_empty() { return None; }
+   if values.iter().any(|value| !(value.is_finite() && *value > 0.0)) {
+      return None;
+   }
+   let logs: Vec<f64> = values.iter().map(|value| value.ln()).collect();
+   Some(mean(&logs).exp())
+}
+
+//  The slope of the least squares straight line through a set of values,
+//  taking the x value of each to be its index - so for a series of runs,
+//  it's the change per run. Zero if there are fewer than two values.
+
+pub fn slope (values: &[f64]) -> f64 {
+   let count = values.len();
+   if count < 2 { return 0.0; }
+   let x_mean = (count - 1) as f64 / 2.0;
+   let y_mean = mean(values);
+   let mut sum_xy = 0.0;
+   let mut sum_xx = 0.0;
+   for (index,value) in values.iter().enumerate() {
+      let dx = index as f64 - x_mean;
+      sum_xy += dx * (value - y_mean);
+      sum_xx += dx * dx;
+   }
+   sum_xy / sum_xx
+}
+
+//  The sample standard deviation of a set of values - using n - 1, as is
+//  usual when the values are a sample - or zero if there are fewer than
+//  two values.
+
+pub fn std_dev (values: &[f64]) -> f64 {
+   let count = values.len();
+   if count < 2 { return 0.0; }
+   let centre = mean(values);
+   let sum_squares: f64 = values.iter()
+                 .map(|value| (value - centre) * (value - centre)).sum();
+   (sum_squares / (count - 1) as f64).sqrt()
+}
+
+//  The standard error of the mean of a set of values, or None if there are
+//  fewer than two values, when there's nothing to estimate it from.
+
+pub fn std_error (values: &[f64]) -> Option<f64> {
+   if values.len() < 2 { return None; }
+   Some(std_dev(values) / (values.len() as f64).sqrt())
+}
+
+//  The half width of the two-sided 95% confidence interval for the mean of
+//  a set of values, so the interval is the mean plus or minus this, or None
+//  if there are fewer than two values. This uses Student's t distribution
+//  with n - 1 degrees of freedom, which assumes the values are independent
+//  and drawn from a normal distribution - see the programming notes.
+
+pub fn confidence_95 (values: &[f64]) -> Option<f64> {
+   std_error(values).map(|error| t_95(values.len() - 1) * error)
+}
+
+//  The 97.5th percentile of Student's t distribution - the multiple of the
+//  standard error that gives a two-sided 95% interval - for 1 to 30 degrees
+//  of freedom, and the same for the normal distribution, which it tends to.
+
+const T_95: [f64; 30] = [12.706,4.303,3.182,2.776,2.571,2.447,2.365,2.306,
+   2.262,2.228,2.201,2.179,2.160,2.145,2.131,2.120,2.110,2.101,2.093,2.086,
+   2.080,2.074,2.069,2.064,2.060,2.056,2.052,2.048,2.045,2.042];
+
+const Z_95: f64 = 1.959964;
+
+//  Returns the t value for a 95% interval with the given (nonzero) degrees
+//  of freedom. Beyond the table, the first two terms of the expansion of t
+//  in powers of 1 / degrees (Cornish-Fisher) are good to better than 0.01%.
+
+fn t_95 (degrees: usize) -> f64 {
+   if degrees <= T_95.len() { return T_95[degrees.max(1) - 1]; }
+   let (z,n) = (Z_95,degrees as f64);
+   let first = (z.powi(3) + z) / 4.0;
+   let second = (5.0 * z.powi(5) + 16.0 * z.powi(3) + 3.0 * z) / 96.0;
+   z + first / n + second / (n * n)
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The values are centred on their means before the sums are formed,
+     which avoids the loss of precision the one-pass formula can suffer
+     when the times are large and the differences between them small.
+
+   o The confidence interval is only as good as its assumptions. The times
+     of successive trials aren't really independent - the CPU frequency,
+     the caches and the rest of the system change slowly - and their
+     distribution isn't normal, having a hard floor and a long tail of
+     slow trials. With a handful of trials it's still a fair indication of
+     how well the mean is known, but it says nothing about the minimum,
+     which is what the harness mostly reports, and it can't allow for
+     anything that affects every trial alike.
+
+   o Ratios are summarised with the geometric mean, not the arithmetic
+     mean, because the arithmetic mean of ratios depends on which way up
+     they are. A kernel twice as fast on one array and half as fast on
+     another has ratios of 2 and 0.5, whose arithmetic mean, 1.25, says
+     it's faster overall - while the ratios the other way up, 0.5 and 2,
+     say just the same about the other kernel. The geometric mean of both
+     sets is 1, and in general the geometric mean of the inverted ratios
+     is the inverse of the geometric mean, so the summary doesn't depend
+     on which kernel is taken as the reference. It's worked out as the
+     exponential of the mean of the logarithms, which can't overflow the
+     way the product of many ratios could.
+
+*/
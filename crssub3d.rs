@@ -0,0 +1,74 @@
+//
+//                           c r s s u b 3 d . r s
+//
+// Summary:
+//    3D array access test subroutine in Rust, using a flat Array3D.
+//
+// Introduction:
+//    This is a test routine written as part of a study into how well different
+//    languages handle accessing elements of multidimensional arrays. Most of
+//    the study uses 2D arrays, but this routine extends the same operation to
+//    3D. It is passed a 3D array (In) with Nz planes, each of Ny rows and Nx
+//    columns, and another 3D array of the same size (Out). It modifies Out so
+//    each element of Out is set to the value of the corresponding element of
+//    In, plus the sum of the three index values for the element - ie plus the
+//    plane, row and column numbers. As for the 2D case, the idea is trivial,
+//    but the operation isn't completely trivial to optimise.
+//
+// This version:
+//    This version is for Rust, and uses the flat Array3D type (see
+//    crsarray3d.rs), indexed as array[[ix,iy,iz]], going through the array
+//    in memory order - plane by plane, and row by row within each plane. It
+//    is the 3D equivalent of crssub1d.rs.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::crsarray::Element;
+use crate::crsarray3d::Array3D;
+
+pub fn csub3d<T: Element> (input_array: &Array3D<T>,
+                                             output_array: &mut Array3D<T>) {
+   for iz in 0..input_array.nz() {
+      for iy in 0..input_array.ny() {
+         for ix in 0..input_array.nx() {
+            output_array[[ix,iy,iz]] =
+                        input_array[[ix,iy,iz]] + T::from_index(ix + iy + iz);
+         }
+      }
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The indexing is checked, on all three indices, for every element.
+     Whether the optimiser can get rid of those checks is part of what's
+     being measured, and comparing the time per element with that for a 2D
+     array with the same number of elements shows what the third dimension
+     costs.
+
+*/
@@ -0,0 +1,129 @@
+//
+//                       c r s s u b _ a f f i n e . r s
+//
+// Summary:
+//    2D array access test subroutine in Rust, applying a gain and an
+//    offset to each row.
+//
+// Introduction:
+//    This is a test routine written as part of a study into how well different
+//    languages handle accessing elements of 2D rectangular arrays. The usual
+//    routine is passed a 2D array (In) with Ny rows and Nx columns, and
+//    another of the same size (Out), and sets each element of Out to the
+//    corresponding element of In plus the sum of its two index values. This
+//    one instead sets each element of Out to the corresponding element of In
+//    multiplied by a gain and plus an offset, both of which are the same
+//    along a row and differ from one row to the next - out = in * gain[iy] +
+//    offset[iy]. That's how a lot of real calibration is done, where each
+//    row of a detector - or each channel of a spectrum - has its own
+//    response, and it has a multiply as well as an add, and two short
+//    vectors to read along with the arrays.
+//
+// This version:
+//    This version is for Rust, and uses the flat Array2D type (see
+//    crsarray.rs), with a loop over the elements of each row, as in
+//    crssub1d.rs. The gains and offsets are made by calibration(), once,
+//    before the timing starts, and are read once for each row. Their
+//    values are simple functions of the row number, given by gain_value()
+//    and offset_value(), so the results can be checked without them (see
+//    verify_affine(), in crsverify.rs), but the kernel can't know that, and
+//    has to read them.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::crsarray::{Array2D, Element};
+
+//  The element additions the kernel makes for each element - just the one
+//  for the offset. The multiply by the gain isn't an addition, and isn't
+//  counted by --count-ops, though it is one of the two flops in the MODES
+//  table.
+
+pub const ADDITIONS: usize = 1;
+
+//  The gain and the offset for row iy. The gains are small whole numbers,
+//  so that they mean the same for every element type.
+
+pub fn gain_value (iy: usize) -> usize {
+   1 + iy % 3
+}
+
+pub fn offset_value (iy: usize) -> usize {
+   (iy * 7) % 11
+}
+
+//  The gain and the offset for each row.
+
+pub struct Calibration<T: Element> {
+   pub gains: Vec<T>,
+   pub offsets: Vec<T>,
+}
+
+//  Makes the gains and the offsets for an array of ny rows.
+
+pub fn calibration<T: Element> (ny: usize) -> Calibration<T> {
+   Calibration {
+      gains: (0..ny).map(|iy| T::from_index(gain_value(iy))).collect(),
+      offsets: (0..ny).map(|iy| T::from_index(offset_value(iy))).collect(),
+   }
+}
+
+pub fn csub_affine<T: Element> (input_array: &Array2D<T>,
+           calibration: &Calibration<T>,output_array: &mut Array2D<T>) {
+   for iy in 0..input_array.ny() {
+      let gain = calibration.gains[iy];
+      let offset = calibration.offsets[iy];
+      let in_row = input_array.row(iy);
+      let out_row = output_array.row_mut(iy);
+      for (out,value) in out_row.iter_mut().zip(in_row) {
+         *out = *value * gain + offset;
+      }
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The flops and bytes in the MODES table (see crsconfig.rs) are two
+     flops - the multiply and the add - and 8 bytes per element. The gains
+     and offsets are two values a row, which is nothing next to the row
+     itself, so they aren't counted.
+
+   o The sum is (in * gain) + offset, in that order, and verify_affine()
+     works it out the same way, so the check can be bit for bit. A
+     compiler is free to contract that into a fused multiply-add only if
+     told it can, which rustc isn't by default, so the results are the
+     same with or without FMA instructions.
+
+   o There's no index sum, so the results aren't the usual ones, and none
+     of the element-wise diagnostics, which compare against those, apply.
+
+   o The calibration is per row. Per column would mean a gain and an
+     offset for each element of a row, read alongside it, which has more
+     in common with the binary mode (see crssub_binary.rs) - two more
+     streams of data rather than two values a row.
+
+*/
@@ -0,0 +1,108 @@
+//
+//                       c r s s u b _ a l i a s . r s
+//
+// Summary:
+//    A deliberately broken use of the raw pointer kernel, with the output
+//    overlapping the input. For demonstration only.
+//
+// Introduction:
+//    The unsafe kernels in this study - crssub_unsafe.rs and crssub_rawptr.rs
+//    - are only correct because their input and output arrays are distinct.
+//    For crssub_rawptr.rs that is one of the conditions listed in its Safety
+//    section, and it's the one the borrow checker looks after: the input is
+//    a shared reference and the output a mutable one, so a call such as
+//    csub_rawptr(&array,&mut array) doesn't compile. The safe versions never
+//    need to think about it at all.
+//
+//    This is the same loop as csub_rawptr(), walking raw pointers along each
+//    row, but it takes the pointers themselves, so nothing stops the caller
+//    passing an output that overlaps the input. With the output ahead of the
+//    input in memory, each element written is one the loop has still to
+//    read, and the wrong values it then reads are carried forward along the
+//    array. It's there so that --danger-alias in crsbench.rs can show, with
+//    real numbers, what the no-overlap condition is protecting against.
+//
+// Building:
+//    This is only used if crsbench.rs is built with the 'danger_alias'
+//    feature:
+//
+//    rustc -O --cfg 'feature="danger_alias"' crsbench.rs
+//
+//    Nothing in a normal build can call it.
+//
+// Safety:
+//    The function is unsafe, and the caller must make sure that:
+//
+//    o Both pointers point to the start of nx * ny initialised, properly
+//      aligned f32 values, all within the same allocation.
+//    o Nothing else uses that allocation while the function runs. In
+//      particular, no reference to any part of it may be live - the overlap
+//      is only allowed because everything goes through raw pointers.
+//
+//    Overlap between the two is allowed, which is the point. Rust is happy
+//    for raw pointers to alias, so the program is still well defined, and
+//    the results are simply what the loop computes.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+pub unsafe fn csub_alias (input_ptr: *const f32,output_ptr: *mut f32,
+                                                       nx: usize,ny: usize) {
+   for iy in 0..ny {
+      let mut in_ptr = input_ptr.add(iy * nx);
+      let mut out_ptr = output_ptr.add(iy * nx);
+      for ix in 0..nx {
+
+         //  See the Safety section above: both pointers stay within the
+         //  nx * ny values the caller vouched for.
+
+         *out_ptr = *in_ptr + (ix + iy) as f32;
+         in_ptr = in_ptr.add(1);
+         out_ptr = out_ptr.add(1);
+      }
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o Don't be tempted to make this look more like the real kernels by
+     building a &[f32] and a &mut [f32] over the overlapping regions. That
+     is undefined behaviour the moment the two exist together - the
+     compiler is entitled to assume a mutable reference has no aliases, and
+     may reorder or vectorise the loads and stores on that basis - so the
+     results would no longer show anything about the kernel, just about
+     what the optimiser happened to do.
+
+   o Because the pointers may alias, the compiler has to keep every load
+     after the store before it that might overlap. That makes this slower
+     than csub_rawptr(), but it's never timed.
+
+   o With the output behind the input in memory, each element is read
+     before anything overwrites it, and the result happens to be right.
+     That is luck, not something the kernel promises.
+
+*/
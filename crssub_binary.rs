@@ -0,0 +1,114 @@
+//
+//                       c r s s u b _ b i n a r y . r s
+//
+// Summary:
+//    2D array access test subroutine in Rust, combining two input arrays
+//    element by element.
+//
+// Introduction:
+//    This is a test routine written as part of a study into how well different
+//    languages handle accessing elements of 2D rectangular arrays. The usual
+//    routine is passed one 2D array (In) with Ny rows and Nx columns, and
+//    another of the same size (Out), and sets each element of Out to the
+//    corresponding element of In plus the sum of its two index values. This
+//    one is passed two input arrays, A and B, and sets each element of Out to
+//    the sum of the corresponding elements of A and B, plus the sum of the
+//    index values. That's the shape of a lot of real image processing -
+//    differencing two frames, or applying a flat field - and it reads twice
+//    as much as it writes, so it has a different balance of arithmetic and
+//    memory traffic from the usual routine.
+//
+// This version:
+//    This version is for Rust, and uses the flat Array2D type (see
+//    crsarray.rs), with a loop over the elements of each row for all three
+//    arrays together, as in crssub1d.rs. A is the usual input array, and B
+//    is made by second_input(), once, before the timing starts. Its values
+//    are a simple function of the indices, given by second_value(), so that
+//    the results can be checked without it (see verify_binary(), in
+//    crsverify.rs), but the kernel can't know that, and has to read them.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::crsarray::{Array2D, Element};
+use crate::crserror::BenchError;
+
+//  The element additions the kernel makes for each element - one for the
+//  two inputs, and one for the index sum.
+
+pub const ADDITIONS: usize = 2;
+
+//  The value of element (ix,iy) of the second input array.
+
+pub fn second_value (ix: usize,iy: usize) -> usize {
+   (ix * 3 + iy * 5) % 17
+}
+
+//  Makes the second input array, for an array of nx columns and ny rows.
+
+pub fn second_input<T: Element> (nx: usize,ny: usize)
+                                          -> Result<Array2D<T>,BenchError> {
+   let mut array = Array2D::try_new(nx,ny,T::from_index(0))?;
+   for iy in 0..ny {
+      for (ix,value) in array.row_mut(iy).iter_mut().enumerate() {
+         *value = T::from_index(second_value(ix,iy));
+      }
+   }
+   Ok(array)
+}
+
+pub fn csub_binary<T: Element> (a_array: &Array2D<T>,b_array: &Array2D<T>,
+                                             output_array: &mut Array2D<T>) {
+   for iy in 0..a_array.ny() {
+      let a_row = a_array.row(iy);
+      let b_row = b_array.row(iy);
+      let out_row = output_array.row_mut(iy);
+      for (ix,((a,b),out)) in a_row.iter().zip(b_row).zip(out_row.iter_mut())
+                                                                 .enumerate() {
+         *out = *a + *b + T::from_index(ix + iy);
+      }
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The flops and bytes in the MODES table (see crsconfig.rs) are two
+     additions and 12 bytes per element - two f32 values read and one
+     written - so the arithmetic intensity and the bandwidth figures, and
+     the roofline, allow for the second array.
+
+   o The sum is (a + b) + index, in that order, and verify_binary() works
+     it out the same way, so the check can be bit for bit. Floating point
+     addition isn't associative, so a kernel that added them in another
+     order could be correct and still fail it.
+
+   o The rows are zipped rather than indexed so that the compiler can see
+     that all three rows are the same length, and needs no bounds checks.
+     If b_array were a different shape, the zip would just stop at the
+     shorter row - but it's always made with the same shape as a_array.
+
+*/
@@ -0,0 +1,82 @@
+//
+//                     c r s s u b _ c h e c k e d . r s
+//
+// Summary:
+//    2D array access test subroutine in Rust, the bounds checked twin of the
+//    unsafe version.
+//
+// Introduction:
+//    This is a test routine written as part of a study into how well different
+//    languages handle accessing elements of 2D rectangular arrays. This routine
+//    is passed a 2D array (In) with Ny rows and Nx columns, and another 2D
+//    array of the same size (Out). It modifies Out so so each element of Out
+//    is set to the value of the corresponding element of In, plus the sum of
+//    the two index values for the element - ie plus the row number and the
+//    column number. The idea is trivial, but the operation isn't completely
+//    trivial to optimise, and the intention is to see how well this runs when
+//    compiled using different compilers, or using different options.
+//
+// This version:
+//    This version is for Rust, and uses vectors of 1D vectors to implement
+//    a 2D array. It is there to measure just what the bounds checks cost.
+//    Comparing the original version, crssub.rs, with the unsafe one,
+//    crssub_unsafe.rs, almost does that, but the unsafe version also runs
+//    its loops over i32 values, and converts the i32 index sum to f32 -
+//    which is cheaper than converting a usize, and so muddies the result.
+//    This version is line for line the same as the unsafe one, i32 loops
+//    and all, except that it uses plain array[iy][ix] indexing, with the
+//    bounds checks left in. So the only difference between the two is the
+//    checks.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+pub fn csub_checked (input_array: &[Vec<f32>],nx: usize,ny: usize,
+                                      output_array: &mut [Vec<f32>]) {
+
+   //  Compare this with crssub_unsafe.rs - the loops are the same, and
+   //  only the element access differs.
+
+   for iy in 0..ny as i32 {
+      for ix in 0..nx as i32 {
+         output_array[iy as usize][ix as usize] =
+                     input_array[iy as usize][ix as usize] + (ix + iy) as f32;
+      }
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The checks can't be removed by the optimiser here, since nothing tells
+     it that nx and ny match the lengths of the vectors. What it can do is
+     move some of them out of the inner loop - the check on the row index
+     only needs making once per row - and that's part of what's measured.
+
+   o Just like the unsafe version, this only works for arrays with fewer
+     than 2^31 rows and columns, and an index sum that fits in an i32.
+
+*/
@@ -0,0 +1,98 @@
+//
+//                       c r s s u b _ c h u n k s . r s
+//
+// Summary:
+//    2D array access test subroutine in Rust, working through each row in
+//    fixed size chunks.
+//
+// Introduction:
+//    This is a test routine written as part of a study into how well different
+//    languages handle accessing elements of 2D rectangular arrays. This routine
+//    is passed a 2D array (In) with Ny rows and Nx columns, and another 2D
+//    array of the same size (Out). It modifies Out so so each element of Out
+//    is set to the value of the corresponding element of In, plus the sum of
+//    the two index values for the element - ie plus the row number and the
+//    column number. The idea is trivial, but the operation isn't completely
+//    trivial to optimise, and the intention is to see how well this runs when
+//    compiled using different compilers, or using different options.
+//
+// This version:
+//    This version is for Rust, and uses the flat Array2D type (see
+//    crsarray.rs). Each row is split into chunks of CHUNK elements using
+//    chunks_exact(), which guarantees every chunk is exactly CHUNK long, so
+//    the inner loop has a length the compiler knows and can vectorise, with
+//    no bounds checks and no unsafe code. Any elements left over at the end
+//    of the row are handled separately. This is an idiom that's often
+//    recommended as the safe way to get vectorised code, and it's a middle
+//    ground between the iterator version, crssub_iter.rs, and the const
+//    generic version, crssub_const.rs, which needs the whole row length to
+//    be known at compile time.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::crsarray::{Array2D, Element};
+
+//  The number of elements in each chunk.
+
+pub const CHUNK: usize = 8;
+
+pub fn csub_chunks<T: Element> (input_array: &Array2D<T>,
+                                             output_array: &mut Array2D<T>) {
+   for iy in 0..input_array.ny() {
+      let in_chunks = input_array.row(iy).chunks_exact(CHUNK);
+      let mut out_chunks = output_array.row_mut(iy).chunks_exact_mut(CHUNK);
+
+      //  The input remainder has to be found before the zip consumes
+      //  in_chunks.
+
+      let in_rest = in_chunks.remainder();
+      let mut ix = 0;
+      for (in_chunk,out_chunk) in in_chunks.zip(&mut out_chunks) {
+         for i in 0..CHUNK {
+            out_chunk[i] = in_chunk[i] + T::from_index(ix + i + iy);
+         }
+         ix += CHUNK;
+      }
+      let out_rest = out_chunks.into_remainder();
+      for (i,(e,r)) in in_rest.iter().zip(out_rest.iter_mut()).enumerate() {
+         *r = *e + T::from_index(ix + i + iy);
+      }
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The remainder code only runs if nx isn't a multiple of CHUNK, which
+     the default of 2000 is. The harness checks it separately, on a small
+     array with a remainder, whatever the size of the main array.
+
+   o For ChunksExactMut, into_remainder() consumes the iterator, which is
+     why the chunks are iterated through a mutable reference - that leaves
+     out_chunks still available once the loop over the chunks is done.
+
+*/
@@ -0,0 +1,115 @@
+//
+//                       c r s s u b _ c o n s t . r s
+//
+// Summary:
+//    2D array access test subroutine in Rust, with the row length fixed at
+//    compile time.
+//
+// Introduction:
+//    This is a test routine written as part of a study into how well different
+//    languages handle accessing elements of 2D rectangular arrays. This routine
+//    is passed a 2D array (In) with Ny rows and Nx columns, and another 2D
+//    array of the same size (Out). It modifies Out so so each element of Out
+//    is set to the value of the corresponding element of In, plus the sum of
+//    the two index values for the element - ie plus the row number and the
+//    column number. The idea is trivial, but the operation isn't completely
+//    trivial to optimise, and the intention is to see how well this runs when
+//    compiled using different compilers, or using different options.
+//
+// This version:
+//    This version is for Rust, and uses the flat Array2D type (see
+//    crsarray.rs). The inner routine, csub_const(), takes the number of
+//    columns as a const generic parameter, NX, so each row is a fixed size
+//    array whose length is known to the compiler, which can unroll and
+//    vectorise the inner loop as it sees fit, with no remainder handling and
+//    no bounds checks. The idea is to see just how much knowing the size
+//    buys.
+//
+//    Obviously this only works for sizes known when the program is built, so
+//    csub_dispatch() picks a specialised version for a few common column
+//    counts - see SPECIALISED_NX - and falls back to the dynamic version in
+//    crssub1d.rs for anything else.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::convert::TryFrom;
+
+use crate::crsarray::{Array2D, Element};
+use crate::crssub1d;
+
+//  The column counts that have specialised versions.
+
+pub const SPECIALISED_NX: [usize; 4] = [256,512,1024,2048];
+
+//  True if there is a specialised version for this number of columns.
+
+pub fn is_specialised (nx: usize) -> bool {
+   SPECIALISED_NX.contains(&nx)
+}
+
+//  Calls the specialised version for the array's number of columns, if
+//  there is one, or the dynamic version in crssub1d.rs if not.
+
+pub fn csub_dispatch<T: Element> (input_array: &Array2D<T>,
+                                             output_array: &mut Array2D<T>) {
+   match input_array.nx() {
+      256 => csub_const::<T,256>(input_array,output_array),
+      512 => csub_const::<T,512>(input_array,output_array),
+      1024 => csub_const::<T,1024>(input_array,output_array),
+      2048 => csub_const::<T,2048>(input_array,output_array),
+      _ => crssub1d::csub1d(input_array,output_array),
+   }
+}
+
+//  The version for arrays with exactly NX columns. The rows are taken as
+//  fixed size arrays, so the conversions check the length once per row and
+//  the inner loop has nothing left to check.
+
+pub fn csub_const<T: Element,const NX: usize> (input_array: &Array2D<T>,
+                                             output_array: &mut Array2D<T>) {
+   assert_eq!(input_array.nx(),NX);
+   for iy in 0..input_array.ny() {
+      let in_row: &[T; NX] = TryFrom::try_from(input_array.row(iy)).unwrap();
+      let out_row: &mut [T; NX] =
+                           TryFrom::try_from(output_array.row_mut(iy)).unwrap();
+      for ix in 0..NX {
+         out_row[ix] = in_row[ix] + T::from_index(ix + iy);
+      }
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o Each specialisation is a separate copy of the code, so the list of
+     sizes is kept short. Adding another is just a matter of adding it to
+     SPECIALISED_NX and to the match in csub_dispatch().
+
+   o TryFrom is only in the prelude from the 2021 edition, hence the use
+     statement, which is needed for rustc's default 2015 edition.
+
+*/
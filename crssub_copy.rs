@@ -0,0 +1,90 @@
+//
+//                         c r s s u b _ c o p y . r s
+//
+// Summary:
+//    2D array access test subroutine in Rust, copying the input array to
+//    the output with no arithmetic at all.
+//
+// Introduction:
+//    This is a test routine written as part of a study into how well different
+//    languages handle accessing elements of 2D rectangular arrays. The usual
+//    routine is passed a 2D array (In) with Ny rows and Nx columns, and
+//    another of the same size (Out), and sets each element of Out to the
+//    corresponding element of In plus the sum of its two index values. The
+//    main program's introduction explains that the index sum is there
+//    because a plain copy is easy to optimise away. This routine is that
+//    plain copy, deliberately: each element of Out is set to the matching
+//    element of In and nothing else, so its time is just the time to move
+//    the data, and the difference between it and the flat mode's time is
+//    what the arithmetic costs.
+//
+// This version:
+//    This version is for Rust, and uses the flat Array2D type (see
+//    crsarray.rs), with a loop over the elements of each row, as in
+//    crssub1d.rs. The compiler will usually turn that into a call to
+//    memcpy(), which is fine - that's the floor being measured. Keeping the
+//    copy from being optimised away is left to the harness, which passes
+//    the input through black_box() as it does for every mode, and also
+//    passes the output through black_box() after every call (see the
+//    notes below).
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::crsarray::{Array2D, Element};
+
+pub fn csub_copy<T: Element> (input_array: &Array2D<T>,
+                                             output_array: &mut Array2D<T>) {
+   for iy in 0..input_array.ny() {
+      let in_row = input_array.row(iy);
+      let out_row = output_array.row_mut(iy);
+      for (out,value) in out_row.iter_mut().zip(in_row) {
+         *out = *value;
+      }
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o Every call of the usual kernel writes the same values, but the
+     compiler can't see that, because of the index sums and because the
+     input comes through black_box(). A copy is simpler: after inlining,
+     the compiler can see that one call's stores are overwritten by the
+     next call's identical ones, and would be entitled to make only the
+     last. So the harness hands the output to black_box() after each
+     timed call as well, which says it might be read there, and each
+     call's stores have to be made. That costs nothing at run time.
+
+   o What is left at the end is also checked, by verify_copy() (see
+     crsverify.rs), and fed to the checksum if one is asked for, so the
+     last call's output is always used.
+
+   o The MODES table (see crsconfig.rs) gives no flops and 8 bytes per
+     element, so the arithmetic intensity is zero, and the bandwidth is
+     the figure that means something.
+
+*/
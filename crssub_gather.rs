@@ -0,0 +1,134 @@
+//
+//                      c r s s u b _ g a t h e r . r s
+//
+// Summary:
+//    2D array access test subroutine in Rust, reading the input through a
+//    permutation index.
+//
+// Introduction:
+//    This is a test routine written as part of a study into how well different
+//    languages handle accessing elements of 2D rectangular arrays. This routine
+//    is passed a 2D array (In) with Ny rows and Nx columns, and another 2D
+//    array of the same size (Out). It modifies Out so so each element of Out
+//    is set to the value of the corresponding element of In, plus the sum of
+//    the two index values for the element - ie plus the row number and the
+//    column number. The idea is trivial, but the operation isn't completely
+//    trivial to optimise, and the intention is to see how well this runs when
+//    compiled using different compilers, or using different options.
+//
+// This version:
+//    This version is for Rust, and uses the flat Array2D type (see
+//    crsarray.rs). It writes the output in memory order, as usual, but it
+//    reads the input through a permutation index - the result for the
+//    element at offset k in the output comes from the input element whose
+//    indices are Perm[k], and is that element plus the sum of those indices.
+//    This is the gather pattern of sparse matrix code and of resampling, and
+//    it stresses the read side of the memory system in a way the contiguous
+//    versions can't.
+//
+//    With the identity permutation, from identity(), every element comes from
+//    its own place and the output is exactly that of the other versions. With
+//    a shuffled permutation, from shuffled(), the reads are all over the
+//    array, and the output holds the same values as usual, but in the
+//    shuffled order - which is why it's then checked by comparing the sorted
+//    values (see crsverify.rs).
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::crsarray::{Array2D, Element};
+use crate::crsinput::Rng;
+
+//  Returns a description of what's wrong with using a permutation index for
+//  an array of nx columns and ny rows, or None if it can be used. The
+//  indices are held as u32 values, to keep the index array small.
+
+pub fn size_problem (nx: usize,ny: usize) -> Option<String> {
+   let limit = u32::MAX as usize;
+   if nx > limit || ny > limit {
+      return Some(format!("The gather mode needs no more than {} rows and \
+                                                           columns",limit));
+   }
+   None
+}
+
+//  Returns the identity permutation for an array of nx columns and ny rows,
+//  which lists the elements in memory order.
+
+pub fn identity (nx: usize,ny: usize) -> Vec<(u32,u32)> {
+   let mut perm = Vec::with_capacity(nx * ny);
+   for iy in 0..ny {
+      for ix in 0..nx {
+         perm.push((ix as u32,iy as u32));
+      }
+   }
+   perm
+}
+
+//  Returns a permutation of the elements of an array of nx columns and ny
+//  rows, shuffled using the given seed. The same seed always gives the
+//  same permutation.
+
+pub fn shuffled (nx: usize,ny: usize,seed: u64) -> Vec<(u32,u32)> {
+   let mut perm = identity(nx,ny);
+   let mut rng = Rng::new(seed);
+   for index in (1..perm.len()).rev() {
+      let other = (rng.next_u64() % (index as u64 + 1)) as usize;
+      perm.swap(index,other);
+   }
+   perm
+}
+
+//  The kernel. The permutation must have one entry for each element, and
+//  come from identity() or shuffled(), but anything else just leads to a
+//  wrong answer (or, if an index is out of range, a panic), since all the
+//  indexing is checked.
+
+pub fn csub_gather<T: Element> (input_array: &Array2D<T>,
+                          output_array: &mut Array2D<T>,perm: &[(u32,u32)]) {
+   let out = output_array.as_mut_slice();
+   for (value,&(ix,iy)) in out.iter_mut().zip(perm) {
+      let (ix,iy) = (ix as usize,iy as usize);
+      *value = input_array[[ix,iy]] + T::from_index(ix + iy);
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The index holds both indices of each element, rather than its offset,
+     so the index sum doesn't have to be worked out from the offset with a
+     division. That makes each entry 8 bytes, which is counted as part of
+     the memory traffic in the MODES table in crsconfig.rs.
+
+   o The shuffle is the usual Fisher-Yates one. Taking the random value
+     modulo the range is very slightly biased, but far too little to matter
+     for a memory access pattern.
+
+   o The permutation is made before the timing starts, and isn't part of
+     what's timed.
+
+*/
@@ -0,0 +1,132 @@
+//
+//                  c r s s u b _ i n t e r l e a v e d . r s
+//
+// Summary:
+//    2D array access test subroutine in Rust, dealing the elements out to
+//    threads a chunk at a time, for the false sharing study.
+//
+// Introduction:
+//    This is a test routine written as part of a study into how well different
+//    languages handle accessing elements of 2D rectangular arrays. This routine
+//    is passed a 2D array (In) with Ny rows and Nx columns, and another 2D
+//    array of the same size (Out). It modifies Out so so each element of Out
+//    is set to the value of the corresponding element of In, plus the sum of
+//    the two index values for the element - ie plus the row number and the
+//    column number.
+//
+//    Two threads that write to different parts of the same cache line share
+//    it falsely: neither uses the other's data, but the line has to move
+//    between their cores for every write, much as if they did. This kernel
+//    is there to show what that costs. Rather than giving each thread one
+//    band of rows, as crssub_threads.rs does, it deals the elements out in
+//    chunks, in turn - chunk 0 to thread 0, chunk 1 to thread 1, and so on,
+//    round and round - so every chunk boundary is a boundary between two
+//    threads. With chunks of a cache line that start on line boundaries, no
+//    line is written by more than one thread. With the same chunks shifted
+//    half a line along, every line is written by two. The work is otherwise
+//    identical, so the difference in time is the false sharing.
+//
+// This version:
+//    This version is for Rust, and uses the flat Array2D type (see
+//    crsarray.rs). The output is cut into its chunks with split_at_mut(),
+//    and each thread given a list of its own chunks, so - like the other
+//    threaded kernels - it's parallel code with no unsafe code in it. It's
+//    not one of the modes: it's only run by --false-share-study, in
+//    crsbench.rs, which works out where the chunks should start.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::thread;
+
+use crate::crsarray::{Array2D, Element};
+
+//  Sets the output as csub() does, using nthreads threads, dealing the
+//  elements out in chunks of chunk elements, except that the first chunk
+//  has just first elements, which must be from 1 to chunk.
+
+pub fn csub_interleaved<T: Element> (input_array: &Array2D<T>,
+                        output_array: &mut Array2D<T>,nthreads: usize,
+                                                  chunk: usize,first: usize) {
+   let nx = input_array.nx();
+   let nthreads = nthreads.max(1);
+   let mut pieces: Vec<Vec<(usize,&mut [T])>> =
+                                   (0..nthreads).map(|_| Vec::new()).collect();
+   let mut rest = output_array.as_mut_slice();
+   let mut start = 0;
+   let mut ichunk = 0;
+   while !rest.is_empty() {
+      let size = if ichunk == 0 { first } else { chunk }.min(rest.len());
+      let (piece,tail) = rest.split_at_mut(size);
+      pieces[ichunk % nthreads].push((start,piece));
+      rest = tail;
+      start += size;
+      ichunk += 1;
+   }
+   let input = input_array.as_slice();
+   thread::scope(|scope| {
+      for thread_pieces in pieces {
+         scope.spawn(move || {
+            for (start,piece) in thread_pieces {
+               let values = &input[start..start + piece.len()];
+               let (mut ix,mut iy) = (start % nx,start / nx);
+               for (out,&value) in piece.iter_mut().zip(values) {
+                  *out = value + T::from_index(ix + iy);
+                  ix += 1;
+                  if ix == nx { ix = 0; iy += 1; }
+               }
+            }
+         });
+      }
+   });
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o Dealing out single cache lines is about the worst way there is to
+     divide up an array, with false sharing or without it - no prefetcher
+     follows a thread that jumps several lines at a time, and the list of
+     chunks has one entry per line. That's deliberate: it makes every line
+     a boundary, so the false sharing is as bad as it can be, and the
+     aligned case is what it should be compared with, not the threads mode.
+
+   o For the contiguous bands of crssub_threads.rs, only the line at each
+     band boundary can be shared, once per call. A row boundary that isn't
+     on a line boundary costs a few hundred nanoseconds at most, lost in
+     the time of a call for an array of any size, which is why the study
+     doesn't use it.
+
+   o Only the output is shared between the threads in a way that matters.
+     Reading a line that another core also reads doesn't need it to move,
+     so the input doesn't suffer.
+
+   o The penalty needs the threads to be running on different cores at
+     the same time. With more threads than cores, or on a machine with
+     just one, they take turns, a line is only ever in one core's cache,
+     and the two times come out the same to within the noise.
+
+*/
@@ -0,0 +1,139 @@
+//
+//                        c r s s u b _ l a n e s . r s
+//
+// Summary:
+//    2D array access test subroutines in Rust, working in groups of four
+//    lanes, with a plain add or with a multiply and add.
+//
+// Introduction:
+//    This is a test routine written as part of a study into how well different
+//    languages handle accessing elements of 2D rectangular arrays. This routine
+//    is passed a 2D array (In) with Ny rows and Nx columns, and another 2D
+//    array of the same size (Out). It modifies Out so so each element of Out
+//    is set to the value of the corresponding element of In, plus the sum of
+//    the two index values for the element - ie plus the row number and the
+//    column number. The idea is trivial, but the operation isn't completely
+//    trivial to optimise, and the intention is to see how well this runs when
+//    compiled using different compilers, or using different options.
+//
+// This version:
+//    This version is for Rust, and uses the flat Array2D type (see
+//    crsarray.rs). Each row is handled in groups of LANES (four) elements,
+//    written as they would be for a four lane SIMD register - four values
+//    loaded, four addends formed, four results stored - with a plain loop
+//    for any elements left over at the end of the row. There are two
+//    versions of the arithmetic:
+//
+//    csub_simd4()  each result is value + addend, as in every other kernel.
+//    csub_fma4()   each result is value * one + addend, where one is 1, but
+//                  read through black_box() so the compiler can't know it
+//                  and drop the multiply. That's exactly the shape of a
+//                  fused multiply-add, and the point is to see whether the
+//                  compiler contracts it into FMA instructions, what that
+//                  does to the speed, and whether the results stay bit for
+//                  bit the same as the plain add.
+//
+//    Since multiplying by one is exact, value * one + addend has the same
+//    correctly rounded value whether it's fused or not, so both versions
+//    should always give identical results. A difference would mean
+//    something other than contraction was going on.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::hint::black_box;
+
+use crate::crsarray::{Array2D, Element};
+
+//  The number of elements in a group - the lanes of a 128 bit register for
+//  f32.
+
+pub const LANES: usize = 4;
+
+pub fn csub_simd4<T: Element> (input_array: &Array2D<T>,
+                                             output_array: &mut Array2D<T>) {
+   by_lanes(input_array,output_array,|value,addend| value + addend);
+}
+
+pub fn csub_fma4<T: Element> (input_array: &Array2D<T>,
+                                             output_array: &mut Array2D<T>) {
+   let one = black_box(T::from_index(1));
+   by_lanes(input_array,output_array,|value,addend| value * one + addend);
+}
+
+//  Works through each row in groups of LANES, setting each output element
+//  to op(value,addend) for the corresponding input value and its index sum.
+
+#[inline(always)]
+fn by_lanes<T: Element,F> (input_array: &Array2D<T>,
+                output_array: &mut Array2D<T>,op: F) where F: Fn(T,T) -> T {
+   for iy in 0..input_array.ny() {
+      let in_groups = input_array.row(iy).chunks_exact(LANES);
+      let mut out_groups = output_array.row_mut(iy).chunks_exact_mut(LANES);
+      let in_rest = in_groups.remainder();
+      let mut ix = 0;
+      for (in_group,out_group) in in_groups.zip(&mut out_groups) {
+         let values = [in_group[0],in_group[1],in_group[2],in_group[3]];
+         let addends = [T::from_index(ix + iy),T::from_index(ix + 1 + iy),
+                    T::from_index(ix + 2 + iy),T::from_index(ix + 3 + iy)];
+         for lane in 0..LANES {
+            out_group[lane] = op(values[lane],addends[lane]);
+         }
+         ix += LANES;
+      }
+      let out_rest = out_groups.into_remainder();
+      for (i,(e,r)) in in_rest.iter().zip(out_rest.iter_mut()).enumerate() {
+         *r = op(*e,T::from_index(ix + i + iy));
+      }
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o rustc never contracts a multiply and an add into an FMA on its own.
+     Unlike C compilers, which may (gcc does by default, with
+     -ffp-contract=fast), it treats the two as separate operations, each
+     rounded, unless told otherwise with f32::mul_add(). So the expected
+     result is that csub_fma4() is just csub_simd4() with an extra multiply
+     per element - slower if anything, and never different. That's the
+     data point: the FMA-shaped expression costs a multiply and gains
+     nothing. Compiling with -C target-cpu=native doesn't change that,
+     though it does make FMA instructions available to mul_add().
+
+   o To see what the compiler did, get an assembly listing of the harness
+     (see crsasm.rs) and look in csub_fma4 for vmulps and vaddps, or fmul
+     and fadd, rather than a vfmadd or fmla.
+
+   o one has to be read through black_box(), once per call, since the
+     compiler knows that x * 1.0 is exactly x, and would otherwise remove
+     the multiply altogether, leaving csub_simd4().
+
+   o With integer elements the multiply is an integer one, and there's no
+     such thing as contraction, but the kernel still has to be generic to
+     be run as the flat modes are, eg by --compare-types.
+
+*/
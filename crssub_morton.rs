@@ -0,0 +1,134 @@
+//
+//                      c r s s u b _ m o r t o n . r s
+//
+// Summary:
+//    2D array access test subroutine in Rust, visiting the elements in
+//    Morton (Z) order.
+//
+// Introduction:
+//    This is a test routine written as part of a study into how well different
+//    languages handle accessing elements of 2D rectangular arrays. This routine
+//    is passed a 2D array (In) with Ny rows and Nx columns, and another 2D
+//    array of the same size (Out). It modifies Out so so each element of Out
+//    is set to the value of the corresponding element of In, plus the sum of
+//    the two index values for the element - ie plus the row number and the
+//    column number. The idea is trivial, but the operation isn't completely
+//    trivial to optimise, and the intention is to see how well this runs when
+//    compiled using different compilers, or using different options.
+//
+// This version:
+//    This version is for Rust, and uses the flat Array2D type (see
+//    crsarray.rs). Rather than going through the array row by row, it visits
+//    the elements in Morton order - also called Z-order - where the bits of
+//    the row and column numbers are interleaved to give a single index, and
+//    the elements are visited in order of that index. This works through
+//    the array in ever larger square blocks, which keeps elements that are
+//    close together in 2D close together in time. For this operation, which
+//    only ever looks at one element at a time, that's no help at all, and
+//    row-major order ought to win easily, but it's interesting to see by how
+//    much, and the pattern matters for operations that look at neighbours.
+//
+//    The row and column numbers are worked out from the Morton index on the
+//    fly, for each element. An array that isn't square, or whose sides
+//    aren't powers of two, is covered by the smallest 2^a by 2^b rectangle
+//    that holds it. Where a and b differ, only the low bits of the two
+//    numbers are interleaved and the extra high bits of the longer side go
+//    on top, so the array is covered by a row (or column) of Morton ordered
+//    squares. Codes that fall outside the array are skipped, and there are
+//    never more than four times as many codes as elements.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::crsarray::{Array2D, Element};
+
+//  The number of bits needed for indices 0 to n - 1, ie the log2 of n
+//  rounded up.
+
+fn index_bits (n: usize) -> u32 {
+   n.next_power_of_two().trailing_zeros()
+}
+
+//  Collects the even numbered bits of a value into its low 32 bits - the
+//  inverse of spreading them out to interleave them with another value.
+
+#[inline(always)]
+fn compact_bits (value: u64) -> u64 {
+   let mut bits = value & 0x5555_5555_5555_5555;
+   bits = (bits | (bits >> 1)) & 0x3333_3333_3333_3333;
+   bits = (bits | (bits >> 2)) & 0x0f0f_0f0f_0f0f_0f0f;
+   bits = (bits | (bits >> 4)) & 0x00ff_00ff_00ff_00ff;
+   bits = (bits | (bits >> 8)) & 0x0000_ffff_0000_ffff;
+   (bits | (bits >> 16)) & 0x0000_0000_ffff_ffff
+}
+
+pub fn csub_morton<T: Element> (input_array: &Array2D<T>,
+                                             output_array: &mut Array2D<T>) {
+   let nx = input_array.nx();
+   let ny = input_array.ny();
+   let x_bits = index_bits(nx);
+   let y_bits = index_bits(ny);
+
+   //  The low 2 * shared bits of a code are interleaved, column number in
+   //  the even bits, row number in the odd bits. Any bits above those
+   //  belong to the longer side.
+
+   let shared = x_bits.min(y_bits);
+   let low_mask = (1u64 << (2 * shared)) - 1;
+   let x_longer = x_bits > y_bits;
+   let codes = 1u64 << (x_bits + y_bits);
+   for code in 0..codes {
+      let low = code & low_mask;
+      let high = (code >> (2 * shared)) << shared;
+      let mut ix = compact_bits(low) as usize;
+      let mut iy = compact_bits(low >> 1) as usize;
+      if x_longer { ix |= high as usize; } else { iy |= high as usize; }
+      if ix < nx && iy < ny {
+         output_array[[ix,iy]] =
+                              input_array[[ix,iy]] + T::from_index(ix + iy);
+      }
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o Decoding each code costs a dozen or so shifts, ands and ors, which for
+     this operation is more than the work being done on the element. That
+     is part of the cost of Morton order, unless the indices are
+     precomputed, and a table of precomputed indices would itself have to
+     be read from memory, so it isn't obviously better.
+
+   o The codes are u64 whatever the size of usize. The bits needed for nx
+     and ny add up to at most two more than the bits in nx * ny, so for any
+     array that fits in memory the number of codes fits easily.
+
+   o The skipped codes are the ones that land beyond the far edges of the
+     array, and checking for them costs a couple of compares per code.
+     Relying on the bounds checks in the indexing instead isn't an option,
+     since they panic rather than skip.
+
+*/
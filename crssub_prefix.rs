@@ -0,0 +1,101 @@
+//
+//                       c r s s u b _ p r e f i x . r s
+//
+// Summary:
+//    2D array access test subroutine in Rust, setting each element of the
+//    output to the running sum of the input along its row.
+//
+// Introduction:
+//    This is a test routine written as part of a study into how well different
+//    languages handle accessing elements of 2D rectangular arrays. The usual
+//    routine is passed a 2D array (In) with Ny rows and Nx columns, and
+//    another of the same size (Out), and sets each element of Out to the
+//    corresponding element of In plus the sum of its two index values. Every
+//    element of that can be worked out independently of every other, which
+//    is what lets a compiler vectorise it. This routine computes a prefix
+//    sum along each row instead: element (ix,iy) of Out is the sum of the
+//    elements of In from (0,iy) to (ix,iy) inclusive. Each element depends
+//    on the one before it, a dependency carried from one iteration of the
+//    loop to the next, so the additions along a row have to be done one
+//    after another, each waiting for the last - which makes this a test of
+//    the latency of an addition rather than of the memory bandwidth.
+//
+// This version:
+//    This version is for Rust, and uses the flat Array2D type (see
+//    crsarray.rs), with a running total kept for each row and added to
+//    each input element in turn, in the obvious order, as in crssub1d.rs.
+//    The rows are independent of each other, but the loop over a row is
+//    the inner loop, so nothing here makes use of that.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::crsarray::{Array2D, Element};
+
+//  The additions made for each element - one, adding it to the total so
+//  far. The harness's operation count (--count-ops) expects this many.
+
+pub const ADDITIONS: usize = 1;
+
+pub fn csub_prefix<T: Element> (input_array: &Array2D<T>,
+                                             output_array: &mut Array2D<T>) {
+   for iy in 0..input_array.ny() {
+      let in_row = input_array.row(iy);
+      let out_row = output_array.row_mut(iy);
+      let mut total = T::from_index(0);
+      for (out,value) in out_row.iter_mut().zip(in_row) {
+         total = total + *value;
+         *out = total;
+      }
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The total starts at zero and every element is added to it, the first
+     included, so there is exactly one addition per element, and the
+     MODES table (see crsconfig.rs) gives one flop and 8 bytes an element -
+     the same arithmetic intensity as the flat mode, which makes the
+     difference in their times all the more telling.
+
+   o For floating point elements the compiler can't reassociate the sum,
+     so it can't use the usual tree of partial sums across the lanes of a
+     vector; it could for the integer types, where addition is associative,
+     and sometimes does, so the integer times from --compare-types can be
+     quite different from the floating point ones.
+
+   o verify_prefix() (see crsverify.rs) forms the same running sums, in
+     the same order and in the same type, so for floating point the
+     results have to match exactly, rounding and all, and any
+     reassociation would show up as a mismatch.
+
+   o With a large array, or large input values, the running sums can get
+     big: for f32 they lose precision, but the check expects just that,
+     and for the integer types they could overflow, which the usual inputs
+     and array sizes don't come close to.
+
+*/
@@ -0,0 +1,103 @@
+//
+//                      c r s s u b _ r a w p t r . r s
+//
+// Summary:
+//    2D array access test subroutine in Rust, using raw pointers.
+//
+// Introduction:
+//    This is a test routine written as part of a study into how well different
+//    languages handle accessing elements of 2D rectangular arrays. This routine
+//    is passed a 2D array (In) with Ny rows and Nx columns, and another 2D
+//    array of the same size (Out). It modifies Out so so each element of Out
+//    is set to the value of the corresponding element of In, plus the sum of
+//    the two index values for the element - ie plus the row number and the
+//    column number. The idea is trivial, but the operation isn't completely
+//    trivial to optimise, and the intention is to see how well this runs when
+//    compiled using different compilers, or using different options.
+//
+// This version:
+//    This version is for Rust, and uses the flat Array2D type (see
+//    crsarray.rs). It is as close as Rust gets to the way this would be
+//    written in C: it takes raw pointers to the start of each row of the two
+//    arrays and walks them along the row, one element at a time, using
+//    pointer arithmetic. The unsafe version, crssub_unsafe.rs, still indexes
+//    the rows, just without the bounds checks, and it's interesting to see
+//    whether the optimiser treats the two any differently.
+//
+// Safety:
+//    The unsafe block relies on the following, all of which are checked or
+//    guaranteed before any pointer is used:
+//
+//    o The two arrays have the same dimensions. This is asserted at the
+//      start, so each output row is exactly as long as its input row.
+//    o Each pointer stays within its row. Both start at the first element
+//      of a row slice of nx elements, and are advanced nx times - the last
+//      advance leaves them one past the end, which is allowed, and they are
+//      never dereferenced there.
+//    o The input and output don't overlap. The input is a shared reference
+//      and the output a mutable one, so the borrow checker guarantees they
+//      are different arrays.
+//    o The elements are initialised and properly aligned, since they come
+//      from the slices of an Array2D.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::crsarray::{Array2D, Element};
+
+pub fn csub_rawptr<T: Element> (input_array: &Array2D<T>,
+                                             output_array: &mut Array2D<T>) {
+   let nx = input_array.nx();
+   assert!(output_array.nx() == nx && output_array.ny() == input_array.ny());
+   for iy in 0..input_array.ny() {
+      let mut in_ptr: *const T = input_array.row(iy).as_ptr();
+      let mut out_ptr: *mut T = output_array.row_mut(iy).as_mut_ptr();
+      for ix in 0..nx {
+
+         //  See the Safety section above: both pointers are within rows of
+         //  nx elements, the arrays are distinct, and ix < nx.
+
+         unsafe {
+            *out_ptr = *in_ptr + T::from_index(ix + iy);
+            in_ptr = in_ptr.add(1);
+            out_ptr = out_ptr.add(1);
+         }
+      }
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The pointers are taken afresh for each row, rather than once for the
+     whole array, so the code doesn't rely on the rows being contiguous -
+     even though in an Array2D they are.
+
+   o Because T is Copy, reading *in_ptr and writing *out_ptr don't run any
+     destructors, so plain assignment through the pointer is fine - there's
+     no need for ptr::read() or ptr::write().
+
+*/
@@ -0,0 +1,241 @@
+//
+//                   c r s s u b _ r e g t r a n s p o s e . r s
+//
+// Summary:
+//    2D array access test subroutine in Rust, writing the results out
+//    transposed, a 4 x 4 block at a time, with the blocks transposed in
+//    registers.
+//
+// Introduction:
+//    This is a test routine written as part of a study into how well different
+//    languages handle accessing elements of 2D rectangular arrays. The usual
+//    routine is passed a 2D array (In) with Ny rows and Nx columns, and
+//    another of the same size (Out), and sets each element of Out to the
+//    corresponding element of In plus the sum of its two index values. This
+//    one does the same sum, but writes its result for element (ix,iy) of In
+//    to element (iy,ix) of Out, which it treats as an array of Nx rows of Ny
+//    columns - so Out ends up holding the transpose of the usual results.
+//
+//    A transpose written the obvious way reads along the rows of one array
+//    and writes down the columns of the other, and one of the two is always
+//    going the wrong way through memory. The classic way round that is to
+//    work in small square blocks: load a few rows of a block into registers,
+//    transpose the block inside the registers with shuffles, and write the
+//    registers out as rows of the transposed block. Every load and every
+//    store is then a contiguous run of elements.
+//
+// This version:
+//    This version is for Rust, and uses the flat Array2D type (see
+//    crsarray.rs), in blocks of BLOCK (four) rows of four columns. With the
+//    simd feature, on x86_64, f32 arrays are handled with SSE intrinsics:
+//    each row of a block is loaded into a 128 bit register, the index sums
+//    are added four at a time, and the four registers are transposed with
+//    the usual unpack and move shuffles - the sequence of the C macro
+//    _MM_TRANSPOSE4_PS. Otherwise - other element types, other processors,
+//    or a build without the feature - the same blocks are handled in plain
+//    Rust, loaded into a small local array that stands in for the registers
+//    and transposed there. Either way, the elements left over at the right
+//    hand side and the bottom of the array, when the dimensions aren't
+//    multiples of four, are handled one at a time.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+use std::any::Any;
+
+use crate::crsarray::{Array2D, Element};
+
+//  The size of the blocks - four rows of four f32 values, one 128 bit
+//  register for each row.
+
+pub const BLOCK: usize = 4;
+
+pub fn csub_regtranspose<T: Element> (input_array: &Array2D<T>,
+                                             output_array: &mut Array2D<T>) {
+   #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+   {
+      let input: &dyn Any = input_array;
+      let output: &mut dyn Any = &mut *output_array;
+      if let (Some(input),Some(output)) = (input.downcast_ref::<Array2D<f32>>(),
+                                       output.downcast_mut::<Array2D<f32>>()) {
+         if sse::usable(input) {
+            sse::by_blocks(input,output);
+            edges(input,output);
+            return;
+         }
+      }
+   }
+   by_blocks(input_array,output_array);
+   edges(input_array,output_array);
+}
+
+//  Handles all the complete blocks, in plain Rust.
+
+fn by_blocks<T: Element> (input_array: &Array2D<T>,
+                                             output_array: &mut Array2D<T>) {
+   let nx = input_array.nx();
+   let ny = input_array.ny();
+   let output = output_array.as_mut_slice();
+   for iy0 in (0..ny - ny % BLOCK).step_by(BLOCK) {
+      for ix0 in (0..nx - nx % BLOCK).step_by(BLOCK) {
+
+         //  Load the block, a row at a time, adding the index sums, with
+         //  each row going into a column of the block, so it ends up
+         //  transposed, then store each row of the transpose as part of a
+         //  row of the output.
+
+         let mut block = [[T::from_index(0); BLOCK]; BLOCK];
+         for (i,iy) in (iy0..iy0 + BLOCK).enumerate() {
+            let row = &input_array.row(iy)[ix0..ix0 + BLOCK];
+            for (j,&value) in row.iter().enumerate() {
+               block[j][i] = value + T::from_index(ix0 + j + iy);
+            }
+         }
+         for (j,values) in block.iter().enumerate() {
+            let start = (ix0 + j) * ny + iy0;
+            output[start..start + BLOCK].copy_from_slice(values);
+         }
+      }
+   }
+}
+
+//  Handles the elements that aren't in a complete block - the columns past
+//  the last multiple of BLOCK, and the rows past the last multiple of BLOCK.
+
+fn edges<T: Element> (input_array: &Array2D<T>,
+                                             output_array: &mut Array2D<T>) {
+   let nx = input_array.nx();
+   let ny = input_array.ny();
+   let full_nx = nx - nx % BLOCK;
+   let full_ny = ny - ny % BLOCK;
+   let output = output_array.as_mut_slice();
+   for iy in 0..ny {
+      let row = input_array.row(iy);
+      let first = if iy < full_ny { full_nx } else { 0 };
+      for ix in first..nx {
+         output[ix * ny + iy] = row[ix] + T::from_index(ix + iy);
+      }
+   }
+}
+
+//  The SSE version of by_blocks(), for f32 on x86_64, where SSE2 is always
+//  available.
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod sse {
+   use std::arch::x86_64::{_mm_add_epi32, _mm_add_ps, _mm_cvtepi32_ps,
+                           _mm_loadu_ps, _mm_movehl_ps, _mm_movelh_ps,
+                           _mm_set1_epi32, _mm_setr_epi32, _mm_setzero_ps,
+                           _mm_storeu_ps, _mm_unpackhi_ps, _mm_unpacklo_ps};
+
+   use super::BLOCK;
+   use crate::crsarray::Array2D;
+
+   //  The index sums are converted to f32 four at a time from 32 bit
+   //  integers, which gives exactly what ix + iy as f32 does so long as
+   //  they fit. If they might not, the plain Rust version is used.
+
+   pub fn usable (input_array: &Array2D<f32>) -> bool {
+      input_array.nx() + input_array.ny() <= i32::MAX as usize
+   }
+
+   pub fn by_blocks (input_array: &Array2D<f32>,
+                                             output_array: &mut Array2D<f32>) {
+      let nx = input_array.nx();
+      let ny = input_array.ny();
+      let output = output_array.as_mut_slice();
+
+      //  Safety: the loads and stores are each of four f32 values, through
+      //  pointers to slices that the indexing has checked are four long.
+      //  Everything else needs only SSE2, which every x86_64 has.
+
+      unsafe {
+         let steps = _mm_setr_epi32(0,1,2,3);
+         for iy0 in (0..ny - ny % BLOCK).step_by(BLOCK) {
+            for ix0 in (0..nx - nx % BLOCK).step_by(BLOCK) {
+               let mut rows = [_mm_setzero_ps(); BLOCK];
+               for (i,register) in rows.iter_mut().enumerate() {
+                  let row = &input_array.row(iy0 + i)[ix0..ix0 + BLOCK];
+                  let sums = _mm_add_epi32(
+                            _mm_set1_epi32((ix0 + iy0 + i) as i32),steps);
+                  *register = _mm_add_ps(_mm_loadu_ps(row.as_ptr()),
+                                                      _mm_cvtepi32_ps(sums));
+               }
+
+               //  The transpose: rows a, b, c and d become the columns
+               //  a0 b0 c0 d0, a1 b1 c1 d1, and so on.
+
+               let ab_low = _mm_unpacklo_ps(rows[0],rows[1]);
+               let cd_low = _mm_unpacklo_ps(rows[2],rows[3]);
+               let ab_high = _mm_unpackhi_ps(rows[0],rows[1]);
+               let cd_high = _mm_unpackhi_ps(rows[2],rows[3]);
+               let columns = [_mm_movelh_ps(ab_low,cd_low),
+                              _mm_movehl_ps(cd_low,ab_low),
+                              _mm_movelh_ps(ab_high,cd_high),
+                              _mm_movehl_ps(cd_high,ab_high)];
+               for (j,column) in columns.iter().enumerate() {
+                  let start = (ix0 + j) * ny + iy0;
+                  let out = &mut output[start..start + BLOCK];
+                  _mm_storeu_ps(out.as_mut_ptr(),*column);
+               }
+            }
+         }
+      }
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The output array has the same Array2D shape as the input, nx columns
+     of ny rows, but the kernel ignores that and uses its elements as ny
+     columns of nx rows. For a square array that's the same thing. The
+     results are checked with verify_transpose() (see crsverify.rs), and
+     none of the element-wise diagnostics apply.
+
+   o The modes are generic over the element type, so the SSE version is
+     picked by checking, with Any, whether the arrays are of f32 - a
+     check the compiler resolves for each element type it generates the
+     kernel for. That needs the element types to be 'static, which they
+     all are (see Element, in crsarray.rs).
+
+   o Each block is read as four contiguous runs of four from four rows of
+     the input, and written as four contiguous runs of four into four rows
+     of the output, so both arrays are read and written 16 bytes at a time.
+     The rows of a block are still a long way apart in a large array,
+     which is what a blocked transpose with larger tiles tries to fix - this
+     only fixes the access within a block.
+
+   o An 8 x 8 transpose with AVX would need the processor checking at run
+     time, or the program building with -C target-cpu, and SSE2 is always
+     there on x86_64, so this sticks to 4 x 4.
+
+   o The scalar version's local block isn't guaranteed to end up in
+     registers - that's up to the compiler - but it is a register's worth
+     of values per row, and with optimisation it usually does.
+
+*/
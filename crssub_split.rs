@@ -0,0 +1,157 @@
+//
+//                        c r s s u b _ s p l i t . r s
+//
+// Summary:
+//    2D array access test subroutine in Rust, dividing the rows between
+//    threads by splitting them in half, and in half again.
+//
+// Introduction:
+//    This is a test routine written as part of a study into how well different
+//    languages handle accessing elements of 2D rectangular arrays. This routine
+//    is passed a 2D array (In) with Ny rows and Nx columns, and another 2D
+//    array of the same size (Out). It modifies Out so so each element of Out
+//    is set to the value of the corresponding element of In, plus the sum of
+//    the two index values for the element - ie plus the row number and the
+//    column number. The idea is trivial, but the operation isn't completely
+//    trivial to optimise, and the intention is to see how well this runs when
+//    compiled using different compilers, or using different options.
+//
+// This version:
+//    This version is for Rust, and uses the flat Array2D type (see
+//    crsarray.rs). Like crssub_threads.rs it divides the rows between a
+//    number of scoped threads, but rather than working out all the bands
+//    first it divides and conquers: the output rows are split in two with
+//    split_at_mut(), one half goes to a newly spawned thread, and the
+//    thread doing the splitting carries on with the other, splitting it in
+//    turn, until each thread has a single band. The threads are shared out
+//    between the halves in proportion, and so are the rows, so it works for
+//    any number of threads, not just a power of two. split_at_mut() only
+//    ever hands out slices that don't overlap, so this is parallel code
+//    whose safety the compiler checks, with no unsafe code at all.
+//
+//    As for crssub_threads.rs, there is an instrumented version,
+//    csub_split_counted(), which also counts the writes to each element, so
+//    the harness can check that the halves cover every element once.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread::{self, Scope};
+
+use crate::crsarray::{Array2D, Element};
+
+pub fn csub_split<T: Element> (input_array: &Array2D<T>,
+                     output_array: &mut Array2D<T>,nthreads: usize) {
+   csub_halves::<T,false>(input_array,output_array,nthreads,&[]);
+}
+
+//  As csub_split(), but also increments counters[iy * nx + ix] each time
+//  element (ix,iy) is written. The counters array must have nx * ny elements.
+
+pub fn csub_split_counted<T: Element> (input_array: &Array2D<T>,
+                     output_array: &mut Array2D<T>,nthreads: usize,
+                                                      counters: &[AtomicU32]) {
+   csub_halves::<T,true>(input_array,output_array,nthreads,counters);
+}
+
+//  The code common to both versions. COUNTED is a compile time constant, so
+//  the version without the counters has no trace of them.
+
+fn csub_halves<T: Element,const COUNTED: bool> (input_array: &Array2D<T>,
+                     output_array: &mut Array2D<T>,nthreads: usize,
+                                                      counters: &[AtomicU32]) {
+   thread::scope(|scope| {
+      split::<T,COUNTED>(scope,input_array,output_array.as_mut_slice(),0,
+                                                 nthreads.max(1),counters);
+   });
+}
+
+//  Handles the rows of the output from row first on, which are all of
+//  band, using nthreads threads, including this one. With more than one
+//  thread and more than one row, the rows are split in two, the first part
+//  is handed to a new thread with its share of the threads, and this
+//  thread goes on with the rest. Otherwise the rows are simply processed.
+
+fn split<'scope,'env,T: Element,const COUNTED: bool> (
+                     scope: &'scope Scope<'scope,'env>,
+                     input_array: &'scope Array2D<T>,band: &'scope mut [T],
+                     first: usize,nthreads: usize,
+                                           counters: &'scope [AtomicU32]) {
+   let nx = input_array.nx();
+   let rows = band.len() / nx;
+   if nthreads > 1 && rows > 1 {
+      let spawned_threads = nthreads / 2;
+      let spawned_rows = rows * spawned_threads / nthreads;
+      let (spawned_band,rest) = band.split_at_mut(spawned_rows * nx);
+      scope.spawn(move || split::<T,COUNTED>(scope,input_array,spawned_band,
+                                           first,spawned_threads,counters));
+      split::<T,COUNTED>(scope,input_array,rest,first + spawned_rows,
+                                           nthreads - spawned_threads,counters);
+      return;
+   }
+   for (irow,out_row) in band.chunks_mut(nx).enumerate() {
+      let iy = first + irow;
+      let in_row = input_array.row(iy);
+      for ix in 0..nx {
+         out_row[ix] = in_row[ix] + T::from_index(ix + iy);
+         if COUNTED {
+            counters[iy * nx + ix].fetch_add(1,Ordering::Relaxed);
+         }
+      }
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o With nthreads threads, nthreads - 1 are spawned, since the thread
+     doing the splitting handles the last band itself, where csub_threads()
+     spawns one for every band and leaves the calling thread waiting. Both
+     create their threads afresh for each call, so the same caveat about
+     small arrays applies.
+
+   o The spawning is a tree, so with many threads the last ones start
+     after log2(nthreads) spawns rather than nthreads, though for the
+     thread counts of a typical machine the difference is small.
+
+   o The compiler does the checking that matters. Each half is a separate
+     &mut slice, moved into the thread that owns it, and the scope makes
+     sure every thread has finished before the output array can be used
+     again. What it can't check is that the halves cover all the rows -
+     which is what csub_split_counted() and --check-coverage are for.
+
+   o The bands can differ in size by a row, as for csub_threads(), but
+     they aren't necessarily the same bands, because the rows are shared
+     out at each split rather than all at once.
+
+   o There's no rayon version in the harness to compare it with. The
+     nearest comparison is with the threads mode, which does the same work
+     in bands shared out all at once - running crsbench with --mode split
+     and then --mode threads, with the same array size and --threads,
+     shows whether the recursive sharing out costs anything.
+
+*/
@@ -0,0 +1,128 @@
+//
+//                     c r s s u b _ s t r i d e d . r s
+//
+// Summary:
+//    2D array access test subroutine in Rust, scattering the results through
+//    the output array with a fixed stride.
+//
+// Introduction:
+//    This is a test routine written as part of a study into how well different
+//    languages handle accessing elements of 2D rectangular arrays. This routine
+//    is passed a 2D array (In) with Ny rows and Nx columns, and another 2D
+//    array of the same size (Out). It modifies Out so so each element of Out
+//    is set to the value of the corresponding element of In, plus the sum of
+//    the two index values for the element - ie plus the row number and the
+//    column number. The idea is trivial, but the operation isn't completely
+//    trivial to optimise, and the intention is to see how well this runs when
+//    compiled using different compilers, or using different options.
+//
+// This version:
+//    This version is for Rust, and uses the flat Array2D type (see
+//    crsarray.rs). It reads the input in memory order, as usual, but it
+//    doesn't write the results where they belong. Instead, successive
+//    results are written Stride elements apart in the output, wrapping round
+//    to the start when they get to the end - so the result for the element
+//    at offset k in the input goes to offset (k * Stride) mod (Nx * Ny) in
+//    the output. This is a deliberately unfriendly scatter pattern, and
+//    stresses the write side of the memory system, and the TLB, in a way the
+//    other versions don't.
+//
+//    So that every element of the output is written exactly once, the stride
+//    must be less than Nx * Ny and have no factor in common with it - see
+//    stride_problem(). gather() puts the results back in their proper
+//    places, so they can be checked in the usual way.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::crsarray::{Array2D, Element};
+
+//  Returns a description of what's wrong with a stride for an array of len
+//  elements, or None if it can be used.
+
+pub fn stride_problem (stride: usize,len: usize) -> Option<String> {
+   if stride == 0 || stride >= len {
+      return Some(format!("The output stride must be between 1 and {}",
+                                                      len.saturating_sub(1)));
+   }
+   let common = gcd(stride,len);
+   if common != 1 {
+      return Some(format!("The output stride {} and the number of elements \
+              {} have a common factor of {}, so some elements would be \
+              written more than once",stride,len,common));
+   }
+   None
+}
+
+fn gcd (a: usize,b: usize) -> usize {
+   if b == 0 { a } else { gcd(b,a % b) }
+}
+
+//  The kernel. The stride must have been checked using stride_problem(), but
+//  a bad one just leads to a wrong answer (or, if it's too big, a panic),
+//  since all the indexing is checked.
+
+pub fn csub_strided<T: Element> (input_array: &Array2D<T>,
+                            output_array: &mut Array2D<T>,stride: usize) {
+   let len = input_array.len();
+   let out = output_array.as_mut_slice();
+   let mut offset = 0;
+   for iy in 0..input_array.ny() {
+      let in_row = input_array.row(iy);
+      for (ix,&value) in in_row.iter().enumerate() {
+         out[offset] = value + T::from_index(ix + iy);
+         offset += stride;
+         if offset >= len { offset -= len; }
+      }
+   }
+}
+
+//  Undoes the scatter, returning an array with each result where it would
+//  have been written by the ordinary kernels.
+
+pub fn gather<T: Element> (output_array: &Array2D<T>,stride: usize)
+                                                               -> Array2D<T> {
+   let nx = output_array.nx();
+   let len = output_array.len() as u128;
+   let out = output_array.as_slice();
+   Array2D::from_fn(nx,output_array.ny(),|ix,iy| {
+      let offset = ((iy * nx + ix) as u128 * stride as u128) % len;
+      out[offset as usize]
+   })
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The output offset is kept up to date by adding the stride and wrapping
+     with a single subtraction, which works because the stride is always
+     less than the array length. That avoids a multiplication and a
+     division for every element.
+
+   o gather() does the multiplication, in u128 so that it can't overflow,
+     but it's only used for checking, outside the timing.
+
+*/
@@ -0,0 +1,138 @@
+//
+//                      c r s s u b _ t h r e a d s . r s
+//
+// Summary:
+//    2D array access test subroutine in Rust, using multiple threads.
+//
+// Introduction:
+//    This is a test routine written as part of a study into how well different
+//    languages handle accessing elements of 2D rectangular arrays. This routine
+//    is passed a 2D array (In) with Ny rows and Nx columns, and another 2D
+//    array of the same size (Out). It modifies Out so so each element of Out
+//    is set to the value of the corresponding element of In, plus the sum of
+//    the two index values for the element - ie plus the row number and the
+//    column number. The idea is trivial, but the operation isn't completely
+//    trivial to optimise, and the intention is to see how well this runs when
+//    compiled using different compilers, or using different options.
+//
+// This version:
+//    This version is for Rust, and uses the flat Array2D type (see crsarray.rs)
+//    rather than vectors of vectors. It is generic, and works with any element
+//    type that implements the Element trait. The rows of the array are split
+//    into a number of contiguous bands, one for each thread, and each band is
+//    processed by its own thread. The threads are scoped threads, created
+//    using std::thread::scope(), so they can borrow the arrays directly, and
+//    they are given disjoint mutable slices of the output array, so there is
+//    no unsafe code here at all.
+//
+//    There is also an instrumented version, csub_threads_counted(), which
+//    does exactly the same work but also increments a counter for each
+//    element it writes. That is very much slower, and is only there so that
+//    the harness can check that the bands cover every element exactly once.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//    14th Oct 2026. Made generic over the Element trait. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::mem;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread;
+
+use crate::crsarray::{Array2D, Element};
+
+//  Splits ny rows into nthreads contiguous bands, as near equal in size as
+//  possible, returning the first row and one past the last row of each band.
+//  If there are more threads than rows, some bands will be empty.
+
+pub fn row_bands (ny: usize,nthreads: usize) -> Vec<(usize,usize)> {
+   let nthreads = nthreads.max(1);
+   (0..nthreads).map(|ithread|
+         (ithread * ny / nthreads,(ithread + 1) * ny / nthreads)).collect()
+}
+
+pub fn csub_threads<T: Element> (input_array: &Array2D<T>,
+                     output_array: &mut Array2D<T>,nthreads: usize) {
+   csub_bands::<T,false>(input_array,output_array,nthreads,&[]);
+}
+
+//  As csub_threads(), but also increments counters[iy * nx + ix] each time
+//  element (ix,iy) is written. The counters array must have nx * ny elements.
+
+pub fn csub_threads_counted<T: Element> (input_array: &Array2D<T>,
+                     output_array: &mut Array2D<T>,nthreads: usize,
+                                                      counters: &[AtomicU32]) {
+   csub_bands::<T,true>(input_array,output_array,nthreads,counters);
+}
+
+//  The code common to both versions. COUNTED is a compile time constant, so
+//  the version without the counters has no trace of them.
+
+fn csub_bands<T: Element,const COUNTED: bool> (input_array: &Array2D<T>,
+                     output_array: &mut Array2D<T>,nthreads: usize,
+                                                      counters: &[AtomicU32]) {
+   let nx = input_array.nx();
+   let bands = row_bands(input_array.ny(),nthreads);
+   thread::scope(|scope| {
+
+      //  Each band of rows is split off the front of what's left of the
+      //  output array, so the threads get separate slices.
+
+      let mut rest = output_array.as_mut_slice();
+      for &(first,last) in &bands {
+         let band_size = (last - first) * nx;
+         let (band,tail) = mem::take(&mut rest).split_at_mut(band_size);
+         rest = tail;
+         scope.spawn(move || {
+            for (irow,out_row) in band.chunks_mut(nx).enumerate() {
+               let iy = first + irow;
+               let in_row = input_array.row(iy);
+               for ix in 0..nx {
+                  out_row[ix] = in_row[ix] + T::from_index(ix + iy);
+                  if COUNTED {
+                     counters[iy * nx + ix].fetch_add(1,Ordering::Relaxed);
+                  }
+               }
+            }
+         });
+      }
+   });
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The threads are created afresh for each call, which is a significant
+     overhead for small arrays - for the default 10 rows of 2000 columns it
+     easily outweighs any gain from the parallelism. Larger arrays are needed
+     to see any benefit.
+
+   o Since the output bands are separate slices, the borrow checker makes it
+     impossible for two threads to write the same element. What it can't do
+     is make sure the bands cover the whole array - a mistake in row_bands()
+     could quite happily leave rows unprocessed - which is what the counted
+     version is there to check.
+
+*/
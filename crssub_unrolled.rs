@@ -0,0 +1,180 @@
+//
+//                    c r s s u b _ u n r o l l e d . r s
+//
+// Summary:
+//    2D array access test subroutine in Rust, with the inner loop unrolled
+//    by hand.
+//
+// Introduction:
+//    This is a test routine written as part of a study into how well different
+//    languages handle accessing elements of 2D rectangular arrays. This routine
+//    is passed a 2D array (In) with Ny rows and Nx columns, and another 2D
+//    array of the same size (Out). It modifies Out so so each element of Out
+//    is set to the value of the corresponding element of In, plus the sum of
+//    the two index values for the element - ie plus the row number and the
+//    column number. The idea is trivial, but the operation isn't completely
+//    trivial to optimise, and the intention is to see how well this runs when
+//    compiled using different compilers, or using different options.
+//
+// This version:
+//    This version is for Rust, and uses the flat Array2D type (see
+//    crsarray.rs). It goes through the array in the same order as the flat
+//    version, crssub1d.rs, but each pass of the inner loop handles N
+//    columns, written out as N separate statements, with a plain loop for
+//    the columns left over at the end of each row when nx isn't a multiple
+//    of N. This is manual unrolling, the classic hand optimisation, and it
+//    is there to see whether doing it helps or hinders an optimiser that
+//    will usually unroll - and vectorise - the plain loop anyway.
+//
+//    The unroll factor N can be 1, 2, 4 or 8, each a separate function.
+//    With N of 1 the code is the same shape as the others, with the main
+//    loop and the remainder, but nothing unrolled, so comparing it with
+//    crssub1d.rs shows what the shape itself costs.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::crsarray::{Array2D, Element};
+
+//  The unroll factors there are versions for.
+
+pub const UNROLL_FACTORS: [usize; 4] = [1,2,4,8];
+
+//  Calls the version for the given unroll factor, which must be one of
+//  UNROLL_FACTORS. BenchConfigBuilder::check() (see crsconfig.rs) rejects
+//  any other factor, however the configuration was set up, so the panic is
+//  for a caller that makes up its own.
+
+pub fn csub_unrolled<T: Element> (input_array: &Array2D<T>,
+                                output_array: &mut Array2D<T>,factor: usize) {
+   match factor {
+      1 => csub_unrolled_1(input_array,output_array),
+      2 => csub_unrolled_2(input_array,output_array),
+      4 => csub_unrolled_4(input_array,output_array),
+      8 => csub_unrolled_8(input_array,output_array),
+      _ => panic!("There is no version unrolled by {}",factor),
+   }
+}
+
+fn csub_unrolled_1<T: Element> (input_array: &Array2D<T>,
+                                             output_array: &mut Array2D<T>) {
+   let nx = input_array.nx();
+   for iy in 0..input_array.ny() {
+      let in_row = input_array.row(iy);
+      let out_row = output_array.row_mut(iy);
+      let mut ix = 0;
+      while ix < nx {
+         out_row[ix] = in_row[ix] + T::from_index(ix + iy);
+         ix += 1;
+      }
+   }
+}
+
+fn csub_unrolled_2<T: Element> (input_array: &Array2D<T>,
+                                             output_array: &mut Array2D<T>) {
+   let nx = input_array.nx();
+   let main = nx - nx % 2;
+   for iy in 0..input_array.ny() {
+      let in_row = input_array.row(iy);
+      let out_row = output_array.row_mut(iy);
+      let mut ix = 0;
+      while ix < main {
+         out_row[ix] = in_row[ix] + T::from_index(ix + iy);
+         out_row[ix + 1] = in_row[ix + 1] + T::from_index(ix + 1 + iy);
+         ix += 2;
+      }
+      while ix < nx {
+         out_row[ix] = in_row[ix] + T::from_index(ix + iy);
+         ix += 1;
+      }
+   }
+}
+
+fn csub_unrolled_4<T: Element> (input_array: &Array2D<T>,
+                                             output_array: &mut Array2D<T>) {
+   let nx = input_array.nx();
+   let main = nx - nx % 4;
+   for iy in 0..input_array.ny() {
+      let in_row = input_array.row(iy);
+      let out_row = output_array.row_mut(iy);
+      let mut ix = 0;
+      while ix < main {
+         out_row[ix] = in_row[ix] + T::from_index(ix + iy);
+         out_row[ix + 1] = in_row[ix + 1] + T::from_index(ix + 1 + iy);
+         out_row[ix + 2] = in_row[ix + 2] + T::from_index(ix + 2 + iy);
+         out_row[ix + 3] = in_row[ix + 3] + T::from_index(ix + 3 + iy);
+         ix += 4;
+      }
+      while ix < nx {
+         out_row[ix] = in_row[ix] + T::from_index(ix + iy);
+         ix += 1;
+      }
+   }
+}
+
+fn csub_unrolled_8<T: Element> (input_array: &Array2D<T>,
+                                             output_array: &mut Array2D<T>) {
+   let nx = input_array.nx();
+   let main = nx - nx % 8;
+   for iy in 0..input_array.ny() {
+      let in_row = input_array.row(iy);
+      let out_row = output_array.row_mut(iy);
+      let mut ix = 0;
+      while ix < main {
+         out_row[ix] = in_row[ix] + T::from_index(ix + iy);
+         out_row[ix + 1] = in_row[ix + 1] + T::from_index(ix + 1 + iy);
+         out_row[ix + 2] = in_row[ix + 2] + T::from_index(ix + 2 + iy);
+         out_row[ix + 3] = in_row[ix + 3] + T::from_index(ix + 3 + iy);
+         out_row[ix + 4] = in_row[ix + 4] + T::from_index(ix + 4 + iy);
+         out_row[ix + 5] = in_row[ix + 5] + T::from_index(ix + 5 + iy);
+         out_row[ix + 6] = in_row[ix + 6] + T::from_index(ix + 6 + iy);
+         out_row[ix + 7] = in_row[ix + 7] + T::from_index(ix + 7 + iy);
+         ix += 8;
+      }
+      while ix < nx {
+         out_row[ix] = in_row[ix] + T::from_index(ix + iy);
+         ix += 1;
+      }
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The statements really are written out by hand, rather than generated
+     by a macro or a loop over a const generic count, since the point is to
+     see what the optimiser makes of code written that way. A fixed length
+     inner loop would just be the chunked version, crssub_chunks.rs.
+
+   o The rows are slices, and each access is bounds checked. The optimiser
+     can usually see that ix + 7 < main <= nx, and drop the checks in the
+     main loop, but whether it does is part of what's being measured.
+
+   o The remainder loop is where unrolled code usually goes wrong, so the
+     self test runs every factor on an array whose width leaves a remainder
+     for all of them but 1.
+
+*/
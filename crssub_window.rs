@@ -0,0 +1,135 @@
+//
+//                      c r s s u b _ w i n d o w . r s
+//
+// Summary:
+//    2D array access test subroutine in Rust, working through the array in
+//    a sliding window of rows.
+//
+// Introduction:
+//    This is a test routine written as part of a study into how well different
+//    languages handle accessing elements of 2D rectangular arrays. This routine
+//    is passed a 2D array (In) with Ny rows and Nx columns, and another 2D
+//    array of the same size (Out). It modifies Out so so each element of Out
+//    is set to the value of the corresponding element of In, plus the sum of
+//    the two index values for the element - ie plus the row number and the
+//    column number. The idea is trivial, but the operation isn't completely
+//    trivial to optimise, and the intention is to see how well this runs when
+//    compiled using different compilers, or using different options.
+//
+// This version:
+//    This version is for Rust, and uses the flat Array2D type (see
+//    crsarray.rs). Rather than going through the whole array once, it works
+//    on a window of W rows at a time, processing every row in the window,
+//    then moving the window on by S rows and processing it again, until the
+//    window reaches the last row. With S less than W each row is processed
+//    several times while it is in the window, and only the W rows of the
+//    window are being used at any time, so it models streaming processing
+//    with a bounded working set: the window can stay in cache, however big
+//    the array. The output at the end of each call is exactly that of the
+//    other versions, since every row is processed at least once and
+//    processing a row again gives the same values.
+//
+//    The last window is cut short if it would run past the end of the
+//    array, so with ny rows the windows start at rows 0, S, 2S ... up to
+//    the first start for which start + W reaches ny.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::crsarray::{Array2D, Element};
+
+//  Returns a description of what's wrong with a window of window rows that
+//  moves on by stride rows, for an array of ny rows, or None if it can be
+//  used. Every row has to be covered, so the window can't move on by more
+//  than its own height.
+
+pub fn window_problem (ny: usize,window: usize,stride: usize)
+                                                          -> Option<String> {
+   if window == 0 || stride == 0 {
+      return Some("The window and its stride must not be zero".to_string());
+   }
+   if window > ny {
+      return Some(format!("A window of {} rows is taller than the array, \
+                                             which has {}",window,ny));
+   }
+   if stride > window {
+      return Some(format!("A window stride of {} rows would skip rows, \
+            since the window is only {} rows - the stride can be at most \
+                                             the window",stride,window));
+   }
+   None
+}
+
+//  Returns the number of windows in a call, and the total number of rows
+//  they process, which is more than ny if the windows overlap.
+
+pub fn window_counts (ny: usize,window: usize,stride: usize) -> (usize,usize) {
+   let mut windows = 0;
+   let mut rows = 0;
+   let mut first = 0;
+   loop {
+      let last = (first + window).min(ny);
+      windows += 1;
+      rows += last - first;
+      if last == ny { break; }
+      first += stride;
+   }
+   (windows,rows)
+}
+
+//  The kernel. The window and stride must have been checked using
+//  window_problem() - a zero stride would never finish.
+
+pub fn csub_window<T: Element> (input_array: &Array2D<T>,
+                   output_array: &mut Array2D<T>,window: usize,stride: usize) {
+   let ny = input_array.ny();
+   let mut first = 0;
+   loop {
+      let last = (first + window).min(ny);
+      for iy in first..last {
+         for ix in 0..input_array.nx() {
+            output_array[[ix,iy]] =
+                              input_array[[ix,iy]] + T::from_index(ix + iy);
+         }
+      }
+      if last == ny { break; }
+      first += stride;
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o A call counts as one pass over the array, as it does for the other
+     versions, so the times and throughputs in the usual results are per
+     array element. The work actually done per call is rows / ny times
+     that, with rows from window_counts(), and crsbench.rs reports the
+     throughput for the elements actually processed as well.
+
+   o The indexing is all checked, as it is in crssub1d.rs, which this
+     matches exactly when the window is a single row and the stride is 1.
+
+*/
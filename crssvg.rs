@@ -0,0 +1,191 @@
+//
+//                             c r s s v g . r s
+//
+// Summary:
+//    SVG histograms of the trial times for the Rust 2D array access
+//    benchmark harness.
+//
+// Introduction:
+//    The harness reports the minimum and mean time per call, and the
+//    spread of the trials as a confidence interval, but a figure showing
+//    how the times are distributed says more - whether there is one peak or
+//    two, a long tail, a few outliers. histogram() draws one as a
+//    standalone SVG document: a bar for each bin of times, with the times
+//    along the bottom and the number of trials up the side, both labelled,
+//    and a title. It's plain SVG 1.1, written out by hand with no styling
+//    beyond the attributes themselves, so it can go straight into a paper
+//    or a web page, or be converted with any of the usual tools.
+//
+//    The harness writes one for --svg-histogram, once the trials are done -
+//    nothing here happens while anything is being timed.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//    14th Oct 2026. The unit is chosen by crsunits.rs, as it is for the
+//                   scaled times in the text output. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::fmt::Write;
+
+use crate::crsunits;
+
+//  The size of the image, and the margins round the plot area, in pixels.
+
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 400.0;
+const LEFT: f64 = 70.0;
+const RIGHT: f64 = 20.0;
+const TOP: f64 = 40.0;
+const BOTTOM: f64 = 60.0;
+
+//  The most bins, and the most tick labels along each axis.
+
+const MAX_BINS: usize = 40;
+const MAX_TICKS: usize = 10;
+
+//  Returns an SVG document with a histogram of the given times, in ns, with
+//  the given title. The number of bins is the square root of the number of
+//  times, rounded up, and they span the range of the times exactly. The
+//  times are shown in whichever of ns, us, ms or s suits them.
+
+pub fn histogram (times_ns: &[f64],title: &str) -> String {
+   let low = times_ns.iter().cloned().fold(f64::INFINITY,f64::min);
+   let high = times_ns.iter().cloned().fold(f64::NEG_INFINITY,f64::max);
+   let bins = if high > low {
+      ((times_ns.len() as f64).sqrt().ceil() as usize).clamp(1,MAX_BINS)
+   } else {
+      1
+   };
+   let (low,high) = if high > low { (low,high) } else { (low - 1.0,low + 1.0) };
+   let mut counts = vec![0usize; bins];
+   for &time in times_ns {
+      let bin = ((time - low) / (high - low) * bins as f64) as usize;
+      counts[bin.min(bins - 1)] += 1;
+   }
+   let most = counts.iter().cloned().max().unwrap_or(0).max(1);
+   let unit = crsunits::time_unit(high,0);
+   let (scale,unit) = (unit.ns,unit.ascii);
+
+   let plot_width = WIDTH - LEFT - RIGHT;
+   let plot_height = HEIGHT - TOP - BOTTOM;
+   let x_of = |time: f64| LEFT + (time - low) / (high - low) * plot_width;
+   let y_of = |count: usize| TOP + plot_height
+                                    - count as f64 / most as f64 * plot_height;
+
+   let mut svg = String::new();
+   let _ = writeln!(svg,"<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+   let _ = writeln!(svg,"<svg xmlns=\"http://www.w3.org/2000/svg\" \
+         version=\"1.1\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" \
+         font-family=\"sans-serif\" font-size=\"12\">",
+                                                    WIDTH,HEIGHT,WIDTH,HEIGHT);
+   let _ = writeln!(svg,"<rect width=\"{}\" height=\"{}\" fill=\"white\"/>",
+                                                              WIDTH,HEIGHT);
+   let _ = writeln!(svg,"<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" \
+         font-size=\"14\">{}</text>",WIDTH / 2.0,TOP / 2.0 + 5.0,
+                                                             escape(title));
+
+   //  The bars.
+
+   let bin_width = (high - low) / bins as f64;
+   for (bin,&count) in counts.iter().enumerate() {
+      if count == 0 { continue; }
+      let left = x_of(low + bin as f64 * bin_width);
+      let right = x_of(low + (bin + 1) as f64 * bin_width);
+      let top = y_of(count);
+      let _ = writeln!(svg,"<rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" \
+            height=\"{:.2}\" fill=\"steelblue\" stroke=\"black\" \
+            stroke-width=\"0.5\"/>",left,top,right - left,
+                                                      TOP + plot_height - top);
+   }
+
+   //  The axes, with their ticks and labels. The time ticks are at the bin
+   //  edges, or every so many of them if there are a lot.
+
+   let _ = writeln!(svg,"<path d=\"M {} {} V {} H {}\" fill=\"none\" \
+         stroke=\"black\"/>",LEFT,TOP,TOP + plot_height,LEFT + plot_width);
+   let places = time_places((high - low) / scale / bins as f64);
+   let every = bins.div_ceil(MAX_TICKS);
+   for edge in (0..=bins).step_by(every.max(1)) {
+      let time = low + edge as f64 * bin_width;
+      let x = x_of(time);
+      let _ = writeln!(svg,"<line x1=\"{:.2}\" y1=\"{}\" x2=\"{:.2}\" \
+            y2=\"{}\" stroke=\"black\"/>",x,TOP + plot_height,x,
+                                                      TOP + plot_height + 5.0);
+      let _ = writeln!(svg,"<text x=\"{:.2}\" y=\"{}\" \
+            text-anchor=\"middle\">{:.*}</text>",x,TOP + plot_height + 18.0,
+                                                        places,time / scale);
+   }
+   let step = most.div_ceil(MAX_TICKS);
+   for count in (0..=most).step_by(step.max(1)) {
+      let y = y_of(count);
+      let _ = writeln!(svg,"<line x1=\"{}\" y1=\"{:.2}\" x2=\"{}\" \
+            y2=\"{:.2}\" stroke=\"black\"/>",LEFT - 5.0,y,LEFT,y);
+      let _ = writeln!(svg,"<text x=\"{}\" y=\"{:.2}\" \
+            text-anchor=\"end\">{}</text>",LEFT - 8.0,y + 4.0,count);
+   }
+   let _ = writeln!(svg,"<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">\
+         Time per call ({})</text>",LEFT + plot_width / 2.0,HEIGHT - 15.0,unit);
+   let _ = writeln!(svg,"<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" \
+         transform=\"rotate(-90 {} {})\">Trials</text>",20.0,
+         TOP + plot_height / 2.0,20.0,TOP + plot_height / 2.0);
+   svg.push_str("</svg>\n");
+   svg
+}
+
+//  The number of decimal places needed to tell apart tick labels that
+//  differ by step.
+
+fn time_places (step: f64) -> usize {
+   if step <= 0.0 || !step.is_finite() { return 0; }
+   (-step.log10().floor()).max(0.0) as usize + 1
+}
+
+//  Escapes the characters that can't appear as themselves in SVG text.
+
+fn escape (text: &str) -> String {
+   text.replace('&',"&amp;").replace('<',"&lt;").replace('>',"&gt;")
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The square root rule for the number of bins is the simplest there is,
+     and does well enough for the few tens or hundreds of trials a run
+     usually has. With the default five trials there are only three bins,
+     and a histogram says little more than the numbers do - it's meant for
+     runs with --trials set high.
+
+   o If every time is the same, there's a single bin, a ns either side of
+     it, so the axis still has a range.
+
+   o The unit is written 'us', rather than with a mu, so the file is
+     plain ASCII apart from whatever is in the title, and the title is
+     escaped so that a label with an ampersand or an angle bracket in it
+     still gives valid XML.
+
+   o Each writeln!() into a String can't fail, and the results are
+     ignored.
+
+*/
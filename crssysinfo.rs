@@ -0,0 +1,312 @@
+//
+//                          c r s s y s i n f o . r s
+//
+// Summary:
+//    System information for the Rust 2D array access benchmark harness.
+//
+// Introduction:
+//    A timing is only meaningful alongside a description of the machine it
+//    was made on, and when results are being collected from a number of
+//    different machines it's all too easy to lose track of which was which.
+//    This module gathers the basic details - the CPU model, the number of
+//    logical and physical cores, and the operating system - so that the
+//    harness can report them along with the results.
+//
+//    Only the standard library is used. On Linux the CPU details come from
+//    /proc/cpuinfo and the OS details from /etc/os-release and /proc; on
+//    other systems, or if any of those can't be read, the details that can't
+//    be found are given as 'unknown'. Nothing here ever fails.
+//
+//    There is also a check on the CPU frequency settings, which looks in
+//    the Linux cpufreq files in sysfs for the common causes of timings that
+//    can't be reproduced: CPUs running above their base frequency because
+//    boost (turbo) is active, and frequency governors other than
+//    'performance', which let the clock speed wander. None of it needs root,
+//    and it's purely advisory. The same files also give a reading of the
+//    current and maximum frequencies, which the harness uses to warm up
+//    until the clock speed has settled.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//    14th Oct 2026. Added frequency_warnings(). agent.
+//    14th Oct 2026. Added frequency_reading(). agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::collections::{BTreeMap, HashSet};
+use std::env;
+use std::fs;
+use std::thread;
+
+const UNKNOWN: &str = "unknown";
+
+//  The details gathered about the system. The core counts are None if they
+//  couldn't be found.
+
+pub struct SysInfo {
+   pub cpu_model: String,
+   pub logical_cores: Option<usize>,
+   pub physical_cores: Option<usize>,
+   pub os: String,
+   pub os_release: String,
+   pub arch: String,
+}
+
+impl SysInfo {
+
+   //  The details as (name,value) pairs, in the order they are reported.
+
+   pub fn fields (&self) -> Vec<(&'static str,String)> {
+      let count = |cores: Option<usize>| match cores {
+         Some(cores) => cores.to_string(),
+         None => UNKNOWN.to_string(),
+      };
+      vec![
+         ("cpu_model",self.cpu_model.clone()),
+         ("logical_cores",count(self.logical_cores)),
+         ("physical_cores",count(self.physical_cores)),
+         ("os",self.os.clone()),
+         ("os_release",self.os_release.clone()),
+         ("arch",self.arch.clone()),
+      ]
+   }
+}
+
+//  Gathers what it can about the system.
+
+pub fn gather () -> SysInfo {
+   let cpuinfo = fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+   SysInfo {
+      cpu_model: cpu_model(&cpuinfo),
+      logical_cores: thread::available_parallelism().ok().map(|n| n.get()),
+      physical_cores: physical_cores(&cpuinfo),
+      os: os_name(),
+      os_release: fs::read_to_string("/proc/sys/kernel/osrelease")
+            .map(|text| text.trim().to_string())
+            .unwrap_or_else(|_| UNKNOWN.to_string()),
+      arch: env::consts::ARCH.to_string(),
+   }
+}
+
+//  Returns the value of the first line in /proc/cpuinfo style text that
+//  starts with the given key, if there is one.
+
+fn cpuinfo_value<'a> (cpuinfo: &'a str,key: &str) -> Option<&'a str> {
+   cpuinfo.lines().filter(|line| line.starts_with(key))
+      .filter_map(|line| line.split_once(':')).map(|(_,value)| value.trim())
+      .next()
+}
+
+//  The CPU model. x86 systems have a 'model name' line, but ARM systems
+//  often only have 'Hardware' or 'CPU part' lines, or nothing useful.
+
+fn cpu_model (cpuinfo: &str) -> String {
+   for key in &["model name","Hardware","Processor","cpu model"] {
+      if let Some(value) = cpuinfo_value(cpuinfo,key) {
+         if !value.is_empty() { return value.to_string(); }
+      }
+   }
+   UNKNOWN.to_string()
+}
+
+//  The number of physical cores, counted as the number of distinct pairs of
+//  'physical id' and 'core id' values. Each processor entry in cpuinfo is a
+//  paragraph, so the pairs are collected paragraph by paragraph.
+
+fn physical_cores (cpuinfo: &str) -> Option<usize> {
+   let mut cores = HashSet::new();
+   for entry in cpuinfo.split("\n\n") {
+      let physical = cpuinfo_value(entry,"physical id");
+      let core = cpuinfo_value(entry,"core id");
+      if let (Some(physical),Some(core)) = (physical,core) {
+         cores.insert((physical.to_string(),core.to_string()));
+      }
+   }
+   if cores.is_empty() { None } else { Some(cores.len()) }
+}
+
+//  The operating system - the distribution name from /etc/os-release if
+//  there is one, otherwise just the OS family that Rust was built for.
+
+fn os_name () -> String {
+   if let Ok(text) = fs::read_to_string("/etc/os-release") {
+      for line in text.lines() {
+         if let Some(value) = line.strip_prefix("PRETTY_NAME=") {
+            return value.trim_matches('"').to_string();
+         }
+      }
+   }
+   env::consts::OS.to_string()
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                      F r e q u e n c y   C h e c k
+
+//  Where Linux keeps the per-CPU directories.
+
+const CPU_ROOT: &str = "/sys/devices/system/cpu";
+
+//  A reading of the CPU frequency, in kHz. The current frequency is the
+//  highest of any CPU, and the maximum is the highest any CPU can reach, if
+//  that's known.
+
+#[derive(Clone, Copy, Debug)]
+pub struct FrequencyReading {
+   pub current_khz: u64,
+   pub max_khz: Option<u64>,
+}
+
+//  Reads a sysfs setting, without the trailing newline.
+
+fn read_setting (path: String) -> Option<String> {
+   fs::read_to_string(path).ok().map(|text| text.trim().to_string())
+}
+
+fn read_khz (path: String) -> Option<u64> {
+   read_setting(path).and_then(|text| text.parse::<u64>().ok())
+}
+
+//  Returns the cpufreq directory of each CPU under the given root - not all
+//  of which need exist - or None if the root can't be read.
+
+fn cpufreq_dirs (root: &str) -> Option<Vec<String>> {
+   let entries = fs::read_dir(root).ok()?;
+   let mut dirs = Vec::new();
+   for entry in entries.filter_map(|entry| entry.ok()) {
+      let name = entry.file_name().to_string_lossy().to_string();
+      let is_cpu = name.len() > 3 && name.starts_with("cpu")
+                             && name[3..].chars().all(|c| c.is_ascii_digit());
+      if is_cpu { dirs.push(format!("{}/{}/cpufreq",root,name)); }
+   }
+   Some(dirs)
+}
+
+//  Returns the current CPU frequency, or None if there are no frequency
+//  readings. While the harness is busy warming up, the CPU it's running on
+//  is the one most likely to be running fastest, so the highest frequency
+//  is taken to be the one that matters.
+
+pub fn frequency_reading () -> Option<FrequencyReading> {
+   read_frequency(CPU_ROOT)
+}
+
+fn read_frequency (root: &str) -> Option<FrequencyReading> {
+   let mut current: Option<u64> = None;
+   let mut max: Option<u64> = None;
+   for dir in cpufreq_dirs(root)? {
+      if let Some(khz) = read_khz(format!("{}/scaling_cur_freq",dir)) {
+         current = Some(current.map_or(khz,|highest| highest.max(khz)));
+      }
+      if let Some(khz) = read_khz(format!("{}/cpuinfo_max_freq",dir)) {
+         max = Some(max.map_or(khz,|highest| highest.max(khz)));
+      }
+   }
+   current.map(|current_khz| FrequencyReading { current_khz,
+                                                              max_khz: max })
+}
+
+//  Looks at the CPU frequency settings and returns a warning for each
+//  thing found that makes timings less reproducible. Returns None if there
+//  is no cpufreq information at all, as in most virtual machines and on
+//  anything other than Linux.
+
+pub fn frequency_warnings () -> Option<Vec<String>> {
+   check_frequency(CPU_ROOT)
+}
+
+//  Does the work for frequency_warnings(), given the directory holding the
+//  per-CPU directories - cpu0, cpu1 and so on. The frequencies are in kHz.
+
+fn check_frequency (root: &str) -> Option<Vec<String>> {
+   let mut cpus = 0;
+   let mut above_base = 0;
+   let mut highest: Option<(u64,u64)> = None;
+   let mut governors: BTreeMap<String,usize> = BTreeMap::new();
+   for dir in cpufreq_dirs(root)? {
+      let governor = match read_setting(format!("{}/scaling_governor",dir)) {
+         Some(governor) => governor,
+         None => continue,
+      };
+      cpus += 1;
+      if governor != "performance" {
+         *governors.entry(governor).or_insert(0) += 1;
+      }
+      let current = read_khz(format!("{}/scaling_cur_freq",dir));
+      let base = read_khz(format!("{}/base_frequency",dir));
+      if let (Some(current),Some(base)) = (current,base) {
+         if current > base {
+            above_base += 1;
+            if highest.is_none_or(|(khz,_)| current > khz) {
+               highest = Some((current,base));
+            }
+         }
+      }
+   }
+   if cpus == 0 { return None; }
+
+   let mut warnings = Vec::new();
+   if let Some((current,base)) = highest {
+      warnings.push(format!("{} of {} CPUs are running above their base \
+             frequency (up to {} MHz, base {} MHz) - boost appears to be \
+             active",above_base,cpus,current / 1000,base / 1000));
+   }
+   if read_setting(format!("{}/intel_pstate/no_turbo",root)).as_deref()
+                                                              == Some("0") {
+      warnings.push("Turbo boost is enabled (intel_pstate/no_turbo is 0)"
+                                                                .to_string());
+   }
+   if read_setting(format!("{}/cpufreq/boost",root)).as_deref()
+                                                              == Some("1") {
+      warnings.push("Frequency boost is enabled (cpufreq/boost is 1)"
+                                                                .to_string());
+   }
+   for (governor,count) in &governors {
+      warnings.push(format!("{} of {} CPUs use the '{}' governor rather \
+                             than 'performance'",count,cpus,governor));
+   }
+   Some(warnings)
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The number of logical cores is the number this program is allowed to
+     use, which can be less than the number in the machine if it's been
+     restricted by taskset or a container. The physical core count comes
+     from cpuinfo, which shows the whole machine.
+
+   o Only intel_pstate provides base_frequency, so with other drivers the
+     check can't tell whether a CPU is boosting, and relies on the boost
+     switches and the governors. scaling_cur_freq is only a snapshot, and
+     an idle CPU will usually be well below its base frequency, which is
+     why the harness makes the check straight after the timed runs.
+
+   o The harness could find out which CPU it's running on (it's in
+     /proc/self/stat), but the scheduler is free to move it between one
+     reading and the next. Taking the highest frequency of any CPU is
+     simpler, and on an otherwise idle machine comes to the same thing. On
+     a busy one, it can be fooled by some other process.
+
+*/
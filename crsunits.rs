@@ -0,0 +1,174 @@
+//
+//                           c r s u n i t s . r s
+//
+// Summary:
+//    Times scaled to a suitable unit, for the Rust 2D array access benchmark
+//    harness.
+//
+// Introduction:
+//    The harness works in nanoseconds throughout, and usually reports times
+//    as so many ns. That's fine for one run, but a sweep can go from a few
+//    ns a call for a tiny array to hundreds of millions for a large one, and
+//    a column of numbers spanning eight orders of magnitude is hard to take
+//    in at a glance. time_text() writes a time in whichever of ns, µs, ms or
+//    s suits it, with the unit - '3.21 µs' - or, if it's told not to scale
+//    it, as so many ns, which is what the harness does unless --si-times
+//    is given. time_unit() says which unit a time goes in, so that anything
+//    else showing times, such as the axis of a histogram (see crssvg.rs),
+//    can scale them the same way.
+//
+//    This is only for output people read. The machine formats - CSV, JSON
+//    lines, Prometheus and the results files - always stay in ns, so they
+//    can be compared and processed without having to parse units.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version. agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//  A unit to show times in: the number of ns in one of it, its name, and
+//  its name in plain ASCII, for output that has to stay ASCII.
+
+#[derive(Debug)]
+pub struct TimeUnit {
+   pub ns: f64,
+   pub name: &'static str,
+   pub ascii: &'static str,
+}
+
+//  The units, smallest first.
+
+pub const TIME_UNITS: [TimeUnit; 4] = [
+   TimeUnit { ns: 1.0, name: "ns", ascii: "ns" },
+   TimeUnit { ns: 1.0e3, name: "µs", ascii: "us" },
+   TimeUnit { ns: 1.0e6, name: "ms", ascii: "ms" },
+   TimeUnit { ns: 1.0e9, name: "s", ascii: "s" },
+];
+
+//  The unit to show a time of ns nanoseconds in, to the given number of
+//  decimal places. This is the largest unit the time is at least one of,
+//  unless written to that many places it would come out as 1000 or more,
+//  when it's the next one up - so 999.9996 ns to three places is 1.000 µs,
+//  not 1000.000 ns. Times under 1 ns, zero, and anything that isn't a
+//  finite number are shown in ns, and times of 1000 s or more in s.
+
+pub fn time_unit (ns: f64,places: usize) -> &'static TimeUnit {
+   let size = ns.abs();
+   if !size.is_finite() { return &TIME_UNITS[0]; }
+   let mut index = TIME_UNITS.iter().rposition(|unit| size >= unit.ns)
+                                                               .unwrap_or(0);
+   while index + 1 < TIME_UNITS.len() {
+      let text = format!("{:.*}",places,size / TIME_UNITS[index].ns);
+      if text.parse::<f64>().map_or(true,|value| value < 1000.0) { break; }
+      index += 1;
+   }
+   &TIME_UNITS[index]
+}
+
+//  A time of ns nanoseconds, to the given number of decimal places, and
+//  followed by the unit - eg '3.21 µs'. If scaled is true, it's in the unit
+//  time_unit() picks for it, and otherwise it's in ns.
+
+pub fn time_text (ns: f64,places: usize,scaled: bool) -> String {
+   let unit = if scaled { time_unit(ns,places) } else { &TIME_UNITS[0] };
+   format!("{:.*} {}",places,ns / unit.ns,unit.name)
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                                 T e s t s
+
+#[cfg(test)]
+mod tests {
+   use super::{time_text, time_unit};
+
+   //  A time that would be written as 1000 of one unit moves up to the
+   //  next, and one just short of that doesn't.
+
+   #[test]
+   fn rounding_up () {
+      assert_eq!(time_text(999.9995,3,true),"1.000 µs");
+      assert_eq!(time_text(999.9994,3,true),"999.999 ns");
+      assert_eq!(time_text(999.96,1,true),"1.0 µs");
+      assert_eq!(time_text(999.94,1,true),"999.9 ns");
+      assert_eq!(time_text(999_999.6,0,true),"1 ms");
+      assert_eq!(time_text(1.0e9 - 0.0005,3,true),"1.000 s");
+      assert_eq!(time_unit(999.9995,6).name,"ns");
+   }
+
+   //  Seconds are the largest unit, so 1000 s and up stay in s.
+
+   #[test]
+   fn top_unit () {
+      assert_eq!(time_text(1.0e12,1,true),"1000.0 s");
+      assert_eq!(time_text(999.99996e9,3,true),"1000.000 s");
+      assert_eq!(time_text(2.5e15,0,true),"2500000 s");
+   }
+
+   //  A negative time scales just as its size would, keeping the sign.
+
+   #[test]
+   fn negative () {
+      assert_eq!(time_text(-3210.0,2,true),"-3.21 µs");
+      assert_eq!(time_text(-999.9995,3,true),"-1.000 µs");
+      assert_eq!(time_text(-0.25,2,true),"-0.25 ns");
+      assert_eq!(time_text(-1.0e12,0,true),"-1000 s");
+   }
+
+   //  Anything that isn't a finite number, and anything under 1 ns, is
+   //  left in ns.
+
+   #[test]
+   fn not_finite () {
+      assert_eq!(time_text(f64::NAN,1,true),"NaN ns");
+      assert_eq!(time_text(f64::INFINITY,1,true),"inf ns");
+      assert_eq!(time_text(f64::NEG_INFINITY,1,true),"-inf ns");
+      assert_eq!(time_text(0.0,3,true),"0.000 ns");
+      assert_eq!(time_text(0.5,1,true),"0.5 ns");
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o The decision about moving up a unit is made on the text the value
+     will be written as, rather than on the value, so that it can't
+     disagree with the formatting about where the rounding goes - which a
+     test of the value against, say, 999.9995 could, both because that
+     isn't exactly representable and because the formatting rounds exact
+     halves to even.
+
+   o The number of decimal places is kept whatever the unit, so a time
+     that was 1234.567 ns becomes 1.235 µs, and loses a digit or so. That's
+     the point - a scaled time is for reading, and --precision still sets
+     the places. Anyone who wants every digit can leave the times in ns.
+
+   o The sign is kept, so a change in a time, as in a drift slope, scales
+     just as a time does.
+
+   o The unit for microseconds is a micro sign, U+00B5, which any UTF-8
+     terminal will show. The ASCII names are for the SVG files, which are
+     otherwise kept to ASCII.
+
+*/
@@ -0,0 +1,865 @@
+//
+//                           c r s v e r i f y . r s
+//
+// Summary:
+//    Result checking for the Rust 2D array access benchmark harness.
+//
+// Introduction:
+//    All the test programs in this study finish by checking that each element
+//    of the output array is the corresponding input element plus the sum of
+//    its row and column numbers. The check is done in single precision, just
+//    as the test itself does it, so it tests that the code did the right
+//    thing, and an exact comparison is the right test.
+//
+//    That isn't the same as saying the result is accurate. An f32 has a 24
+//    bit significand, so once the index sum gets beyond 2^24 (16777216) it
+//    can't be represented exactly, and nor can the result. This module
+//    provides the standard check used by crsbench.rs to decide if a test
+//    passed, and also a diagnostic check that compares the results against
+//    the true (double precision) sum, both exactly and with a tolerance, to
+//    show where single precision starts to lose out.
+//
+// Author(s): agent, agent@local
+//
+// History:
+//    14th Oct 2026. Original version, split out from crsbench.rs, with the
+//                   addition of the exact and tolerant accuracy checks. agent.
+//    14th Oct 2026. Added check_coverage(). agent.
+//    14th Oct 2026. Added diff_map() and write_raw(). agent.
+//    14th Oct 2026. Added compare_outputs(). agent.
+//    14th Oct 2026. Added Check, so each kernel can say how its results
+//                   should be checked. agent.
+//    14th Oct 2026. write_raw() now returns a BenchError. agent.
+//    14th Oct 2026. Added verify_3d(). agent.
+//    14th Oct 2026. Added verify_generic(). agent.
+//    14th Oct 2026. Added Endian, so raw files have a fixed byte order -
+//                   little endian unless asked otherwise - and read_raw().
+//                   agent.
+//    14th Oct 2026. Added strict_reference(). agent.
+//    14th Oct 2026. Added the permutation check, and verify_permutation().
+//                   agent.
+//    14th Oct 2026. Added check_shuffled(). agent.
+//    14th Oct 2026. Added the expression check, and verify_expression().
+//                   agent.
+//    14th Oct 2026. Added the binary check, verify_binary(), and
+//                   Check::verify_generic(). agent.
+//    14th Oct 2026. Added the transpose check, verify_transpose(). agent.
+//    14th Oct 2026. Added the affine check, verify_affine(). agent.
+//    14th Oct 2026. Added the copy check, verify_copy(). agent.
+//    14th Oct 2026. Added the prefix check, verify_prefix(). agent.
+//
+// Copyright (c) 2026 agent
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use crate::crsarray::{Array2D, Element};
+use crate::crsarray3d::Array3D;
+use crate::crserror::BenchError;
+use crate::crsexpr::Expr;
+use crate::crsinput::Rng;
+use crate::crssub_affine;
+use crate::crssub_binary;
+
+//  The default relative tolerance for the tolerant check. Adding two values
+//  and rounding to f32 has an error of at most half an ulp, and converting
+//  the index sum to f32 first can add another half ulp, so the result should
+//  always be within two epsilons of the true value.
+
+pub const DEFAULT_TOLERANCE: f64 = 2.0 * f32::EPSILON as f64;
+
+//  ----------------------------------------------------------------------------
+//
+//                          K e r n e l   C h e c k s
+
+//  The ways a kernel's results can be checked. Each mode declares its check
+//  in the MODES table (see crsconfig.rs), and the harness uses that rather
+//  than assuming every kernel sets each output element from the matching
+//  input element. A kernel that does something else - a reduction to a
+//  single sum, say - needs a variant here, with its own verify routine.
+
+//  The Expression variant is much bigger than the others, but a Check is
+//  made once for each run and copied about freely, so it isn't boxed.
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Check {
+
+   //  Each output element is the input element plus its index sum. This
+   //  is the standard check, verify(), below.
+
+   ElementWise,
+
+   //  The output holds the same values as the standard check expects, but
+   //  in some other order, as the gather mode gives with a shuffled index.
+   //  This is verify_permutation(), below.
+
+   Permutation,
+
+   //  Each output element is the value of the expression for the input
+   //  element, as the expr mode gives. This is verify_expression(), below.
+
+   Expression(Expr),
+
+   //  Each output element is the input element plus the matching element
+   //  of the second input array, plus its index sum, as the binary mode
+   //  gives. This is verify_binary(), below.
+
+   Binary,
+
+   //  The output holds the standard results transposed, as the regtranspose
+   //  mode gives, treated as an array of nx rows of ny columns. This is
+   //  verify_transpose(), below.
+
+   Transpose,
+
+   //  Each output element is the input element times its row's gain, plus
+   //  its row's offset, as the affine mode gives. This is verify_affine(),
+   //  below.
+
+   Affine,
+
+   //  Each output element is the input element, unchanged, as the copy
+   //  mode gives. This is verify_copy(), below.
+
+   Copy,
+
+   //  Each output element is the sum of the input elements along its row
+   //  up to and including its own, as the prefix mode gives. This is
+   //  verify_prefix(), below.
+
+   Prefix,
+}
+
+impl Check {
+
+   pub fn name (&self) -> &'static str {
+      match *self {
+         Check::ElementWise => "elementwise",
+         Check::Permutation => "permutation",
+         Check::Expression(_) => "expression",
+         Check::Binary => "binary",
+         Check::Transpose => "transpose",
+         Check::Affine => "affine",
+         Check::Copy => "copy",
+         Check::Prefix => "prefix",
+      }
+   }
+
+   //  Checks a kernel's results, returning true if they are correct.
+
+   pub fn verify (&self,in_array: &Array2D<f32>,out_array: &Array2D<f32>)
+                                                                     -> bool {
+      match *self {
+         Check::ElementWise => verify(in_array,out_array),
+         Check::Permutation => verify_permutation(in_array,out_array),
+         Check::Expression(ref expr) =>
+                                   verify_expression(in_array,out_array,expr),
+         Check::Binary => verify_binary(in_array,out_array),
+         Check::Transpose => verify_transpose(in_array,out_array),
+         Check::Affine => verify_affine(in_array,out_array),
+         Check::Copy => verify_copy(in_array,out_array),
+         Check::Prefix => verify_prefix(in_array,out_array),
+      }
+   }
+
+   //  The same, for the generic kernels with elements of any type. Only
+   //  the binary, transpose, affine, copy and prefix checks have generic
+   //  versions of their own, since those kernels don't leave the standard
+   //  results where the standard check looks for them. The others only
+   //  differ from the standard one for modes whose results are only checked
+   //  with f32 elements, so the standard check is used for them.
+
+   pub fn verify_generic<T: Element> (&self,in_array: &Array2D<T>,
+                                            out_array: &Array2D<T>) -> bool {
+      match *self {
+         Check::Binary => verify_binary(in_array,out_array),
+         Check::Transpose => verify_transpose(in_array,out_array),
+         Check::Affine => verify_affine(in_array,out_array),
+         Check::Copy => verify_copy(in_array,out_array),
+         Check::Prefix => verify_prefix(in_array,out_array),
+         _ => verify_generic(in_array,out_array),
+      }
+   }
+
+   //  True if the output corresponds element by element to the input, so
+   //  the element-wise diagnostics - the difference map, the accuracy
+   //  checks and the extended precision comparison - make sense for it.
+
+   pub fn is_elementwise (&self) -> bool {
+      *self == Check::ElementWise
+   }
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                          S t a n d a r d   C h e c k
+
+//  Checks that each element of the output array is the corresponding input
+//  element plus the sum of its indices, calculated in single precision as
+//  csub() does it. Reports the first element that doesn't match and returns
+//  false, or returns true if they all match. This is the check that decides
+//  if a test passed.
+
+pub fn verify (in_array: &Array2D<f32>,out_array: &Array2D<f32>) -> bool {
+   for iy in 0..in_array.ny() {
+      for ix in 0..in_array.nx() {
+         if out_array[[ix,iy]] != in_array[[ix,iy]] + (ix + iy) as f32 {
+            eprintln!("Error {} {} {} {}",
+                              ix,iy,out_array[[ix,iy]],in_array[[ix,iy]]);
+            return false;
+         }
+      }
+   }
+   true
+}
+
+//  The same check for any element type, with the index sum converted using
+//  Element::from_index(), as the generic kernels do it.
+
+pub fn verify_generic<T: Element> (in_array: &Array2D<T>,
+                                            out_array: &Array2D<T>) -> bool {
+   for iy in 0..in_array.ny() {
+      for ix in 0..in_array.nx() {
+         if out_array[[ix,iy]] != in_array[[ix,iy]] + T::from_index(ix + iy) {
+            eprintln!("Error {} {} {:?} {:?}",
+                              ix,iy,out_array[[ix,iy]],in_array[[ix,iy]]);
+            return false;
+         }
+      }
+   }
+   true
+}
+
+//  The check for an output that should hold the standard results in some
+//  unknown order. The expected results and the output are both sorted, and
+//  then compared bit for bit. Reports the first place the sorted values
+//  don't match and returns false, or returns true if they all match.
+
+pub fn verify_permutation (in_array: &Array2D<f32>,out_array: &Array2D<f32>)
+                                                                     -> bool {
+   let mut expected: Vec<f32> = Vec::with_capacity(in_array.len());
+   for iy in 0..in_array.ny() {
+      for (ix,value) in in_array.row(iy).iter().enumerate() {
+         expected.push(value + (ix + iy) as f32);
+      }
+   }
+   let mut actual = out_array.as_slice().to_vec();
+   expected.sort_by(|a,b| a.total_cmp(b));
+   actual.sort_by(|a,b| a.total_cmp(b));
+   for (index,(value,wanted)) in actual.iter().zip(&expected).enumerate() {
+      if value.to_bits() != wanted.to_bits() {
+         eprintln!("Error: sorted value {} is {}, expected {}",index,value,
+                                                                     wanted);
+         return false;
+      }
+   }
+   true
+}
+
+//  The check for an output set by an expression. Each element should be,
+//  bit for bit, the value of the expression as evaluated by its reference
+//  evaluation. Reports the first element that isn't and returns false, or
+//  returns true if they all are.
+
+pub fn verify_expression (in_array: &Array2D<f32>,out_array: &Array2D<f32>,
+                                                        expr: &Expr) -> bool {
+   for iy in 0..in_array.ny() {
+      for ix in 0..in_array.nx() {
+         let value = out_array[[ix,iy]];
+         let wanted = expr.reference(in_array[[ix,iy]],ix,iy);
+         if value.to_bits() != wanted.to_bits() {
+            eprintln!("Error: element ({},{}) is {}, expected {}, the value \
+                                of {}",ix,iy,value,wanted,expr);
+            return false;
+         }
+      }
+   }
+   true
+}
+
+//  The check for an output that combines the input with the second input
+//  array the binary mode uses (see crssub_binary.rs). The second array's
+//  values come from crssub_binary::second_value(), and the sum is made in
+//  the same order the kernel makes it, so it can be checked bit for bit.
+//  Reports the first element that doesn't match and returns false, or
+//  returns true if they all match.
+
+pub fn verify_binary<T: Element> (in_array: &Array2D<T>,
+                                            out_array: &Array2D<T>) -> bool {
+   for iy in 0..in_array.ny() {
+      for ix in 0..in_array.nx() {
+         let second = T::from_index(crssub_binary::second_value(ix,iy));
+         let wanted = in_array[[ix,iy]] + second + T::from_index(ix + iy);
+         if out_array[[ix,iy]] != wanted {
+            eprintln!("Error {} {} {:?} {:?}, expected {:?}",
+                           ix,iy,out_array[[ix,iy]],in_array[[ix,iy]],wanted);
+            return false;
+         }
+      }
+   }
+   true
+}
+
+//  The check for an output that holds the transpose of the standard
+//  results, as the regtranspose mode gives (see crssub_regtranspose.rs).
+//  The output array's elements are taken as nx rows of ny columns, so the
+//  result for input element (ix,iy) should be at element iy of row ix.
+//  Reports the first element that doesn't match and returns false, or
+//  returns true if they all match.
+
+pub fn verify_transpose<T: Element> (in_array: &Array2D<T>,
+                                            out_array: &Array2D<T>) -> bool {
+   let ny = in_array.ny();
+   let transposed = out_array.as_slice();
+   for iy in 0..ny {
+      for ix in 0..in_array.nx() {
+         let wanted = in_array[[ix,iy]] + T::from_index(ix + iy);
+         if transposed[ix * ny + iy] != wanted {
+            eprintln!("Error {} {} {:?} {:?}, expected {:?} at row {} \
+                column {} of the transpose",ix,iy,transposed[ix * ny + iy],
+                                               in_array[[ix,iy]],wanted,ix,iy);
+            return false;
+         }
+      }
+   }
+   true
+}
+
+//  The check for an output calibrated with a gain and an offset for each
+//  row, as the affine mode gives (see crssub_affine.rs). The gains and
+//  offsets come from crssub_affine::gain_value() and offset_value(), and
+//  the result is worked out in the same order the kernel works it out, so
+//  it can be checked bit for bit. Reports the first element that doesn't
+//  match and returns false, or returns true if they all match.
+
+pub fn verify_affine<T: Element> (in_array: &Array2D<T>,
+                                            out_array: &Array2D<T>) -> bool {
+   for iy in 0..in_array.ny() {
+      let gain = T::from_index(crssub_affine::gain_value(iy));
+      let offset = T::from_index(crssub_affine::offset_value(iy));
+      for ix in 0..in_array.nx() {
+         let wanted = in_array[[ix,iy]] * gain + offset;
+         if out_array[[ix,iy]] != wanted {
+            eprintln!("Error {} {} {:?} {:?}, expected {:?}",
+                           ix,iy,out_array[[ix,iy]],in_array[[ix,iy]],wanted);
+            return false;
+         }
+      }
+   }
+   true
+}
+
+//  The check for an output that should be an exact copy of the input, as
+//  the copy mode gives (see crssub_copy.rs). Reports the first element that
+//  doesn't match and returns false, or returns true if they all match.
+
+pub fn verify_copy<T: Element> (in_array: &Array2D<T>,
+                                            out_array: &Array2D<T>) -> bool {
+   for iy in 0..in_array.ny() {
+      for ix in 0..in_array.nx() {
+         if out_array[[ix,iy]] != in_array[[ix,iy]] {
+            eprintln!("Error {} {} {:?}, expected a copy of {:?}",
+                                  ix,iy,out_array[[ix,iy]],in_array[[ix,iy]]);
+            return false;
+         }
+      }
+   }
+   true
+}
+
+//  The check for an output holding the running sums of the input along
+//  each row, as the prefix mode gives (see crssub_prefix.rs). The sums are
+//  formed in the same order and the same type as the kernel forms them, so
+//  they can be checked bit for bit. Reports the first element that doesn't
+//  match and returns false, or returns true if they all match.
+
+pub fn verify_prefix<T: Element> (in_array: &Array2D<T>,
+                                            out_array: &Array2D<T>) -> bool {
+   for iy in 0..in_array.ny() {
+      let mut total = T::from_index(0);
+      for ix in 0..in_array.nx() {
+         total = total + in_array[[ix,iy]];
+         if out_array[[ix,iy]] != total {
+            eprintln!("Error {} {} {:?} {:?}, expected a running sum of {:?}",
+                           ix,iy,out_array[[ix,iy]],in_array[[ix,iy]],total);
+            return false;
+         }
+      }
+   }
+   true
+}
+
+//  The same check for a 3D array, where the index sum includes the plane
+//  number.
+
+pub fn verify_3d (in_array: &Array3D<f32>,out_array: &Array3D<f32>) -> bool {
+   for iz in 0..in_array.nz() {
+      for iy in 0..in_array.ny() {
+         for ix in 0..in_array.nx() {
+            let expected = in_array[[ix,iy,iz]] + (ix + iy + iz) as f32;
+            if out_array[[ix,iy,iz]] != expected {
+               eprintln!("Error {} {} {} {} {}",ix,iy,iz,
+                                  out_array[[ix,iy,iz]],in_array[[ix,iy,iz]]);
+               return false;
+            }
+         }
+      }
+   }
+   true
+}
+
+//  Returns the difference map for a result - an array the same size as
+//  the input whose elements are the absolute differences between each
+//  output element and the value the standard check expects. Every element
+//  is zero for a result that passes.
+
+pub fn diff_map (in_array: &Array2D<f32>,out_array: &Array2D<f32>)
+                                                             -> Array2D<f32> {
+   Array2D::from_fn(in_array.nx(),in_array.ny(),|ix,iy| {
+      let expected = in_array[[ix,iy]] + (ix + iy) as f32;
+      (out_array[[ix,iy]] - expected).abs()
+   })
+}
+
+//  The byte order of the values in a raw file. A file written in a fixed
+//  order reads back the same on any machine, which the native order doesn't
+//  guarantee, so little endian - what almost every machine uses anyway - is
+//  the default.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Endian {
+   Little,
+   Big,
+   Native,
+}
+
+impl Endian {
+   pub fn from_name (name: &str) -> Option<Endian> {
+      match name {
+         "little" => Some(Endian::Little),
+         "big" => Some(Endian::Big),
+         "native" => Some(Endian::Native),
+         _ => None,
+      }
+   }
+
+   pub fn name (&self) -> &'static str {
+      match *self {
+         Endian::Little => "little",
+         Endian::Big => "big",
+         Endian::Native => "native",
+      }
+   }
+
+   //  The bytes of a value in this byte order, and the value given its
+   //  bytes.
+
+   pub fn bytes_of (self,value: f32) -> [u8; 4] {
+      match self {
+         Endian::Little => value.to_le_bytes(),
+         Endian::Big => value.to_be_bytes(),
+         Endian::Native => value.to_ne_bytes(),
+      }
+   }
+
+   pub fn value_of (self,bytes: [u8; 4]) -> f32 {
+      match self {
+         Endian::Little => f32::from_le_bytes(bytes),
+         Endian::Big => f32::from_be_bytes(bytes),
+         Endian::Native => f32::from_ne_bytes(bytes),
+      }
+   }
+}
+
+//  Writes an array to a file as raw f32 values, in memory order - so row by
+//  row - with no header, in the given byte order.
+
+pub fn write_raw (path: &str,array: &Array2D<f32>,endian: Endian)
+                                                   -> Result<(),BenchError> {
+   let io_error = |action: &'static str,error: std::io::Error| BenchError::Io {
+         path: path.to_string(), action, error: error.to_string() };
+   let file = match File::create(path) {
+      Ok(file) => file,
+      Err(error) => return Err(io_error("create",error)),
+   };
+   let mut writer = BufWriter::new(file);
+   for value in array.as_slice() {
+      if let Err(error) = writer.write_all(&endian.bytes_of(*value)) {
+         return Err(io_error("write",error));
+      }
+   }
+   writer.flush().map_err(|error| io_error("write",error))
+}
+
+//  Reads an array of nx columns and ny rows from a raw file, as written by
+//  write_raw() with the same byte order. The file has to be exactly the
+//  right size.
+
+pub fn read_raw (path: &str,nx: usize,ny: usize,endian: Endian)
+                                        -> Result<Array2D<f32>,BenchError> {
+   let bytes = match fs::read(path) {
+      Ok(bytes) => bytes,
+      Err(error) => return Err(BenchError::Io { path: path.to_string(),
+                                 action: "read", error: error.to_string() }),
+   };
+   let expected = nx.checked_mul(ny).and_then(|len| len.checked_mul(4));
+   if expected != Some(bytes.len()) {
+      return Err(BenchError::Format { path: path.to_string(),
+         message: format!("holds {} bytes, which isn't {} rows of {} f32 \
+                                         values",bytes.len(),ny,nx) });
+   }
+   Ok(Array2D::from_fn(nx,ny,|ix,iy| {
+      let offset = (iy * nx + ix) * 4;
+      endian.value_of([bytes[offset],bytes[offset + 1],bytes[offset + 2],
+                                                          bytes[offset + 3]])
+   }))
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                         A c c u r a c y   C h e c k
+
+//  The result of comparing the output with the true sums.
+
+pub struct AccuracyCheck {
+
+   //  The first element, in memory order, whose value isn't exactly the true
+   //  sum, as (ix,iy), or None if they all are.
+
+   pub first_inexact: Option<(usize,usize)>,
+
+   //  The number of elements that aren't exactly the true sum.
+
+   pub inexact: usize,
+
+   //  The relative tolerance used, and the number of elements whose relative
+   //  error is greater than that.
+
+   pub tolerance: f64,
+   pub out_of_tolerance: usize,
+}
+
+//  Compares each element of the output array with the true sum of the input
+//  element and its indices, calculated in double precision (which is exact
+//  for any array that will fit in memory), both exactly and to within the
+//  given relative tolerance.
+
+pub fn check_accuracy (in_array: &Array2D<f32>,out_array: &Array2D<f32>,
+                                             tolerance: f64) -> AccuracyCheck {
+   let mut check = AccuracyCheck { first_inexact: None, inexact: 0,
+                                   tolerance, out_of_tolerance: 0 };
+   for iy in 0..in_array.ny() {
+      for ix in 0..in_array.nx() {
+         let expected = in_array[[ix,iy]] as f64 + (ix + iy) as f64;
+         let actual = out_array[[ix,iy]] as f64;
+         if actual != expected {
+            check.inexact += 1;
+            if check.first_inexact.is_none() {
+               check.first_inexact = Some((ix,iy));
+            }
+            if (actual - expected).abs() > tolerance * expected.abs() {
+               check.out_of_tolerance += 1;
+            }
+         }
+      }
+   }
+   check
+}
+
+//  Returns the output array that strict IEEE 754 single precision arithmetic
+//  gives for an input array - the index sum converted to f32, rounding to
+//  nearest, added to the input element, rounding to nearest again. The
+//  addition is done in double precision and then rounded to f32, which
+//  gives exactly the correctly rounded f32 sum (a double has more than
+//  twice the bits of a float, plus two, so the double rounding can never
+//  differ), but doesn't depend on how the compiler handles f32 arithmetic.
+
+pub fn strict_reference (in_array: &Array2D<f32>)
+                                         -> Result<Array2D<f32>,BenchError> {
+   let mut reference = Array2D::try_new(in_array.nx(),in_array.ny(),0.0f32)?;
+   for iy in 0..in_array.ny() {
+      for ix in 0..in_array.nx() {
+         let index_sum = (ix + iy) as f32;
+         reference[[ix,iy]] =
+                         (in_array[[ix,iy]] as f64 + index_sum as f64) as f32;
+      }
+   }
+   Ok(reference)
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                         C o v e r a g e   C h e c k
+
+//  The result of checking the per-element write counters from one of the
+//  instrumented parallel kernels.
+
+pub struct CoverageCheck {
+   pub missed: usize,
+   pub repeated: usize,
+   pub first_bad: Option<(usize,usize,u32)>,
+}
+
+impl CoverageCheck {
+   pub fn passed (&self) -> bool {
+      self.missed == 0 && self.repeated == 0
+   }
+}
+
+//  Checks that each element of an nx by ny array was written exactly once,
+//  given a counter for each element in row-major order. Counts the elements
+//  that were never written and those that were written more than once, and
+//  records the first bad element as (ix,iy,count). A value check can miss a
+//  repeated write, since writing the same element twice generally gives the
+//  same value, but this can't.
+
+pub fn check_coverage (counters: &[AtomicU32],nx: usize) -> CoverageCheck {
+   let mut check = CoverageCheck { missed: 0, repeated: 0, first_bad: None };
+   for (offset,counter) in counters.iter().enumerate() {
+      let count = counter.load(Ordering::Relaxed);
+      if count != 1 {
+         if count == 0 { check.missed += 1; } else { check.repeated += 1; }
+         if check.first_bad.is_none() {
+            check.first_bad = Some((offset % nx,offset / nx,count));
+         }
+      }
+   }
+   check
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                         S h u f f l e d   C h e c k
+
+//  The result of the standard check made in a random order.
+
+pub struct ShuffledCheck {
+
+   //  The number of elements that don't match, out of all of them.
+
+   pub mismatches: usize,
+
+   //  The rows and columns the mismatches span, as the (first,last) row
+   //  and (first,last) column, or None if there aren't any.
+
+   pub rows: Option<(usize,usize)>,
+   pub columns: Option<(usize,usize)>,
+
+   //  The first mismatches found, in the random order, as (ix,iy,value,
+   //  expected) - so a random sample of them, rather than the ones that
+   //  happen to come first in memory.
+
+   pub sample: Vec<(usize,usize,f32,f32)>,
+}
+
+//  Makes the standard check - the same comparison as verify() - but visits
+//  the elements in a random order, shuffled using the given seed, and
+//  carries on to the end rather than stopping at the first mismatch. Up to
+//  sample_size of the mismatches are kept, as they are found.
+
+pub fn check_shuffled (in_array: &Array2D<f32>,out_array: &Array2D<f32>,
+                              seed: u64,sample_size: usize) -> ShuffledCheck {
+   let nx = in_array.nx();
+   let mut order: Vec<usize> = (0..in_array.len()).collect();
+   let mut rng = Rng::new(seed);
+   for index in (1..order.len()).rev() {
+      let other = (rng.next_u64() % (index as u64 + 1)) as usize;
+      order.swap(index,other);
+   }
+   let mut check = ShuffledCheck { mismatches: 0, rows: None, columns: None,
+                                                          sample: Vec::new() };
+   for offset in order {
+      let (ix,iy) = (offset % nx,offset / nx);
+      let expected = in_array[[ix,iy]] + (ix + iy) as f32;
+      let value = out_array[[ix,iy]];
+      if value != expected {
+         check.mismatches += 1;
+         check.rows = Some(match check.rows {
+            Some((first,last)) => (first.min(iy),last.max(iy)),
+            None => (iy,iy),
+         });
+         check.columns = Some(match check.columns {
+            Some((first,last)) => (first.min(ix),last.max(ix)),
+            None => (ix,ix),
+         });
+         if check.sample.len() < sample_size {
+            check.sample.push((ix,iy,value,expected));
+         }
+      }
+   }
+   check
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                     O u t p u t   C o m p a r i s o n
+
+//  The result of comparing the outputs of two different kernels.
+
+pub struct OutputComparison {
+
+   //  The first element, in memory order, where the two outputs differ at
+   //  all, as (ix,iy), or None if they are bitwise identical.
+
+   pub first_difference: Option<(usize,usize)>,
+
+   //  The number of elements that differ at all, and the number whose
+   //  relative difference is greater than the tolerance.
+
+   pub differences: usize,
+   pub tolerance: f64,
+   pub out_of_tolerance: usize,
+}
+
+//  Compares two output arrays element by element, both bitwise and to
+//  within the given relative tolerance. Two NaNs only match if they have
+//  the same bit pattern, and a NaN is never within tolerance of anything.
+
+pub fn compare_outputs (array_a: &Array2D<f32>,array_b: &Array2D<f32>,
+                                          tolerance: f64) -> OutputComparison {
+   let mut comparison = OutputComparison { first_difference: None,
+               differences: 0, tolerance, out_of_tolerance: 0 };
+   for iy in 0..array_a.ny() {
+      for ix in 0..array_a.nx() {
+         let value_a = array_a[[ix,iy]];
+         let value_b = array_b[[ix,iy]];
+         if value_a.to_bits() != value_b.to_bits() {
+            comparison.differences += 1;
+            if comparison.first_difference.is_none() {
+               comparison.first_difference = Some((ix,iy));
+            }
+            let scale = (value_a as f64).abs().max((value_b as f64).abs());
+            let difference = (value_a as f64 - value_b as f64).abs();
+            let limit = tolerance * scale;
+            if difference.is_nan() || limit.is_nan() || difference > limit {
+               comparison.out_of_tolerance += 1;
+            }
+         }
+      }
+   }
+   comparison
+}
+
+//  ----------------------------------------------------------------------------
+//
+//                                 T e s t s
+
+#[cfg(test)]
+mod tests {
+   use std::fs;
+
+   use super::{read_raw, write_raw, Endian};
+   use crate::crsarray::Array2D;
+
+   //  A file in the temporary directory, named for the test using it, since
+   //  the tests run in parallel.
+
+   fn temp_path (name: &str) -> String {
+      let mut path = std::env::temp_dir();
+      path.push(format!("crsverify_{}_{}.raw",name,std::process::id()));
+      path.to_string_lossy().into_owned()
+   }
+
+   //  A small array whose first value is 1.0, which is 3F800000, so the first
+   //  four bytes of a file show which order it was written in.
+
+   fn test_array () -> Array2D<f32> {
+      Array2D::from_fn(3,2,|ix,iy| (ix * 10 + iy) as f32 + 1.0)
+   }
+
+   #[test]
+   fn raw_byte_order () {
+      let path = temp_path("byte_order");
+      let array = test_array();
+      write_raw(&path,&array,Endian::Big).unwrap();
+      let bytes = fs::read(&path).unwrap();
+      assert_eq!(bytes.len(),3 * 2 * 4);
+      assert_eq!(&bytes[..4],&[0x3F,0x80,0x00,0x00]);
+      write_raw(&path,&array,Endian::Little).unwrap();
+      let bytes = fs::read(&path).unwrap();
+      assert_eq!(&bytes[..4],&[0x00,0x00,0x80,0x3F]);
+      fs::remove_file(&path).unwrap();
+   }
+
+   #[test]
+   fn raw_round_trip () {
+      let path = temp_path("round_trip");
+      let array = test_array();
+      for &endian in &[Endian::Little,Endian::Big,Endian::Native] {
+         write_raw(&path,&array,endian).unwrap();
+         assert_eq!(read_raw(&path,3,2,endian).unwrap(),array);
+      }
+      fs::remove_file(&path).unwrap();
+   }
+
+   //  Reading a little endian file as big endian gets each value with its
+   //  bytes reversed.
+
+   #[test]
+   fn raw_wrong_order () {
+      let path = temp_path("wrong_order");
+      let array = test_array();
+      write_raw(&path,&array,Endian::Little).unwrap();
+      let read = read_raw(&path,3,2,Endian::Big).unwrap();
+      assert_eq!(read[[0,0]].to_bits(),0x0000803F);
+      for (value,original) in read.as_slice().iter().zip(array.as_slice()) {
+         assert_eq!(value.to_bits(),original.to_bits().swap_bytes());
+      }
+      fs::remove_file(&path).unwrap();
+   }
+
+   #[test]
+   fn raw_wrong_size () {
+      let path = temp_path("wrong_size");
+      write_raw(&path,&test_array(),Endian::Little).unwrap();
+      assert!(read_raw(&path,2,2,Endian::Little).is_err());
+      assert!(read_raw(&path,3,3,Endian::Little).is_err());
+      fs::remove_file(&path).unwrap();
+   }
+}
+
+/*  ----------------------------------------------------------------------------
+
+                  P r o g r a m m i n g   N o t e s
+
+   o With the standard input values, the true result is close to nx + ny for
+     every element, so nothing is inexact until nx + ny gets beyond 2^24,
+     and once it does the inexact elements start almost at once, because
+     the results themselves need more than 24 bits. Running with nx either
+     side of 2^24 - ny shows the boundary quite nicely.
+
+   o The difference map is written with no header so that it can be read
+     straight into almost anything - eg numpy.fromfile(path,'<f4')
+     .reshape(ny,nx) for the default little endian order, or '>f4' for big
+     endian. A NaN in the map means the output element was NaN.
+
+   o The shuffled check uses the same comparison as verify(), so it can't
+     disagree about whether the output is right. The shuffle costs a usize
+     for every element, which is more memory than the arrays themselves
+     for f32, but it's only made when it's asked for.
+
+   o The byte swapping, where there is any, is done a value at a time as
+     the file is written or read. That's never part of anything timed, so
+     there's no point in being cleverer about it.
+
+*/